@@ -8,18 +8,45 @@ fn structured_order_book_benchmarks(c: &mut Criterion) {
     full_benchmark_suite(c, factories::create_hashmap_order_book);
     full_benchmark_suite(c, factories::create_priority_queue_order_book);
     full_benchmark_suite(c, factories::create_array_queue_order_book);
+    full_benchmark_suite(c, factories::create_persistent_order_book);
 }
 
 fn structured_multi_symbol_comparison(c: &mut Criterion) {
     bench_multi_symbol_generic(c, factories::create_hashmap_order_book);
     bench_multi_symbol_generic(c, factories::create_priority_queue_order_book);
     bench_multi_symbol_generic(c, factories::create_array_queue_order_book);
+    bench_multi_symbol_generic(c, factories::create_persistent_order_book);
 }
 
 fn structured_high_frequency_trading(c: &mut Criterion) {
     bench_high_frequency_generic(c, factories::create_hashmap_order_book);
     bench_high_frequency_generic(c, factories::create_priority_queue_order_book);
     bench_high_frequency_generic(c, factories::create_array_queue_order_book);
+    bench_high_frequency_generic(c, factories::create_persistent_order_book);
+}
+
+fn structured_pathological_inputs(c: &mut Criterion) {
+    bench_pathological_generic(c, factories::create_hashmap_order_book);
+    bench_pathological_generic(c, factories::create_priority_queue_order_book);
+    bench_pathological_generic(c, factories::create_array_queue_order_book);
+    bench_pathological_generic(c, factories::create_persistent_order_book);
+}
+
+fn structured_best_prices_by_depth(c: &mut Criterion) {
+    bench_best_prices_by_depth_generic(c, factories::create_hashmap_order_book);
+    bench_best_prices_by_depth_generic(c, factories::create_priority_queue_order_book);
+    bench_best_prices_by_depth_generic(c, factories::create_array_queue_order_book);
+    bench_best_prices_by_depth_generic(c, factories::create_persistent_order_book);
+}
+
+fn structured_journal_replay(c: &mut Criterion) {
+    let Some(journal) = load_replay_journal() else {
+        return;
+    };
+    bench_replay_generic(c, factories::create_hashmap_order_book, &journal);
+    bench_replay_generic(c, factories::create_priority_queue_order_book, &journal);
+    bench_replay_generic(c, factories::create_array_queue_order_book, &journal);
+    bench_replay_generic(c, factories::create_persistent_order_book, &journal);
 }
 
 fn configure_criterion() -> Criterion {
@@ -31,6 +58,6 @@ fn configure_criterion() -> Criterion {
 criterion_group! {
     name = structured_order_book_benches;
     config = configure_criterion();
-    targets = structured_order_book_benchmarks, structured_multi_symbol_comparison, structured_high_frequency_trading
+    targets = structured_order_book_benchmarks, structured_multi_symbol_comparison, structured_high_frequency_trading, structured_pathological_inputs, structured_best_prices_by_depth, structured_journal_replay
 }
 criterion_main!(structured_order_book_benches);
\ No newline at end of file
@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput, BenchmarkId};
 
-use rust_order_book::engine::{OrderBookType, create_order_book};
+use rust_order_book::engine::{DenseMaps, FxMaps, HashMapOrderBook, MapBackend, OrderBookCore, OrderBookLifecycle, OrderBookTrait, OrderBookType, PersistentOrderBook, create_any_order_book, create_order_book};
 use rust_order_book::types::order::{new_order, OrderSide};
 
 mod shared_benchmark;
@@ -10,6 +10,7 @@ const ORDER_BOOK_TYPES: &[OrderBookType] = &[
     OrderBookType::HashMap,
     OrderBookType::PriorityQueue,
     OrderBookType::ArrayQueue,
+    OrderBookType::Persistent,
 ];
 
 fn get_impl_name(order_book_type: OrderBookType) -> &'static str {
@@ -17,6 +18,8 @@ fn get_impl_name(order_book_type: OrderBookType) -> &'static str {
         OrderBookType::HashMap => "hashmap",
         OrderBookType::PriorityQueue => "priorityqueue", 
         OrderBookType::ArrayQueue => "arrayqueue",
+        OrderBookType::Persistent => "persistent",
+        OrderBookType::FixedCapacity => "fixedcapacity",
     }
 }
 
@@ -158,6 +161,43 @@ fn bench_matching(c: &mut Criterion) {
     group.finish();
 }
 
+/// Same add-then-match workload through `Box<dyn OrderBookTrait>` and `AnyOrderBook`.
+fn bench_dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dispatch");
+    group.throughput(Throughput::Elements(100));
+    let data = BenchmarkData::new();
+
+    fn add_and_match<B: OrderBookTrait + ?Sized>(order_book: &mut B, data: &BenchmarkData) {
+        for (buy, sell) in data.matching_buy_orders[0..50].iter().zip(&data.matching_sell_orders[0..50]) {
+            order_book.add_order_fast(buy.clone());
+            order_book.add_order_fast(sell.clone());
+            order_book.match_orders();
+        }
+    }
+
+    for &order_book_type in ORDER_BOOK_TYPES {
+        let impl_name = get_impl_name(order_book_type);
+
+        group.bench_with_input(BenchmarkId::new(impl_name, "boxed_dyn"), &order_book_type, |b, &impl_type| {
+            b.iter_batched(
+                || create_order_book(impl_type, data.symbols.clone()),
+                |mut order_book| add_and_match(order_book.as_mut(), &data),
+                BatchSize::SmallInput,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new(impl_name, "enum"), &order_book_type, |b, &impl_type| {
+            b.iter_batched(
+                || create_any_order_book(impl_type, data.symbols.clone()),
+                |mut order_book| add_and_match(&mut order_book, &data),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
 fn bench_queries(c: &mut Criterion) {
     let mut group = c.benchmark_group("queries");
     group.throughput(Throughput::Elements(1));
@@ -209,12 +249,121 @@ fn bench_queries(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_snapshot_reads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("snapshot_reads");
+    group.throughput(Throughput::Elements(1));
+    let data = BenchmarkData::new();
+
+    for &order_book_type in ORDER_BOOK_TYPES {
+        let impl_name = get_impl_name(order_book_type);
+        let mut order_book = create_order_book(order_book_type, data.symbols.clone());
+        for order in &data.single_symbol_orders[0..1000] {
+            order_book.add_order_fast(order.clone());
+        }
+
+        group.bench_function(BenchmarkId::new(impl_name, "resting_orders_copy"), |b| {
+            b.iter(|| order_book.resting_orders(0))
+        });
+    }
+
+    let mut persistent_book = PersistentOrderBook::new(data.symbols.clone());
+    for order in &data.single_symbol_orders[0..1000] {
+        persistent_book.add_order_fast(order.clone());
+    }
+    group.bench_function(BenchmarkId::new("persistent", "cow_snapshot"), |b| {
+        b.iter(|| persistent_book.snapshot(0))
+    });
+
+    group.finish();
+}
+
+fn bench_mixed_read_write(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mixed_read_write");
+    let data = BenchmarkData::new();
+    let orders = &data.single_symbol_orders[0..200];
+
+    // Every `depth_every`-th query walks the resting orders, the rest are BBO polls
+    let depth_every = 5;
+    for queries_per_order in [1usize, 5, 20] {
+        group.throughput(Throughput::Elements((orders.len() * (queries_per_order + 1)) as u64));
+
+        for &order_book_type in ORDER_BOOK_TYPES {
+            let impl_name = get_impl_name(order_book_type);
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("{impl_name}/queries_per_order"), queries_per_order),
+                &(order_book_type, queries_per_order),
+                |b, &(impl_type, query_count)| {
+                    b.iter_batched(
+                        || create_order_book(impl_type, data.symbols.clone()),
+                        |mut order_book| {
+                            let mut query = 0usize;
+                            for order in orders {
+                                order_book.add_order_fast(order.clone());
+                                order_book.match_orders();
+                                for _ in 0..query_count {
+                                    query += 1;
+                                    if query.is_multiple_of(depth_every) {
+                                        std::hint::black_box(order_book.resting_orders(0));
+                                    } else {
+                                        std::hint::black_box(order_book.get_best_prices(0));
+                                    }
+                                }
+                            }
+                        },
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// The same multi-symbol workload on each `MapBackend`: every add, cancel and best
+/// price query looks its symbol's matcher up, and cancels also hit the id index.
+fn bench_map_backends(c: &mut Criterion) {
+    let mut group = c.benchmark_group("map_backends");
+    let data = BenchmarkData::new();
+    group.throughput(Throughput::Elements(data.multi_symbol_orders.len() as u64 * 3));
+
+    fn run<B: MapBackend>(group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>, name: &str, data: &BenchmarkData) {
+        group.bench_function(BenchmarkId::new("hashmap", name), |b| {
+            b.iter_batched(
+                || HashMapOrderBook::<B>::new(data.symbols.clone()),
+                |mut order_book| {
+                    for order in &data.multi_symbol_orders {
+                        order_book.add_order_fast(order.clone());
+                    }
+                    for order in &data.multi_symbol_orders {
+                        std::hint::black_box(order_book.get_best_prices(order.symbol));
+                    }
+                    for order in &data.multi_symbol_orders {
+                        let _ = order_book.cancel_order(order.symbol, order.id);
+                    }
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
 
+    run::<FxMaps>(&mut group, "fx", &data);
+    run::<DenseMaps>(&mut group, "dense", &data);
+    #[cfg(feature = "hashbrown")]
+    run::<rust_order_book::engine::RawTableMaps>(&mut group, "raw_table", &data);
+
+    group.finish();
+}
 
 criterion_group!(benches,
     bench_add_order_single,
     bench_add_order_batch,
     bench_matching,
-    bench_queries
+    bench_snapshot_reads,
+    bench_mixed_read_write,
+    bench_dispatch,
+    bench_queries,
+    bench_map_backends
 );
 criterion_main!(benches); 
\ No newline at end of file
@@ -1,15 +1,24 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
 use criterion::{Criterion, criterion_group, criterion_main, BatchSize, Throughput, BenchmarkId};
-use rustc_hash::FxHashSet;
+use hdrhistogram::Histogram;
+use rustc_hash::{FxHashMap, FxHashSet};
 use rust_order_book::{
     engine::OrderBookType,
-    router::OrderRouter,
+    events::{self, JournalRecords, JournalWriter},
+    router::{AckDelay, AckDelayConfig, AdaptiveBatching, OrderRouter, ShardConfig, ShardedRouter},
     types::order::{new_order, OrderSide},
 };
 
+mod shared_benchmark;
+use shared_benchmark::BenchmarkData;
+
 const ORDER_BOOK_TYPES: &[OrderBookType] = &[
     OrderBookType::HashMap,
     OrderBookType::PriorityQueue, 
     OrderBookType::ArrayQueue,
+    OrderBookType::Persistent,
 ];
 
 fn get_impl_name(order_book_type: OrderBookType) -> &'static str {
@@ -17,6 +26,8 @@ fn get_impl_name(order_book_type: OrderBookType) -> &'static str {
         OrderBookType::HashMap => "hashmap",
         OrderBookType::PriorityQueue => "priorityqueue", 
         OrderBookType::ArrayQueue => "arrayqueue",
+        OrderBookType::Persistent => "persistent",
+        OrderBookType::FixedCapacity => "fixedcapacity",
     }
 }
 
@@ -124,6 +135,44 @@ fn bench_routing_bulk(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_bulk_cancel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk_cancel");
+    let symbols = FxHashSet::from_iter([0, 1, 2, 3, 4]);
+    let resting_orders: Vec<_> = (0..1000)
+        .map(|i| new_order(i as u64, i % 5, 100, 100.0 + (i % 50) as f64 * 0.1,
+                          if i % 2 == 0 { OrderSide::Buy } else { OrderSide::Sell }))
+        .collect();
+
+    for &cancel_count in &[50u64, 100, 500] {
+        group.throughput(Throughput::Elements(cancel_count));
+        let order_ids: Vec<u64> = (0..cancel_count).map(|i| i * 2).collect();
+
+        for &order_book_type in ORDER_BOOK_TYPES {
+            let impl_name = get_impl_name(order_book_type);
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("{impl_name}/cancel_batch"), cancel_count),
+                &(order_book_type, order_ids.clone()),
+                |b, (impl_type, order_ids)| {
+                    b.iter_batched(
+                        || {
+                            let mut router = OrderRouter::new_direct(symbols.clone(), *impl_type);
+                            for order in &resting_orders {
+                                let _ = router.route_order(order.clone());
+                            }
+                            router
+                        },
+                        |mut router| router.cancel_orders_batch(order_ids).cancelled_count(),
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 fn bench_routing_error_handling(c: &mut Criterion) {
     let mut group = c.benchmark_group("routing_errors");
     group.throughput(Throughput::Elements(1));
@@ -150,10 +199,220 @@ fn bench_routing_error_handling(c: &mut Criterion) {
     group.finish();
 }
 
+fn sharded_configs(shard_count: usize, pinned: bool, batching: Option<AdaptiveBatching>) -> Vec<ShardConfig> {
+    (0..shard_count)
+        .map(|i| {
+            let mut config = ShardConfig::default().with_queue_capacity(4096);
+            config.batching = batching;
+            if pinned { config.pinned_to(i + 1) } else { config }
+        })
+        .collect()
+}
+
+fn bench_sharded_high_frequency(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sharded_high_frequency");
+    let data = BenchmarkData::new();
+    let symbols = FxHashSet::from_iter([0, 1, 2]);
+    let orders = &data.high_frequency_orders[0..1000];
+    group.throughput(Throughput::Elements(orders.len() as u64));
+
+    // Pinning only takes effect with `--features affinity`, otherwise both runs are unpinned
+    let batched = Some(AdaptiveBatching::new(1, 256));
+    for (label, pinned, batching) in [("unpinned", false, None), ("pinned", true, None), ("batched", false, batched)] {
+        for &order_book_type in ORDER_BOOK_TYPES {
+            let impl_name = get_impl_name(order_book_type);
+            let router = ShardedRouter::new(symbols.clone(), order_book_type, sharded_configs(3, pinned, batching));
+
+            group.bench_function(BenchmarkId::new(impl_name, format!("{label}_rapid_fire_1000")), |b| {
+                b.iter(|| {
+                    for order in orders {
+                        let _ = router.route_order(order.clone());
+                    }
+                    router.sync();
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+/// Per-order latency from `route_order` to the shard's ack, pinned against
+/// unpinned shards. Acks go out with no delay, so each sample is the time an order
+/// spent queued and executing plus the hop through the ack dispatcher.
+fn bench_sharded_latency_distribution(_c: &mut Criterion) {
+    let data = BenchmarkData::new();
+    let symbols = FxHashSet::from_iter([0, 1, 2]);
+    let orders = &data.high_frequency_orders[0..1000];
+    let immediate_acks = AckDelayConfig { delay: AckDelay::Fixed(Duration::ZERO), seed: 0 };
+
+    // Pinning only takes effect with `--features affinity`, otherwise both runs are unpinned
+    for (label, pinned) in [("unpinned", false), ("pinned", true)] {
+        for &order_book_type in ORDER_BOOK_TYPES {
+            let mut histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000_000, 3).unwrap();
+            for _ in 0..50 {
+                let configs = sharded_configs(3, pinned, None).into_iter().map(|config| config.with_ack_delay(immediate_acks)).collect();
+                let router = ShardedRouter::new(symbols.clone(), order_book_type, configs);
+                let acks = router.acks().unwrap();
+                let collector = thread::spawn(move || acks.iter().map(|ack| (ack.order_id, Instant::now())).collect::<Vec<_>>());
+
+                let mut enqueued = FxHashMap::default();
+                for order in orders {
+                    let enqueued_at = Instant::now();
+                    if router.route_order(order.clone()).is_ok() {
+                        enqueued.insert(order.id, enqueued_at);
+                    }
+                }
+                // Shutting down drains every shard and the dispatcher, which ends the collector
+                drop(router);
+                for (id, processed_at) in collector.join().unwrap() {
+                    if let Some(enqueued_at) = enqueued.get(&id) {
+                        histogram.saturating_record(processed_at.duration_since(*enqueued_at).as_nanos() as u64);
+                    }
+                }
+            }
+            println!(
+                "sharded_latency/{}/{label}: {} orders, p50 {}ns p99 {}ns p99.9 {}ns max {}ns",
+                get_impl_name(order_book_type),
+                histogram.len(),
+                histogram.value_at_quantile(0.5),
+                histogram.value_at_quantile(0.99),
+                histogram.value_at_quantile(0.999),
+                histogram.max(),
+            );
+        }
+    }
+}
+
+/// Write cost and stored size against recovery time for plain and compressed snapshots.
+#[cfg(feature = "compression")]
+fn bench_snapshot_compression(c: &mut Criterion) {
+    use rust_order_book::snapshot::{latest_snapshot_in, write_snapshot_to};
+    use rust_order_book::storage::{CompressedStorage, MemoryStorage, Storage};
+
+    let data = BenchmarkData::new();
+    let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+    for order in data.high_frequency_orders.iter().filter(|order| order.order_type == OrderSide::Buy) {
+        let _ = router.route_order(order.clone());
+    }
+
+    let plain = MemoryStorage::new();
+    let key = write_snapshot_to(&router, &plain).unwrap();
+    let raw_bytes = plain.get(&key).unwrap().unwrap().len();
+    let mut group = c.benchmark_group("snapshot_compression");
+    group.throughput(Throughput::Bytes(raw_bytes as u64));
+
+    for level in [0, 1, 6, 9] {
+        let storage = CompressedStorage::new(MemoryStorage::new()).with_level(level);
+        let key = write_snapshot_to(&router, &storage).unwrap();
+        let stored_bytes = storage.inner().get(&key).unwrap().unwrap().len();
+        println!("snapshot_compression/level_{level}: {stored_bytes} of {raw_bytes} bytes ({:.3})", stored_bytes as f64 / raw_bytes as f64);
+
+        group.bench_function(BenchmarkId::new("write", format!("level_{level}")), |b| {
+            b.iter(|| write_snapshot_to(&router, &storage).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("recover", format!("level_{level}")), |b| {
+            b.iter(|| latest_snapshot_in(&storage).unwrap())
+        });
+    }
+    group.bench_function(BenchmarkId::new("write", "plain"), |b| b.iter(|| write_snapshot_to(&router, &plain).unwrap()));
+    group.bench_function(BenchmarkId::new("recover", "plain"), |b| b.iter(|| latest_snapshot_in(&plain).unwrap()));
+
+    group.finish();
+}
+
+#[cfg(not(feature = "compression"))]
+fn bench_snapshot_compression(_c: &mut Criterion) {}
+
+/// Stored size and write cost against decompress-and-replay time for a binary journal
+/// framed through `FrameEncoder`.
+#[cfg(feature = "compression")]
+fn bench_journal_compression(c: &mut Criterion) {
+    use std::io::{Read, Write};
+    use rust_order_book::storage::{FrameDecoder, FrameEncoder};
+
+    let symbols = FxHashSet::from_iter([0, 1, 2, 3, 4]);
+    let record_count = 100_000u64;
+    let mut writer = JournalWriter::new(Vec::new()).unwrap();
+    for i in 0..record_count {
+        let side = if i % 2 == 0 { OrderSide::Buy } else { OrderSide::Sell };
+        writer.append(&new_order(i, (i % 5) as u32, 100, 100.0 + (i % 20) as f64 * 0.1 - 1.0, side)).unwrap();
+    }
+    let journal = writer.into_inner().unwrap();
+    let compress = |level| {
+        let mut encoder = FrameEncoder::with_level(Vec::new(), level);
+        encoder.write_all(&journal).unwrap();
+        encoder.finish().unwrap()
+    };
+    let mut group = c.benchmark_group("journal_compression");
+    group.throughput(Throughput::Elements(record_count));
+
+    for level in [1, 6, 9] {
+        let compressed = compress(level);
+        println!("journal_compression/level_{level}: {} of {} bytes ({:.3})", compressed.len(), journal.len(), compressed.len() as f64 / journal.len() as f64);
+
+        group.bench_function(BenchmarkId::new("write", format!("level_{level}")), |b| b.iter(|| compress(level)));
+        group.bench_function(BenchmarkId::new("recover", format!("level_{level}")), |b| {
+            b.iter_batched(
+                || OrderRouter::new_direct(symbols.clone(), OrderBookType::HashMap),
+                |mut router| {
+                    let mut bytes = Vec::with_capacity(journal.len());
+                    FrameDecoder::new(compressed.as_slice()).read_to_end(&mut bytes).unwrap();
+                    events::replay(&mut router, JournalRecords::parse(&bytes).unwrap(), 1_000).unwrap()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.bench_function(BenchmarkId::new("recover", "plain"), |b| {
+        b.iter_batched(
+            || OrderRouter::new_direct(symbols.clone(), OrderBookType::HashMap),
+            |mut router| events::replay(&mut router, JournalRecords::parse(&journal).unwrap(), 1_000).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+#[cfg(not(feature = "compression"))]
+fn bench_journal_compression(_c: &mut Criterion) {}
+
+fn bench_journal_replay(c: &mut Criterion) {
+    let mut group = c.benchmark_group("journal_replay");
+    let symbols = FxHashSet::from_iter([0, 1, 2, 3, 4]);
+    let record_count = 100_000u64;
+    let mut writer = JournalWriter::new(Vec::new()).unwrap();
+    for i in 0..record_count {
+        let side = if i % 2 == 0 { OrderSide::Buy } else { OrderSide::Sell };
+        writer.append(&new_order(i, (i % 5) as u32, 100, 100.0 + (i % 20) as f64 * 0.1 - 1.0, side)).unwrap();
+    }
+    let journal = writer.into_inner().unwrap();
+    group.throughput(Throughput::Elements(record_count));
+
+    for match_interval in [1, 1_000] {
+        group.bench_with_input(BenchmarkId::new("HashMap/match_every", match_interval), &match_interval, |b, &match_interval| {
+            b.iter_batched(
+                || OrderRouter::new_direct(symbols.clone(), OrderBookType::HashMap),
+                |mut router| events::replay(&mut router, JournalRecords::parse(&journal).unwrap(), match_interval).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(benches, 
     bench_routing_single,
     bench_routing_multi_symbol, 
     bench_routing_bulk,
-    bench_routing_error_handling
+    bench_bulk_cancel,
+    bench_routing_error_handling,
+    bench_sharded_high_frequency,
+    bench_sharded_latency_distribution,
+    bench_snapshot_compression,
+    bench_journal_compression,
+    bench_journal_replay
 );
 criterion_main!(benches);
@@ -1,6 +1,6 @@
 use rand::prelude::*;
 use rand_distr::{Normal, Distribution};
-use rust_order_book::types::order::{Order, OrderSide, new_order};
+use rust_order_book::types::{order::{Order, OrderSide, new_order}, symbol_mapping::SymbolId};
 
 /*
     Generate orders using an Ornstein-Uhlenbeck process.
@@ -16,7 +16,7 @@ pub struct MarketSimParams<'a> {
     pub drift: f64,
     pub mean_reversion_strength: f64,
     pub volatility: f64,
-    pub symbols: &'a Vec<u16>,
+    pub symbols: &'a Vec<SymbolId>,
 }
 
 pub fn generate_ou_orders<'a>(params: MarketSimParams<'a>) -> Vec<Order> {
@@ -12,6 +12,8 @@ pub fn get_impl_name(order_book_type: OrderBookType) -> &'static str {
         OrderBookType::HashMap => "hashmap",
         OrderBookType::PriorityQueue => "priorityqueue", 
         OrderBookType::ArrayQueue => "arrayqueue",
+        OrderBookType::Persistent => "persistent",
+        OrderBookType::FixedCapacity => "fixedcapacity",
     }
 }
 
@@ -505,4 +507,228 @@ pub fn bench_queries_generic<T>(
     );
 
     group.finish();
-}
\ No newline at end of file
+}
+
+/// Best price queries against books of growing depth. A flat line across depths shows
+/// the query does not walk the levels.
+#[allow(dead_code)]
+pub fn bench_best_prices_by_depth_generic<T>(
+    c: &mut Criterion,
+    create_order_book: impl Fn(FxHashSet<SymbolId>) -> T,
+) where
+    T: OrderBookTrait + 'static,
+{
+    let order_book_type = create_order_book(FxHashSet::from_iter([0])).order_book_type();
+    let impl_name = get_impl_name(order_book_type);
+    let mut group = c.benchmark_group("best_prices_by_depth");
+    group.throughput(Throughput::Elements(1));
+
+    for levels in [10u64, 100, 1_000, 10_000] {
+        let mut order_book = create_order_book(FxHashSet::from_iter([0]));
+        for level in 0..levels {
+            order_book.add_order_fast(new_order(level * 2, 0, 100, 1_000.0 - level as f64 * 0.01, OrderSide::Buy));
+            order_book.add_order_fast(new_order(level * 2 + 1, 0, 100, 1_000.01 + level as f64 * 0.01, OrderSide::Sell));
+        }
+        group.bench_with_input(
+            BenchmarkId::new(impl_name, levels),
+            &order_book,
+            |b, order_book| b.iter(|| std::hint::black_box(order_book).get_best_prices(0)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Path of a journal (one JSON `Order` per line, as in `tests/corpus`) to replay in
+/// the `replay` group. The group is skipped when unset. With `--features compression`
+/// the journal may also be a framed compressed stream.
+pub const REPLAY_JOURNAL_ENV: &str = "BENCH_REPLAY_JOURNAL";
+
+#[allow(dead_code)]
+pub fn load_replay_journal() -> Option<Vec<Order>> {
+    use std::io::BufRead;
+
+    let path = std::env::var_os(REPLAY_JOURNAL_ENV)?;
+    let path = std::path::Path::new(&path);
+    let file = std::fs::File::open(path).unwrap_or_else(|err| panic!("reading {}: {err}", path.display()));
+    let reader: Box<dyn BufRead> = journal_reader(std::io::BufReader::new(file));
+    let journal = reader
+        .lines()
+        .map(|line| line.unwrap_or_else(|err| panic!("reading {}: {err}", path.display())))
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(&line).unwrap_or_else(|err| panic!("{}: {err}", path.display())))
+        .collect();
+    Some(journal)
+}
+
+#[cfg(feature = "compression")]
+fn journal_reader(mut reader: std::io::BufReader<std::fs::File>) -> Box<dyn std::io::BufRead> {
+    use rust_order_book::storage::{is_compressed, FrameDecoder};
+    use std::io::BufRead;
+
+    // Decompress one frame at a time rather than inflating the whole capture up front
+    if reader.fill_buf().is_ok_and(is_compressed) {
+        Box::new(std::io::BufReader::new(FrameDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    }
+}
+
+#[cfg(not(feature = "compression"))]
+fn journal_reader(reader: std::io::BufReader<std::fs::File>) -> Box<dyn std::io::BufRead> {
+    Box::new(reader)
+}
+
+/// Adds and matches every journal order in sequence, the way the router replays flow.
+#[allow(dead_code)]
+pub fn bench_replay_generic<T>(
+    c: &mut Criterion,
+    create_order_book: impl Fn(FxHashSet<SymbolId>) -> T,
+    journal: &[Order],
+) where
+    T: OrderBookTrait + 'static,
+{
+    let symbols: FxHashSet<SymbolId> = journal.iter().map(|order| order.symbol).collect();
+    let order_book_type = create_order_book(symbols.clone()).order_book_type();
+    let impl_name = get_impl_name(order_book_type);
+    let mut group = c.benchmark_group("replay");
+
+    group.throughput(Throughput::Elements(journal.len() as u64));
+    group.bench_with_input(
+        BenchmarkId::new(impl_name, format!("journal_{}", journal.len())),
+        &(),
+        |b, _| {
+            b.iter_batched(
+                || create_order_book(symbols.clone()),
+                |mut order_book| {
+                    for order in journal {
+                        order_book.add_order_fast(order.clone());
+                        order_book.match_orders();
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        },
+    );
+
+    group.finish();
+}
+
+pub struct PathologicalData {
+    /// Every order at the same price, so one level gets very deep.
+    pub single_level_buys: Vec<Order>,
+    pub single_level_sells: Vec<Order>,
+    /// Each buy improves the best bid and each sell the best ask, opening a new level every time.
+    pub monotone_buys: Vec<Order>,
+    pub monotone_sells: Vec<Order>,
+    /// More resting orders on one side than an ArrayQueue book can hold.
+    pub overflow_buys: Vec<Order>,
+}
+
+impl Default for PathologicalData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathologicalData {
+    pub fn new() -> Self {
+        let count = 1000;
+        Self {
+            single_level_buys: (0..count).map(|i| new_order(i, 0, 100, 100.0, OrderSide::Buy)).collect(),
+            single_level_sells: (0..count).map(|i| new_order(count + i, 0, 100, 100.0, OrderSide::Sell)).collect(),
+            monotone_buys: (0..count)
+                .map(|i| new_order(i, 0, 100, 50.0 + i as f64 * 0.01, OrderSide::Buy))
+                .collect(),
+            monotone_sells: (0..count)
+                .map(|i| new_order(count + i, 0, 100, 150.0 - i as f64 * 0.01, OrderSide::Sell))
+                .collect(),
+            overflow_buys: (0..5000).map(|i| new_order(i, 0, 100, 100.0, OrderSide::Buy)).collect(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn bench_pathological_generic<T>(
+    c: &mut Criterion,
+    create_order_book: impl Fn(FxHashSet<SymbolId>) -> T,
+) where
+    T: OrderBookTrait + 'static,
+{
+    let order_book_type = create_order_book(FxHashSet::from_iter([0])).order_book_type();
+    let impl_name = get_impl_name(order_book_type);
+    let mut group = c.benchmark_group("pathological");
+    let data = PathologicalData::new();
+
+    group.throughput(Throughput::Elements((data.single_level_buys.len() * 2) as u64));
+    group.bench_with_input(
+        BenchmarkId::new(impl_name, "deep_single_level"),
+        &(),
+        |b, _| {
+            b.iter_batched(
+                || create_order_book(FxHashSet::from_iter([0])),
+                |mut order_book| {
+                    for order in data.single_level_buys.iter().chain(&data.single_level_sells) {
+                        order_book.add_order_fast(order.clone());
+                    }
+                    order_book.match_orders()
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+
+    group.throughput(Throughput::Elements((data.monotone_buys.len() * 2) as u64));
+    group.bench_with_input(
+        BenchmarkId::new(impl_name, "monotone_level_churn"),
+        &(),
+        |b, _| {
+            b.iter_batched(
+                || create_order_book(FxHashSet::from_iter([0])),
+                |mut order_book| {
+                    for order in data.monotone_buys.iter().chain(&data.monotone_sells) {
+                        order_book.add_order_fast(order.clone());
+                    }
+                    order_book.match_orders()
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+
+    group.throughput(Throughput::Elements((data.single_level_buys.len() * 2) as u64));
+    group.bench_with_input(
+        BenchmarkId::new(impl_name, "alternating_cross_uncross"),
+        &(),
+        |b, _| {
+            b.iter_batched(
+                || create_order_book(FxHashSet::from_iter([0])),
+                |mut order_book| {
+                    for (buy, sell) in data.single_level_buys.iter().zip(&data.single_level_sells) {
+                        order_book.add_order_fast(buy.clone());
+                        order_book.add_order_fast(sell.clone());
+                        order_book.match_orders();
+                    }
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+
+    group.throughput(Throughput::Elements(data.overflow_buys.len() as u64));
+    group.bench_with_input(
+        BenchmarkId::new(impl_name, format!("one_sided_overflow_{}", data.overflow_buys.len())),
+        &(),
+        |b, _| {
+            b.iter_batched(
+                || create_order_book(FxHashSet::from_iter([0])),
+                |mut order_book| {
+                    order_book.add_orders_batch_fast(&data.overflow_buys)
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+
+    group.finish();
+}
@@ -0,0 +1,61 @@
+//! Order intake with gateway-style checks, matching, and a market data feed read from
+//! another thread.
+//!
+//! ```text
+//! cargo run --example gateway_feed
+//! ```
+
+use rustc_hash::FxHashSet;
+
+use rust_order_book::engine::{OrderBookType, ParticipantLimits};
+use rust_order_book::market_data::BookMirror;
+use rust_order_book::router::{DedupWindow, OrderRouter};
+use rust_order_book::types::order::{new_order, price_to_u64, u64_to_price, OrderSide};
+use rust_order_book::types::symbol_mapping::SYMBOL_TO_ID;
+
+fn main() {
+    let apple = SYMBOL_TO_ID["AAPL"];
+    let mut router = OrderRouter::new_direct(FxHashSet::from_iter([apple]), OrderBookType::HashMap);
+
+    // Gateway policy: drop retransmits and cap each firm's resting orders
+    router.enable_dedup(DedupWindow::new(1024));
+    router.set_participant_limits(ParticipantLimits::default().with_max_orders_per_book(3));
+
+    let (mirror, publisher) = BookMirror::start();
+    router.attach_mirror(publisher);
+
+    let flow = [
+        new_order(1, apple, 100, 149.90, OrderSide::Buy).with_participant(10),
+        new_order(2, apple, 100, 150.10, OrderSide::Sell).with_participant(20),
+        new_order(2, apple, 100, 150.10, OrderSide::Sell).with_participant(20),
+        new_order(3, apple, 50, 149.95, OrderSide::Buy).with_participant(10),
+        new_order(4, apple, 50, 149.95, OrderSide::Sell).with_participant(30),
+    ];
+    for order in flow {
+        let id = order.id;
+        match router.route_order(order) {
+            Ok(()) => println!("order {id}: accepted"),
+            Err(reason) => println!("order {id}: rejected ({reason})"),
+        }
+        router.match_all_orders();
+    }
+
+    let depth = router.aggregated_depth(apple, price_to_u64(0.5), 5).unwrap();
+    for band in &depth.bids {
+        println!("bid band {:.2}: {} across {} orders", u64_to_price(band.price), band.total_quantity, band.order_count);
+    }
+    println!("duplicates dropped: {}", router.dedup_hits());
+
+    // The mirror applies updates on its own thread; dropping the router closes the feed
+    let last_trade = router.last_trade_price(apple);
+    drop(router);
+    let reader = mirror.join();
+    let view = reader.view(apple).unwrap();
+    println!(
+        "feed: bid {:?} ask {:?} last trade {:?} after {} updates",
+        view.best_bid.map(u64_to_price),
+        view.best_ask.map(u64_to_price),
+        last_trade.map(u64_to_price),
+        view.updates,
+    );
+}
@@ -0,0 +1,51 @@
+//! Splits a marketable buy across venues using consolidated depth, best price first.
+//!
+//! ```text
+//! cargo run --example venue_routing
+//! ```
+
+use rustc_hash::FxHashSet;
+
+use rust_order_book::engine::{create_any_order_book, AnyOrderBook, OrderBookCore, OrderBookType};
+use rust_order_book::market_data::{consolidated_depth, VenueId};
+use rust_order_book::types::order::{new_order, price_to_u64, u64_to_price, OrderSide};
+use rust_order_book::types::symbol_mapping::SymbolId;
+
+const SYMBOL: SymbolId = 0;
+
+fn venue(order_book_type: OrderBookType, asks: &[(f64, u64)]) -> AnyOrderBook {
+    let mut book = create_any_order_book(order_book_type, FxHashSet::from_iter([SYMBOL]));
+    for (id, &(price, quantity)) in asks.iter().enumerate() {
+        book.add_order(new_order(id as u64, SYMBOL, quantity, price, OrderSide::Sell)).unwrap();
+    }
+    book
+}
+
+fn main() {
+    let venues = [
+        (1, venue(OrderBookType::HashMap, &[(100.02, 300), (100.05, 500)])),
+        (2, venue(OrderBookType::PriorityQueue, &[(100.01, 200), (100.02, 100)])),
+        (3, venue(OrderBookType::FixedCapacity, &[(100.03, 1000)])),
+    ];
+    let books: Vec<(VenueId, &AnyOrderBook)> = venues.iter().map(|(id, book)| (*id, book)).collect();
+
+    let (mut remaining, limit) = (800, price_to_u64(100.03));
+    let mut slices = Vec::new();
+    for level in consolidated_depth(&books, SYMBOL, OrderSide::Sell, 10) {
+        if remaining == 0 || level.price > limit {
+            break;
+        }
+        for share in level.venues {
+            let quantity = share.quantity.min(remaining);
+            if quantity > 0 {
+                slices.push((share.venue, level.price, quantity));
+                remaining -= quantity;
+            }
+        }
+    }
+
+    for (venue, price, quantity) in &slices {
+        println!("venue {venue}: buy {quantity} @ {:.2}", u64_to_price(*price));
+    }
+    println!("unrouted: {remaining}");
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_order_book::types::order::{DecodeError, ORDER_WIRE_SIZE, decode_order, encode_order};
+
+fuzz_target!(|frame: &[u8]| {
+    match decode_order(frame) {
+        Ok(order) => assert_eq!(encode_order(&order)[..], frame[..ORDER_WIRE_SIZE]),
+        Err(DecodeError::Truncated { len }) => assert!(len < ORDER_WIRE_SIZE),
+        Err(DecodeError::InvalidSide(side)) => assert!(side > 1),
+        Err(DecodeError::InvalidTimeInForce(time_in_force)) => assert!(time_in_force > 4),
+    }
+});
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crossbeam::channel::Sender;
+use rustc_hash::FxHashMap;
+
+use crate::types::timestamp::now_nanos;
+
+/// What an admin session may do, separate from order entry: participants and their
+/// limits govern orders, roles govern the control surface. Each role may run every
+/// command the roles below it may.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AdminRole {
+    /// Read-only: `stats` and `depth`.
+    Viewer,
+    /// Halts, resumes and snapshots.
+    Operator,
+    /// Mass cancels, drains and config reloads.
+    Admin,
+}
+
+impl fmt::Display for AdminRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AdminRole::Viewer => "viewer",
+            AdminRole::Operator => "operator",
+            AdminRole::Admin => "admin",
+        })
+    }
+}
+
+/// Someone a token authenticates as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminPrincipal {
+    pub name: String,
+    pub role: AdminRole,
+}
+
+/// One command run or refused on the admin shell or over HTTP. Tokens are never recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminAuditEntry {
+    pub timestamp: u64,
+    /// `None` for a session that has not authenticated.
+    pub principal: Option<String>,
+    /// The command line as typed, or just `auth` for an authentication attempt.
+    pub command: String,
+    /// The reply's status line, starting with `ok` or `error`.
+    pub status: String,
+}
+
+/// Who may use the admin shell and where its audit trail goes. The default admits no
+/// one until a session sends `auth <token>` with a registered token.
+#[derive(Debug, Clone, Default)]
+pub struct AdminAccess {
+    tokens: FxHashMap<String, AdminPrincipal>,
+    anonymous: Option<AdminRole>,
+    audit: Option<Sender<AdminAuditEntry>>,
+}
+
+impl AdminAccess {
+    /// Every session holds `AdminRole::Admin` without authenticating; what
+    /// `AdminShell::start` uses, leaving access to the socket's file permissions.
+    pub fn open() -> Self {
+        Self::default().with_anonymous_role(AdminRole::Admin)
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>, name: impl Into<String>, role: AdminRole) -> Self {
+        self.tokens.insert(token.into(), AdminPrincipal { name: name.into(), role });
+        self
+    }
+
+    /// The role sessions hold before authenticating, e.g. `Viewer` for a read-only
+    /// default.
+    pub fn with_anonymous_role(mut self, role: AdminRole) -> Self {
+        self.anonymous = Some(role);
+        self
+    }
+
+    /// Sends an `AdminAuditEntry` for every command, authentication attempt and line
+    /// that fails to parse.
+    pub fn with_audit(mut self, audit: Sender<AdminAuditEntry>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    pub fn authenticate(&self, token: &str) -> Option<&AdminPrincipal> {
+        self.tokens.get(token)
+    }
+
+    #[inline(always)]
+    pub fn anonymous_role(&self) -> Option<AdminRole> {
+        self.anonymous
+    }
+
+    pub(crate) fn record(&self, principal: Option<&str>, command: String, status: &str) {
+        if let Some(audit) = &self.audit {
+            let _ = audit.send(AdminAuditEntry {
+                timestamp: now_nanos(),
+                principal: principal.map(str::to_string),
+                command,
+                status: status.to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_and_tokens() {
+        assert!(AdminRole::Viewer < AdminRole::Operator && AdminRole::Operator < AdminRole::Admin);
+        assert_eq!(AdminRole::Operator.to_string(), "operator");
+
+        let access = AdminAccess::default().with_token("s3cret", "ops-bot", AdminRole::Operator);
+        assert_eq!(access.authenticate("s3cret").map(|principal| (principal.name.as_str(), principal.role)), Some(("ops-bot", AdminRole::Operator)));
+        assert_eq!(access.authenticate("guess"), None);
+        assert_eq!(access.anonymous_role(), None);
+        assert_eq!(AdminAccess::open().anonymous_role(), Some(AdminRole::Admin));
+    }
+}
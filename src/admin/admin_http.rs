@@ -0,0 +1,262 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use tokio::sync::oneshot;
+use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::Filter;
+
+use crate::admin::access::AdminAccess;
+use crate::admin::admin_shell::{AdminCommand, Denied, Session, ShellState};
+use crate::router::OrderRouter;
+use crate::storage::Storage;
+
+const MAX_COMMAND_BYTES: u64 = 4096;
+
+/// The admin shell's commands over HTTP. Each `POST /admin` carries one command line as
+/// its body, e.g. `halt 3` or `drain 7`, and authenticates with an
+/// `Authorization: Bearer <token>` header instead of `auth`. The reply body is what the
+/// shell would print; the status is 200 for `ok`, 401 without a valid token, 403 when
+/// the token's role is too low and 400 for anything else refused.
+pub struct AdminHttp {
+    address: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AdminHttp {
+    /// Serves on `address`, port 0 picking a free one, with sessions held to the roles
+    /// in `access` and every request audited. Runs its own runtime on one thread.
+    pub fn start(
+        router: Arc<Mutex<OrderRouter>>,
+        address: SocketAddr,
+        storage: Arc<dyn Storage>,
+        access: AdminAccess,
+    ) -> io::Result<Self> {
+        let state = Arc::new(ShellState::new(router, storage, access));
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        let (bound, bound_address) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("order-book-admin-http".into())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime,
+                    Err(error) => {
+                        let _ = bound.send(Err(error));
+                        return;
+                    }
+                };
+                runtime.block_on(async move {
+                    let server = warp::serve(routes(state)).try_bind_with_graceful_shutdown(address, async {
+                        let _ = stopped.await;
+                    });
+                    match server {
+                        Ok((address, server)) => {
+                            let _ = bound.send(Ok(address));
+                            server.await;
+                        }
+                        Err(error) => {
+                            let _ = bound.send(Err(io::Error::new(io::ErrorKind::AddrNotAvailable, error)));
+                        }
+                    }
+                });
+            })?;
+
+        match bound_address.recv() {
+            Ok(Ok(address)) => Ok(Self { address, shutdown: Some(shutdown), handle: Some(handle) }),
+            Ok(Err(error)) => {
+                let _ = handle.join();
+                Err(error)
+            }
+            Err(_) => Err(io::Error::other("admin http thread exited before binding")),
+        }
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Finishes requests in flight and stops listening.
+    pub fn stop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for AdminHttp {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn routes(state: Arc<ShellState>) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::path("admin"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::content_length_limit(MAX_COMMAND_BYTES))
+        .and(warp::body::bytes())
+        .and_then(move |authorization: Option<String>, body: Bytes| {
+            let state = state.clone();
+            async move {
+                // The router lock is held for the whole command, so keep it off the runtime
+                let (status, reply) = tokio::task::spawn_blocking(move || respond(&state, authorization.as_deref(), &body))
+                    .await
+                    .unwrap_or_else(|_| (StatusCode::INTERNAL_SERVER_ERROR, "error: command panicked".into()));
+                Ok::<_, warp::Rejection>(warp::reply::with_status(reply + "\n", status))
+            }
+        })
+}
+
+/// Authenticates one request, runs its command and audits it like the shell does.
+fn respond(state: &ShellState, authorization: Option<&str>, body: &[u8]) -> (StatusCode, String) {
+    let mut session = Session { principal: None, role: state.access.anonymous_role() };
+    if let Some(authorization) = authorization {
+        let principal = authorization.strip_prefix("Bearer ").and_then(|token| state.access.authenticate(token.trim()));
+        let Some(principal) = principal else {
+            let reply = "error: invalid token".to_string();
+            state.access.record(None, "auth".into(), &reply);
+            return (StatusCode::UNAUTHORIZED, reply);
+        };
+        session = Session { principal: Some(principal.name.clone()), role: Some(principal.role) };
+    }
+
+    let line = String::from_utf8_lossy(body);
+    let line = line.trim();
+    let (status, reply) = match AdminCommand::parse(line) {
+        Ok(AdminCommand::Auth(_)) => (StatusCode::BAD_REQUEST, "error: send the token as an Authorization: Bearer header".into()),
+        Ok(command) => match session.authorize(&command) {
+            Err(Denied::Unauthenticated) => (StatusCode::UNAUTHORIZED, "error: not authenticated, send an Authorization: Bearer header".into()),
+            Err(Denied::Requires(required)) => (StatusCode::FORBIDDEN, format!("error: requires the {required} role")),
+            Ok(()) => {
+                let reply = state.execute(command);
+                let status = if reply.lines().last().is_some_and(|status| status.starts_with("ok")) { StatusCode::OK } else { StatusCode::BAD_REQUEST };
+                (status, reply)
+            }
+        },
+        Err(error) => (StatusCode::BAD_REQUEST, format!("error: {error}")),
+    };
+    let audited = if line.split_whitespace().next() == Some("auth") { "auth" } else { line };
+    state.access.record(session.principal.as_deref(), audited.to_string(), reply.lines().last().unwrap_or_default());
+    (status, reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use rustc_hash::FxHashSet;
+
+    use super::*;
+    use crate::admin::access::AdminRole;
+    use crate::engine::OrderBookType;
+    use crate::storage::MemoryStorage;
+    use crate::types::order::{new_order, OrderSide};
+
+    fn router_with_orders() -> Arc<Mutex<OrderRouter>> {
+        let router = Arc::new(Mutex::new(OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap)));
+        router.lock().unwrap().route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.lock().unwrap().route_order(new_order(2, 1, 5, 101.0, OrderSide::Sell)).unwrap();
+        router
+    }
+
+    async fn post(
+        routes: &(impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone + 'static),
+        token: Option<&str>,
+        command: &str,
+    ) -> (u16, String) {
+        let mut request = warp::test::request().method("POST").path("/admin").body(command);
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        let response = request.reply(routes).await;
+        (response.status().as_u16(), String::from_utf8_lossy(response.body()).trim_end().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_http_enforces_roles_and_audits() {
+        let router = router_with_orders();
+        let (audit, audit_trail) = crossbeam::channel::unbounded();
+        let access = AdminAccess::default()
+            .with_token("view-token", "dashboard", AdminRole::Viewer)
+            .with_token("ops-token", "on-call", AdminRole::Operator)
+            .with_token("admin-token", "root", AdminRole::Admin)
+            .with_audit(audit);
+        let routes = routes(Arc::new(ShellState::new(router.clone(), Arc::new(MemoryStorage::new()), access)));
+
+        assert_eq!(post(&routes, None, "stats").await, (401, "error: not authenticated, send an Authorization: Bearer header".into()));
+        assert_eq!(post(&routes, Some("wrong"), "stats").await, (401, "error: invalid token".into()));
+        assert_eq!(post(&routes, Some("view-token"), "depth 0").await, (200, "bid 100000 10 1\nok".into()));
+        assert_eq!(post(&routes, Some("view-token"), "halt 0").await, (403, "error: requires the operator role".into()));
+        assert_eq!(post(&routes, Some("ops-token"), "halt 0").await, (200, "ok halted 1".into()));
+        assert!(router.lock().unwrap().is_frozen(0));
+        assert_eq!(post(&routes, Some("ops-token"), "drain 1").await, (403, "error: requires the admin role".into()));
+        assert_eq!(post(&routes, Some("ops-token"), "auth ops-token").await.0, 400);
+        assert_eq!(post(&routes, Some("admin-token"), "drain 9").await, (400, "error: Invalid symbol".into()));
+        assert_eq!(post(&routes, Some("admin-token"), "drain 0").await, (200, "ok drained 1".into()));
+        assert!(!router.lock().unwrap().supports_symbol(0));
+        assert!(!router.lock().unwrap().is_frozen(0));
+
+        let entries: Vec<_> = audit_trail.try_iter().map(|entry| (entry.principal, entry.command, entry.status)).collect();
+        let expected = [
+            (None, "stats", "error: not authenticated, send an Authorization: Bearer header"),
+            (None, "auth", "error: invalid token"),
+            (Some("dashboard"), "depth 0", "ok"),
+            (Some("dashboard"), "halt 0", "error: requires the operator role"),
+            (Some("on-call"), "halt 0", "ok halted 1"),
+            (Some("on-call"), "drain 1", "error: requires the admin role"),
+            (Some("on-call"), "auth", "error: send the token as an Authorization: Bearer header"),
+            (Some("root"), "drain 9", "error: Invalid symbol"),
+            (Some("root"), "drain 0", "ok drained 1"),
+        ];
+        let expected: Vec<_> = expected.iter().map(|(principal, command, status)| (principal.map(str::to_string), command.to_string(), status.to_string())).collect();
+        assert_eq!(entries, expected);
+    }
+
+    #[tokio::test]
+    async fn test_http_reloads_config() {
+        let router = router_with_orders();
+        let path = std::env::temp_dir().join(format!("rust-order-book-admin-config-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"tick_sizes": {"ticks": {"0": 5000}, "policy": "Reject"}, "price_scales": {"scales": {"1": 2}}}"#).unwrap();
+        let routes = routes(Arc::new(ShellState::new(router.clone(), Arc::new(MemoryStorage::new()), AdminAccess::open())));
+
+        assert_eq!(post(&routes, None, &format!("reload-config {}", path.display())).await, (200, "ok reloaded 2".into()));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(router.lock().unwrap().tick_sizes().tick(0), Some(5000));
+        assert_eq!(router.lock().unwrap().price_scale(1).decimals(), 2);
+        let (status, reply) = post(&routes, None, &format!("reload-config {}", path.display())).await;
+        assert_eq!(status, 400);
+        assert!(reply.starts_with("error: "));
+    }
+
+    #[test]
+    fn test_http_server_serves_and_stops() {
+        let router = router_with_orders();
+        let access = AdminAccess::default().with_token("admin-token", "root", AdminRole::Admin);
+        let mut server = AdminHttp::start(router.clone(), "127.0.0.1:0".parse().unwrap(), Arc::new(MemoryStorage::new()), access).unwrap();
+
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+        let command = "cancel-all";
+        write!(
+            stream,
+            "POST /admin HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer admin-token\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{command}",
+            command.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("ok cancelled 2\n"), "{response}");
+
+        let address = server.address();
+        server.stop();
+        assert!(TcpStream::connect(address).is_err());
+    }
+}
@@ -0,0 +1,552 @@
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use rustc_hash::FxHashSet;
+
+use crate::admin::access::{AdminAccess, AdminRole};
+use crate::engine::lot_size::LotSizes;
+use crate::engine::tick_size::TickSizes;
+use crate::router::OrderRouter;
+use crate::snapshot::write_snapshot_to;
+use crate::storage::Storage;
+use crate::types::price_scale::PriceScales;
+use crate::types::symbol_mapping::SymbolId;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+const DEFAULT_DEPTH_LEVELS: usize = 5;
+const HELP: &str = "ok commands: auth <token>, halt [symbol..], resume [symbol..], stats, depth <symbol> [levels], cancel-all [symbol], drain <symbol>, reload-config <path>, snapshot-now, help, quit";
+
+/// One line typed into the admin shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminCommand {
+    /// Authenticates the session with a token registered in the shell's `AdminAccess`.
+    Auth(String),
+    /// Freezes intake for the listed symbols, or every symbol when none are listed.
+    Halt(Vec<SymbolId>),
+    /// Lifts a halt placed from the shell, for the listed symbols or all of them.
+    Resume(Vec<SymbolId>),
+    /// Sequence, session state and intake counters.
+    Stats,
+    /// Best `levels` price levels per side, asks above bids.
+    Depth { symbol: SymbolId, levels: usize },
+    /// Cancels every order, or one symbol's, as `ExpiryReason::OperatorCancel`.
+    CancelAll(Option<SymbolId>),
+    /// Removes the symbol's book with `OrderRouter::drain_symbol`, cancelling everything it held.
+    Drain(SymbolId),
+    /// Reads an `AdminConfig` from a JSON file on the engine's host and applies it.
+    ReloadConfig(PathBuf),
+    /// Writes a router snapshot to the shell's storage right away.
+    SnapshotNow,
+    Help,
+    Quit,
+}
+
+impl AdminCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return Err("empty command".into());
+        };
+        let arguments: Vec<&str> = words.collect();
+        let symbols = || arguments.iter()
+            .map(|word| word.parse::<SymbolId>().map_err(|_| format!("invalid symbol '{word}'")))
+            .collect::<Result<Vec<_>, _>>();
+        let no_arguments = |command: AdminCommand| {
+            if arguments.is_empty() {
+                Ok(command)
+            } else {
+                Err(format!("{name} takes no arguments"))
+            }
+        };
+
+        match name {
+            "auth" => match arguments[..] {
+                [token] => Ok(AdminCommand::Auth(token.to_string())),
+                _ => Err("usage: auth <token>".into()),
+            },
+            "halt" => symbols().map(AdminCommand::Halt),
+            "resume" => symbols().map(AdminCommand::Resume),
+            "stats" => no_arguments(AdminCommand::Stats),
+            "depth" => {
+                let usage = || "usage: depth <symbol> [levels]".to_string();
+                let (symbol, levels) = match arguments[..] {
+                    [symbol] => (symbol, None),
+                    [symbol, levels] => (symbol, Some(levels)),
+                    _ => return Err(usage()),
+                };
+                Ok(AdminCommand::Depth {
+                    symbol: symbol.parse().map_err(|_| format!("invalid symbol '{symbol}'"))?,
+                    levels: levels.map_or(Ok(DEFAULT_DEPTH_LEVELS), str::parse).map_err(|_| usage())?,
+                })
+            }
+            "cancel-all" => match symbols()?[..] {
+                [] => Ok(AdminCommand::CancelAll(None)),
+                [symbol] => Ok(AdminCommand::CancelAll(Some(symbol))),
+                _ => Err("usage: cancel-all [symbol]".into()),
+            },
+            "drain" => match symbols()?[..] {
+                [symbol] => Ok(AdminCommand::Drain(symbol)),
+                _ => Err("usage: drain <symbol>".into()),
+            },
+            "reload-config" => match arguments[..] {
+                [path] => Ok(AdminCommand::ReloadConfig(PathBuf::from(path))),
+                _ => Err("usage: reload-config <path>".into()),
+            },
+            "snapshot-now" => no_arguments(AdminCommand::SnapshotNow),
+            "help" => no_arguments(AdminCommand::Help),
+            "quit" | "exit" => no_arguments(AdminCommand::Quit),
+            _ => Err(format!("unknown command '{name}'")),
+        }
+    }
+
+    /// The least role allowed to run the command, `None` for commands anyone may send.
+    pub fn required_role(&self) -> Option<AdminRole> {
+        match self {
+            AdminCommand::Stats | AdminCommand::Depth { .. } => Some(AdminRole::Viewer),
+            AdminCommand::Halt(_) | AdminCommand::Resume(_) | AdminCommand::SnapshotNow => Some(AdminRole::Operator),
+            AdminCommand::CancelAll(_) | AdminCommand::Drain(_) | AdminCommand::ReloadConfig(_) => Some(AdminRole::Admin),
+            AdminCommand::Auth(_) | AdminCommand::Help | AdminCommand::Quit => None,
+        }
+    }
+}
+
+/// Validation tables `reload-config` swaps in. Tables left out of the file keep their
+/// current contents.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct AdminConfig {
+    pub tick_sizes: Option<TickSizes>,
+    pub lot_sizes: Option<LotSizes>,
+    pub price_scales: Option<PriceScales>,
+}
+
+impl AdminConfig {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read(path).map_err(|error| format!("{}: {error}", path.display()))?;
+        serde_json::from_slice(&contents).map_err(|error| format!("{}: {error}", path.display()))
+    }
+
+    /// Replaces each table the config carries and returns how many it replaced.
+    pub fn apply(self, router: &mut OrderRouter) -> usize {
+        let mut replaced = 0;
+        if let Some(tick_sizes) = self.tick_sizes {
+            router.set_tick_sizes(tick_sizes);
+            replaced += 1;
+        }
+        if let Some(lot_sizes) = self.lot_sizes {
+            router.set_lot_sizes(lot_sizes);
+            replaced += 1;
+        }
+        if let Some(price_scales) = self.price_scales {
+            router.set_price_scales(price_scales);
+            replaced += 1;
+        }
+        replaced
+    }
+}
+
+/// Who is on one connection: the principal it authenticated as, if any, and its role,
+/// which starts as the shell's anonymous role.
+pub(super) struct Session {
+    pub(super) principal: Option<String>,
+    pub(super) role: Option<AdminRole>,
+}
+
+/// Why a session may not run a command.
+pub(super) enum Denied {
+    Unauthenticated,
+    Requires(AdminRole),
+}
+
+impl Session {
+    pub(super) fn authorize(&self, command: &AdminCommand) -> Result<(), Denied> {
+        match (command.required_role(), self.role) {
+            (Some(_), None) => Err(Denied::Unauthenticated),
+            (Some(required), Some(role)) if role < required => Err(Denied::Requires(required)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// State the shell keeps across connections: the halts it placed, so `resume` only
+/// lifts those and leaves freezes taken elsewhere in place.
+pub(super) struct ShellState {
+    router: Arc<Mutex<OrderRouter>>,
+    storage: Arc<dyn Storage>,
+    pub(super) access: AdminAccess,
+    halted: Mutex<FxHashSet<SymbolId>>,
+}
+
+impl ShellState {
+    pub(super) fn new(router: Arc<Mutex<OrderRouter>>, storage: Arc<dyn Storage>, access: AdminAccess) -> Self {
+        Self { router, storage, access, halted: Mutex::new(FxHashSet::default()) }
+    }
+
+    /// Checks the session's role, runs the command and records it in the audit trail.
+    fn respond(&self, session: &mut Session, line: &str, command: AdminCommand) -> String {
+        let (audited, reply) = match command {
+            AdminCommand::Auth(token) => {
+                let reply = match self.access.authenticate(&token) {
+                    Some(principal) => {
+                        session.principal = Some(principal.name.clone());
+                        session.role = Some(principal.role);
+                        format!("ok authenticated {} as {}", principal.name, principal.role)
+                    }
+                    None => "error: invalid token".into(),
+                };
+                ("auth".to_string(), reply)
+            }
+            command => {
+                let reply = match session.authorize(&command) {
+                    Err(Denied::Unauthenticated) => "error: not authenticated, send auth <token>".into(),
+                    Err(Denied::Requires(required)) => format!("error: requires the {required} role"),
+                    Ok(()) => self.execute(command),
+                };
+                (line.trim().to_string(), reply)
+            }
+        };
+        self.access.record(session.principal.as_deref(), audited, reply.lines().last().unwrap_or_default());
+        reply
+    }
+
+    /// Replies to a line that did not parse and audits it like any other command. A
+    /// malformed `auth` is recorded as just `auth`, since it may still carry a token.
+    fn refuse(&self, session: &Session, line: &str, error: &str) -> String {
+        let reply = format!("error: {error}");
+        let line = line.trim();
+        let audited = if line.split_whitespace().next() == Some("auth") { "auth" } else { line };
+        self.access.record(session.principal.as_deref(), audited.to_string(), &reply);
+        reply
+    }
+
+    /// Runs one command against the router and renders the reply.
+    pub(super) fn execute(&self, command: AdminCommand) -> String {
+        let Ok(mut router) = self.router.lock() else {
+            return "error: router lock poisoned".into();
+        };
+        let Ok(mut halted) = self.halted.lock() else {
+            return "error: shell state poisoned".into();
+        };
+        match command {
+            AdminCommand::Halt(mut symbols) => {
+                if symbols.is_empty() {
+                    symbols = router.get_symbols();
+                }
+                symbols.retain(|symbol| !halted.contains(symbol));
+                match router.freeze_symbols(&symbols) {
+                    Ok(()) => {
+                        halted.extend(&symbols);
+                        format!("ok halted {}", symbols.len())
+                    }
+                    Err(error) => format!("error: {error}"),
+                }
+            }
+            AdminCommand::Resume(symbols) => {
+                let symbols: Vec<SymbolId> = if symbols.is_empty() {
+                    halted.iter().copied().collect()
+                } else {
+                    symbols.into_iter().filter(|symbol| halted.contains(symbol)).collect()
+                };
+                match router.unfreeze_symbols(&symbols) {
+                    Ok(()) => {
+                        symbols.iter().for_each(|symbol| { halted.remove(symbol); });
+                        format!("ok resumed {}", symbols.len())
+                    }
+                    Err(error) => format!("error: {error}"),
+                }
+            }
+            AdminCommand::Stats => {
+                let stats = router.session_stats();
+                let readiness = router.readiness();
+                format!(
+                    "ok sequence={} session={:?} routed={} rejected={} symbols={} halted={} ready={}",
+                    router.sequence(),
+                    router.session_state(),
+                    stats.orders_routed,
+                    stats.orders_rejected,
+                    router.get_symbols().len(),
+                    halted.len(),
+                    readiness.is_ready(),
+                )
+            }
+            AdminCommand::Depth { symbol, levels } => {
+                let Some(depth) = router.get_depth(symbol, levels) else {
+                    return "error: Invalid symbol".into();
+                };
+                let mut reply = String::new();
+                for (side, level) in depth.asks.iter().rev().map(|level| ("ask", level)).chain(depth.bids.iter().map(|level| ("bid", level))) {
+                    let _ = writeln!(reply, "{side} {} {} {}", level.price, level.total_quantity, level.order_count);
+                }
+                reply.push_str("ok");
+                reply
+            }
+            AdminCommand::CancelAll(symbol) => {
+                if symbol.is_some_and(|symbol| !router.supports_symbol(symbol)) {
+                    return "error: Invalid symbol".into();
+                }
+                format!("ok cancelled {}", router.cancel_all(symbol).len())
+            }
+            AdminCommand::Drain(symbol) => {
+                if !router.supports_symbol(symbol) {
+                    return "error: Invalid symbol".into();
+                }
+                // Lift the shell's own halt so a later book for the symbol starts open
+                if halted.remove(&symbol) {
+                    let _ = router.unfreeze_symbols(&[symbol]);
+                }
+                format!("ok drained {}", router.drain_symbol(symbol).len())
+            }
+            AdminCommand::ReloadConfig(path) => match AdminConfig::read(&path) {
+                Ok(config) => format!("ok reloaded {}", config.apply(&mut router)),
+                Err(error) => format!("error: {error}"),
+            },
+            AdminCommand::SnapshotNow => match write_snapshot_to(&router, self.storage.as_ref()) {
+                Ok(key) => format!("ok {key}"),
+                Err(error) => format!("error: {error}"),
+            },
+            AdminCommand::Auth(_) => "error: auth is handled per session".into(),
+            AdminCommand::Help => HELP.into(),
+            AdminCommand::Quit => "ok bye".into(),
+        }
+    }
+}
+
+/// Operator shell on a local unix socket. Each connection sends one command per line
+/// and gets the reply back, ending in a line that starts with `ok` or `error`. Attach with e.g. `nc -U <path>` or `socat - UNIX-CONNECT:<path>`.
+pub struct AdminShell {
+    path: PathBuf,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AdminShell {
+    /// Listens on `path`, replacing a stale socket left by an earlier process.
+    /// `snapshot-now` writes into `storage`. Every session may run every command, so
+    /// restrict the socket's file permissions or use `start_with_access`.
+    pub fn start(router: Arc<Mutex<OrderRouter>>, path: impl AsRef<Path>, storage: Arc<dyn Storage>) -> io::Result<Self> {
+        Self::start_with_access(router, path, storage, AdminAccess::open())
+    }
+
+    /// `start`, with sessions held to the roles in `access` and every command audited.
+    pub fn start_with_access(
+        router: Arc<Mutex<OrderRouter>>,
+        path: impl AsRef<Path>,
+        storage: Arc<dyn Storage>,
+        access: AdminAccess,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        match std::fs::remove_file(&path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(ShellState::new(router, storage, access));
+
+        let accept_shutdown = shutdown.clone();
+        let handle = thread::Builder::new()
+            .name("order-book-admin".into())
+            .spawn(move || {
+                let mut sessions = Vec::new();
+                while !accept_shutdown.load(Ordering::Acquire) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            let (state, shutdown) = (state.clone(), accept_shutdown.clone());
+                            sessions.extend(thread::Builder::new()
+                                .name("order-book-admin-session".into())
+                                .spawn(move || {
+                                    let _ = serve(stream, &state, &shutdown);
+                                })
+                                .ok());
+                        }
+                        Err(error) if error.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                        Err(_) => break,
+                    }
+                    sessions.retain(|session: &JoinHandle<()>| !session.is_finished());
+                }
+                sessions.into_iter().for_each(|session| { let _ = session.join(); });
+            })?;
+
+        Ok(Self { path, shutdown, handle: Some(handle) })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Closes open sessions, stops listening and removes the socket file.
+    pub fn stop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl Drop for AdminShell {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn serve(stream: UnixStream, state: &ShellState, shutdown: &AtomicBool) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let mut session = Session { principal: None, role: state.access.anonymous_role() };
+    while !shutdown.load(Ordering::Acquire) {
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                if line.trim().is_empty() {
+                    line.clear();
+                    continue;
+                }
+                let command = AdminCommand::parse(&line);
+                let quit = command == Ok(AdminCommand::Quit);
+                let reply = match command {
+                    Ok(command) => state.respond(&mut session, &line, command),
+                    Err(error) => state.refuse(&session, &line, &error),
+                };
+                line.clear();
+                writeln!(writer, "{reply}")?;
+                if quit {
+                    break;
+                }
+            }
+            // A timeout keeps any partial line in `line` and checks for shutdown
+            Err(error) if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::OrderBookType;
+    use crate::storage::MemoryStorage;
+    use crate::types::order::{new_order, OrderSide};
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(AdminCommand::parse("halt"), Ok(AdminCommand::Halt(vec![])));
+        assert_eq!(AdminCommand::parse(" resume 1 2\n"), Ok(AdminCommand::Resume(vec![1, 2])));
+        assert_eq!(AdminCommand::parse("depth 3"), Ok(AdminCommand::Depth { symbol: 3, levels: DEFAULT_DEPTH_LEVELS }));
+        assert_eq!(AdminCommand::parse("depth 3 10"), Ok(AdminCommand::Depth { symbol: 3, levels: 10 }));
+        assert_eq!(AdminCommand::parse("cancel-all 4"), Ok(AdminCommand::CancelAll(Some(4))));
+        assert_eq!(AdminCommand::parse("drain 2"), Ok(AdminCommand::Drain(2)));
+        assert_eq!(AdminCommand::parse("reload-config /etc/book.json"), Ok(AdminCommand::ReloadConfig("/etc/book.json".into())));
+        assert!(AdminCommand::parse("drain").is_err());
+        assert!(AdminCommand::parse("reload-config").is_err());
+        assert_eq!(AdminCommand::parse("auth t0k3n"), Ok(AdminCommand::Auth("t0k3n".into())));
+        assert!(AdminCommand::parse("auth").is_err());
+        assert!(AdminCommand::parse("depth").is_err());
+        assert!(AdminCommand::parse("halt x").is_err());
+        assert!(AdminCommand::parse("stats now").is_err());
+        assert!(AdminCommand::parse("reboot").is_err());
+    }
+
+    /// Opens a session and returns a function sending one command and reading its reply.
+    fn connect(path: &Path) -> impl FnMut(&str) -> Vec<String> {
+        let stream = UnixStream::connect(path).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        move |command: &str| {
+            writeln!(&stream, "{command}").unwrap();
+            let mut reply = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let done = line.starts_with("ok") || line.starts_with("error");
+                reply.push(line.trim_end().to_string());
+                if done {
+                    return reply;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shell_drives_running_router() {
+        let path = std::env::temp_dir().join(format!("rust-order-book-admin-{}.sock", std::process::id()));
+        let router = Arc::new(Mutex::new(OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap)));
+        let storage = Arc::new(MemoryStorage::new());
+        let mut shell = AdminShell::start(router.clone(), &path, storage.clone()).unwrap();
+        router.lock().unwrap().route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.lock().unwrap().route_order(new_order(2, 0, 5, 101.0, OrderSide::Sell)).unwrap();
+
+        let mut send = connect(&path);
+
+        assert_eq!(send("depth 0"), vec!["ask 101000 5 1", "bid 100000 10 1", "ok"]);
+        assert_eq!(send("halt 0"), vec!["ok halted 1"]);
+        assert!(router.lock().unwrap().route_order(new_order(3, 0, 5, 99.0, OrderSide::Buy)).is_err());
+        assert_eq!(send("resume"), vec!["ok resumed 1"]);
+        assert!(!router.lock().unwrap().is_frozen(0));
+        assert_eq!(send("cancel-all 0"), vec!["ok cancelled 2"]);
+        assert!(send("stats")[0].starts_with("ok sequence=3 session=Open routed=2 rejected=1"));
+        assert!(send("snapshot-now")[0].starts_with("ok snapshot-"));
+        assert_eq!(storage.list("snapshot-").unwrap().len(), 1);
+        assert_eq!(send("bogus"), vec!["error: unknown command 'bogus'"]);
+        assert_eq!(send("quit"), vec!["ok bye"]);
+
+        shell.stop();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_shell_enforces_roles_and_audits() {
+        let path = std::env::temp_dir().join(format!("rust-order-book-admin-rbac-{}.sock", std::process::id()));
+        let router = Arc::new(Mutex::new(OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap)));
+        router.lock().unwrap().route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        let (audit, audit_trail) = crossbeam::channel::unbounded();
+        let access = AdminAccess::default()
+            .with_token("view-token", "dashboard", AdminRole::Viewer)
+            .with_token("ops-token", "on-call", AdminRole::Operator)
+            .with_audit(audit);
+        let mut shell = AdminShell::start_with_access(router.clone(), &path, Arc::new(MemoryStorage::new()), access).unwrap();
+
+        let mut viewer = connect(&path);
+        assert_eq!(viewer("stats"), vec!["error: not authenticated, send auth <token>"]);
+        assert_eq!(viewer("auth wrong"), vec!["error: invalid token"]);
+        assert_eq!(viewer("auth view-token"), vec!["ok authenticated dashboard as viewer"]);
+        assert_eq!(viewer("depth 0"), vec!["bid 100000 10 1", "ok"]);
+        assert_eq!(viewer("halt 0"), vec!["error: requires the operator role"]);
+        assert!(!router.lock().unwrap().is_frozen(0));
+
+        let mut operator = connect(&path);
+        operator("auth ops-token");
+        assert_eq!(operator("halt 0"), vec!["ok halted 1"]);
+        assert_eq!(operator("cancel-all"), vec!["error: requires the admin role"]);
+        assert_eq!(operator("stats now"), vec!["error: stats takes no arguments"]);
+        assert_eq!(operator("auth ops-token again"), vec!["error: usage: auth <token>"]);
+        assert_eq!(router.lock().unwrap().get_depth(0, 1).unwrap().bids.len(), 1);
+        shell.stop();
+
+        let entries: Vec<_> = audit_trail.try_iter().map(|entry| (entry.principal, entry.command, entry.status)).collect();
+        let expected = [
+            (None, "stats", "error: not authenticated, send auth <token>"),
+            (None, "auth", "error: invalid token"),
+            (Some("dashboard"), "auth", "ok authenticated dashboard as viewer"),
+            (Some("dashboard"), "depth 0", "ok"),
+            (Some("dashboard"), "halt 0", "error: requires the operator role"),
+            (Some("on-call"), "auth", "ok authenticated on-call as operator"),
+            (Some("on-call"), "halt 0", "ok halted 1"),
+            (Some("on-call"), "cancel-all", "error: requires the admin role"),
+            (Some("on-call"), "stats now", "error: stats takes no arguments"),
+            (Some("on-call"), "auth", "error: usage: auth <token>"),
+        ];
+        let expected: Vec<_> = expected.iter().map(|(principal, command, status)| (principal.map(str::to_string), command.to_string(), status.to_string())).collect();
+        assert_eq!(entries, expected);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod access;
+pub mod admin_http;
+pub mod admin_shell;
+
+pub use access::{AdminAccess, AdminAuditEntry, AdminPrincipal, AdminRole};
+pub use admin_http::AdminHttp;
+pub use admin_shell::{AdminCommand, AdminConfig, AdminShell};
@@ -0,0 +1,3 @@
+pub mod stdin_stream;
+
+pub use stdin_stream::{StreamCommand, StreamEvent};
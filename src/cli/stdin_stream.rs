@@ -0,0 +1,242 @@
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::router::{OrderRouter, RejectCode};
+use crate::types::order::{OrderSide, ParticipantId};
+use crate::types::price_scale::PriceScale;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// One input line of the `--stdin` stream, either CSV (`add,1,0,buy,100,150.25`) or a
+/// JSON object tagged by `cmd` (`{"cmd":"add","id":1,"symbol":0,"side":"buy",...}`).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum StreamCommand {
+    /// `add,<id>,<symbol>,<buy|sell>,<quantity>,<price>[,<participant>]`
+    Add {
+        id: u64,
+        symbol: SymbolId,
+        #[serde(deserialize_with = "deserialize_side")]
+        side: OrderSide,
+        quantity: u64,
+        price: f64,
+        #[serde(default)]
+        participant: ParticipantId,
+    },
+    /// `cancel,<id>[,<id>..]`
+    Cancel { ids: Vec<u64> },
+    /// `reduce,<symbol>,<id>,<quantity>`
+    Reduce { symbol: SymbolId, id: u64, quantity: u64 },
+    /// `match`: runs a matching cycle on every book.
+    Match,
+    /// `best,<symbol>`
+    Best { symbol: SymbolId },
+}
+
+/// JSON lines written to stdout: one per command, except `match`, which writes one
+/// `trade` line per execution. Prices are decimals at the symbol's `PriceScale`, like
+/// the prices `add` takes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum StreamEvent {
+    Ack { line: usize, id: u64 },
+    /// `code` is the numeric `RejectCode`; `reason` is for people.
+    Reject { line: usize, code: u16, reason: String },
+    Cancelled { line: usize, ids: Vec<u64>, not_found: Vec<u64> },
+    Reduced { line: usize, id: u64, quantity: u64 },
+    Trade {
+        line: usize,
+        symbol: SymbolId,
+        taker_order_id: u64,
+        maker_order_id: u64,
+        price: f64,
+        quantity: u64,
+        side: OrderSide,
+    },
+    Best { line: usize, symbol: SymbolId, bid: Option<f64>, ask: Option<f64> },
+}
+
+impl StreamEvent {
+    fn trade(line: usize, trade: &Trade, scale: PriceScale) -> Self {
+        StreamEvent::Trade {
+            line,
+            symbol: trade.symbol,
+            taker_order_id: trade.taker_order_id,
+            maker_order_id: trade.maker_order_id,
+            price: scale.to_price(trade.price),
+            quantity: trade.quantity,
+            side: trade.side,
+        }
+    }
+}
+
+fn parse_side(side: &str) -> Result<OrderSide, String> {
+    match side.to_ascii_lowercase().as_str() {
+        "buy" | "b" => Ok(OrderSide::Buy),
+        "sell" | "s" => Ok(OrderSide::Sell),
+        _ => Err(format!("invalid side '{side}'")),
+    }
+}
+
+fn deserialize_side<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<OrderSide, D::Error> {
+    let side = String::deserialize(deserializer)?;
+    parse_side(&side).map_err(serde::de::Error::custom)
+}
+
+impl StreamCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        if line.starts_with('{') {
+            return serde_json::from_str(line).map_err(|error| error.to_string());
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        fn number<T: std::str::FromStr>(field: &str, what: &str) -> Result<T, String> {
+            field.parse().map_err(|_| format!("invalid {what} '{field}'"))
+        }
+        match fields[..] {
+            ["add", id, symbol, side, quantity, price] | ["add", id, symbol, side, quantity, price, _] => Ok(StreamCommand::Add {
+                id: number(id, "id")?,
+                symbol: number(symbol, "symbol")?,
+                side: parse_side(side)?,
+                quantity: number(quantity, "quantity")?,
+                price: number(price, "price")?,
+                participant: fields.get(6).map_or(Ok(0), |participant| number(participant, "participant"))?,
+            }),
+            ["add", ..] => Err("usage: add,<id>,<symbol>,<buy|sell>,<quantity>,<price>[,<participant>]".into()),
+            ["cancel", ref ids @ ..] if !ids.is_empty() => Ok(StreamCommand::Cancel {
+                ids: ids.iter().map(|id| number(id, "id")).collect::<Result<_, _>>()?,
+            }),
+            ["cancel", ..] => Err("usage: cancel,<id>[,<id>..]".into()),
+            ["reduce", symbol, id, quantity] => Ok(StreamCommand::Reduce {
+                symbol: number(symbol, "symbol")?,
+                id: number(id, "id")?,
+                quantity: number(quantity, "quantity")?,
+            }),
+            ["reduce", ..] => Err("usage: reduce,<symbol>,<id>,<quantity>".into()),
+            ["match"] => Ok(StreamCommand::Match),
+            ["best", symbol] => Ok(StreamCommand::Best { symbol: number(symbol, "symbol")? }),
+            ["best", ..] => Err("usage: best,<symbol>".into()),
+            _ => Err(format!("unknown command '{}'", fields[0])),
+        }
+    }
+}
+
+/// Applies one command to the router and returns the events it produced.
+pub fn apply(router: &mut OrderRouter, line: usize, command: StreamCommand) -> Vec<StreamEvent> {
+    let reject = |reason: &str| vec![StreamEvent::Reject { line, code: RejectCode::from_reason(reason).code(), reason: reason.to_string() }];
+    match command {
+        StreamCommand::Add { id, symbol, side, quantity, price, participant } => {
+            let order = router.price_scale(symbol).order(id, symbol, quantity, price, side);
+            match router.route_order(order.with_participant(participant)) {
+                Ok(()) => vec![StreamEvent::Ack { line, id }],
+                Err(error) => reject(error),
+            }
+        }
+        StreamCommand::Cancel { ids } => {
+            let result = router.cancel_orders_batch(&ids);
+            vec![StreamEvent::Cancelled {
+                line,
+                ids: result.cancelled.iter().map(|order| order.id).collect(),
+                not_found: result.not_found,
+            }]
+        }
+        StreamCommand::Reduce { symbol, id, quantity } => match router.reduce_order(symbol, id, quantity) {
+            Ok(order) => vec![StreamEvent::Reduced { line, id, quantity: order.quantity }],
+            Err(error) => reject(error),
+        },
+        StreamCommand::Match => router.match_all_orders().iter()
+            .map(|trade| StreamEvent::trade(line, trade, router.price_scale(trade.symbol)))
+            .collect(),
+        StreamCommand::Best { symbol } => match router.get_best_prices(symbol) {
+            Some((bid, ask)) => {
+                let scale = router.price_scale(symbol);
+                vec![StreamEvent::Best { line, symbol, bid: bid.map(|price| scale.to_price(price)), ask: ask.map(|price| scale.to_price(price)) }]
+            }
+            None => reject("Invalid symbol"),
+        },
+    }
+}
+
+/// Reads commands line by line until end of input, applying each in order and writing
+/// its events as JSON lines. Blank lines and lines starting with `#` are skipped; a line
+/// that does not parse is answered with a `reject` event rather than ending the stream.
+pub fn run(router: &mut OrderRouter, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let line_number = index + 1;
+        let events = match StreamCommand::parse(trimmed) {
+            Ok(command) => apply(router, line_number, command),
+            Err(reason) => vec![StreamEvent::Reject { line: line_number, code: RejectCode::InvalidRequest.code(), reason }],
+        };
+        for event in events {
+            serde_json::to_writer(&mut output, &event)?;
+            output.write_all(b"\n")?;
+        }
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashSet;
+    use crate::engine::OrderBookType;
+
+    #[test]
+    fn test_parse_csv_and_json() {
+        let csv = StreamCommand::parse("add,1,0,buy,100,150.25").unwrap();
+        let json = StreamCommand::parse(r#"{"cmd":"add","id":1,"symbol":0,"side":"Buy","quantity":100,"price":150.25}"#).unwrap();
+        assert_eq!(csv, json);
+        assert_eq!(StreamCommand::parse("cancel,3,4"), Ok(StreamCommand::Cancel { ids: vec![3, 4] }));
+        assert_eq!(StreamCommand::parse(r#"{"cmd":"match"}"#), Ok(StreamCommand::Match));
+        assert!(StreamCommand::parse("add,1,0,hold,100,150").is_err());
+        assert!(StreamCommand::parse("launch").is_err());
+    }
+
+    #[test]
+    fn test_run_streams_events() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        let input = "\
+# opening orders
+add,1,0,buy,10,100
+{\"cmd\":\"add\",\"id\":2,\"symbol\":0,\"side\":\"sell\",\"quantity\":10,\"price\":100}
+add,3,7,buy,10,100
+match
+cancel,1,9
+best,0
+";
+        let mut output = Vec::new();
+        run(&mut router, input.as_bytes(), &mut output).unwrap();
+        let lines: Vec<_> = String::from_utf8(output).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(lines, vec![
+            r#"{"event":"ack","line":2,"id":1}"#,
+            r#"{"event":"ack","line":3,"id":2}"#,
+            r#"{"event":"reject","line":4,"code":1,"reason":"Invalid symbol"}"#,
+            r#"{"event":"trade","line":5,"symbol":0,"taker_order_id":2,"maker_order_id":1,"price":100.0,"quantity":10,"side":"Sell"}"#,
+            r#"{"event":"cancelled","line":6,"ids":[],"not_found":[1,9]}"#,
+            r#"{"event":"best","line":7,"symbol":0,"bid":null,"ask":null}"#,
+        ]);
+    }
+
+    #[test]
+    fn test_run_uses_symbol_price_scale() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.set_price_scale(0, PriceScale::new(6).unwrap());
+        let input = "add,1,0,buy,10,0.000125\nadd,2,0,sell,4,0.000125\nadd,3,0,sell,5,0.00013\nmatch\nbest,0\n";
+        let mut output = Vec::new();
+        run(&mut router, input.as_bytes(), &mut output).unwrap();
+        let lines: Vec<_> = String::from_utf8(output).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(router.get_best_prices(0), Some((Some(125), Some(130))));
+        assert_eq!(lines[3..], [
+            r#"{"event":"trade","line":4,"symbol":0,"taker_order_id":2,"maker_order_id":1,"price":0.000125,"quantity":4,"side":"Sell"}"#,
+            r#"{"event":"best","line":5,"symbol":0,"bid":0.000125,"ask":0.00013}"#,
+        ]);
+    }
+}
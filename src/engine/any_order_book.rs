@@ -0,0 +1,244 @@
+use rustc_hash::FxHashSet;
+
+use crate::engine::order_book_trait::{OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData};
+use crate::engine::{
+    ArrayQueueOrderBook, BookDepth, FixedCapacityOrderBook, HashMapOrderBook, LevelChange, LotSizes, MatchStats, OrderBookType, ParticipantLimits, PersistentOrderBook, PriceLevelIter,
+    PriorityQueueOrderBook, TickSizes, TieBreak,
+};
+use crate::types::{order::{Order, OrderSide}, symbol_mapping::SymbolId, trade::{Trade, TradePriceRule}};
+
+/// Closed set of the built-in books. Dispatch is a `match` the compiler can inline
+/// through, where `Box<dyn OrderBookTrait>` pays an indirect call per operation.
+pub enum AnyOrderBook {
+    HashMap(HashMapOrderBook),
+    PriorityQueue(PriorityQueueOrderBook),
+    ArrayQueue(ArrayQueueOrderBook),
+    Persistent(PersistentOrderBook),
+    FixedCapacity(FixedCapacityOrderBook),
+}
+
+macro_rules! delegate {
+    ($self:ident, $book:ident => $body:expr) => {
+        match $self {
+            AnyOrderBook::HashMap($book) => $body,
+            AnyOrderBook::PriorityQueue($book) => $body,
+            AnyOrderBook::ArrayQueue($book) => $body,
+            AnyOrderBook::Persistent($book) => $body,
+            AnyOrderBook::FixedCapacity($book) => $body,
+        }
+    };
+}
+
+impl AnyOrderBook {
+    pub fn with_type(order_book_type: OrderBookType, symbols: FxHashSet<SymbolId>) -> Self {
+        match order_book_type {
+            OrderBookType::HashMap => AnyOrderBook::HashMap(HashMapOrderBook::new(symbols)),
+            OrderBookType::PriorityQueue => AnyOrderBook::PriorityQueue(PriorityQueueOrderBook::new(symbols)),
+            OrderBookType::ArrayQueue => AnyOrderBook::ArrayQueue(ArrayQueueOrderBook::new(symbols)),
+            OrderBookType::Persistent => AnyOrderBook::Persistent(PersistentOrderBook::new(symbols)),
+            OrderBookType::FixedCapacity => AnyOrderBook::FixedCapacity(FixedCapacityOrderBook::new(symbols)),
+        }
+    }
+}
+
+impl OrderBookCore for AnyOrderBook {
+    /// Builds the default book type; use `with_type` to pick another.
+    fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        Self::with_type(OrderBookType::default(), symbols)
+    }
+
+    #[inline(always)]
+    fn add_order(&mut self, order: Order) -> Result<bool, OrderBookError> {
+        delegate!(self, book => book.add_order(order))
+    }
+
+    #[inline(always)]
+    fn add_order_fast(&mut self, order: Order) -> bool {
+        delegate!(self, book => book.add_order_fast(order))
+    }
+
+    #[inline(always)]
+    unsafe fn add_order_unchecked(&mut self, order: Order) {
+        delegate!(self, book => unsafe { book.add_order_unchecked(order) })
+    }
+
+    #[inline(always)]
+    fn match_orders(&mut self) -> Vec<Trade> {
+        delegate!(self, book => book.match_orders())
+    }
+
+    #[inline(always)]
+    fn add_orders_batch_fast(&mut self, orders: &[Order]) -> (u32, u32) {
+        delegate!(self, book => book.add_orders_batch_fast(orders))
+    }
+
+    #[inline(always)]
+    unsafe fn add_orders_batch_unchecked(&mut self, orders: &[Order]) -> u32 {
+        delegate!(self, book => unsafe { book.add_orders_batch_unchecked(orders) })
+    }
+
+    #[inline(always)]
+    fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
+        delegate!(self, book => book.get_best_prices(symbol))
+    }
+
+    #[inline(always)]
+    fn can_match(&self, symbol: SymbolId) -> bool {
+        delegate!(self, book => book.can_match(symbol))
+    }
+
+    #[inline(always)]
+    fn is_valid_symbol(&self, symbol: SymbolId) -> bool {
+        delegate!(self, book => book.is_valid_symbol(symbol))
+    }
+
+    #[inline(always)]
+    fn get_symbols(&self) -> &FxHashSet<SymbolId> {
+        delegate!(self, book => book.get_symbols())
+    }
+
+    #[inline(always)]
+    fn order_book_type(&self) -> OrderBookType {
+        delegate!(self, book => book.order_book_type())
+    }
+}
+
+impl OrderBookLifecycle for AnyOrderBook {
+    fn resting_orders(&self, symbol: SymbolId) -> Option<Vec<Order>> {
+        delegate!(self, book => book.resting_orders(symbol))
+    }
+
+    #[inline(always)]
+    fn set_trade_price_rule(&mut self, rule: TradePriceRule) {
+        delegate!(self, book => book.set_trade_price_rule(rule))
+    }
+
+    #[inline(always)]
+    fn trade_price_rule(&self) -> TradePriceRule {
+        delegate!(self, book => book.trade_price_rule())
+    }
+
+    fn set_tie_break(&mut self, tie_break: TieBreak) {
+        delegate!(self, book => book.set_tie_break(tie_break))
+    }
+
+    #[inline(always)]
+    fn tie_break(&self) -> TieBreak {
+        delegate!(self, book => book.tie_break())
+    }
+
+    fn set_participant_limits(&mut self, limits: ParticipantLimits) {
+        delegate!(self, book => book.set_participant_limits(limits))
+    }
+
+    #[inline(always)]
+    fn participant_limits(&self) -> ParticipantLimits {
+        delegate!(self, book => book.participant_limits())
+    }
+
+    fn set_tick_sizes(&mut self, tick_sizes: TickSizes) {
+        delegate!(self, book => book.set_tick_sizes(tick_sizes))
+    }
+
+    #[inline(always)]
+    fn tick_sizes(&self) -> &TickSizes {
+        delegate!(self, book => book.tick_sizes())
+    }
+
+    fn set_lot_sizes(&mut self, lot_sizes: LotSizes) {
+        delegate!(self, book => book.set_lot_sizes(lot_sizes))
+    }
+
+    #[inline(always)]
+    fn lot_sizes(&self) -> &LotSizes {
+        delegate!(self, book => book.lot_sizes())
+    }
+
+    fn set_max_depth(&mut self, max_levels: Option<usize>) {
+        delegate!(self, book => book.set_max_depth(max_levels))
+    }
+
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        delegate!(self, book => book.max_depth())
+    }
+
+    fn take_evicted_orders(&mut self) -> Vec<Order> {
+        delegate!(self, book => book.take_evicted_orders())
+    }
+
+    fn set_level_journal(&mut self, enabled: bool) {
+        delegate!(self, book => book.set_level_journal(enabled))
+    }
+
+    #[inline(always)]
+    fn level_journal(&self) -> bool {
+        delegate!(self, book => book.level_journal())
+    }
+
+    fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>> {
+        delegate!(self, book => book.take_level_changes(symbol))
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Result<Order, OrderBookError> {
+        delegate!(self, book => book.reduce_order(order_id, quantity_delta))
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool) -> Vec<Order> {
+        delegate!(self, book => book.cancel_where(predicate))
+    }
+
+    fn cancel_order(&mut self, symbol: SymbolId, order_id: u64) -> Result<Order, OrderBookError> {
+        delegate!(self, book => book.cancel_order(symbol, order_id))
+    }
+
+    fn cancel_orders(&mut self, order_ids: &FxHashSet<u64>) -> Vec<Order> {
+        delegate!(self, book => book.cancel_orders(order_ids))
+    }
+
+    fn take_expired_orders(&mut self, now: u64) -> Vec<Order> {
+        delegate!(self, book => book.take_expired_orders(now))
+    }
+}
+
+impl OrderBookMarketData for AnyOrderBook {
+    fn price_levels(&self, symbol: SymbolId, side: OrderSide, with_order_ids: bool) -> Option<PriceLevelIter<'_>> {
+        delegate!(self, book => book.price_levels(symbol, side, with_order_ids))
+    }
+
+    fn get_depth(&self, symbol: SymbolId, levels: usize) -> Option<BookDepth> {
+        delegate!(self, book => book.get_depth(symbol, levels))
+    }
+
+    #[inline(always)]
+    fn last_trade_price(&self, symbol: SymbolId) -> Option<u64> {
+        delegate!(self, book => book.last_trade_price(symbol))
+    }
+
+    #[inline(always)]
+    fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats> {
+        delegate!(self, book => book.last_match_stats(symbol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    fn test_any_order_book_delegates_to_variant() {
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = AnyOrderBook::with_type(order_book_type, FxHashSet::from_iter([0]));
+            assert_eq!(order_book.order_book_type(), order_book_type);
+
+            assert!(order_book.add_order_fast(new_order(1, 0, 100, 101.0, OrderSide::Buy)));
+            assert!(order_book.add_order_fast(new_order(2, 0, 100, 100.0, OrderSide::Sell)));
+            assert!(order_book.add_order_fast(new_order(3, 0, 100, 99.0, OrderSide::Buy)));
+            order_book.match_orders();
+
+            assert!(order_book.last_trade_price(0).is_some(), "{order_book_type}");
+            assert_eq!(order_book.resting_orders(0).unwrap().len(), 1, "{order_book_type}");
+        }
+    }
+}
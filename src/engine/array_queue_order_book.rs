@@ -1,97 +1,287 @@
 use crossbeam::queue::ArrayQueue;
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_hash::FxHashSet;
 
-use crate::engine::order_book_trait::{OrderBookTrait, OrderBookError};
+use crate::engine::order_book_trait::{OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData};
 use crate::engine::OrderBookType;
-use crate::types::{order::Order, symbol_mapping::SymbolId};
+use crate::engine::price_level_view::{self, BookDepth, PriceLevelIter};
+use crate::engine::participant_limits::{ParticipantLimits, ParticipantOrderCounts};
+use crate::engine::lot_size::LotSizes;
+use crate::engine::symbol_map::{FxMaps, IdMap, MapBackend, SymbolMap};
+use crate::engine::tick_size::TickSizes;
+use crate::engine::depth_limit::DepthLimit;
+use crate::engine::match_stats::{self, MatchCycle, MatchStats, TakeOnArrival};
+use crate::engine::level_journal::{LevelChange, LevelJournal};
+use crate::engine::tie_break::TieBreak;
+use crate::engine::reduction;
+use crate::engine::lazy_matchers;
+use crate::types::{order::{Order, OrderSide, OrderFlags}, symbol_mapping::SymbolId, trade::{Trade, TradePriceRule}};
 
 const DEFAULT_QUEUE_SIZE: usize = 4096;
 
 #[derive(Debug)]
-struct ArrayQueueMatcher {
-    bids: Arc<ArrayQueue<Order>>,
-    asks: Arc<ArrayQueue<Order>>,
+struct ArrayQueueMatcher<B: MapBackend> {
+    // Order ids in arrival order; the orders themselves live in `orders`
+    bids: Arc<ArrayQueue<u64>>,
+    asks: Arc<ArrayQueue<u64>>,
+    orders: B::Ids<Order>,
+    // Ids cancelled while still queued, dropped when they are next popped. They hold
+    // queue slots, so a full queue is compacted before anything is refused
+    tombstones: FxHashSet<u64>,
+    // Resting orders per price, so best prices never rotate a queue
+    bid_levels: BTreeMap<u64, usize>,
+    ask_levels: BTreeMap<u64, usize>,
     best_bid: Option<u64>,
     best_ask: Option<u64>,
+    last_trade_price: Option<u64>,
+    participants: ParticipantOrderCounts,
+    depth: DepthLimit,
+    evicted: Vec<Order>,
+    arrival_trades: Vec<Trade>,
+    match_stats: Option<MatchStats>,
+    level_changes: LevelJournal,
 }
 
-impl ArrayQueueMatcher {
+impl<B: MapBackend> ArrayQueueMatcher<B> {
     fn new() -> Self {
         Self {
             bids: Arc::new(ArrayQueue::new(DEFAULT_QUEUE_SIZE)),
             asks: Arc::new(ArrayQueue::new(DEFAULT_QUEUE_SIZE)),
+            orders: Default::default(),
+            tombstones: FxHashSet::default(),
+            bid_levels: BTreeMap::new(),
+            ask_levels: BTreeMap::new(),
             best_bid: None,
             best_ask: None,
+            last_trade_price: None,
+            participants: ParticipantOrderCounts::default(),
+            depth: DepthLimit::default(),
+            evicted: Vec::new(),
+            arrival_trades: Vec::new(),
+            match_stats: None,
+            level_changes: LevelJournal::default(),
+        }
+    }
+
+    #[inline(always)]
+    fn queue(&self, side: OrderSide) -> &ArrayQueue<u64> {
+        match side {
+            OrderSide::Buy => &self.bids,
+            OrderSide::Sell => &self.asks,
         }
     }
 
     #[inline(always)]
     fn add_order(&mut self, order: Order) -> bool {
-        match order.order_type {
-            crate::types::order::OrderSide::Buy => {
-                let price = order.price;
-                if self.bids.push(order).is_ok() {
-                    self.best_bid = Some(self.best_bid.map_or(price, |current| current.max(price)));
-                    true
-                } else {
-                    false
-                }
+        let (side, price, quantity) = (order.order_type, order.price, order.quantity);
+        // A tombstone would also hide the new entry, so clear it out first
+        if self.tombstones.contains(&order.id) || self.queue(side).is_full() {
+            self.compact();
+        }
+        if self.queue(side).push(order.id).is_err() {
+            return false;
+        }
+        match side {
+            OrderSide::Buy => self.best_bid = Some(self.best_bid.map_or(price, |current| current.max(price))),
+            OrderSide::Sell => self.best_ask = Some(self.best_ask.map_or(price, |current| current.min(price))),
+        }
+        self.participants.record(&order);
+        self.level_changes.record(side, price, quantity);
+        self.rest(order);
+        if let Some(far_price) = self.depth.record(side, price) {
+            self.evict_level(side, far_price);
+        }
+        true
+    }
+
+    #[inline(always)]
+    unsafe fn add_order_unchecked(&mut self, order: Order) {
+        let (side, price) = (order.order_type, order.price);
+        if self.tombstones.contains(&order.id) || self.queue(side).is_full() {
+            self.compact();
+        }
+        self.participants.record(&order);
+        self.level_changes.record(side, price, order.quantity);
+        match side {
+            OrderSide::Buy => self.best_bid = Some(self.best_bid.map_or(price, |current| current.max(price))),
+            OrderSide::Sell => self.best_ask = Some(self.best_ask.map_or(price, |current| current.min(price))),
+        }
+        // Compacted, so whatever a full queue pushes out is a resting order
+        if let Some(evicted) = self.queue(side).force_push(order.id).and_then(|id| self.unrest(id)) {
+            self.participants.release(&evicted);
+            self.depth.release(&evicted);
+            self.level_changes.release(&evicted);
+        }
+        self.rest(order);
+        if let Some(far_price) = self.depth.record(side, price) {
+            self.evict_level(side, far_price);
+        }
+    }
+
+    /// Indexes a queued order and counts it at its price. Leaves the cached best to
+    /// the caller.
+    #[inline(always)]
+    fn rest(&mut self, order: Order) {
+        let levels = match order.order_type {
+            OrderSide::Buy => &mut self.bid_levels,
+            OrderSide::Sell => &mut self.ask_levels,
+        };
+        *levels.entry(order.price).or_default() += 1;
+        self.orders.insert(order.id, order);
+    }
+
+    /// Takes an order out of the index and its price count. Its id stays queued, so
+    /// callers either drop it from the queue or tombstone it.
+    #[inline(always)]
+    fn unrest(&mut self, order_id: u64) -> Option<Order> {
+        let order = self.orders.remove(order_id)?;
+        let levels = match order.order_type {
+            OrderSide::Buy => &mut self.bid_levels,
+            OrderSide::Sell => &mut self.ask_levels,
+        };
+        if let Some(count) = levels.get_mut(&order.price) {
+            *count -= 1;
+            if *count == 0 {
+                levels.remove(&order.price);
             }
-            crate::types::order::OrderSide::Sell => {
-                let price = order.price;
-                if self.asks.push(order).is_ok() {
-                    self.best_ask = Some(self.best_ask.map_or(price, |current| current.min(price)));
-                    true
-                } else {
-                    false
+        }
+        Some(order)
+    }
+
+    // The cached best only moves outwards on adds and is rebuilt after a match or
+    // cancel, so a match cycle runs on the prices it started with
+    #[inline(always)]
+    fn recalculate_best_prices(&mut self) {
+        self.best_bid = self.bid_levels.last_key_value().map(|(&price, _)| price);
+        self.best_ask = self.ask_levels.first_key_value().map(|(&price, _)| price);
+    }
+
+    /// Rotates both queues once, dropping every tombstone.
+    fn compact(&mut self) {
+        for queue in [&self.bids, &self.asks] {
+            for _ in 0..queue.len() {
+                if let Some(id) = queue.pop() && !self.tombstones.remove(&id) {
+                    let _ = queue.push(id);
                 }
             }
         }
+        self.tombstones.clear();
     }
 
-    #[inline(always)]
-    unsafe fn add_order_unchecked(&mut self, order: Order) {
-        match order.order_type {
-            crate::types::order::OrderSide::Buy => {
-                let price = order.price;
-                let _ = self.bids.force_push(order);
-                self.best_bid = Some(self.best_bid.map_or(price, |current| current.max(price)));
+    fn evict_level(&mut self, side: OrderSide, price: u64) {
+        let queue = Arc::clone(match side {
+            OrderSide::Buy => &self.bids,
+            OrderSide::Sell => &self.asks,
+        });
+        for _ in 0..queue.len() {
+            let Some(id) = queue.pop() else {
+                break;
+            };
+            if self.tombstones.remove(&id) {
+                continue;
             }
-            crate::types::order::OrderSide::Sell => {
-                let price = order.price;
-                let _ = self.asks.force_push(order);
-                self.best_ask = Some(self.best_ask.map_or(price, |current| current.min(price)));
+            if self.orders.get(id).is_some_and(|order| order.price == price) && let Some(order) = self.unrest(id) {
+                self.participants.release(&order);
+                self.level_changes.release(&order);
+                self.evicted.push(order);
+            } else {
+                let _ = queue.push(id);
             }
         }
+        self.recalculate_best_prices();
     }
 
     #[inline(always)]
-    fn match_orders(&mut self) {
+    fn match_orders(&mut self, rule: TradePriceRule, trades: &mut Vec<Trade>) {
+        if !self.can_match_optimistic() {
+            return;
+        }
+        let mut cycle = MatchCycle::start();
+        let (mut bid_prices, mut ask_prices) = (Vec::new(), Vec::new());
         let mut matched_count = 0;
-        let max_matches = 100;
-        
-        for _ in 0..max_matches {
-            if !self.can_match_optimistic() {
+
+        // The per-price counts know the best prices, so each side's earliest order at its
+        // best price is taken out of the queue wherever it sits
+        while let (Some((&bid_price, _)), Some((&ask_price, _))) = (self.bid_levels.last_key_value(), self.ask_levels.first_key_value()) {
+            if bid_price < ask_price {
                 break;
             }
-            
-            match (self.bids.pop(), self.asks.pop()) {
-                (Some(bid_order), Some(ask_order)) => {
-                    if bid_order.price >= ask_order.price {
-                        matched_count += 1;
-                    } else {
-                        let _ = self.bids.push(bid_order);
-                        let _ = self.asks.push(ask_order);
-                        break;
-                    }
-                }
-                _ => break,
+            let bid = Self::take_first_at(&self.bids, &mut self.tombstones, &self.orders, bid_price);
+            let ask = Self::take_first_at(&self.asks, &mut self.tombstones, &self.orders, ask_price);
+            let (Some(bid_order), Some(ask_order)) = (bid.and_then(|id| self.orders.get(id).cloned()), ask.and_then(|id| self.orders.get(id).cloned())) else {
+                break;
+            };
+            self.last_trade_price = Some(cycle.record_trade(&bid_order, &ask_order, rule));
+            bid_prices.push(bid_order.price);
+            ask_prices.push(ask_order.price);
+            let quantity = bid_order.quantity.min(ask_order.quantity);
+            self.settle_fill(&bid_order, quantity);
+            self.settle_fill(&ask_order, quantity);
+            if bid_order.quantity > quantity {
+                Self::push_front(&self.bids, bid_order.id);
+            }
+            if ask_order.quantity > quantity {
+                Self::push_front(&self.asks, ask_order.id);
             }
+            matched_count += 1;
         }
-        
+
         if matched_count > 0 {
             self.recalculate_best_prices();
+            for (levels, mut prices) in [(&self.bid_levels, bid_prices), (&self.ask_levels, ask_prices)] {
+                prices.sort_unstable();
+                prices.dedup();
+                prices.iter().filter(|price| !levels.contains_key(price)).for_each(|_| cycle.level_cleared());
+            }
+        }
+        self.match_stats = Some(cycle.finish(trades));
+    }
+
+    /// Takes the earliest queued order at `price` out of `queue`, rotating the queue
+    /// once so everything else keeps its order. Tombstones passed on the way are dropped.
+    fn take_first_at(queue: &ArrayQueue<u64>, tombstones: &mut FxHashSet<u64>, orders: &B::Ids<Order>, price: u64) -> Option<u64> {
+        let mut taken = None;
+        for _ in 0..queue.len() {
+            let Some(id) = queue.pop() else { break };
+            if tombstones.remove(&id) {
+                continue;
+            }
+            if taken.is_none() && orders.get(id).is_some_and(|order| order.price == price) {
+                taken = Some(id);
+            } else {
+                let _ = queue.push(id);
+            }
+        }
+        taken
+    }
+
+    /// Books a fill of `quantity` against `order` as it stood before the trade.
+    #[inline(always)]
+    fn settle_fill(&mut self, order: &Order, quantity: u64) {
+        if quantity == order.quantity {
+            self.unrest(order.id);
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        } else {
+            if let Some(resting) = self.orders.get_mut(order.id) {
+                resting.quantity -= quantity;
+            }
+            self.level_changes.reduce(order, quantity);
+        }
+    }
+
+    /// Returns a partly filled order to the head of its queue by cycling everything
+    /// queued behind it. The order was just popped, so there is room for it.
+    fn push_front(queue: &ArrayQueue<u64>, order_id: u64) {
+        let behind = queue.len();
+        if queue.push(order_id).is_ok() {
+            for _ in 0..behind {
+                if let Some(id) = queue.pop() {
+                    let _ = queue.push(id);
+                }
+            }
         }
     }
 
@@ -103,12 +293,6 @@ impl ArrayQueueMatcher {
         }
     }
 
-    #[inline(always)]
-    fn recalculate_best_prices(&mut self) {
-        self.best_bid = None;
-        self.best_ask = None;
-    }
-
     #[inline(always)]
     fn get_best_prices(&self) -> (Option<u64>, Option<u64>) {
         (self.best_bid, self.best_ask)
@@ -138,28 +322,174 @@ impl ArrayQueueMatcher {
 
     #[inline(always)]
     fn is_empty(&self) -> bool {
-        self.bids.is_empty() && self.asks.is_empty()
+        self.orders.is_empty()
+    }
+
+    // Quantity plays no part in queue order, so only the order itself changes
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Option<Result<Order, OrderBookError>> {
+        let reduced = reduction::reduce(self.orders.get_mut(order_id)?, quantity_delta);
+        if let Ok(order) = &reduced {
+            self.level_changes.reduce(order, quantity_delta);
+        }
+        Some(reduced)
+    }
+
+    /// Removes the order and tombstones its queued id.
+    fn cancel_order(&mut self, order_id: u64) -> Option<Order> {
+        let order = self.unrest(order_id)?;
+        self.tombstones.insert(order_id);
+        self.recalculate_best_prices();
+        self.participants.release(&order);
+        self.depth.release(&order);
+        self.level_changes.release(&order);
+        Some(order)
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool, cancelled: &mut Vec<Order>) {
+        let start = cancelled.len();
+        for queue in [Arc::clone(&self.bids), Arc::clone(&self.asks)] {
+            for _ in 0..queue.len() {
+                let Some(id) = queue.pop() else {
+                    break;
+                };
+                if self.tombstones.remove(&id) {
+                    continue;
+                }
+                if self.orders.get(id).is_some_and(predicate) && let Some(order) = self.unrest(id) {
+                    cancelled.push(order);
+                } else {
+                    let _ = queue.push(id);
+                }
+            }
+        }
+        for order in &cancelled[start..] {
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        }
+        if cancelled.len() > start {
+            self.recalculate_best_prices();
+        }
+    }
+
+    // ArrayQueue has no iterator, so rotate each queue once to read it in FIFO order
+    // Queue order within a price, best price first
+    fn resting_orders(&self) -> Vec<Order> {
+        let mut orders = Vec::with_capacity(self.orders.len());
+        for (queue, side) in [(&self.bids, OrderSide::Buy), (&self.asks, OrderSide::Sell)] {
+            let start = orders.len();
+            for _ in 0..queue.len() {
+                if let Some(id) = queue.pop() {
+                    if !self.tombstones.contains(&id) && let Some(order) = self.orders.get(id) {
+                        orders.push(order.clone());
+                    }
+                    let _ = queue.push(id);
+                }
+            }
+            match side {
+                OrderSide::Buy => orders[start..].sort_by_key(|order| std::cmp::Reverse(order.price)),
+                OrderSide::Sell => orders[start..].sort_by_key(|order| order.price),
+            }
+        }
+        orders
+    }
+}
+
+// Takes the earliest order at the other side's best price, the same as `match_orders`
+impl<B: MapBackend> TakeOnArrival for ArrayQueueMatcher<B> {
+    type Slot = ();
+
+    fn next_resting(&mut self, taker: &Order) -> Option<(Self::Slot, Order)> {
+        let (queue, best_price) = match taker.order_type {
+            OrderSide::Buy => (&self.asks, self.ask_levels.first_key_value()),
+            OrderSide::Sell => (&self.bids, self.bid_levels.last_key_value()),
+        };
+        let price = best_price.map(|(&price, _)| price).filter(|&price| taker.order_type.crosses(taker.price, price))?;
+        let id = Self::take_first_at(queue, &mut self.tombstones, &self.orders, price)?;
+        Some(((), self.orders.get(id)?.clone()))
+    }
+
+    fn fill_resting(&mut self, _: Self::Slot, resting: &Order, quantity: u64) -> bool {
+        self.settle_fill(resting, quantity);
+        if resting.quantity > quantity {
+            Self::push_front(self.queue(resting.order_type), resting.id);
+        }
+        let levels = match resting.order_type {
+            OrderSide::Buy => &self.bid_levels,
+            OrderSide::Sell => &self.ask_levels,
+        };
+        !levels.contains_key(&resting.price)
+    }
+
+    fn arrived(&mut self, mut trades: Vec<Trade>, last_trade_price: Option<u64>, remainder: Option<Order>) {
+        self.recalculate_best_prices();
+        self.arrival_trades.append(&mut trades);
+        self.last_trade_price = last_trade_price.or(self.last_trade_price);
+        self.evicted.extend(remainder);
     }
 }
 
 #[repr(align(64))]
-pub struct ArrayQueueOrderBook {
+pub struct ArrayQueueOrderBook<B: MapBackend = FxMaps> {
     symbols: FxHashSet<SymbolId>,
-    matchers: FxHashMap<SymbolId, ArrayQueueMatcher>,
+    matchers: B::Matchers<ArrayQueueMatcher<B>>,
+    trade_price_rule: TradePriceRule,
+    tie_break: TieBreak,
+    participant_limits: ParticipantLimits,
+    tick_sizes: TickSizes,
+    lot_sizes: LotSizes,
+    max_depth: Option<usize>,
+    level_journal: bool,
 }
 
-impl OrderBookTrait for ArrayQueueOrderBook {
+impl<B: MapBackend> ArrayQueueOrderBook<B> {
+    #[inline(always)]
+    fn matcher_mut(&mut self, symbol: SymbolId) -> Option<&mut ArrayQueueMatcher<B>> {
+        let (limits, max_depth, level_journal) = (self.participant_limits, self.max_depth, self.level_journal);
+        lazy_matchers::matcher_entry(&mut self.matchers, &self.symbols, symbol, || {
+            let mut matcher = ArrayQueueMatcher::new();
+            matcher.participants.set_limits(limits, []);
+            let _ = matcher.depth.set_max_levels(max_depth, []);
+            matcher.level_changes.set_enabled(level_journal, []);
+            matcher
+        })
+    }
+}
+
+impl ArrayQueueOrderBook {
+    pub fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        <Self as OrderBookCore>::new(symbols)
+    }
+}
+
+impl<B: MapBackend> OrderBookCore for ArrayQueueOrderBook<B> {
     fn new(symbols: FxHashSet<SymbolId>) -> Self {
-        let mut matchers = FxHashMap::with_capacity_and_hasher(symbols.len(), Default::default());
-        for &symbol in &symbols {
-            matchers.insert(symbol, ArrayQueueMatcher::new());
+        ArrayQueueOrderBook {
+            symbols,
+            matchers: Default::default(),
+            trade_price_rule: TradePriceRule::default(),
+            tie_break: TieBreak::default(),
+            participant_limits: ParticipantLimits::default(),
+            tick_sizes: TickSizes::default(),
+            lot_sizes: LotSizes::default(),
+            max_depth: None,
+            level_journal: false,
         }
-        ArrayQueueOrderBook { symbols, matchers }
     }
 
     #[inline(always)]
     fn add_order(&mut self, order: Order) -> Result<bool, OrderBookError> {
-        if let Some(matcher) = self.matchers.get_mut(&order.symbol) {
+        let order = self.tick_sizes.conform(order)?;
+        self.lot_sizes.check(&order)?;
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return Err(OrderBookError::ParticipantLimitExceeded);
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+                return Ok(true);
+            }
             Ok(matcher.add_order(order))
         } else {
             Err(OrderBookError::InvalidSymbol)
@@ -168,7 +498,19 @@ impl OrderBookTrait for ArrayQueueOrderBook {
 
     #[inline(always)]
     fn add_order_fast(&mut self, order: Order) -> bool {
-        if let Some(matcher) = self.matchers.get_mut(&order.symbol) {
+        let Ok(order) = self.tick_sizes.conform(order) else { return false };
+        if self.lot_sizes.check(&order).is_err() {
+            return false;
+        }
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return false;
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+                return true;
+            }
             matcher.add_order(order)
         } else {
             false
@@ -177,18 +519,23 @@ impl OrderBookTrait for ArrayQueueOrderBook {
 
     #[inline(always)]
     unsafe fn add_order_unchecked(&mut self, order: Order) {
-        unsafe {
-            self.matchers.get_mut(&order.symbol)
-                .unwrap_unchecked()
-                .add_order_unchecked(order);
+        let rule = self.trade_price_rule;
+        let matcher = unsafe { self.matcher_mut(order.symbol).unwrap_unchecked() };
+        if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+            match_stats::take_on_arrival(matcher, order, rule);
+        } else {
+            unsafe { matcher.add_order_unchecked(order); }
         }
     }
 
     #[inline(always)]
-    fn match_orders(&mut self) {
+    fn match_orders(&mut self) -> Vec<Trade> {
+        let mut trades = Vec::new();
         for matcher in self.matchers.values_mut() {
-            matcher.match_orders();
+            trades.append(&mut matcher.arrival_trades);
+            matcher.match_orders(self.trade_price_rule, &mut trades);
         }
+        trades
     }
 
     #[inline(always)]
@@ -217,13 +564,15 @@ impl OrderBookTrait for ArrayQueueOrderBook {
 
     #[inline(always)]
     fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
-        self.matchers.get(&symbol)
-            .map(|matcher| matcher.get_best_prices())
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.get_best_prices()),
+            None => self.symbols.contains(&symbol).then_some((None, None)),
+        }
     }
 
     #[inline(always)]
     fn can_match(&self, symbol: SymbolId) -> bool {
-        self.matchers.get(&symbol)
+        self.matchers.get(symbol)
             .is_some_and(|matcher| matcher.can_match())
     }
 
@@ -243,10 +592,156 @@ impl OrderBookTrait for ArrayQueueOrderBook {
     }
 }
 
-impl ArrayQueueOrderBook {
+impl<B: MapBackend> OrderBookLifecycle for ArrayQueueOrderBook<B> {
+    fn resting_orders(&self, symbol: SymbolId) -> Option<Vec<Order>> {
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.resting_orders()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    #[inline(always)]
+    fn set_trade_price_rule(&mut self, rule: TradePriceRule) {
+        self.trade_price_rule = rule;
+    }
+
+    #[inline(always)]
+    fn trade_price_rule(&self) -> TradePriceRule {
+        self.trade_price_rule
+    }
+
+    /// Stored only: this book always matches first in, first out within a price.
+    fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    #[inline(always)]
+    fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    fn set_participant_limits(&mut self, limits: ParticipantLimits) {
+        self.participant_limits = limits;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.participants.set_limits(limits, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn participant_limits(&self) -> ParticipantLimits {
+        self.participant_limits
+    }
+
+    fn set_tick_sizes(&mut self, tick_sizes: TickSizes) {
+        self.tick_sizes = tick_sizes;
+    }
+
+    #[inline(always)]
+    fn tick_sizes(&self) -> &TickSizes {
+        &self.tick_sizes
+    }
+
+    fn set_lot_sizes(&mut self, lot_sizes: LotSizes) {
+        self.lot_sizes = lot_sizes;
+    }
+
+    #[inline(always)]
+    fn lot_sizes(&self) -> &LotSizes {
+        &self.lot_sizes
+    }
+
+    fn set_max_depth(&mut self, max_levels: Option<usize>) {
+        self.max_depth = max_levels;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            for (side, price) in matcher.depth.set_max_levels(max_levels, &resting) {
+                matcher.evict_level(side, price);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    fn take_evicted_orders(&mut self) -> Vec<Order> {
+        self.matchers.values_mut().flat_map(|matcher| matcher.evicted.drain(..)).collect()
+    }
+
+    fn set_level_journal(&mut self, enabled: bool) {
+        self.level_journal = enabled;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.level_changes.set_enabled(enabled, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn level_journal(&self) -> bool {
+        self.level_journal
+    }
+
+    fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>> {
+        match self.matchers.get_mut(symbol) {
+            Some(matcher) => Some(matcher.level_changes.take()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Result<Order, OrderBookError> {
+        self.matchers.values_mut()
+            .find_map(|matcher| matcher.reduce_order(order_id, quantity_delta))
+            .unwrap_or(Err(OrderBookError::OrderNotFound))
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool) -> Vec<Order> {
+        let mut cancelled = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            matcher.cancel_where(predicate, &mut cancelled);
+        }
+        cancelled
+    }
+
+    fn cancel_order(&mut self, symbol: SymbolId, order_id: u64) -> Result<Order, OrderBookError> {
+        lazy_matchers::cancel_in(&mut self.matchers, &self.symbols, symbol, |matcher| matcher.cancel_order(order_id))
+    }
+
+    fn cancel_orders(&mut self, order_ids: &FxHashSet<u64>) -> Vec<Order> {
+        lazy_matchers::cancel_each_in(&mut self.matchers, order_ids, |matcher, order_id| matcher.cancel_order(order_id))
+    }
+}
+
+impl<B: MapBackend> OrderBookMarketData for ArrayQueueOrderBook<B> {
+    fn price_levels(&self, symbol: SymbolId, side: OrderSide, with_order_ids: bool) -> Option<PriceLevelIter<'_>> {
+        let resting = self.resting_orders(symbol)?;
+        let levels = price_level_view::aggregate_levels(resting.iter(), side, with_order_ids);
+        Some(Box::new(levels.into_iter()))
+    }
+
+    fn get_depth(&self, symbol: SymbolId, levels: usize) -> Option<BookDepth> {
+        let resting = self.resting_orders(symbol)?;
+        Some(price_level_view::top_levels(resting.iter(), levels))
+    }
+
+    #[inline(always)]
+    fn last_trade_price(&self, symbol: SymbolId) -> Option<u64> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.last_trade_price)
+    }
+
+    #[inline(always)]
+    fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.match_stats)
+    }
+}
+
+impl<B: MapBackend> ArrayQueueOrderBook<B> {
     #[inline(always)]
     pub fn get_queue_stats(&self, symbol: SymbolId) -> Option<(usize, usize, usize, usize)> {
-        self.matchers.get(&symbol).map(|matcher| {
+        self.matchers.get(symbol).map(|matcher| {
             let (bid_cap, ask_cap) = matcher.queue_capacities();
             let (bid_len, ask_len) = matcher.queue_lengths();
             (bid_cap, ask_cap, bid_len, ask_len)
@@ -255,13 +750,13 @@ impl ArrayQueueOrderBook {
     
     #[inline(always)]
     pub fn is_symbol_empty(&self, symbol: SymbolId) -> bool {
-        self.matchers.get(&symbol)
+        self.matchers.get(symbol)
             .is_none_or(|matcher| matcher.is_empty())
     }
     
     #[inline(always)]
     pub fn queue_utilization(&self, symbol: SymbolId) -> Option<(f64, f64)> {
-        self.matchers.get(&symbol).map(|matcher| {
+        self.matchers.get(symbol).map(|matcher| {
             let (bid_cap, ask_cap) = matcher.queue_capacities();
             let (bid_len, ask_len) = matcher.queue_lengths();
             
@@ -311,4 +806,16 @@ mod tests {
         assert_eq!(successful, 3);
         assert_eq!(failed, 0);
     }
+
+    #[test]
+    fn test_best_prices_survive_partial_match() {
+        let mut order_book = ArrayQueueOrderBook::new(FxHashSet::from_iter([APPLE_SYMBOL]));
+        order_book.add_order_fast(new_order(1, APPLE_SYMBOL, 100, 150.0, OrderSide::Buy));
+        order_book.add_order_fast(new_order(2, APPLE_SYMBOL, 100, 149.0, OrderSide::Buy));
+        order_book.add_order_fast(new_order(3, APPLE_SYMBOL, 100, 148.0, OrderSide::Sell));
+        order_book.match_orders();
+
+        let best_bid = crate::types::order::price_to_u64(149.0);
+        assert_eq!(order_book.get_best_prices(APPLE_SYMBOL), Some((Some(best_bid), None)));
+    }
 } 
\ No newline at end of file
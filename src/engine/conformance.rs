@@ -0,0 +1,352 @@
+//! Behaviour every `OrderBookTrait` implementation is expected to share, packaged as
+//! a battery that third-party books can run with one call:
+//!
+//! ```
+//! use rust_order_book::engine::{conformance, factories};
+//!
+//! conformance::check_conformance(factories::create_hashmap_order_book).assert_conformant();
+//! ```
+
+use std::fmt::{self, Debug};
+
+use rustc_hash::FxHashSet;
+
+use crate::engine::{OrderBookError, OrderBookTrait, PriceLevelView};
+use crate::types::order::{new_order, Order, OrderSide};
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::{Trade, TradePriceRule};
+
+/// The one symbol each check's book is created with.
+pub const SYMBOL: SymbolId = 1;
+/// A symbol no check's book knows.
+pub const UNKNOWN_SYMBOL: SymbolId = 2;
+
+type Check<T> = fn(&dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceFailure {
+    pub check: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub passed: Vec<&'static str>,
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl ConformanceReport {
+    #[inline(always)]
+    pub fn is_conformant(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Panics listing every failed check, for use inside a `#[test]`.
+    pub fn assert_conformant(&self) {
+        assert!(self.is_conformant(), "order book is not conformant:\n{self}");
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} passed, {} failed", self.passed.len(), self.failures.len())?;
+        for failure in &self.failures {
+            writeln!(f, "  {}: {}", failure.check, failure.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every check, each against a fresh book from `create_order_book`, and reports
+/// the failures instead of stopping at the first.
+pub fn check_conformance<T: OrderBookTrait>(create_order_book: impl Fn(FxHashSet<SymbolId>) -> T) -> ConformanceReport {
+    let checks: [(&'static str, Check<T>); 11] = [
+        ("empty_book", empty_book),
+        ("invalid_symbol", invalid_symbol),
+        ("best_prices", best_prices),
+        ("price_priority", price_priority),
+        ("time_priority", time_priority),
+        ("fill_quantities", fill_quantities),
+        ("trade_price_rules", trade_price_rules),
+        ("no_cross_after_match", no_cross_after_match),
+        ("cancels", cancels),
+        ("reductions", reductions),
+        ("edge_prices", edge_prices),
+    ];
+
+    let mut report = ConformanceReport::default();
+    for (check, run) in checks {
+        match run(&create_order_book) {
+            Ok(()) => report.passed.push(check),
+            Err(message) => report.failures.push(ConformanceFailure { check, message }),
+        }
+    }
+    report
+}
+
+fn expect_eq<V: PartialEq + Debug>(what: &str, actual: V, expected: V) -> Result<(), String> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!("{what}: expected {expected:?}, got {actual:?}"))
+    }
+}
+
+fn book<T>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> T {
+    create_order_book(FxHashSet::from_iter([SYMBOL]))
+}
+
+fn add<T: OrderBookTrait>(order_book: &mut T, orders: &[Order]) -> Result<(), String> {
+    for order in orders {
+        if let Err(error) = order_book.add_order(order.clone()) {
+            return Err(format!("add_order({}) failed with {error:?}", order.id));
+        }
+    }
+    Ok(())
+}
+
+fn resting_ids<T: OrderBookTrait>(order_book: &T) -> Vec<u64> {
+    let mut ids: Vec<_> = order_book.resting_orders(SYMBOL).unwrap_or_default().iter().map(|order| order.id).collect();
+    ids.sort_unstable();
+    ids
+}
+
+fn levels<T: OrderBookTrait>(order_book: &T, side: OrderSide) -> Vec<(u64, u64, usize)> {
+    order_book.price_levels(SYMBOL, side, false)
+        .map(|levels| levels.map(|PriceLevelView { price, total_quantity, order_count, .. }| (price, total_quantity, order_count)).collect())
+        .unwrap_or_default()
+}
+
+fn empty_book<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let order_book = book(create_order_book);
+    expect_eq("is_valid_symbol", order_book.is_valid_symbol(SYMBOL), true)?;
+    expect_eq("best prices", order_book.get_best_prices(SYMBOL), Some((None, None)))?;
+    expect_eq("can_match", order_book.can_match(SYMBOL), false)?;
+    expect_eq("resting orders", order_book.resting_orders(SYMBOL).map(|orders| orders.len()), Some(0))?;
+    expect_eq("last trade price", order_book.last_trade_price(SYMBOL), None)
+}
+
+fn invalid_symbol<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    let order = new_order(1, UNKNOWN_SYMBOL, 10, 100.0, OrderSide::Buy);
+    expect_eq("is_valid_symbol", order_book.is_valid_symbol(UNKNOWN_SYMBOL), false)?;
+    expect_eq(
+        "add_order",
+        order_book.add_order(order.clone()).map_err(|error| matches!(error, OrderBookError::InvalidSymbol)),
+        Err(true),
+    )?;
+    expect_eq("add_order_fast", order_book.add_order_fast(order.clone()), false)?;
+    expect_eq("add_orders_batch_fast", order_book.add_orders_batch_fast(&[order]), (0, 1))?;
+    expect_eq("best prices", order_book.get_best_prices(UNKNOWN_SYMBOL), None)?;
+    expect_eq("resting orders", order_book.resting_orders(UNKNOWN_SYMBOL).is_none(), true)?;
+    expect_eq("price levels", order_book.price_levels(UNKNOWN_SYMBOL, OrderSide::Buy, false).is_none(), true)?;
+    expect_eq("resting orders on the valid symbol", resting_ids(&order_book), Vec::new())
+}
+
+fn best_prices<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    add(&mut order_book, &[
+        new_order(1, SYMBOL, 10, 99.0, OrderSide::Buy),
+        new_order(2, SYMBOL, 20, 99.5, OrderSide::Buy),
+        new_order(3, SYMBOL, 30, 99.5, OrderSide::Buy),
+        new_order(4, SYMBOL, 40, 101.0, OrderSide::Sell),
+        new_order(5, SYMBOL, 50, 100.5, OrderSide::Sell),
+    ])?;
+    expect_eq("best prices", order_book.get_best_prices(SYMBOL), Some((Some(99_500), Some(100_500))))?;
+    expect_eq("can_match", order_book.can_match(SYMBOL), false)?;
+    expect_eq("bid levels", levels(&order_book, OrderSide::Buy), vec![(99_500, 50, 2), (99_000, 10, 1)])?;
+    expect_eq("ask levels", levels(&order_book, OrderSide::Sell), vec![(100_500, 50, 1), (101_000, 40, 1)])?;
+
+    order_book.match_orders();
+    expect_eq("resting orders after a match with no cross", resting_ids(&order_book), vec![1, 2, 3, 4, 5])
+}
+
+fn price_priority<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    add(&mut order_book, &[
+        new_order(1, SYMBOL, 10, 100.0, OrderSide::Buy),
+        new_order(2, SYMBOL, 10, 101.0, OrderSide::Buy),
+        new_order(3, SYMBOL, 10, 103.0, OrderSide::Sell),
+        new_order(4, SYMBOL, 10, 102.0, OrderSide::Sell),
+    ])?;
+    add(&mut order_book, &[new_order(5, SYMBOL, 10, 100.0, OrderSide::Sell)])?;
+    order_book.match_orders();
+    expect_eq("resting orders after a sell crossed both bids", resting_ids(&order_book), vec![1, 3, 4])?;
+    add(&mut order_book, &[new_order(6, SYMBOL, 10, 103.0, OrderSide::Buy)])?;
+    order_book.match_orders();
+    expect_eq("resting orders after a buy crossed both asks", resting_ids(&order_book), vec![1, 3])?;
+    expect_eq("best prices", order_book.get_best_prices(SYMBOL), Some((Some(100_000), Some(103_000))))
+}
+
+fn time_priority<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    add(&mut order_book, &[
+        new_order(1, SYMBOL, 10, 100.0, OrderSide::Buy),
+        new_order(2, SYMBOL, 10, 100.0, OrderSide::Buy),
+        new_order(3, SYMBOL, 10, 100.0, OrderSide::Buy),
+        new_order(4, SYMBOL, 10, 100.0, OrderSide::Sell),
+    ])?;
+    order_book.match_orders();
+    expect_eq("resting orders after the earliest bid matched", resting_ids(&order_book), vec![2, 3])?;
+
+    let queue: Vec<_> = order_book.price_levels(SYMBOL, OrderSide::Buy, true)
+        .and_then(|mut levels| levels.next())
+        .and_then(|level| level.order_ids)
+        .unwrap_or_default();
+    expect_eq("queue order at the level", queue, vec![2, 3])
+}
+
+fn fill_quantities<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    add(&mut order_book, &[
+        new_order(1, SYMBOL, 1_000, 100.0, OrderSide::Buy),
+        new_order(2, SYMBOL, 10, 100.0, OrderSide::Buy),
+        new_order(3, SYMBOL, 10, 100.0, OrderSide::Sell),
+    ])?;
+    let trades = order_book.match_orders();
+    expect_eq("trades", trades, vec![Trade {
+        symbol: SYMBOL,
+        taker_order_id: 3,
+        maker_order_id: 1,
+        price: 100_000,
+        quantity: 10,
+        side: OrderSide::Sell,
+    }])?;
+    let stats = order_book.last_match_stats(SYMBOL).ok_or("no match stats after a cross")?;
+    expect_eq("match stats trades", stats.trades, 1)?;
+    expect_eq("volume counts the smaller order", stats.volume, 10)?;
+    expect_eq("resting after a partial fill", resting_ids(&order_book), vec![1, 2])?;
+    expect_eq("residual quantity at the level", levels(&order_book, OrderSide::Buy), vec![(100_000, 1_000, 2)])?;
+    let queue: Vec<_> = order_book.price_levels(SYMBOL, OrderSide::Buy, true)
+        .and_then(|mut levels| levels.next())
+        .and_then(|level| level.order_ids)
+        .unwrap_or_default();
+    expect_eq("residual keeps its queue position", queue, vec![1, 2])?;
+    expect_eq("trades from a book with no cross", order_book.match_orders(), Vec::new())?;
+
+    add(&mut order_book, &[new_order(4, SYMBOL, 995, 100.0, OrderSide::Sell)])?;
+    let trades: Vec<_> = order_book.match_orders().iter().map(|trade| (trade.maker_order_id, trade.quantity)).collect();
+    expect_eq("residual fills before later orders", trades, vec![(1, 990), (2, 5)])?;
+    expect_eq("resting after the residual filled", resting_ids(&order_book), vec![2])?;
+    expect_eq("level after the residual filled", levels(&order_book, OrderSide::Buy), vec![(100_000, 5, 1)])
+}
+
+fn trade_price_rules<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    for (rule, expected) in [
+        (TradePriceRule::RestingOrder, 101_000),
+        (TradePriceRule::Aggressor, 100_000),
+        (TradePriceRule::Midpoint, 100_500),
+    ] {
+        let mut order_book = book(create_order_book);
+        order_book.set_trade_price_rule(rule);
+        expect_eq("trade price rule", order_book.trade_price_rule(), rule)?;
+        add(&mut order_book, &[
+            new_order(1, SYMBOL, 10, 101.0, OrderSide::Buy),
+            new_order(2, SYMBOL, 10, 100.0, OrderSide::Sell),
+        ])?;
+        let trades = order_book.match_orders();
+        expect_eq(&format!("trade price under {rule:?}"), trades.first().map(|trade| trade.price), Some(expected))?;
+        expect_eq(&format!("last trade price under {rule:?}"), order_book.last_trade_price(SYMBOL), Some(expected))?;
+    }
+    Ok(())
+}
+
+fn no_cross_after_match<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    let orders: Vec<_> = (0..40u64)
+        .map(|id| {
+            let side = if id % 3 == 0 { OrderSide::Sell } else { OrderSide::Buy };
+            new_order(id + 1, SYMBOL, 10 + id % 7, 100.0 + (id * 7 % 11) as f64 * 0.25, side)
+        })
+        .collect();
+    add(&mut order_book, &orders)?;
+    order_book.match_orders();
+    expect_eq("can_match after matching", order_book.can_match(SYMBOL), false)?;
+    match order_book.get_best_prices(SYMBOL) {
+        Some((Some(bid), Some(ask))) if bid >= ask => Err(format!("book left crossed at bid {bid} / ask {ask}")),
+        _ => Ok(()),
+    }
+}
+
+fn cancels<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    add(&mut order_book, &[
+        new_order(1, SYMBOL, 10, 100.0, OrderSide::Buy),
+        new_order(2, SYMBOL, 10, 99.0, OrderSide::Buy),
+        new_order(3, SYMBOL, 10, 101.0, OrderSide::Sell),
+    ])?;
+    let mut cancelled: Vec<_> = order_book.cancel_orders(&FxHashSet::from_iter([1, 3, 42]))
+        .iter()
+        .map(|order| order.id)
+        .collect();
+    cancelled.sort_unstable();
+    expect_eq("cancelled ids", cancelled, vec![1, 3])?;
+    expect_eq("best prices after cancels", order_book.get_best_prices(SYMBOL), Some((Some(99_000), None)))?;
+    expect_eq("cancelling again", order_book.cancel_orders(&FxHashSet::from_iter([1])).len(), 0)?;
+
+    let pulled = order_book.cancel_where(&|order| order.order_type == OrderSide::Buy);
+    expect_eq("cancel_where", pulled.iter().map(|order| order.id).collect::<Vec<_>>(), vec![2])?;
+    expect_eq("best prices on an emptied book", order_book.get_best_prices(SYMBOL), Some((None, None)))?;
+
+    add(&mut order_book, &[
+        new_order(4, SYMBOL, 10, 98.0, OrderSide::Buy),
+        new_order(5, SYMBOL, 5, 98.0, OrderSide::Buy),
+    ])?;
+    let cancelled = order_book.cancel_order(SYMBOL, 4).map_err(|error| format!("cancel_order failed with {error:?}"))?;
+    expect_eq("cancel_order", (cancelled.id, cancelled.quantity), (4, 10))?;
+    expect_eq("level after cancel_order", levels(&order_book, OrderSide::Buy), vec![(98_000, 5, 1)])?;
+    let rejected = |result: Result<Order, OrderBookError>| result.err().map(|error| format!("{error:?}"));
+    expect_eq("cancel_order twice", rejected(order_book.cancel_order(SYMBOL, 4)), Some("OrderNotFound".to_string()))?;
+    expect_eq("cancel_order on an unknown symbol", rejected(order_book.cancel_order(UNKNOWN_SYMBOL, 5)), Some("InvalidSymbol".to_string()))?;
+    order_book.cancel_order(SYMBOL, 5).map_err(|error| format!("cancel_order failed with {error:?}"))?;
+    expect_eq("best prices after cancel_order", order_book.get_best_prices(SYMBOL), Some((None, None)))
+}
+
+fn reductions<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    add(&mut order_book, &[
+        new_order(1, SYMBOL, 10, 100.0, OrderSide::Buy),
+        new_order(2, SYMBOL, 10, 100.0, OrderSide::Buy),
+    ])?;
+    let reduced = order_book.reduce_order(1, 4).map_err(|error| format!("reduce_order failed with {error:?}"))?;
+    expect_eq("reduced quantity", reduced.quantity, 6)?;
+    expect_eq("level after reduction", levels(&order_book, OrderSide::Buy), vec![(100_000, 16, 2)])?;
+
+    let rejected = |result: Result<Order, OrderBookError>| result.err().map(|error| format!("{error:?}"));
+    expect_eq("reducing by the whole quantity", rejected(order_book.reduce_order(1, 6)), Some("InvalidQuantity".to_string()))?;
+    expect_eq("reducing by zero", rejected(order_book.reduce_order(1, 0)), Some("InvalidQuantity".to_string()))?;
+    expect_eq("reducing an unknown order", rejected(order_book.reduce_order(42, 1)), Some("OrderNotFound".to_string()))?;
+
+    add(&mut order_book, &[new_order(3, SYMBOL, 6, 100.0, OrderSide::Sell)])?;
+    order_book.match_orders();
+    expect_eq("reduced order keeps its queue position", resting_ids(&order_book), vec![2])
+}
+
+fn edge_prices<T: OrderBookTrait>(create_order_book: &dyn Fn(FxHashSet<SymbolId>) -> T) -> Result<(), String> {
+    let mut order_book = book(create_order_book);
+    let order = |id, price, side| Order { price, ..new_order(id, SYMBOL, 10, 0.0, side) };
+    add(&mut order_book, &[order(1, 0, OrderSide::Buy), order(2, u64::MAX, OrderSide::Sell)])?;
+    expect_eq("best prices at the extremes", order_book.get_best_prices(SYMBOL), Some((Some(0), Some(u64::MAX))))?;
+    expect_eq("can_match", order_book.can_match(SYMBOL), false)?;
+
+    order_book.set_trade_price_rule(TradePriceRule::Midpoint);
+    add(&mut order_book, &[order(3, u64::MAX, OrderSide::Buy), order(4, u64::MAX - 1, OrderSide::Sell)])?;
+    order_book.match_orders();
+    expect_eq("midpoint near u64::MAX", order_book.last_trade_price(SYMBOL), Some(u64::MAX - 1))?;
+    expect_eq("resting orders", resting_ids(&order_book), vec![1, 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::factories;
+
+    #[test]
+    fn test_built_in_books_conform() {
+        check_conformance(factories::create_hashmap_order_book).assert_conformant();
+        check_conformance(factories::create_priority_queue_order_book).assert_conformant();
+        check_conformance(factories::create_persistent_order_book).assert_conformant();
+        check_conformance(factories::create_fixed_capacity_order_book).assert_conformant();
+        check_conformance(factories::create_array_queue_order_book).assert_conformant();
+    }
+}
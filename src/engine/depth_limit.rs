@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use crate::types::order::{Order, OrderSide};
+
+/// Price levels per side for the optional max depth cap. Like the participant counts,
+/// nothing is tracked while uncapped.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DepthLimit {
+    max_levels: Option<usize>,
+    bids: BTreeMap<u64, usize>,
+    asks: BTreeMap<u64, usize>,
+}
+
+impl DepthLimit {
+    /// Rebuilds the counts and returns the levels that must go to fit the new cap.
+    pub(crate) fn set_max_levels<'a>(
+        &mut self,
+        max_levels: Option<usize>,
+        resting: impl IntoIterator<Item = &'a Order>,
+    ) -> Vec<(OrderSide, u64)> {
+        self.max_levels = max_levels;
+        self.bids.clear();
+        self.asks.clear();
+        let Some(max_levels) = max_levels else {
+            return Vec::new();
+        };
+
+        for order in resting {
+            *self.levels_mut(order.order_type).entry(order.price).or_default() += 1;
+        }
+        let mut evict = Vec::new();
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            while self.levels_mut(side).len() > max_levels {
+                if let Some(price) = self.pop_farthest(side) {
+                    evict.push((side, price));
+                }
+            }
+        }
+        evict
+    }
+
+    /// Counts an order resting at `price` and, if its side now exceeds the cap, returns
+    /// the farthest price, which the caller must evict.
+    #[inline(always)]
+    pub(crate) fn record(&mut self, side: OrderSide, price: u64) -> Option<u64> {
+        let max_levels = self.max_levels?;
+        let levels = self.levels_mut(side);
+        *levels.entry(price).or_default() += 1;
+        if levels.len() <= max_levels {
+            return None;
+        }
+        self.pop_farthest(side)
+    }
+
+    #[inline(always)]
+    pub(crate) fn release(&mut self, order: &Order) {
+        if self.max_levels.is_none() {
+            return;
+        }
+        let levels = self.levels_mut(order.order_type);
+        if let Some(count) = levels.get_mut(&order.price) {
+            *count -= 1;
+            if *count == 0 {
+                levels.remove(&order.price);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn levels_mut(&mut self, side: OrderSide) -> &mut BTreeMap<u64, usize> {
+        match side {
+            OrderSide::Buy => &mut self.bids,
+            OrderSide::Sell => &mut self.asks,
+        }
+    }
+
+    fn pop_farthest(&mut self, side: OrderSide) -> Option<u64> {
+        match side {
+            OrderSide::Buy => self.bids.pop_first(),
+            OrderSide::Sell => self.asks.pop_last(),
+        }
+        .map(|(price, _)| price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    fn record_order(depth: &mut DepthLimit, order: Order) -> Option<u64> {
+        depth.record(order.order_type, order.price)
+    }
+
+    #[test]
+    fn test_depth_limit_picks_far_touch() {
+        let mut depth = DepthLimit::default();
+        let resting = [100.0, 99.0, 98.0].map(|price| new_order(1, 0, 10, price, OrderSide::Buy));
+        let evict = depth.set_max_levels(Some(2), &resting);
+        assert_eq!(evict, vec![(OrderSide::Buy, resting[2].price)]);
+
+        assert_eq!(record_order(&mut depth, new_order(2, 0, 10, 99.0, OrderSide::Buy)), None);
+        assert_eq!(record_order(&mut depth, new_order(3, 0, 10, 101.0, OrderSide::Buy)), Some(resting[1].price));
+
+        let far_ask = new_order(5, 0, 10, 51.0, OrderSide::Sell);
+        assert_eq!(record_order(&mut depth, new_order(4, 0, 10, 50.0, OrderSide::Sell)), None);
+        assert_eq!(record_order(&mut depth, far_ask.clone()), None);
+        assert_eq!(record_order(&mut depth, new_order(6, 0, 10, 49.0, OrderSide::Sell)), Some(far_ask.price));
+
+        depth.release(&new_order(6, 0, 10, 49.0, OrderSide::Sell));
+        assert_eq!(record_order(&mut depth, new_order(7, 0, 10, 52.0, OrderSide::Sell)), None);
+    }
+}
@@ -0,0 +1,837 @@
+use rustc_hash::FxHashSet;
+
+use crate::engine::order_book_trait::{OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData};
+use crate::engine::OrderBookType;
+use crate::engine::price_level_view::{PriceLevelIter, PriceLevelView};
+use crate::engine::participant_limits::{ParticipantLimits, ParticipantOrderCounts};
+use crate::engine::lot_size::LotSizes;
+use crate::engine::symbol_map::{FxMaps, MapBackend, SymbolMap};
+use crate::engine::tick_size::TickSizes;
+use crate::engine::depth_limit::DepthLimit;
+use crate::engine::match_stats::{self, MatchCycle, MatchStats, TakeOnArrival};
+use crate::engine::level_journal::{LevelChange, LevelJournal};
+use crate::engine::tie_break::{TieBreak, TieBreaker};
+use crate::engine::reduction;
+use crate::engine::lazy_matchers;
+use crate::types::{order::{Order, OrderSide, OrderFlags}, symbol_mapping::SymbolId, trade::{Trade, TradePriceRule}};
+
+const NIL: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    price: u64,
+    head: u32,
+    tail: u32,
+    count: u32,
+    quantity: u64,
+}
+
+impl Level {
+    const EMPTY: Level = Level { price: 0, head: NIL, tail: NIL, count: 0, quantity: 0 };
+}
+
+/// Order id to slot in `2 * MAX_ORDERS` open-addressed buckets held inline, so the
+/// index allocates nothing either and stays at most half full. A removal shifts the
+/// rest of its probe run back instead of leaving a tombstone.
+#[derive(Debug)]
+struct SlotIndex<const MAX_ORDERS: usize> {
+    buckets: [[(u64, u32); 2]; MAX_ORDERS],
+}
+
+impl<const MAX_ORDERS: usize> SlotIndex<MAX_ORDERS> {
+    fn new() -> Self {
+        Self { buckets: [[(0, NIL); 2]; MAX_ORDERS] }
+    }
+
+    #[inline(always)]
+    fn home(order_id: u64) -> usize {
+        let hash = order_id.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        ((hash as u128 * (2 * MAX_ORDERS) as u128) >> 64) as usize
+    }
+
+    // The bucket holding `order_id`, or the empty one ending its probe run
+    #[inline(always)]
+    fn probe(&self, order_id: u64) -> usize {
+        let buckets = self.buckets.as_flattened();
+        let mut bucket = Self::home(order_id);
+        while buckets[bucket].1 != NIL && buckets[bucket].0 != order_id {
+            bucket = (bucket + 1) % buckets.len();
+        }
+        bucket
+    }
+
+    #[inline(always)]
+    fn get(&self, order_id: u64) -> Option<u32> {
+        if MAX_ORDERS == 0 {
+            return None;
+        }
+        let (_, slot) = self.buckets.as_flattened()[self.probe(order_id)];
+        (slot != NIL).then_some(slot)
+    }
+
+    #[inline(always)]
+    fn insert(&mut self, order_id: u64, slot: u32) {
+        let bucket = self.probe(order_id);
+        self.buckets.as_flattened_mut()[bucket] = (order_id, slot);
+    }
+
+    fn remove(&mut self, order_id: u64) -> Option<u32> {
+        if MAX_ORDERS == 0 {
+            return None;
+        }
+        let mut hole = self.probe(order_id);
+        let buckets = self.buckets.as_flattened_mut();
+        let (_, slot) = buckets[hole];
+        if slot == NIL {
+            return None;
+        }
+        // Entries after the hole move into it unless that would put them before home
+        let mut bucket = hole;
+        loop {
+            bucket = (bucket + 1) % buckets.len();
+            let (id, next_slot) = buckets[bucket];
+            if next_slot == NIL {
+                break;
+            }
+            let home = Self::home(id);
+            if (hole + buckets.len() - home) % buckets.len() < (bucket + buckets.len() - home) % buckets.len() {
+                buckets[hole] = buckets[bucket];
+                hole = bucket;
+            }
+        }
+        buckets[hole] = (0, NIL);
+        Some(slot)
+    }
+}
+
+/// One symbol's book in fixed arrays. Levels are kept worst to best so the best
+/// level is always last and can be consumed without shifting.
+#[derive(Debug)]
+struct FixedMatcher<const MAX_LEVELS: usize, const MAX_ORDERS: usize> {
+    slots: [Option<Order>; MAX_ORDERS],
+    // Order id to slot, so cancels and reductions skip the level walk
+    ids: SlotIndex<MAX_ORDERS>,
+    next: [u32; MAX_ORDERS],
+    free: [u32; MAX_ORDERS],
+    free_len: usize,
+    bids: [Level; MAX_LEVELS],
+    bid_len: usize,
+    asks: [Level; MAX_LEVELS],
+    ask_len: usize,
+    last_trade_price: Option<u64>,
+    participants: ParticipantOrderCounts,
+    depth: DepthLimit,
+    evicted: Vec<Order>,
+    arrival_trades: Vec<Trade>,
+    match_stats: Option<MatchStats>,
+    level_changes: LevelJournal,
+    tie_breaker: TieBreaker,
+}
+
+impl<const MAX_LEVELS: usize, const MAX_ORDERS: usize> FixedMatcher<MAX_LEVELS, MAX_ORDERS> {
+    const CAPACITY_FITS_U32: () = assert!(MAX_ORDERS < NIL as usize, "MAX_ORDERS must fit in a u32 slot index");
+
+    fn new() -> Self {
+        let () = Self::CAPACITY_FITS_U32;
+        Self {
+            slots: [const { None }; MAX_ORDERS],
+            ids: SlotIndex::new(),
+            next: [NIL; MAX_ORDERS],
+            free: std::array::from_fn(|i| (MAX_ORDERS - 1 - i) as u32),
+            free_len: MAX_ORDERS,
+            bids: [Level::EMPTY; MAX_LEVELS],
+            bid_len: 0,
+            asks: [Level::EMPTY; MAX_LEVELS],
+            ask_len: 0,
+            last_trade_price: None,
+            participants: ParticipantOrderCounts::default(),
+            depth: DepthLimit::default(),
+            evicted: Vec::new(),
+            arrival_trades: Vec::new(),
+            match_stats: None,
+            level_changes: LevelJournal::default(),
+            tie_breaker: TieBreaker::default(),
+        }
+    }
+
+    /// Ordering of a level relative to `price`, worst first for the given side.
+    #[inline(always)]
+    fn compare(side: OrderSide, level_price: u64, price: u64) -> std::cmp::Ordering {
+        match side {
+            OrderSide::Buy => level_price.cmp(&price),
+            OrderSide::Sell => price.cmp(&level_price),
+        }
+    }
+
+    #[inline(always)]
+    fn side(&self, side: OrderSide) -> &[Level] {
+        match side {
+            OrderSide::Buy => &self.bids[..self.bid_len],
+            OrderSide::Sell => &self.asks[..self.ask_len],
+        }
+    }
+
+    /// Position of the level at `price`, if `side` has one.
+    #[inline(always)]
+    fn level_index(&self, side: OrderSide, price: u64) -> Option<usize> {
+        self.side(side).binary_search_by(|level| Self::compare(side, level.price, price)).ok()
+    }
+
+    #[inline(always)]
+    fn side_mut(&mut self, side: OrderSide) -> (&mut [Level; MAX_LEVELS], &mut usize) {
+        match side {
+            OrderSide::Buy => (&mut self.bids, &mut self.bid_len),
+            OrderSide::Sell => (&mut self.asks, &mut self.ask_len),
+        }
+    }
+
+    #[inline(always)]
+    fn add_order(&mut self, order: Order) -> Result<(), OrderBookError> {
+        if self.free_len == 0 {
+            return Err(OrderBookError::CapacityExceeded);
+        }
+
+        let side = order.order_type;
+        let price = order.price;
+        let (levels, len) = self.side_mut(side);
+        let index = match levels[..*len].binary_search_by(|level| Self::compare(side, level.price, price)) {
+            Ok(index) => index,
+            Err(_) if *len == MAX_LEVELS => return Err(OrderBookError::CapacityExceeded),
+            Err(index) => {
+                levels.copy_within(index..*len, index + 1);
+                levels[index] = Level { price, ..Level::EMPTY };
+                *len += 1;
+                index
+            }
+        };
+
+        self.free_len -= 1;
+        let slot = self.free[self.free_len];
+        let (levels, _) = self.side_mut(side);
+        let level = &mut levels[index];
+        let previous_tail = level.tail;
+        level.tail = slot;
+        if previous_tail == NIL {
+            level.head = slot;
+        }
+        level.count += 1;
+        level.quantity += order.quantity;
+        if previous_tail != NIL {
+            self.next[previous_tail as usize] = slot;
+        }
+        self.next[slot as usize] = NIL;
+        self.participants.record(&order);
+        self.level_changes.record(side, price, order.quantity);
+        self.ids.insert(order.id, slot);
+        self.slots[slot as usize] = Some(order);
+        if let Some(far_price) = self.depth.record(side, price) {
+            self.evict_level(side, far_price);
+        }
+        Ok(())
+    }
+
+    fn evict_level(&mut self, side: OrderSide, price: u64) {
+        let (levels, len) = match side {
+            OrderSide::Buy => (&mut self.bids, &mut self.bid_len),
+            OrderSide::Sell => (&mut self.asks, &mut self.ask_len),
+        };
+        let Ok(index) = levels[..*len].binary_search_by(|level| Self::compare(side, level.price, price)) else {
+            return;
+        };
+        let mut slot = levels[index].head;
+        levels.copy_within(index + 1..*len, index);
+        *len -= 1;
+        while slot != NIL {
+            if let Some(order) = self.slots[slot as usize].take() {
+                self.ids.remove(order.id);
+                self.participants.release(&order);
+                self.level_changes.release(&order);
+                self.evicted.push(order);
+            }
+            self.free[self.free_len] = slot;
+            self.free_len += 1;
+            slot = self.next[slot as usize];
+        }
+    }
+
+    /// Picks the next order to match at the best level, returning its position in
+    /// the level alongside it.
+    #[inline(always)]
+    fn peek_best(&mut self, side: OrderSide) -> Option<(usize, Order)> {
+        let level = *self.side(side).last()?;
+        let index = if self.tie_breaker.is_fifo() {
+            0
+        } else {
+            let (slots, next, mut slot) = (&self.slots, &self.next, level.head);
+            let quantities = std::iter::from_fn(|| {
+                let quantity = slots.get(slot as usize)?.as_ref().map_or(0, |order| order.quantity);
+                slot = next[slot as usize];
+                Some(quantity)
+            });
+            self.tie_breaker.pick(level.count as usize, quantities)?
+        };
+        let order = self.level_orders(&level).nth(index)?.clone();
+        Some((index, order))
+    }
+
+    /// Fills `quantity` of the order at `index` in the best level. A partly filled
+    /// order keeps its slot; a fully filled one is unlinked and its slot freed.
+    #[inline(always)]
+    fn fill_best(&mut self, side: OrderSide, index: usize, quantity: u64) {
+        let (levels, len) = match side {
+            OrderSide::Buy => (&mut self.bids, &mut self.bid_len),
+            OrderSide::Sell => (&mut self.asks, &mut self.ask_len),
+        };
+        let Some(level) = levels[..*len].last_mut() else { return };
+        let (mut previous, mut slot) = (NIL, level.head);
+        for _ in 0..index {
+            previous = slot;
+            slot = self.next[slot as usize];
+        }
+        let Some(order) = self.slots[slot as usize].as_mut() else { return };
+        level.quantity -= quantity;
+        order.quantity -= quantity;
+        if order.quantity > 0 {
+            return;
+        }
+        let order_id = order.id;
+        self.ids.remove(order_id);
+        self.slots[slot as usize] = None;
+        let next = self.next[slot as usize];
+        if previous == NIL {
+            level.head = next;
+        } else {
+            self.next[previous as usize] = next;
+        }
+        if level.tail == slot {
+            level.tail = previous;
+        }
+        level.count -= 1;
+        if level.count == 0 {
+            *len -= 1;
+        }
+        self.free[self.free_len] = slot;
+        self.free_len += 1;
+    }
+
+    #[inline(always)]
+    fn best_price(&self, side: OrderSide) -> Option<u64> {
+        self.side(side).last().map(|level| level.price)
+    }
+
+    #[inline(always)]
+    fn can_match(&self) -> bool {
+        match (self.best_price(OrderSide::Buy), self.best_price(OrderSide::Sell)) {
+            (Some(bid), Some(ask)) => bid >= ask,
+            _ => false,
+        }
+    }
+
+    fn match_orders(&mut self, rule: TradePriceRule, trades: &mut Vec<Trade>) {
+        if !self.can_match() {
+            return;
+        }
+        let mut cycle = MatchCycle::start();
+        while self.can_match() {
+            let (Some((bid_index, bid_order)), Some((ask_index, ask_order))) = (self.peek_best(OrderSide::Buy), self.peek_best(OrderSide::Sell)) else {
+                break;
+            };
+            self.last_trade_price = Some(cycle.record_trade(&bid_order, &ask_order, rule));
+            let quantity = bid_order.quantity.min(ask_order.quantity);
+            self.fill_best(OrderSide::Buy, bid_index, quantity);
+            self.fill_best(OrderSide::Sell, ask_index, quantity);
+            if self.best_price(OrderSide::Buy) != Some(bid_order.price) {
+                cycle.level_cleared();
+            }
+            if self.best_price(OrderSide::Sell) != Some(ask_order.price) {
+                cycle.level_cleared();
+            }
+            self.settle_fill(&bid_order, quantity);
+            self.settle_fill(&ask_order, quantity);
+        }
+        self.match_stats = Some(cycle.finish(trades));
+    }
+
+    /// Books a fill of `quantity` against `order` as it stood before the trade.
+    #[inline(always)]
+    fn settle_fill(&mut self, order: &Order, quantity: u64) {
+        if quantity == order.quantity {
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        } else {
+            self.level_changes.reduce(order, quantity);
+        }
+    }
+
+    fn level_orders(&self, level: &Level) -> impl Iterator<Item = &Order> + '_ {
+        let mut slot = level.head;
+        std::iter::from_fn(move || {
+            if slot == NIL {
+                return None;
+            }
+            let order = self.slots[slot as usize].as_ref();
+            slot = self.next[slot as usize];
+            order
+        })
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Option<Result<Order, OrderBookError>> {
+        let slot = self.ids.get(order_id)?;
+        let order = self.slots[slot as usize].as_mut()?;
+        let (side, price) = (order.order_type, order.price);
+        let reduced = reduction::reduce(order, quantity_delta);
+        if let Ok(order) = &reduced {
+            let index = self.level_index(side, price)?;
+            self.side_mut(side).0[index].quantity -= quantity_delta;
+            self.level_changes.reduce(order, quantity_delta);
+        }
+        Some(reduced)
+    }
+
+    /// Unlinks the order's slot, finding its level by price and its predecessor by
+    /// walking the level from the head, and drops the level if that emptied it.
+    fn cancel_order(&mut self, order_id: u64) -> Option<Order> {
+        let slot = self.ids.remove(order_id)?;
+        let order = self.slots[slot as usize].take()?;
+        let side = order.order_type;
+        let index = self.level_index(side, order.price)?;
+        let (levels, len) = match side {
+            OrderSide::Buy => (&mut self.bids, &mut self.bid_len),
+            OrderSide::Sell => (&mut self.asks, &mut self.ask_len),
+        };
+        let level = &mut levels[index];
+        let (mut previous, mut current) = (NIL, level.head);
+        while current != slot && current != NIL {
+            previous = current;
+            current = self.next[current as usize];
+        }
+        let next = self.next[slot as usize];
+        if previous == NIL {
+            level.head = next;
+        } else {
+            self.next[previous as usize] = next;
+        }
+        if level.tail == slot {
+            level.tail = previous;
+        }
+        level.count -= 1;
+        level.quantity -= order.quantity;
+        if level.count == 0 {
+            levels.copy_within(index + 1..*len, index);
+            *len -= 1;
+        }
+        self.free[self.free_len] = slot;
+        self.free_len += 1;
+        self.participants.release(&order);
+        self.depth.release(&order);
+        self.level_changes.release(&order);
+        Some(order)
+    }
+
+    // Unlinks matching slots level by level, then compacts the levels left empty
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool, cancelled: &mut Vec<Order>) {
+        let start = cancelled.len();
+        for (levels, len) in [(&mut self.bids, &mut self.bid_len), (&mut self.asks, &mut self.ask_len)] {
+            let mut kept = 0;
+            for index in 0..*len {
+                let mut level = levels[index];
+                let (mut slot, mut previous) = (level.head, NIL);
+                while slot != NIL {
+                    let next = self.next[slot as usize];
+                    if let Some(order) = self.slots[slot as usize].take_if(|order| predicate(order)) {
+                        if previous == NIL {
+                            level.head = next;
+                        } else {
+                            self.next[previous as usize] = next;
+                        }
+                        if level.tail == slot {
+                            level.tail = previous;
+                        }
+                        level.count -= 1;
+                        level.quantity -= order.quantity;
+                        self.free[self.free_len] = slot;
+                        self.free_len += 1;
+                        cancelled.push(order);
+                    } else {
+                        previous = slot;
+                    }
+                    slot = next;
+                }
+                if level.count > 0 {
+                    levels[kept] = level;
+                    kept += 1;
+                }
+            }
+            *len = kept;
+        }
+        for order in &cancelled[start..] {
+            self.ids.remove(order.id);
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        }
+    }
+
+    fn resting_orders(&self) -> Vec<Order> {
+        [OrderSide::Buy, OrderSide::Sell].into_iter()
+            .flat_map(|side| self.side(side).iter().rev())
+            .flat_map(|level| self.level_orders(level).cloned())
+            .collect()
+    }
+}
+
+impl<const MAX_LEVELS: usize, const MAX_ORDERS: usize> TakeOnArrival for FixedMatcher<MAX_LEVELS, MAX_ORDERS> {
+    type Slot = usize;
+
+    fn next_resting(&mut self, taker: &Order) -> Option<(Self::Slot, Order)> {
+        let side = taker.order_type.opposite();
+        self.best_price(side).filter(|&price| taker.order_type.crosses(taker.price, price))?;
+        self.peek_best(side)
+    }
+
+    fn fill_resting(&mut self, index: Self::Slot, resting: &Order, quantity: u64) -> bool {
+        let side = resting.order_type;
+        self.fill_best(side, index, quantity);
+        self.settle_fill(resting, quantity);
+        self.best_price(side) != Some(resting.price)
+    }
+
+    fn arrived(&mut self, mut trades: Vec<Trade>, last_trade_price: Option<u64>, remainder: Option<Order>) {
+        self.arrival_trades.append(&mut trades);
+        self.last_trade_price = last_trade_price.or(self.last_trade_price);
+        self.evicted.extend(remainder);
+    }
+}
+
+/// Fixed-capacity book for embedded and simulation targets. Each symbol's storage is
+/// sized at construction by `MAX_LEVELS` price levels per side and `MAX_ORDERS`
+/// resting orders, so adding and matching never allocate. Orders that would need a
+/// new level or slot beyond that are rejected with `CapacityExceeded`. Unlike the other
+/// books, storage is allocated for every registered symbol up front rather than on its
+/// first order.
+pub struct FixedCapacityOrderBook<const MAX_LEVELS: usize = 64, const MAX_ORDERS: usize = 1024, B: MapBackend = FxMaps> {
+    symbols: FxHashSet<SymbolId>,
+    matchers: B::Matchers<Box<FixedMatcher<MAX_LEVELS, MAX_ORDERS>>>,
+    trade_price_rule: TradePriceRule,
+    tie_break: TieBreak,
+    participant_limits: ParticipantLimits,
+    tick_sizes: TickSizes,
+    lot_sizes: LotSizes,
+    max_depth: Option<usize>,
+    level_journal: bool,
+}
+
+impl<const MAX_LEVELS: usize, const MAX_ORDERS: usize> FixedCapacityOrderBook<MAX_LEVELS, MAX_ORDERS> {
+    pub fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        <Self as OrderBookCore>::new(symbols)
+    }
+}
+
+impl<const MAX_LEVELS: usize, const MAX_ORDERS: usize, B: MapBackend> OrderBookCore for FixedCapacityOrderBook<MAX_LEVELS, MAX_ORDERS, B> {
+    fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        let mut matchers = B::Matchers::default();
+        for &symbol in &symbols {
+            matchers.get_or_create(symbol, || Some(Box::new(FixedMatcher::new())));
+        }
+        FixedCapacityOrderBook {
+            symbols,
+            matchers,
+            trade_price_rule: TradePriceRule::default(),
+            tie_break: TieBreak::default(),
+            participant_limits: ParticipantLimits::default(),
+            tick_sizes: TickSizes::default(),
+            lot_sizes: LotSizes::default(),
+            max_depth: None,
+            level_journal: false,
+        }
+    }
+
+    #[inline(always)]
+    fn add_order(&mut self, order: Order) -> Result<bool, OrderBookError> {
+        let order = self.tick_sizes.conform(order)?;
+        self.lot_sizes.check(&order)?;
+        let matcher = self.matchers.get_mut(order.symbol).ok_or(OrderBookError::InvalidSymbol)?;
+        if !matcher.participants.admits(&order) {
+            return Err(OrderBookError::ParticipantLimitExceeded);
+        }
+        if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+            match_stats::take_on_arrival(&mut **matcher, order, self.trade_price_rule);
+        } else {
+            matcher.add_order(order)?;
+        }
+        Ok(true)
+    }
+
+    #[inline(always)]
+    fn add_order_fast(&mut self, order: Order) -> bool {
+        self.add_order(order).is_ok()
+    }
+
+    #[inline(always)]
+    unsafe fn add_order_unchecked(&mut self, order: Order) {
+        let matcher = unsafe { self.matchers.get_mut(order.symbol).unwrap_unchecked() };
+        if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+            match_stats::take_on_arrival(&mut **matcher, order, self.trade_price_rule);
+        } else {
+            let _ = matcher.add_order(order);
+        }
+    }
+
+    #[inline(always)]
+    fn match_orders(&mut self) -> Vec<Trade> {
+        let rule = self.trade_price_rule;
+        let mut trades = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            trades.append(&mut matcher.arrival_trades);
+            matcher.match_orders(rule, &mut trades);
+        }
+        trades
+    }
+
+    #[inline(always)]
+    fn add_orders_batch_fast(&mut self, orders: &[Order]) -> (u32, u32) {
+        let mut success = 0;
+        let mut failed = 0;
+        for order in orders {
+            if self.add_order_fast(order.clone()) {
+                success += 1;
+            } else {
+                failed += 1;
+            }
+        }
+        (success, failed)
+    }
+
+    #[inline(always)]
+    unsafe fn add_orders_batch_unchecked(&mut self, orders: &[Order]) -> u32 {
+        for order in orders {
+            unsafe { self.add_order_unchecked(order.clone()); }
+        }
+        orders.len() as u32
+    }
+
+    #[inline(always)]
+    fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
+        self.matchers.get(symbol)
+            .map(|matcher| (matcher.best_price(OrderSide::Buy), matcher.best_price(OrderSide::Sell)))
+    }
+
+    #[inline(always)]
+    fn can_match(&self, symbol: SymbolId) -> bool {
+        self.matchers.get(symbol).is_some_and(|matcher| matcher.can_match())
+    }
+
+    #[inline(always)]
+    fn is_valid_symbol(&self, symbol: SymbolId) -> bool {
+        self.symbols.contains(&symbol)
+    }
+
+    #[inline(always)]
+    fn get_symbols(&self) -> &FxHashSet<SymbolId> {
+        &self.symbols
+    }
+
+    #[inline(always)]
+    fn order_book_type(&self) -> OrderBookType {
+        OrderBookType::FixedCapacity
+    }
+}
+
+impl<const MAX_LEVELS: usize, const MAX_ORDERS: usize, B: MapBackend> OrderBookLifecycle for FixedCapacityOrderBook<MAX_LEVELS, MAX_ORDERS, B> {
+    fn resting_orders(&self, symbol: SymbolId) -> Option<Vec<Order>> {
+        self.matchers.get(symbol).map(|matcher| matcher.resting_orders())
+    }
+
+    #[inline(always)]
+    fn set_trade_price_rule(&mut self, rule: TradePriceRule) {
+        self.trade_price_rule = rule;
+    }
+
+    #[inline(always)]
+    fn trade_price_rule(&self) -> TradePriceRule {
+        self.trade_price_rule
+    }
+
+    fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+        for matcher in self.matchers.values_mut() {
+            matcher.tie_breaker = TieBreaker::new(tie_break);
+        }
+    }
+
+    #[inline(always)]
+    fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    /// Unlike order entry, tracking participant caps allocates.
+    fn set_participant_limits(&mut self, limits: ParticipantLimits) {
+        self.participant_limits = limits;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.participants.set_limits(limits, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn participant_limits(&self) -> ParticipantLimits {
+        self.participant_limits
+    }
+
+    fn set_tick_sizes(&mut self, tick_sizes: TickSizes) {
+        self.tick_sizes = tick_sizes;
+    }
+
+    #[inline(always)]
+    fn tick_sizes(&self) -> &TickSizes {
+        &self.tick_sizes
+    }
+
+    fn set_lot_sizes(&mut self, lot_sizes: LotSizes) {
+        self.lot_sizes = lot_sizes;
+    }
+
+    #[inline(always)]
+    fn lot_sizes(&self) -> &LotSizes {
+        &self.lot_sizes
+    }
+
+    fn set_max_depth(&mut self, max_levels: Option<usize>) {
+        self.max_depth = max_levels;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            for (side, price) in matcher.depth.set_max_levels(max_levels, &resting) {
+                matcher.evict_level(side, price);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    fn take_evicted_orders(&mut self) -> Vec<Order> {
+        self.matchers.values_mut().flat_map(|matcher| matcher.evicted.drain(..)).collect()
+    }
+
+    /// Like participant caps, journaling allocates.
+    fn set_level_journal(&mut self, enabled: bool) {
+        self.level_journal = enabled;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.level_changes.set_enabled(enabled, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn level_journal(&self) -> bool {
+        self.level_journal
+    }
+
+    fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>> {
+        self.matchers.get_mut(symbol).map(|matcher| matcher.level_changes.take())
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Result<Order, OrderBookError> {
+        self.matchers.values_mut()
+            .find_map(|matcher| matcher.reduce_order(order_id, quantity_delta))
+            .unwrap_or(Err(OrderBookError::OrderNotFound))
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool) -> Vec<Order> {
+        let mut cancelled = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            matcher.cancel_where(predicate, &mut cancelled);
+        }
+        cancelled
+    }
+
+    fn cancel_order(&mut self, symbol: SymbolId, order_id: u64) -> Result<Order, OrderBookError> {
+        lazy_matchers::cancel_in(&mut self.matchers, &self.symbols, symbol, |matcher| matcher.cancel_order(order_id))
+    }
+
+    fn cancel_orders(&mut self, order_ids: &FxHashSet<u64>) -> Vec<Order> {
+        lazy_matchers::cancel_each_in(&mut self.matchers, order_ids, |matcher, order_id| matcher.cancel_order(order_id))
+    }
+}
+
+impl<const MAX_LEVELS: usize, const MAX_ORDERS: usize, B: MapBackend> OrderBookMarketData for FixedCapacityOrderBook<MAX_LEVELS, MAX_ORDERS, B> {
+    fn price_levels(&self, symbol: SymbolId, side: OrderSide, with_order_ids: bool) -> Option<PriceLevelIter<'_>> {
+        let matcher = self.matchers.get(symbol)?;
+        Some(Box::new(matcher.side(side).iter().rev().map(move |level| PriceLevelView {
+            price: level.price,
+            total_quantity: level.quantity,
+            order_count: level.count as usize,
+            order_ids: with_order_ids.then(|| matcher.level_orders(level).map(|order| order.id).collect()),
+        })))
+    }
+
+    #[inline(always)]
+    fn last_trade_price(&self, symbol: SymbolId) -> Option<u64> {
+        self.matchers.get(symbol).and_then(|matcher| matcher.last_trade_price)
+    }
+
+    #[inline(always)]
+    fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats> {
+        self.matchers.get(symbol).and_then(|matcher| matcher.match_stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    fn test_fixed_capacity_rejects_when_full() {
+        let mut order_book = FixedCapacityOrderBook::<2, 3>::new(FxHashSet::from_iter([0]));
+
+        assert!(order_book.add_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).is_ok());
+        assert!(order_book.add_order(new_order(2, 0, 10, 99.0, OrderSide::Buy)).is_ok());
+        assert!(matches!(
+            order_book.add_order(new_order(3, 0, 10, 98.0, OrderSide::Buy)),
+            Err(OrderBookError::CapacityExceeded)
+        ));
+        assert!(order_book.add_order(new_order(4, 0, 10, 99.0, OrderSide::Buy)).is_ok());
+        assert!(!order_book.add_order_fast(new_order(5, 0, 10, 101.0, OrderSide::Sell)));
+
+        order_book.add_order_fast(new_order(6, 0, 10, 90.0, OrderSide::Sell));
+        assert_eq!(order_book.resting_orders(0).unwrap().iter().map(|order| order.id).collect::<Vec<_>>(), vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_fixed_capacity_reuses_slots_after_matching() {
+        let mut order_book = FixedCapacityOrderBook::<4, 2>::new(FxHashSet::from_iter([0]));
+
+        for round in 0..10 {
+            assert!(order_book.add_order_fast(new_order(round * 2, 0, 10, 100.0, OrderSide::Buy)));
+            assert!(order_book.add_order_fast(new_order(round * 2 + 1, 0, 10, 99.0, OrderSide::Sell)));
+            order_book.match_orders();
+            assert_eq!(order_book.get_best_prices(0), Some((None, None)));
+        }
+        assert!(order_book.last_trade_price(0).is_some());
+    }
+
+    #[test]
+    fn test_slot_index_keeps_probe_runs_after_removals() {
+        let mut index = SlotIndex::<8>::new();
+        let mut expected = std::collections::HashMap::new();
+        let mut state = 7u64;
+        for step in 0..2_000u32 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let order_id = state >> 60;
+            if expected.len() < 8 && !expected.contains_key(&order_id) {
+                index.insert(order_id, step);
+                expected.insert(order_id, step);
+            } else {
+                assert_eq!(index.remove(order_id), expected.remove(&order_id));
+            }
+            for order_id in 0..16 {
+                assert_eq!(index.get(order_id), expected.get(&order_id).copied());
+            }
+        }
+        assert_eq!(SlotIndex::<0>::new().get(1), None);
+    }
+}
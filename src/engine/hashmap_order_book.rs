@@ -1,9 +1,36 @@
 use rustc_hash::FxHashSet;
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-use crate::engine::order_book_trait::{OrderBookTrait, OrderBookError};
+use crate::engine::order_book_trait::{OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData};
 use crate::engine::OrderBookType;
-use crate::types::{order::{self, Order}, symbol_mapping::SymbolId};
+use crate::engine::price_level_view::{PriceLevelIter, PriceLevelView};
+use crate::engine::participant_limits::{ParticipantLimits, ParticipantOrderCounts};
+use crate::engine::lot_size::LotSizes;
+use crate::engine::symbol_map::{FxMaps, IdMap, MapBackend, SymbolMap};
+use crate::engine::tick_size::TickSizes;
+use crate::engine::depth_limit::DepthLimit;
+use crate::engine::match_stats::{self, MatchCycle, MatchStats, TakeOnArrival};
+use crate::engine::level_journal::{LevelChange, LevelJournal};
+use crate::engine::tie_break::{TieBreak, TieBreaker};
+use crate::engine::reduction;
+use crate::engine::lazy_matchers;
+use crate::types::{order::{self, Order, OrderFlags}, symbol_mapping::SymbolId, trade::{Trade, TradePriceRule}};
+
+/// Order slots a new price level reserves unless the book is configured otherwise.
+pub const DEFAULT_LEVEL_CAPACITY: usize = 128;
+
+/// Rough heap footprint of a `HashMapOrderBook`'s levels, for weighing the level
+/// capacity against allocation churn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelMemoryEstimate {
+    pub levels: usize,
+    pub orders: usize,
+    /// Order slots allocated across all levels, used or not.
+    pub reserved_order_slots: usize,
+    /// Levels and reserved slots in bytes. Map node overhead is approximated by the
+    /// key size, so treat this as a lower bound.
+    pub estimated_bytes: usize,
+}
 
 #[repr(align(64))]
 #[derive(Debug)]
@@ -15,9 +42,9 @@ struct PriceLevel {
 }
 
 impl PriceLevel {
-    fn new() -> Self {
+    fn new(capacity: usize) -> Self {
         Self {
-            orders: VecDeque::with_capacity(128),
+            orders: VecDeque::with_capacity(capacity),
             count: 0,
             total_quantity: 0,
             _padding: [0; 28],
@@ -36,72 +63,150 @@ impl PriceLevel {
         self.orders.push_back(order);
     }
 
+    /// Picks the next order to match, leaving it on the level until it is filled.
+    #[inline(always)]
+    fn peek_next(&self, tie_breaker: &mut TieBreaker) -> Option<(usize, order::Order)> {
+        let index = tie_breaker.pick(self.orders.len(), self.orders.iter().map(|order| order.quantity))?;
+        self.orders.get(index).map(|order| (index, order.clone()))
+    }
+
+    /// Fills `quantity` of the order at `index`. A partly filled order keeps its place
+    /// in the queue; a fully filled one leaves the level.
     #[inline(always)]
-    fn pop_front(&mut self) -> Option<order::Order> {
-        if let Some(order) = self.orders.pop_front() {
-            self.total_quantity -= order.quantity;
+    fn fill(&mut self, index: usize, quantity: u64) {
+        self.total_quantity -= quantity;
+        let order = &mut self.orders[index];
+        order.quantity -= quantity;
+        if order.quantity == 0 {
+            self.orders.remove(index);
             self.count -= 1;
-            Some(order)
-        } else {
-            None
+        }
+    }
+
+    fn remove(&mut self, order_id: u64) -> Option<order::Order> {
+        let index = self.orders.iter().position(|order| order.id == order_id)?;
+        let order = self.orders.remove(index)?;
+        self.total_quantity -= order.quantity;
+        self.count -= 1;
+        Some(order)
+    }
+
+    /// Gives back slots a burst grew the queue to once it drains to a quarter of them,
+    /// never going below `capacity`. Emptied levels are dropped from the book entirely.
+    #[inline(always)]
+    fn shrink(&mut self, capacity: usize) {
+        let allocated = self.orders.capacity();
+        if allocated > capacity && self.orders.len() <= allocated / 4 {
+            self.orders.shrink_to(capacity.max(self.orders.len() * 2));
         }
     }
 }
 
 #[repr(align(64))]
 #[derive(Debug)]
-struct HashMapMatcher {
+struct HashMapMatcher<B: MapBackend> {
     bid_levels: BTreeMap<u64, PriceLevel>,
     ask_levels: BTreeMap<u64, PriceLevel>,
+    // Cached touch, kept in step with the level maps so best price queries skip the
+    // BTreeMap. Levels never rest empty, so only removing the touch level moves it
+    // away from the book.
+    best_bid: Option<u64>,
+    best_ask: Option<u64>,
+    // Side and price of every resting order, so a cancel or reduction goes straight to its level
+    order_index: B::Ids<(order::OrderSide, u64)>,
+    // (expires_at, id) of every resting good-till-date order, soonest first
+    expiry_index: BTreeSet<(u64, u64)>,
+    last_trade_price: Option<u64>,
+    participants: ParticipantOrderCounts,
+    depth: DepthLimit,
+    evicted: Vec<Order>,
+    arrival_trades: Vec<Trade>,
+    match_stats: Option<MatchStats>,
+    level_changes: LevelJournal,
+    tie_breaker: TieBreaker,
+    level_capacity: usize,
     _padding: [u8; 48],
 }
 
-impl HashMapMatcher {
+impl<B: MapBackend> HashMapMatcher<B> {
     pub fn new() -> Self {
         Self {
             bid_levels: BTreeMap::new(),
             ask_levels: BTreeMap::new(),
+            best_bid: None,
+            best_ask: None,
+            order_index: Default::default(),
+            expiry_index: BTreeSet::new(),
+            last_trade_price: None,
+            participants: ParticipantOrderCounts::default(),
+            depth: DepthLimit::default(),
+            evicted: Vec::new(),
+            arrival_trades: Vec::new(),
+            match_stats: None,
+            level_changes: LevelJournal::default(),
+            tie_breaker: TieBreaker::default(),
+            level_capacity: DEFAULT_LEVEL_CAPACITY,
             _padding: [0; 48],
         }
     }
 
     #[inline(always)]
     pub fn add_order(&mut self, order: order::Order) {
-        let price = order.price;
+        self.participants.record(&order);
+        self.level_changes.record(order.order_type, order.price, order.quantity);
+        let (price, side) = (order.price, order.order_type);
+        let evict = self.depth.record(side, price);
+        self.index(&order);
         
-        match order.order_type {
+        match side {
             order::OrderSide::Buy => {
                 self.bid_levels.entry(price)
-                    .or_insert_with(PriceLevel::new)
+                    .or_insert_with(|| PriceLevel::new(self.level_capacity))
                     .push_back(order);
             }
             order::OrderSide::Sell => {
                 self.ask_levels.entry(price)
-                    .or_insert_with(PriceLevel::new)
+                    .or_insert_with(|| PriceLevel::new(self.level_capacity))
                     .push_back(order);
             }
         }
+        self.level_added(side, price);
+        if let Some(far_price) = evict {
+            self.evict_level(side, far_price);
+        }
     }
 
     #[inline(always)]
     unsafe fn add_order_unchecked(&mut self, order: order::Order) {
-        let price = order.price;
+        self.participants.record(&order);
+        self.level_changes.record(order.order_type, order.price, order.quantity);
+        let (price, side) = (order.price, order.order_type);
+        let evict = self.depth.record(side, price);
+        self.index(&order);
         
-        match order.order_type {
+        match side {
             order::OrderSide::Buy => {
                 self.bid_levels.entry(price)
-                    .or_insert_with(PriceLevel::new)
+                    .or_insert_with(|| PriceLevel::new(self.level_capacity))
                     .push_back(order);
             }
             order::OrderSide::Sell => {
                 self.ask_levels.entry(price)
-                    .or_insert_with(PriceLevel::new)
+                    .or_insert_with(|| PriceLevel::new(self.level_capacity))
                     .push_back(order);
             }
         }
+        self.level_added(side, price);
+        if let Some(far_price) = evict {
+            self.evict_level(side, far_price);
+        }
     }
 
-    pub fn match_orders(&mut self) {
+    pub fn match_orders(&mut self, rule: TradePriceRule, trades: &mut Vec<Trade>) {
+        if !self.can_match() {
+            return;
+        }
+        let mut cycle = MatchCycle::start();
         loop {
             let can_match = match (self.get_best_bid(), self.get_best_ask()) {
                 (Some(bid_price), Some(ask_price)) => bid_price >= ask_price,
@@ -115,38 +220,121 @@ impl HashMapMatcher {
             let bid_price = self.get_best_bid().unwrap();
             let ask_price = self.get_best_ask().unwrap();
 
-            let bid_order = self.bid_levels.get_mut(&bid_price)
-                .and_then(|level| level.pop_front());
-            let ask_order = self.ask_levels.get_mut(&ask_price)
-                .and_then(|level| level.pop_front());
-
-            match (bid_order, ask_order) {
-                (Some(_bid_order), Some(_ask_order)) => {
-                    if self.bid_levels.get(&bid_price).is_none_or(|level| level.is_empty()) {
-                        self.bid_levels.remove(&bid_price);
+            let bid_next = self.bid_levels.get(&bid_price)
+                .and_then(|level| level.peek_next(&mut self.tie_breaker));
+            let ask_next = self.ask_levels.get(&ask_price)
+                .and_then(|level| level.peek_next(&mut self.tie_breaker));
+
+            match (bid_next, ask_next) {
+                (Some((bid_index, bid_order)), Some((ask_index, ask_order))) => {
+                    self.last_trade_price = Some(cycle.record_trade(&bid_order, &ask_order, rule));
+                    let quantity = bid_order.quantity.min(ask_order.quantity);
+                    self.settle_fill(&bid_order, quantity);
+                    self.settle_fill(&ask_order, quantity);
+                    if self.fill_level(order::OrderSide::Buy, bid_price, bid_index, quantity) {
+                        cycle.level_cleared();
                     }
-                    if self.ask_levels.get(&ask_price).is_none_or(|level| level.is_empty()) {
-                        self.ask_levels.remove(&ask_price);
+                    if self.fill_level(order::OrderSide::Sell, ask_price, ask_index, quantity) {
+                        cycle.level_cleared();
                     }
                 }
                 _ => break,
             }
         }
+        self.match_stats = Some(cycle.finish(trades));
+    }
+
+    /// Fills the order at `index` of the level at `price`, dropping the level once it
+    /// empties. Returns whether it did.
+    #[inline(always)]
+    fn fill_level(&mut self, side: order::OrderSide, price: u64, index: usize, quantity: u64) -> bool {
+        let levels = match side {
+            order::OrderSide::Buy => &mut self.bid_levels,
+            order::OrderSide::Sell => &mut self.ask_levels,
+        };
+        let Some(level) = levels.get_mut(&price) else {
+            return false;
+        };
+        level.fill(index, quantity);
+        if !level.is_empty() {
+            level.shrink(self.level_capacity);
+            return false;
+        }
+        levels.remove(&price);
+        self.refresh_best(side);
+        true
+    }
+
+    #[inline(always)]
+    fn index(&mut self, order: &Order) {
+        self.order_index.insert(order.id, (order.order_type, order.price));
+        if let Some(expires_at) = order.expires_at {
+            self.expiry_index.insert((expires_at, order.id));
+        }
+    }
+
+    #[inline(always)]
+    fn unindex(&mut self, order: &Order) {
+        self.order_index.remove(order.id);
+        if let Some(expires_at) = order.expires_at {
+            self.expiry_index.remove(&(expires_at, order.id));
+        }
+    }
+
+    /// Books a fill of `quantity` against `order` as it stood before the trade.
+    #[inline(always)]
+    fn settle_fill(&mut self, order: &Order, quantity: u64) {
+        if quantity == order.quantity {
+            self.unindex(order);
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        } else {
+            self.level_changes.reduce(order, quantity);
+        }
+    }
+
+    fn evict_level(&mut self, side: order::OrderSide, price: u64) {
+        let levels = match side {
+            order::OrderSide::Buy => &mut self.bid_levels,
+            order::OrderSide::Sell => &mut self.ask_levels,
+        };
+        if let Some(level) = levels.remove(&price) {
+            for order in level.orders {
+                self.unindex(&order);
+                self.participants.release(&order);
+                self.level_changes.release(&order);
+                self.evicted.push(order);
+            }
+            self.refresh_best(side);
+        }
+    }
+
+    #[inline(always)]
+    fn level_added(&mut self, side: order::OrderSide, price: u64) {
+        match side {
+            order::OrderSide::Buy => self.best_bid = self.best_bid.max(Some(price)),
+            order::OrderSide::Sell => self.best_ask = Some(self.best_ask.map_or(price, |best| best.min(price))),
+        }
+    }
+
+    /// Re-reads one side's touch from its level map after levels were removed.
+    #[inline(always)]
+    fn refresh_best(&mut self, side: order::OrderSide) {
+        match side {
+            order::OrderSide::Buy => self.best_bid = self.bid_levels.keys().next_back().copied(),
+            order::OrderSide::Sell => self.best_ask = self.ask_levels.keys().next().copied(),
+        }
     }
 
     #[inline(always)]
     fn get_best_bid(&self) -> Option<u64> {
-        self.bid_levels.iter()
-            .rev()
-            .find(|(_, level)| !level.is_empty())
-            .map(|(&price, _)| price)
+        self.best_bid
     }
 
     #[inline(always)]
     fn get_best_ask(&self) -> Option<u64> {
-        self.ask_levels.iter()
-            .find(|(_, level)| !level.is_empty())
-            .map(|(&price, _)| price)
+        self.best_ask
     }
 
     #[inline(always)]
@@ -160,30 +348,215 @@ impl HashMapMatcher {
             _ => false,
         }
     }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Option<Result<Order, OrderBookError>> {
+        let &(side, price) = self.order_index.get(order_id)?;
+        let level = match side {
+            order::OrderSide::Buy => self.bid_levels.get_mut(&price)?,
+            order::OrderSide::Sell => self.ask_levels.get_mut(&price)?,
+        };
+        let order = level.orders.iter_mut().find(|order| order.id == order_id)?;
+        let reduced = reduction::reduce(order, quantity_delta);
+        if let Ok(order) = &reduced {
+            level.total_quantity -= quantity_delta;
+            self.level_changes.reduce(order, quantity_delta);
+        }
+        Some(reduced)
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool, cancelled: &mut Vec<Order>) {
+        let start = cancelled.len();
+        let capacity = self.level_capacity;
+        for levels in [&mut self.bid_levels, &mut self.ask_levels] {
+            levels.retain(|_, level| {
+                level.orders.retain(|order| {
+                    if !predicate(order) {
+                        return true;
+                    }
+                    level.total_quantity -= order.quantity;
+                    level.count -= 1;
+                    cancelled.push(order.clone());
+                    false
+                });
+                level.shrink(capacity);
+                !level.is_empty()
+            });
+        }
+        self.refresh_best(order::OrderSide::Buy);
+        self.refresh_best(order::OrderSide::Sell);
+        for order in &cancelled[start..] {
+            self.unindex(order);
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        }
+    }
+
+    fn cancel_order(&mut self, order_id: u64) -> Option<Order> {
+        let (side, price) = self.order_index.remove(order_id)?;
+        let levels = match side {
+            order::OrderSide::Buy => &mut self.bid_levels,
+            order::OrderSide::Sell => &mut self.ask_levels,
+        };
+        let level = levels.get_mut(&price)?;
+        let order = level.remove(order_id)?;
+        if level.is_empty() {
+            levels.remove(&price);
+            self.refresh_best(side);
+        } else {
+            level.shrink(self.level_capacity);
+        }
+        if let Some(expires_at) = order.expires_at {
+            self.expiry_index.remove(&(expires_at, order_id));
+        }
+        self.participants.release(&order);
+        self.depth.release(&order);
+        self.level_changes.release(&order);
+        Some(order)
+    }
+
+    fn take_expired(&mut self, now: u64, expired: &mut Vec<Order>) {
+        while let Some(&(expires_at, order_id)) = self.expiry_index.first() && expires_at <= now {
+            self.expiry_index.pop_first();
+            expired.extend(self.cancel_order(order_id));
+        }
+    }
+
+    fn add_memory(&self, estimate: &mut LevelMemoryEstimate) {
+        for level in self.bid_levels.values().chain(self.ask_levels.values()) {
+            estimate.levels += 1;
+            estimate.orders += level.orders.len();
+            estimate.reserved_order_slots += level.orders.capacity();
+        }
+    }
+
+    fn resting_orders(&self) -> Vec<Order> {
+        self.bid_levels.values().rev()
+            .chain(self.ask_levels.values())
+            .flat_map(|level| level.orders.iter().cloned())
+            .collect()
+    }
+}
+
+impl<B: MapBackend> TakeOnArrival for HashMapMatcher<B> {
+    type Slot = (u64, usize);
+
+    fn next_resting(&mut self, taker: &Order) -> Option<(Self::Slot, Order)> {
+        let (price, levels) = match taker.order_type {
+            order::OrderSide::Buy => (self.get_best_ask()?, &self.ask_levels),
+            order::OrderSide::Sell => (self.get_best_bid()?, &self.bid_levels),
+        };
+        if !taker.order_type.crosses(taker.price, price) {
+            return None;
+        }
+        let (index, resting) = levels.get(&price)?.peek_next(&mut self.tie_breaker)?;
+        Some(((price, index), resting))
+    }
+
+    fn fill_resting(&mut self, (price, index): Self::Slot, resting: &Order, quantity: u64) -> bool {
+        self.settle_fill(resting, quantity);
+        self.fill_level(resting.order_type, price, index, quantity)
+    }
+
+    fn arrived(&mut self, mut trades: Vec<Trade>, last_trade_price: Option<u64>, remainder: Option<Order>) {
+        self.arrival_trades.append(&mut trades);
+        self.last_trade_price = last_trade_price.or(self.last_trade_price);
+        self.evicted.extend(remainder);
+    }
 }
 
 #[repr(align(64))]
-pub struct HashMapOrderBook {
+pub struct HashMapOrderBook<B: MapBackend = FxMaps> {
     symbols: FxHashSet<SymbolId>,
-    matchers: rustc_hash::FxHashMap<SymbolId, HashMapMatcher>,
+    matchers: B::Matchers<HashMapMatcher<B>>,
+    trade_price_rule: TradePriceRule,
+    tie_break: TieBreak,
+    participant_limits: ParticipantLimits,
+    tick_sizes: TickSizes,
+    lot_sizes: LotSizes,
+    max_depth: Option<usize>,
+    level_journal: bool,
+    level_capacity: usize,
 }
 
-impl OrderBookTrait for HashMapOrderBook {
-    fn new(symbols: FxHashSet<SymbolId>) -> Self {
-        let mut matchers = rustc_hash::FxHashMap::with_capacity_and_hasher(symbols.len(), Default::default());
-        for &symbol in &symbols {
-            matchers.insert(symbol, HashMapMatcher::new());
+impl<B: MapBackend> HashMapOrderBook<B> {
+    /// Order slots each new price level reserves. Small values suit books with many
+    /// sparse levels; levels still grow as needed. Existing levels keep their slots
+    /// until they drain.
+    pub fn set_level_capacity(&mut self, capacity: usize) {
+        self.level_capacity = capacity;
+        for matcher in self.matchers.values_mut() {
+            matcher.level_capacity = capacity;
+        }
+    }
+
+    #[inline(always)]
+    pub fn level_capacity(&self) -> usize {
+        self.level_capacity
+    }
+
+    pub fn memory_estimate(&self) -> LevelMemoryEstimate {
+        let mut estimate = LevelMemoryEstimate::default();
+        for matcher in self.matchers.values() {
+            matcher.add_memory(&mut estimate);
         }
+        estimate.estimated_bytes = estimate.levels * (size_of::<PriceLevel>() + size_of::<u64>())
+            + estimate.reserved_order_slots * size_of::<Order>();
+        estimate
+    }
+
+    #[inline(always)]
+    fn matcher_mut(&mut self, symbol: SymbolId) -> Option<&mut HashMapMatcher<B>> {
+        let (limits, max_depth, level_journal, tie_break) = (self.participant_limits, self.max_depth, self.level_journal, self.tie_break);
+        let level_capacity = self.level_capacity;
+        lazy_matchers::matcher_entry(&mut self.matchers, &self.symbols, symbol, || {
+            let mut matcher = HashMapMatcher::new();
+            matcher.level_capacity = level_capacity;
+            matcher.participants.set_limits(limits, []);
+            let _ = matcher.depth.set_max_levels(max_depth, []);
+            matcher.level_changes.set_enabled(level_journal, []);
+            matcher.tie_breaker = TieBreaker::new(tie_break);
+            matcher
+        })
+    }
+}
+
+impl HashMapOrderBook {
+    pub fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        <Self as OrderBookCore>::new(symbols)
+    }
+}
+
+impl<B: MapBackend> OrderBookCore for HashMapOrderBook<B> {
+    fn new(symbols: FxHashSet<SymbolId>) -> Self {
         HashMapOrderBook { 
             symbols, 
-            matchers,
+            matchers: Default::default(),
+            trade_price_rule: TradePriceRule::default(),
+            tie_break: TieBreak::default(),
+            participant_limits: ParticipantLimits::default(),
+            tick_sizes: TickSizes::default(),
+            lot_sizes: LotSizes::default(),
+            max_depth: None,
+            level_journal: false,
+            level_capacity: DEFAULT_LEVEL_CAPACITY,
         }
     }
 
     #[inline(always)]
     fn add_order(&mut self, order: Order) -> Result<bool, OrderBookError> {
-        if let Some(matcher) = self.matchers.get_mut(&order.symbol) {
-            matcher.add_order(order);
+        let order = self.tick_sizes.conform(order)?;
+        self.lot_sizes.check(&order)?;
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return Err(OrderBookError::ParticipantLimitExceeded);
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+            } else {
+                matcher.add_order(order);
+            }
             Ok(true)
         } else {
             Err(OrderBookError::InvalidSymbol)
@@ -192,8 +565,20 @@ impl OrderBookTrait for HashMapOrderBook {
 
     #[inline(always)]
     fn add_order_fast(&mut self, order: Order) -> bool {
-        if let Some(matcher) = self.matchers.get_mut(&order.symbol) {
-            matcher.add_order(order);
+        let Ok(order) = self.tick_sizes.conform(order) else { return false };
+        if self.lot_sizes.check(&order).is_err() {
+            return false;
+        }
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return false;
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+            } else {
+                matcher.add_order(order);
+            }
             true
         } else {
             false
@@ -202,18 +587,23 @@ impl OrderBookTrait for HashMapOrderBook {
 
     #[inline(always)]
     unsafe fn add_order_unchecked(&mut self, order: Order) {
-        unsafe {
-            self.matchers.get_mut(&order.symbol)
-                .unwrap_unchecked()
-                .add_order_unchecked(order);
+        let rule = self.trade_price_rule;
+        let matcher = unsafe { self.matcher_mut(order.symbol).unwrap_unchecked() };
+        if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+            match_stats::take_on_arrival(matcher, order, rule);
+        } else {
+            unsafe { matcher.add_order_unchecked(order); }
         }
     }
 
     #[inline(always)]
-    fn match_orders(&mut self) {
+    fn match_orders(&mut self) -> Vec<Trade> {
+        let mut trades = Vec::new();
         for matcher in self.matchers.values_mut() {
-            matcher.match_orders();
+            trades.append(&mut matcher.arrival_trades);
+            matcher.match_orders(self.trade_price_rule, &mut trades);
         }
+        trades
     }
 
     #[inline(always)]
@@ -242,13 +632,15 @@ impl OrderBookTrait for HashMapOrderBook {
 
     #[inline(always)]
     fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
-        self.matchers.get(&symbol)
-            .map(|matcher| matcher.get_best_prices())
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.get_best_prices()),
+            None => self.symbols.contains(&symbol).then_some((None, None)),
+        }
     }
 
     #[inline(always)]
     fn can_match(&self, symbol: SymbolId) -> bool {
-        self.matchers.get(&symbol)
+        self.matchers.get(symbol)
             .is_some_and(|matcher| matcher.can_match())
     }
 
@@ -268,6 +660,167 @@ impl OrderBookTrait for HashMapOrderBook {
     }
 }
 
+impl<B: MapBackend> OrderBookLifecycle for HashMapOrderBook<B> {
+    fn resting_orders(&self, symbol: SymbolId) -> Option<Vec<Order>> {
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.resting_orders()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    #[inline(always)]
+    fn set_trade_price_rule(&mut self, rule: TradePriceRule) {
+        self.trade_price_rule = rule;
+    }
+
+    #[inline(always)]
+    fn trade_price_rule(&self) -> TradePriceRule {
+        self.trade_price_rule
+    }
+
+    fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+        for matcher in self.matchers.values_mut() {
+            matcher.tie_breaker = TieBreaker::new(tie_break);
+        }
+    }
+
+    #[inline(always)]
+    fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    fn set_participant_limits(&mut self, limits: ParticipantLimits) {
+        self.participant_limits = limits;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.participants.set_limits(limits, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn participant_limits(&self) -> ParticipantLimits {
+        self.participant_limits
+    }
+
+    fn set_tick_sizes(&mut self, tick_sizes: TickSizes) {
+        self.tick_sizes = tick_sizes;
+    }
+
+    #[inline(always)]
+    fn tick_sizes(&self) -> &TickSizes {
+        &self.tick_sizes
+    }
+
+    fn set_lot_sizes(&mut self, lot_sizes: LotSizes) {
+        self.lot_sizes = lot_sizes;
+    }
+
+    #[inline(always)]
+    fn lot_sizes(&self) -> &LotSizes {
+        &self.lot_sizes
+    }
+
+    fn set_max_depth(&mut self, max_levels: Option<usize>) {
+        self.max_depth = max_levels;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            for (side, price) in matcher.depth.set_max_levels(max_levels, &resting) {
+                matcher.evict_level(side, price);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    fn take_evicted_orders(&mut self) -> Vec<Order> {
+        self.matchers.values_mut().flat_map(|matcher| matcher.evicted.drain(..)).collect()
+    }
+
+    fn set_level_journal(&mut self, enabled: bool) {
+        self.level_journal = enabled;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.level_changes.set_enabled(enabled, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn level_journal(&self) -> bool {
+        self.level_journal
+    }
+
+    fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>> {
+        match self.matchers.get_mut(symbol) {
+            Some(matcher) => Some(matcher.level_changes.take()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Result<Order, OrderBookError> {
+        self.matchers.values_mut()
+            .find_map(|matcher| matcher.reduce_order(order_id, quantity_delta))
+            .unwrap_or(Err(OrderBookError::OrderNotFound))
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool) -> Vec<Order> {
+        let mut cancelled = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            matcher.cancel_where(predicate, &mut cancelled);
+        }
+        cancelled
+    }
+
+    fn cancel_order(&mut self, symbol: SymbolId, order_id: u64) -> Result<Order, OrderBookError> {
+        lazy_matchers::cancel_in(&mut self.matchers, &self.symbols, symbol, |matcher| matcher.cancel_order(order_id))
+    }
+
+    fn cancel_orders(&mut self, order_ids: &FxHashSet<u64>) -> Vec<Order> {
+        lazy_matchers::cancel_each_in(&mut self.matchers, order_ids, |matcher, order_id| matcher.cancel_order(order_id))
+    }
+
+    fn take_expired_orders(&mut self, now: u64) -> Vec<Order> {
+        let mut expired = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            matcher.take_expired(now, &mut expired);
+        }
+        expired
+    }
+}
+
+impl<B: MapBackend> OrderBookMarketData for HashMapOrderBook<B> {
+    fn price_levels(&self, symbol: SymbolId, side: order::OrderSide, with_order_ids: bool) -> Option<PriceLevelIter<'_>> {
+        let Some(matcher) = self.matchers.get(symbol) else {
+            return self.symbols.contains(&symbol).then(|| Box::new(std::iter::empty()) as PriceLevelIter<'_>);
+        };
+        let view = move |(&price, level): (&u64, &PriceLevel)| PriceLevelView {
+            price,
+            total_quantity: level.total_quantity,
+            order_count: level.count as usize,
+            order_ids: with_order_ids.then(|| level.orders.iter().map(|order| order.id).collect()),
+        };
+        Some(match side {
+            order::OrderSide::Buy => Box::new(matcher.bid_levels.iter().rev().map(view)),
+            order::OrderSide::Sell => Box::new(matcher.ask_levels.iter().map(view)),
+        })
+    }
+
+    #[inline(always)]
+    fn last_trade_price(&self, symbol: SymbolId) -> Option<u64> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.last_trade_price)
+    }
+
+    #[inline(always)]
+    fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.match_stats)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,7 +839,7 @@ mod tests {
 
     #[test]
     fn test_hashmap_matcher_best_prices() {
-        let mut matcher = HashMapMatcher::new();
+        let mut matcher = HashMapMatcher::<FxMaps>::new();
         
         let buy_order = new_order(1, 0, 100, 99.50, OrderSide::Buy);
         let sell_order = new_order(2, 0, 100, 100.50, OrderSide::Sell);
@@ -298,4 +851,78 @@ mod tests {
         assert_eq!(matcher.get_best_ask(), Some(100500)); // 100.50 * 1000
         assert!(!matcher.can_match());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_cached_best_prices_follow_levels() {
+        let mut order_book = HashMapOrderBook::new(FxHashSet::from_iter([APPLE_SYMBOL]));
+        let scanned = |order_book: &HashMapOrderBook| {
+            let matcher = &order_book.matchers[&APPLE_SYMBOL];
+            (matcher.bid_levels.keys().next_back().copied(), matcher.ask_levels.keys().next().copied())
+        };
+
+        for (id, price, side) in [(1, 99.0, OrderSide::Buy), (2, 100.0, OrderSide::Buy), (3, 100.0, OrderSide::Sell), (4, 101.0, OrderSide::Sell)] {
+            order_book.add_order(new_order(id, APPLE_SYMBOL, 10, price, side)).unwrap();
+        }
+        assert_eq!(order_book.get_best_prices(APPLE_SYMBOL), Some((Some(100_000), Some(100_000))));
+
+        order_book.match_orders();
+        assert_eq!(order_book.get_best_prices(APPLE_SYMBOL), Some((Some(99_000), Some(101_000))));
+
+        order_book.cancel_where(&|order| order.id == 4);
+        assert_eq!(order_book.get_best_prices(APPLE_SYMBOL), Some((Some(99_000), None)));
+
+        order_book.set_max_depth(Some(1));
+        order_book.add_order(new_order(5, APPLE_SYMBOL, 10, 98.0, OrderSide::Buy)).unwrap();
+        order_book.add_order(new_order(6, APPLE_SYMBOL, 10, 99.5, OrderSide::Buy)).unwrap();
+        assert_eq!(order_book.get_best_prices(APPLE_SYMBOL), Some((Some(99_500), None)));
+        assert_eq!(order_book.get_best_prices(APPLE_SYMBOL), Some(scanned(&order_book)));
+    }
+
+    #[test]
+    fn test_level_capacity_and_memory_estimate() {
+        let mut order_book = HashMapOrderBook::new(FxHashSet::from_iter([APPLE_SYMBOL]));
+        order_book.set_level_capacity(4);
+        for id in 0..3 {
+            order_book.add_order(new_order(id, APPLE_SYMBOL, 10, 99.0 - id as f64, OrderSide::Buy)).unwrap();
+        }
+        let estimate = order_book.memory_estimate();
+        assert_eq!((estimate.levels, estimate.orders), (3, 3));
+        assert_eq!(estimate.reserved_order_slots, 12);
+
+        for id in 10..74 {
+            order_book.add_order(new_order(id, APPLE_SYMBOL, 10, 100.0, OrderSide::Buy)).unwrap();
+        }
+        assert!(order_book.memory_estimate().reserved_order_slots >= 12 + 64);
+
+        order_book.cancel_where(&|order| order.id >= 12);
+        let estimate = order_book.memory_estimate();
+        assert_eq!((estimate.levels, estimate.orders), (4, 5));
+        assert!(estimate.reserved_order_slots <= 12 + 4);
+        assert!(estimate.estimated_bytes > 0);
+    }
+
+    #[test]
+    fn test_order_index_follows_resting_orders() {
+        let mut order_book = HashMapOrderBook::new(FxHashSet::from_iter([0]));
+        order_book.set_max_depth(Some(1));
+        for (id, quantity, price, side) in [
+            (1, 10, 100.0, order::OrderSide::Buy),
+            (2, 10, 99.0, order::OrderSide::Buy),
+            (3, 10, 101.0, order::OrderSide::Buy),
+            (4, 4, 101.0, order::OrderSide::Sell),
+            (5, 6, 101.0, order::OrderSide::Sell),
+        ] {
+            order_book.add_order_fast(order::new_order(id, 0, quantity, price, side).with_expiry(id * 100));
+        }
+        order_book.match_orders();
+        assert!(order_book.matchers[&0].order_index.is_empty());
+        assert!(order_book.matchers[&0].expiry_index.is_empty());
+
+        order_book.add_order_fast(order::new_order(6, 0, 10, 100.0, order::OrderSide::Sell).with_expiry(600));
+        order_book.add_order_fast(order::new_order(7, 0, 10, 100.0, order::OrderSide::Sell).with_expiry(700));
+        assert_eq!(order_book.cancel_order(0, 6).map(|order| order.id).ok(), Some(6));
+        assert_eq!(order_book.matchers[&0].order_index.keys().copied().collect::<Vec<_>>(), vec![7]);
+        assert_eq!(order_book.matchers[&0].expiry_index.iter().copied().collect::<Vec<_>>(), vec![(700, 7)]);
+        assert_eq!(order_book.get_best_prices(0), Some((None, Some(order::price_to_u64(100.0)))));
+    }
+}
@@ -0,0 +1,67 @@
+use rustc_hash::FxHashSet;
+
+use crate::engine::order_book_trait::OrderBookError;
+use crate::engine::symbol_map::SymbolMap;
+use crate::types::{order::Order, symbol_mapping::SymbolId};
+
+/// Returns the matcher for `symbol`, creating it on the symbol's first order. Books only
+/// hold matchers for registered symbols that have traded, so a large or sparse symbol
+/// universe costs nothing up front.
+#[inline(always)]
+pub(crate) fn matcher_entry<'a, M>(
+    matchers: &'a mut impl SymbolMap<M>,
+    symbols: &FxHashSet<SymbolId>,
+    symbol: SymbolId,
+    create: impl FnOnce() -> M,
+) -> Option<&'a mut M> {
+    matchers.get_or_create(symbol, || symbols.contains(&symbol).then(create))
+}
+
+/// `cancel_order` on `symbol`'s matcher, which finds the order through its id index.
+/// A registered symbol without a matcher has nothing resting yet.
+#[inline(always)]
+pub(crate) fn cancel_in<M>(
+    matchers: &mut impl SymbolMap<M>,
+    symbols: &FxHashSet<SymbolId>,
+    symbol: SymbolId,
+    cancel: impl FnOnce(&mut M) -> Option<Order>,
+) -> Result<Order, OrderBookError> {
+    match matchers.get_mut(symbol) {
+        Some(matcher) => cancel(matcher).ok_or(OrderBookError::OrderNotFound),
+        None if symbols.contains(&symbol) => Err(OrderBookError::OrderNotFound),
+        None => Err(OrderBookError::InvalidSymbol),
+    }
+}
+
+/// `cancel_order` for every id on every matcher, so cancelling a batch costs a lookup
+/// per id in each id index rather than a scan of every resting order.
+#[inline(always)]
+pub(crate) fn cancel_each_in<M>(
+    matchers: &mut impl SymbolMap<M>,
+    order_ids: &FxHashSet<u64>,
+    mut cancel: impl FnMut(&mut M, u64) -> Option<Order>,
+) -> Vec<Order> {
+    let mut cancelled = Vec::new();
+    for matcher in matchers.values_mut() {
+        cancelled.extend(order_ids.iter().filter_map(|&order_id| cancel(matcher, order_id)));
+    }
+    cancelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashMap;
+
+    #[test]
+    fn test_creates_only_registered_symbols() {
+        let symbols = FxHashSet::from_iter([3, 70_000]);
+        let mut matchers: FxHashMap<SymbolId, u32> = FxHashMap::default();
+
+        *matcher_entry(&mut matchers, &symbols, 70_000, || 0).unwrap() += 1;
+        *matcher_entry(&mut matchers, &symbols, 70_000, || 0).unwrap() += 1;
+        assert!(matcher_entry(&mut matchers, &symbols, 4, || 0).is_none());
+        assert_eq!(matchers.len(), 1);
+        assert_eq!(matchers[&70_000], 2);
+    }
+}
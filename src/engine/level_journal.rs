@@ -0,0 +1,128 @@
+use std::io::{self, Write};
+
+use rustc_hash::FxHashMap;
+
+use crate::types::order::{Order, OrderSide};
+use crate::types::timestamp;
+
+/// One change to the resting quantity at a price level. A new level starts from an
+/// `old_quantity` of zero and an emptied level ends at a `new_quantity` of zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct LevelChange {
+    /// Nanoseconds since the Unix epoch when the matcher applied the change.
+    pub timestamp: u64,
+    pub side: OrderSide,
+    pub price: u64,
+    pub old_quantity: u64,
+    pub new_quantity: u64,
+}
+
+/// Per-symbol journal of level changes for queue-dynamics research. Keeps its own
+/// aggregate per level so every book records the same changes whatever its layout.
+/// Like the depth cap, nothing is tracked while disabled.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LevelJournal {
+    enabled: bool,
+    quantities: FxHashMap<(OrderSide, u64), u64>,
+    changes: Vec<LevelChange>,
+}
+
+impl LevelJournal {
+    /// Rebuilds the level aggregates from the orders already resting. Disabling drops
+    /// any changes not yet taken.
+    pub(crate) fn set_enabled<'a>(&mut self, enabled: bool, resting: impl IntoIterator<Item = &'a Order>) {
+        self.enabled = enabled;
+        self.quantities.clear();
+        if !enabled {
+            self.changes = Vec::new();
+            return;
+        }
+        for order in resting {
+            *self.quantities.entry((order.order_type, order.price)).or_default() += order.quantity;
+        }
+    }
+
+    /// Counts `quantity` newly resting at `price`.
+    #[inline(always)]
+    pub(crate) fn record(&mut self, side: OrderSide, price: u64, quantity: u64) {
+        if self.enabled {
+            self.apply(side, price, |resting| resting + quantity);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn release(&mut self, order: &Order) {
+        if self.enabled {
+            self.apply(order.order_type, order.price, |quantity| quantity.saturating_sub(order.quantity));
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn reduce(&mut self, order: &Order, quantity_delta: u64) {
+        if self.enabled {
+            self.apply(order.order_type, order.price, |quantity| quantity.saturating_sub(quantity_delta));
+        }
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<LevelChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    fn apply(&mut self, side: OrderSide, price: u64, update: impl FnOnce(u64) -> u64) {
+        let old_quantity = self.quantities.get(&(side, price)).copied().unwrap_or(0);
+        let new_quantity = update(old_quantity);
+        if new_quantity == 0 {
+            self.quantities.remove(&(side, price));
+        } else {
+            self.quantities.insert((side, price), new_quantity);
+        }
+        self.changes.push(LevelChange { timestamp: timestamp::now_nanos(), side, price, old_quantity, new_quantity });
+    }
+}
+
+/// Writes changes as CSV with a header row, one change per line, for loading into
+/// dataframe tools or converting to a columnar format.
+pub fn write_csv(changes: &[LevelChange], mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "timestamp,side,price,old_quantity,new_quantity")?;
+    for change in changes {
+        let side = match change.side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+        writeln!(writer, "{},{side},{},{},{}", change.timestamp, change.price, change.old_quantity, change.new_quantity)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    fn test_journal_tracks_level_aggregates() {
+        let mut journal = LevelJournal::default();
+        let resting = new_order(1, 0, 10, 100.0, OrderSide::Buy);
+        journal.record(OrderSide::Buy, resting.price, resting.quantity);
+        assert!(journal.take().is_empty());
+
+        journal.set_enabled(true, [&resting]);
+        let added = new_order(2, 0, 5, 100.0, OrderSide::Buy);
+        journal.record(OrderSide::Buy, added.price, added.quantity);
+        journal.reduce(&added, 2);
+        journal.release(&resting);
+        journal.release(&new_order(2, 0, 3, 100.0, OrderSide::Buy));
+
+        let sizes: Vec<_> = journal.take().iter().map(|change| (change.old_quantity, change.new_quantity)).collect();
+        assert_eq!(sizes, vec![(10, 15), (15, 13), (13, 3), (3, 0)]);
+        assert!(journal.take().is_empty());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let change = LevelChange { timestamp: 7, side: OrderSide::Sell, price: 101_000, old_quantity: 0, new_quantity: 25 };
+        let mut out = Vec::new();
+        write_csv(&[change], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "timestamp,side,price,old_quantity,new_quantity\n7,sell,101000,0,25\n");
+    }
+}
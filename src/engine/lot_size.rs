@@ -0,0 +1,114 @@
+use rustc_hash::FxHashMap;
+
+use crate::engine::OrderBookError;
+use crate::types::order::Order;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::validation;
+
+/// Quantity rules for one symbol. Zero leaves a rule off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct LotSize {
+    /// Quantities must be a multiple of this.
+    pub lot_size: u64,
+    pub min_quantity: u64,
+    /// Least price times quantity, in scaled price units like `PaperAccount` money.
+    pub min_notional: u128,
+}
+
+impl LotSize {
+    pub fn new(lot_size: u64) -> Self {
+        Self { lot_size, ..Default::default() }
+    }
+
+    pub fn with_min_quantity(mut self, min_quantity: u64) -> Self {
+        self.min_quantity = min_quantity;
+        self
+    }
+
+    pub fn with_min_notional(mut self, min_notional: u128) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
+    #[inline(always)]
+    pub fn check(&self, price: u64, quantity: u64) -> Result<(), OrderBookError> {
+        if self.lot_size > 1 && !quantity.is_multiple_of(self.lot_size) {
+            return Err(OrderBookError::OddLot);
+        }
+        if quantity < self.min_quantity {
+            return Err(OrderBookError::BelowMinQuantity);
+        }
+        if (price as u128) * (quantity as u128) < self.min_notional {
+            return Err(OrderBookError::BelowMinNotional);
+        }
+        Ok(())
+    }
+}
+
+/// `LotSize` per symbol. Symbols without an entry accept any quantity. Books check the
+/// quantity they are given, so an iceberg's slices must pass too: its display
+/// quantity should be a whole number of lots at or above the minimums.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct LotSizes {
+    rules: FxHashMap<SymbolId, LotSize>,
+}
+
+impl LotSizes {
+    pub fn with_rule(mut self, symbol: SymbolId, rule: LotSize) -> Self {
+        self.set_rule(symbol, rule);
+        self
+    }
+
+    /// A rule with nothing set removes the symbol's entry.
+    pub fn set_rule(&mut self, symbol: SymbolId, rule: LotSize) {
+        if rule == LotSize::default() {
+            self.rules.remove(&symbol);
+        } else {
+            self.rules.insert(symbol, rule);
+        }
+    }
+
+    #[inline(always)]
+    pub fn rule(&self, symbol: SymbolId) -> Option<LotSize> {
+        self.rules.get(&symbol).copied()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Checks `order` against its symbol's rule. Nothing is checked with validation
+    /// stripped.
+    #[inline(always)]
+    pub fn check(&self, order: &Order) -> Result<(), OrderBookError> {
+        if !validation::ENABLED || self.rules.is_empty() {
+            return Ok(());
+        }
+        match self.rules.get(&order.symbol) {
+            Some(rule) => rule.check(order.price, order.quantity),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, price_to_u64, OrderSide};
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_rules_reject_odd_lots_and_small_orders() {
+        let rule = LotSize::new(100).with_min_quantity(200).with_min_notional(price_to_u64(5_000.0) as u128);
+        let lots = LotSizes::default().with_rule(0, rule).with_rule(1, LotSize::default());
+        assert_eq!(lots.rule(1), None);
+
+        let check = |quantity, price| lots.check(&new_order(1, 0, quantity, price, OrderSide::Buy));
+        assert!(matches!(check(1, 100.0), Err(OrderBookError::OddLot)));
+        assert!(matches!(check(100, 100.0), Err(OrderBookError::BelowMinQuantity)));
+        assert!(matches!(check(200, 10.0), Err(OrderBookError::BelowMinNotional)));
+        assert!(check(300, 25.0).is_ok());
+        assert!(lots.check(&new_order(2, 1, 1, 1.0, OrderSide::Sell)).is_ok());
+    }
+}
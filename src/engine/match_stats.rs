@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use crate::types::order::Order;
+use crate::types::trade::{self, Trade, TradePriceRule};
+
+/// What one matching cycle did to one symbol's book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct MatchStats {
+    pub trades: u64,
+    /// Quantity traded, counting the smaller order of each matched pair.
+    pub volume: u64,
+    /// Price levels emptied on either side.
+    pub levels_cleared: u64,
+    pub duration: Duration,
+}
+
+/// Accumulates a cycle's stats. Matchers only start one once they find a cross, so
+/// idle books pay no clock reads.
+pub(crate) struct MatchCycle {
+    stats: MatchStats,
+    trades: Vec<Trade>,
+    started: Instant,
+}
+
+impl MatchCycle {
+    #[inline(always)]
+    pub(crate) fn start() -> Self {
+        Self { stats: MatchStats::default(), trades: Vec::new(), started: Instant::now() }
+    }
+
+    /// Records the pair's trade and returns its price under `rule`.
+    #[inline(always)]
+    pub(crate) fn record_trade(&mut self, bid: &Order, ask: &Order, rule: TradePriceRule) -> u64 {
+        let price = trade::execution_price(bid, ask, rule);
+        self.record(Trade::between(bid, ask, price));
+        price
+    }
+
+    /// `record_trade` for an arriving order taking from `resting` on the other side.
+    /// The arriving order is the taker whatever the ids say.
+    #[inline(always)]
+    pub(crate) fn record_take(&mut self, arriving: &Order, resting: &Order, rule: TradePriceRule) -> u64 {
+        let price = trade::taker_price(arriving, resting, rule);
+        self.record(Trade::taken(arriving, resting, price));
+        price
+    }
+
+    #[inline(always)]
+    fn record(&mut self, trade: Trade) {
+        self.stats.trades += 1;
+        self.stats.volume += trade.quantity;
+        self.trades.push(trade);
+    }
+
+    #[inline(always)]
+    pub(crate) fn level_cleared(&mut self) {
+        self.stats.levels_cleared += 1;
+    }
+
+    /// Appends the cycle's trades to `trades` in execution order.
+    #[inline(always)]
+    pub(crate) fn finish(mut self, trades: &mut Vec<Trade>) -> MatchStats {
+        self.stats.duration = self.started.elapsed();
+        trades.append(&mut self.trades);
+        self.stats
+    }
+}
+
+/// A matcher an immediate-or-cancel order can take from as it arrives, without ever
+/// resting in it.
+pub(crate) trait TakeOnArrival {
+    /// Where `next_resting` found an order, for `fill_resting` to find it again.
+    type Slot;
+
+    /// The resting order `taker` trades with next, or `None` once `taker` crosses nothing.
+    fn next_resting(&mut self, taker: &Order) -> Option<(Self::Slot, Order)>;
+
+    /// Books a fill of `quantity` against `resting` as it stood before the trade.
+    /// Returns whether that emptied its price level.
+    fn fill_resting(&mut self, slot: Self::Slot, resting: &Order, quantity: u64) -> bool;
+
+    /// Takes what the arrival did: its trades, handed out ahead of those from the next
+    /// `match_orders`, the price of the last one, and the unfilled remainder, which is
+    /// cancelled rather than resting.
+    fn arrived(&mut self, trades: Vec<Trade>, last_trade_price: Option<u64>, remainder: Option<Order>);
+}
+
+/// Matches `taker` alone against the other side of `matcher`. Crosses already resting
+/// in the book wait for the next `match_orders`, and the cycle's stats are not kept.
+pub(crate) fn take_on_arrival(matcher: &mut impl TakeOnArrival, mut taker: Order, rule: TradePriceRule) {
+    let mut cycle = MatchCycle::start();
+    let mut last_trade_price = None;
+    while taker.quantity > 0 && let Some((slot, resting)) = matcher.next_resting(&taker) {
+        last_trade_price = Some(cycle.record_take(&taker, &resting, rule));
+        let quantity = taker.quantity.min(resting.quantity);
+        if matcher.fill_resting(slot, &resting, quantity) {
+            cycle.level_cleared();
+        }
+        taker.quantity -= quantity;
+    }
+    let mut trades = Vec::new();
+    cycle.finish(&mut trades);
+    matcher.arrived(trades, last_trade_price, (taker.quantity > 0).then_some(taker));
+}
@@ -3,9 +3,39 @@ pub mod order_book;
 pub mod order_book_trait;
 pub mod priority_queue_order_book;
 pub mod array_queue_order_book;
+pub mod persistent_order_book;
+pub mod fixed_capacity_order_book;
+pub mod participant_limits;
+pub mod tick_size;
+pub mod lot_size;
+pub mod symbol_map;
+pub(crate) mod depth_limit;
+pub(crate) mod lazy_matchers;
+pub(crate) mod reduction;
+pub mod match_stats;
+pub mod level_journal;
+pub mod tie_break;
+pub mod price_level_view;
+pub mod any_order_book;
+pub mod conformance;
+pub mod seeding;
 
-pub use order_book_trait::{OrderBookTrait, OrderBookError};
-pub use order_book::{OrderBookType, create_order_book, factories};
-pub use hashmap_order_book::HashMapOrderBook;
+pub use order_book_trait::{OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData, OrderBookTrait};
+pub use order_book::{OrderBookType, create_any_order_book, create_order_book, factories};
+pub use hashmap_order_book::{HashMapOrderBook, LevelMemoryEstimate};
 pub use priority_queue_order_book::PriorityQueueOrderBook;
-pub use array_queue_order_book::ArrayQueueOrderBook;
\ No newline at end of file
+pub use array_queue_order_book::ArrayQueueOrderBook;
+pub use persistent_order_book::{PersistentOrderBook, PersistentBookView};
+pub use fixed_capacity_order_book::FixedCapacityOrderBook;
+pub use participant_limits::ParticipantLimits;
+pub use tick_size::{OffTickPolicy, TickSizes};
+pub use lot_size::{LotSize, LotSizes};
+pub use symbol_map::{DenseMaps, DenseSymbolMap, FxMaps, IdMap, MapBackend, SymbolMap};
+#[cfg(feature = "hashbrown")]
+pub use symbol_map::{RawTableMap, RawTableMaps};
+pub use price_level_view::{AggregatedDepth, BookDepth, PriceBand, PriceLevelIter, PriceLevelView};
+pub use any_order_book::AnyOrderBook;
+pub use match_stats::MatchStats;
+pub use level_journal::LevelChange;
+pub use tie_break::TieBreak;
+pub use seeding::{QuantityProfile, seed_book, seed_orders};
\ No newline at end of file
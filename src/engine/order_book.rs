@@ -1,7 +1,6 @@
 use std::fmt;
 use rustc_hash::FxHashSet;
 use crate::types::symbol_mapping::SymbolId;
-use crate::engine::order_book_trait::OrderBookTrait;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[derive(Default)]
@@ -10,6 +9,23 @@ pub enum OrderBookType {
     HashMap,
     PriorityQueue,
     ArrayQueue,
+    Persistent,
+    /// `FixedCapacityOrderBook` with its default capacities.
+    FixedCapacity,
+}
+
+impl OrderBookType {
+    /// Parses a book name case-insensitively, e.g. `hashmap` or `FixedCapacity`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "hashmap" => Some(OrderBookType::HashMap),
+            "priorityqueue" => Some(OrderBookType::PriorityQueue),
+            "arrayqueue" => Some(OrderBookType::ArrayQueue),
+            "persistent" => Some(OrderBookType::Persistent),
+            "fixedcapacity" => Some(OrderBookType::FixedCapacity),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for OrderBookType {
@@ -18,6 +34,8 @@ impl fmt::Display for OrderBookType {
             OrderBookType::HashMap => "HashMap",
             OrderBookType::PriorityQueue => "PriorityQueue",
             OrderBookType::ArrayQueue => "ArrayQueue",
+            OrderBookType::Persistent => "Persistent",
+            OrderBookType::FixedCapacity => "FixedCapacity",
         };
         write!(f, "{s}")
     }
@@ -37,9 +55,21 @@ pub fn create_order_book(
         OrderBookType::ArrayQueue => {
             Box::new(crate::engine::array_queue_order_book::ArrayQueueOrderBook::new(symbols))
         }
+        OrderBookType::Persistent => {
+            Box::new(crate::engine::persistent_order_book::PersistentOrderBook::new(symbols))
+        }
+        OrderBookType::FixedCapacity => {
+            Box::new(crate::engine::fixed_capacity_order_book::FixedCapacityOrderBook::<64, 1024>::new(symbols))
+        }
     }
 }
 
+/// Enum-dispatched counterpart of `create_order_book`, avoiding the boxed trait object.
+#[inline(always)]
+pub fn create_any_order_book(order_book_type: OrderBookType, symbols: FxHashSet<SymbolId>) -> crate::engine::AnyOrderBook {
+    crate::engine::AnyOrderBook::with_type(order_book_type, symbols)
+}
+
 pub mod factories {
     use super::*;
     use crate::engine::OrderBookTrait;
@@ -55,13 +85,21 @@ pub mod factories {
     pub fn create_array_queue_order_book(symbols: FxHashSet<SymbolId>) -> impl OrderBookTrait {
         crate::engine::array_queue_order_book::ArrayQueueOrderBook::new(symbols)
     }
+    
+    pub fn create_persistent_order_book(symbols: FxHashSet<SymbolId>) -> impl OrderBookTrait {
+        crate::engine::persistent_order_book::PersistentOrderBook::new(symbols)
+    }
+
+    pub fn create_fixed_capacity_order_book(symbols: FxHashSet<SymbolId>) -> impl OrderBookTrait {
+        crate::engine::fixed_capacity_order_book::FixedCapacityOrderBook::<64, 1024>::new(symbols)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::engine::OrderBookTrait;
-    use crate::types::order::{new_order, OrderSide};
+    use crate::engine::{BookDepth, OrderBookCore, OrderBookMarketData};
+    use crate::types::order::{new_order, price_to_u64, OrderSide};
 
     #[test]
     fn test_factory_creates_all_types() {
@@ -70,12 +108,506 @@ mod tests {
         let hashmap_book = create_order_book(OrderBookType::HashMap, symbols.clone());
         let priority_book = create_order_book(OrderBookType::PriorityQueue, symbols.clone());
         let array_book = create_order_book(OrderBookType::ArrayQueue, symbols.clone());
+        let persistent_book = create_order_book(OrderBookType::Persistent, symbols.clone());
         
         assert_eq!(hashmap_book.order_book_type(), OrderBookType::HashMap);
         assert_eq!(priority_book.order_book_type(), OrderBookType::PriorityQueue);
         assert_eq!(array_book.order_book_type(), OrderBookType::ArrayQueue);
+        assert_eq!(persistent_book.order_book_type(), OrderBookType::Persistent);
     }
     
+    #[test]
+    fn test_map_backends_consistent_across_types() {
+        use crate::engine::{ArrayQueueOrderBook, DenseMaps, FixedCapacityOrderBook, FxMaps, HashMapOrderBook, MapBackend, OrderBookTrait, PersistentOrderBook, PriorityQueueOrderBook};
+
+        fn run(mut order_book: Box<dyn OrderBookTrait>) -> (usize, Option<u64>, usize) {
+            order_book.add_order_fast(new_order(1, 40, 100, 101.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(2, 40, 60, 100.0, OrderSide::Sell));
+            order_book.add_order_fast(new_order(3, 3, 10, 99.0, OrderSide::Sell));
+            assert!(!order_book.add_order_fast(new_order(4, 41, 10, 99.0, OrderSide::Sell)));
+            let trades = order_book.match_orders().len();
+            order_book.cancel_order(3, 3).unwrap();
+            (trades, order_book.get_best_prices(40).unwrap().0, order_book.resting_orders(3).unwrap().len())
+        }
+        fn books<B: MapBackend>() -> [Box<dyn OrderBookTrait>; 5] {
+            let symbols = || FxHashSet::from_iter([3, 40]);
+            [
+                Box::new(HashMapOrderBook::<B>::new(symbols())),
+                Box::new(PriorityQueueOrderBook::<B>::new(symbols())),
+                Box::new(ArrayQueueOrderBook::<B>::new(symbols())),
+                Box::new(PersistentOrderBook::<B>::new(symbols())),
+                Box::new(FixedCapacityOrderBook::<64, 1024, B>::new(symbols())),
+            ]
+        }
+
+        let expected = books::<FxMaps>().map(run);
+        assert_eq!(expected[0], (1, Some(price_to_u64(101.0)), 0));
+        assert_eq!(books::<DenseMaps>().map(run), expected);
+        #[cfg(feature = "hashbrown")]
+        assert_eq!(books::<crate::engine::RawTableMaps>().map(run), expected);
+    }
+
+    #[test]
+    fn test_trade_price_rule_consistent_across_types() {
+        use crate::types::order::price_to_u64;
+        use crate::types::trade::TradePriceRule;
+
+        let cases = [
+            (TradePriceRule::RestingOrder, 101.0),
+            (TradePriceRule::Aggressor, 100.0),
+            (TradePriceRule::Midpoint, 100.5),
+        ];
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            for (rule, expected) in cases {
+                let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+                order_book.set_trade_price_rule(rule);
+                order_book.add_order_fast(new_order(1, 0, 100, 101.0, OrderSide::Buy));
+                order_book.add_order_fast(new_order(2, 0, 100, 100.0, OrderSide::Sell));
+                order_book.match_orders();
+
+                assert_eq!(order_book.last_trade_price(0), Some(price_to_u64(expected)), "{order_book_type} {rule:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_price_levels_consistent_across_types() {
+        use crate::types::order::price_to_u64;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.add_order_fast(new_order(1, 0, 100, 99.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(2, 0, 50, 100.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(3, 0, 25, 100.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(4, 0, 10, 101.0, OrderSide::Sell));
+
+            let bids: Vec<_> = order_book.price_levels(0, OrderSide::Buy, true).unwrap().collect();
+            assert_eq!(bids.len(), 2, "{order_book_type}");
+            assert_eq!(bids[0].price, price_to_u64(100.0), "{order_book_type}");
+            assert_eq!((bids[0].total_quantity, bids[0].order_count), (75, 2), "{order_book_type}");
+            assert_eq!(bids[0].order_ids.as_deref(), Some(&[2, 3][..]), "{order_book_type}");
+            assert_eq!(bids[1].price, price_to_u64(99.0), "{order_book_type}");
+
+            let asks: Vec<_> = order_book.price_levels(0, OrderSide::Sell, false).unwrap().collect();
+            assert_eq!(asks.len(), 1, "{order_book_type}");
+            assert_eq!(asks[0].order_ids, None, "{order_book_type}");
+            assert!(order_book.price_levels(1, OrderSide::Sell, false).is_none());
+        }
+    }
+
+    #[test]
+    fn test_get_depth_consistent_across_types() {
+        use crate::types::order::price_to_u64;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_any_order_book(order_book_type, FxHashSet::from_iter([0, 1]));
+            for (id, price) in [(1, 99.0), (2, 100.0), (3, 98.0), (4, 100.0), (5, 97.0)] {
+                order_book.add_order_fast(new_order(id, 0, id * 10, price, OrderSide::Buy));
+            }
+            order_book.add_order_fast(new_order(6, 0, 5, 101.0, OrderSide::Sell));
+
+            let depth = order_book.get_depth(0, 3).unwrap();
+            let bids: Vec<_> = depth.bids.iter().map(|level| (level.price, level.total_quantity, level.order_count)).collect();
+            assert_eq!(bids, vec![(price_to_u64(100.0), 60, 2), (price_to_u64(99.0), 10, 1), (price_to_u64(98.0), 30, 1)], "{order_book_type}");
+            assert_eq!(depth.asks.len(), 1, "{order_book_type}");
+            assert!(depth.bids.iter().chain(&depth.asks).all(|level| level.order_ids.is_none()), "{order_book_type}");
+
+            assert_eq!(order_book.get_depth(0, 0), Some(BookDepth::default()), "{order_book_type}");
+            assert_eq!(order_book.get_depth(1, 5), Some(BookDepth::default()), "{order_book_type}");
+            assert_eq!(order_book.get_depth(2, 5), None, "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_max_depth_consistent_across_types() {
+        use crate::types::order::price_to_u64;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.set_max_depth(Some(2));
+            order_book.add_order_fast(new_order(1, 0, 10, 100.0, OrderSide::Sell));
+            order_book.add_order_fast(new_order(2, 0, 10, 101.0, OrderSide::Sell));
+            order_book.add_order_fast(new_order(3, 0, 10, 101.0, OrderSide::Sell));
+            assert!(order_book.take_evicted_orders().is_empty(), "{order_book_type}");
+
+            order_book.add_order_fast(new_order(4, 0, 10, 99.0, OrderSide::Sell));
+            let mut evicted: Vec<_> = order_book.take_evicted_orders().iter().map(|order| order.id).collect();
+            evicted.sort_unstable();
+            assert_eq!(evicted, vec![2, 3], "{order_book_type}");
+
+            // A level beyond the far touch is its own eviction
+            order_book.add_order_fast(new_order(5, 0, 10, 105.0, OrderSide::Sell));
+            assert_eq!(order_book.take_evicted_orders()[0].id, 5, "{order_book_type}");
+            assert_eq!(order_book.get_best_prices(0), Some((None, Some(price_to_u64(99.0)))), "{order_book_type}");
+
+            order_book.add_order_fast(new_order(6, 0, 10, 100.0, OrderSide::Buy));
+            order_book.match_orders();
+            order_book.add_order_fast(new_order(7, 0, 10, 102.0, OrderSide::Sell));
+            assert!(order_book.take_evicted_orders().is_empty(), "{order_book_type}");
+            assert_eq!(order_book.resting_orders(0).unwrap().len(), 2, "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_expire_orders_consistent_across_types() {
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0, 1]));
+            order_book.add_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_expiry(1_000)).unwrap();
+            order_book.add_order(new_order(2, 0, 10, 98.0, OrderSide::Buy)).unwrap();
+            order_book.add_order(new_order(3, 1, 10, 101.0, OrderSide::Sell).with_expiry(2_000)).unwrap();
+            order_book.add_order(new_order(4, 0, 10, 102.0, OrderSide::Sell).with_expiry(3_000)).unwrap();
+
+            // A partial fill leaves the residual to expire with its original time
+            order_book.add_order(new_order(5, 1, 4, 101.0, OrderSide::Buy)).unwrap();
+            order_book.match_orders();
+
+            assert!(order_book.expire_orders(999).is_empty(), "{order_book_type}");
+            let mut expired = order_book.expire_orders(2_000);
+            expired.sort_unstable();
+            assert_eq!(expired, vec![1, 3], "{order_book_type}");
+            assert!(order_book.expire_orders(2_000).is_empty(), "{order_book_type}");
+
+            let remaining: Vec<_> = order_book.take_expired_orders(u64::MAX).iter().map(|order| (order.id, order.quantity)).collect();
+            assert_eq!(remaining, vec![(4, 10)], "{order_book_type}");
+            assert_eq!(order_book.resting_orders(0).unwrap().iter().map(|order| order.id).collect::<Vec<_>>(), vec![2], "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_immediate_or_cancel_consistent_across_types() {
+        use crate::types::order::TimeInForce;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.add_order(new_order(1, 0, 10, 100.0, OrderSide::Sell)).unwrap();
+            order_book.add_order(new_order(2, 0, 10, 101.0, OrderSide::Sell)).unwrap();
+
+            // Takes the 100 level, then the remainder is cancelled rather than resting at 100.5
+            let order = new_order(3, 0, 25, 100.5, OrderSide::Buy).with_time_in_force(TimeInForce::ImmediateOrCancel);
+            order_book.add_order(order).unwrap();
+            let remainder: Vec<_> = order_book.take_evicted_orders().iter().map(|order| (order.id, order.quantity)).collect();
+            assert_eq!(remainder, vec![(3, 15)], "{order_book_type}");
+            assert_eq!(order_book.resting_orders(0).unwrap().iter().map(|order| order.id).collect::<Vec<_>>(), vec![2], "{order_book_type}");
+
+            // Arrival trades come out of the next match
+            let trades: Vec<_> = order_book.match_orders().iter().map(|trade| (trade.taker_order_id, trade.maker_order_id, trade.quantity)).collect();
+            assert_eq!(trades, vec![(3, 1, 10)], "{order_book_type}");
+            assert!(order_book.match_orders().is_empty(), "{order_book_type}");
+
+            // Nothing to match against: the whole order is cancelled
+            order_book.add_order_fast(new_order(4, 0, 5, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImmediateOrCancel));
+            assert_eq!(order_book.take_evicted_orders()[0].id, 4, "{order_book_type}");
+            assert_eq!(order_book.get_best_prices(0), Some((None, Some(101_000))), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_immediate_or_cancel_into_crossed_book_consistent_across_types() {
+        use crate::types::order::TimeInForce;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            // Crossed, but not matched yet
+            order_book.add_order_fast(new_order(1, 0, 10, 101.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(2, 0, 10, 100.0, OrderSide::Sell));
+
+            // The arrival only takes from the bid; the resting cross is left to the next match
+            order_book.add_order_fast(new_order(3, 0, 4, 100.0, OrderSide::Sell).with_time_in_force(TimeInForce::ImmediateOrCancel));
+            assert!(order_book.take_evicted_orders().is_empty(), "{order_book_type}");
+            assert!(order_book.last_match_stats(0).is_none(), "{order_book_type}");
+            let resting: Vec<_> = order_book.resting_orders(0).unwrap().iter().map(|order| (order.id, order.quantity)).collect();
+            assert_eq!(resting, vec![(1, 6), (2, 10)], "{order_book_type}");
+
+            let trades: Vec<_> = order_book.match_orders().iter().map(|trade| (trade.taker_order_id, trade.maker_order_id, trade.quantity)).collect();
+            assert_eq!(trades, vec![(3, 1, 4), (2, 1, 6)], "{order_book_type}");
+            assert_eq!(order_book.last_match_stats(0).map(|stats| stats.trades), Some(1), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_immediate_or_cancel_with_lower_id_is_the_taker_across_types() {
+        use crate::types::order::TimeInForce;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.add_order_fast(new_order(5, 0, 10, 100.0, OrderSide::Sell));
+            order_book.add_order_fast(new_order(1, 0, 4, 101.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImmediateOrCancel));
+
+            let trades: Vec<_> = order_book.match_orders().iter().map(|trade| (trade.taker_order_id, trade.maker_order_id, trade.price, trade.side)).collect();
+            assert_eq!(trades, vec![(1, 5, 100_000, OrderSide::Buy)], "{order_book_type}");
+            assert_eq!(order_book.last_trade_price(0), Some(100_000), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_immediate_or_cancel_never_rests_across_types() {
+        use crate::types::order::TimeInForce;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.set_max_depth(Some(1));
+            order_book.set_level_journal(true);
+            order_book.add_order_fast(new_order(1, 0, 10, 99.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(2, 0, 3, 100.0, OrderSide::Sell));
+            order_book.take_level_changes(0);
+
+            // Resting would put a second bid level over the cap and evict bid 1
+            order_book.add_order_fast(new_order(3, 0, 5, 101.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImmediateOrCancel));
+            let evicted: Vec<_> = order_book.take_evicted_orders().iter().map(|order| (order.id, order.quantity)).collect();
+            assert_eq!(evicted, vec![(3, 2)], "{order_book_type}");
+            let resting: Vec<_> = order_book.resting_orders(0).unwrap().iter().map(|order| order.id).collect();
+            assert_eq!(resting, vec![1], "{order_book_type}");
+            let changes: Vec<_> = order_book.take_level_changes(0).unwrap().iter().map(|change| (change.side, change.price, change.old_quantity, change.new_quantity)).collect();
+            assert_eq!(changes, vec![(OrderSide::Sell, 100_000, 3, 0)], "{order_book_type}");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_sparse_symbols_consistent_across_types() {
+        use crate::engine::participant_limits::ParticipantLimits;
+
+        let (first, far, unlisted) = (7, 4_000_000, 8);
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([first, far]));
+            order_book.set_participant_limits(ParticipantLimits::default().with_max_orders_per_book(1));
+            assert_eq!(order_book.get_best_prices(far), Some((None, None)), "{order_book_type}");
+            assert_eq!(order_book.resting_orders(far).map(|orders| orders.len()), Some(0), "{order_book_type}");
+            assert_eq!(order_book.price_levels(far, OrderSide::Buy, false).map(|levels| levels.count()), Some(0), "{order_book_type}");
+            assert!(order_book.get_best_prices(unlisted).is_none(), "{order_book_type}");
+
+            // Limits set before a symbol's first order still apply to it
+            assert!(order_book.add_order_fast(new_order(1, far, 10, 100.0, OrderSide::Buy).with_participant(3)), "{order_book_type}");
+            assert!(!order_book.add_order_fast(new_order(2, far, 10, 99.0, OrderSide::Buy).with_participant(3)), "{order_book_type}");
+            assert!(!order_book.add_order_fast(new_order(3, unlisted, 10, 100.0, OrderSide::Buy)), "{order_book_type}");
+            assert_eq!(order_book.resting_orders(far).unwrap().len(), 1, "{order_book_type}");
+            assert_eq!(order_book.resting_orders(first).unwrap().len(), 0, "{order_book_type}");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_tick_sizes_consistent_across_types() {
+        use crate::engine::{OffTickPolicy, OrderBookError, TickSizes};
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0, 1]));
+            order_book.set_tick_sizes(TickSizes::default().with_tick(0, 50));
+            assert!(matches!(order_book.add_order(new_order(1, 0, 10, 100.02, OrderSide::Buy)), Err(OrderBookError::OffTick)), "{order_book_type}");
+            assert!(!order_book.add_order_fast(new_order(2, 0, 10, 100.02, OrderSide::Buy)), "{order_book_type}");
+            assert!(order_book.add_order_fast(new_order(3, 1, 10, 100.02, OrderSide::Buy)), "{order_book_type}");
+
+            order_book.set_tick_sizes(order_book.tick_sizes().clone().with_policy(OffTickPolicy::RoundPassive));
+            assert!(order_book.add_order_fast(new_order(4, 0, 10, 100.02, OrderSide::Buy)), "{order_book_type}");
+            assert!(order_book.add_order_fast(new_order(5, 0, 10, 100.02, OrderSide::Sell)), "{order_book_type}");
+            assert_eq!(order_book.get_best_prices(0), Some((Some(100_000), Some(100_050))), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_lot_sizes_consistent_across_types() {
+        use crate::engine::{LotSize, LotSizes, OrderBookError};
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0, 1]));
+            order_book.set_lot_sizes(LotSizes::default().with_rule(0, LotSize::new(100).with_min_notional(price_to_u64(1_000.0) as u128)));
+            assert!(matches!(order_book.add_order(new_order(1, 0, 150, 100.0, OrderSide::Buy)), Err(OrderBookError::OddLot)), "{order_book_type}");
+            assert!(matches!(order_book.add_order(new_order(2, 0, 100, 5.0, OrderSide::Buy)), Err(OrderBookError::BelowMinNotional)), "{order_book_type}");
+            assert!(!order_book.add_order_fast(new_order(3, 0, 50, 100.0, OrderSide::Buy)), "{order_book_type}");
+            assert!(order_book.add_order_fast(new_order(4, 0, 200, 100.0, OrderSide::Buy)), "{order_book_type}");
+            assert!(order_book.add_order_fast(new_order(5, 1, 1, 1.0, OrderSide::Buy)), "{order_book_type}");
+            assert_eq!(order_book.resting_orders(0).unwrap().len(), 1, "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_reduce_order_consistent_across_types() {
+        use crate::engine::OrderBookError;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.add_order_fast(new_order(1, 0, 10, 100.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(2, 0, 10, 100.0, OrderSide::Buy));
+
+            assert_eq!(order_book.reduce_order(1, 4).map(|order| order.quantity).ok(), Some(6), "{order_book_type}");
+            assert!(matches!(order_book.reduce_order(1, 6), Err(OrderBookError::InvalidQuantity)), "{order_book_type}");
+            assert!(matches!(order_book.reduce_order(1, 0), Err(OrderBookError::InvalidQuantity)), "{order_book_type}");
+            assert!(matches!(order_book.reduce_order(9, 1), Err(OrderBookError::OrderNotFound)), "{order_book_type}");
+
+            let level = order_book.price_levels(0, OrderSide::Buy, true).unwrap().next().unwrap();
+            assert_eq!((level.total_quantity, level.order_ids), (16, Some(vec![1, 2])), "{order_book_type}");
+
+            // The reduced order keeps its place at the front of the queue, so it fills
+            // first and the next order keeps the residual
+            order_book.add_order_fast(new_order(4, 0, 10, 100.0, OrderSide::Sell));
+            order_book.match_orders();
+            order_book.add_order_fast(new_order(3, 0, 10, 101.0, OrderSide::Sell));
+            assert_eq!(order_book.reduce_order(3, 1).map(|order| order.quantity).ok(), Some(9), "{order_book_type}");
+            let resting: Vec<_> = order_book.resting_orders(0).unwrap().iter().map(|order| (order.id, order.quantity)).collect();
+            assert_eq!(resting, vec![(2, 6), (3, 9)], "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_cancel_orders_consistent_across_types() {
+        use crate::engine::participant_limits::ParticipantLimits;
+        use crate::types::order::price_to_u64;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0, 1]));
+            order_book.set_participant_limits(ParticipantLimits::default().with_max_orders_per_book(3));
+            for (id, price) in [(1, 100.0), (2, 100.0), (3, 99.0)] {
+                order_book.add_order_fast(new_order(id, 0, 10, price, OrderSide::Buy).with_participant(5));
+            }
+            order_book.add_order_fast(new_order(4, 0, 10, 101.0, OrderSide::Sell));
+            order_book.add_order_fast(new_order(5, 1, 10, 101.0, OrderSide::Sell));
+
+            let mut cancelled: Vec<_> = order_book.cancel_orders(&FxHashSet::from_iter([1, 3, 5, 9])).iter().map(|order| order.id).collect();
+            cancelled.sort_unstable();
+            assert_eq!(cancelled, vec![1, 3, 5], "{order_book_type}");
+            assert_eq!(order_book.get_best_prices(0), Some((Some(price_to_u64(100.0)), Some(price_to_u64(101.0)))), "{order_book_type}");
+            let levels: Vec<_> = order_book.price_levels(0, OrderSide::Buy, true).unwrap().map(|level| (level.total_quantity, level.order_ids)).collect();
+            assert_eq!(levels, vec![(10, Some(vec![2]))], "{order_book_type}");
+
+            // Cancelled orders no longer count towards the participant's limit
+            assert!(order_book.add_order_fast(new_order(6, 0, 10, 98.0, OrderSide::Buy).with_participant(5)), "{order_book_type}");
+            assert!(order_book.add_order_fast(new_order(7, 0, 10, 98.0, OrderSide::Buy).with_participant(5)), "{order_book_type}");
+            assert_eq!(order_book.resting_orders(0).unwrap().len(), 4, "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_cancel_order_consistent_across_types() {
+        use crate::engine::OrderBookError;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0, 1]));
+            for (id, price) in [(1, 100.0), (2, 100.0), (3, 100.0), (4, 101.0)] {
+                order_book.add_order_fast(new_order(id, 0, 10, price, OrderSide::Buy));
+            }
+
+            // The middle of a level, then the whole best level
+            assert_eq!(order_book.cancel_order(0, 2).map(|order| order.id).ok(), Some(2), "{order_book_type}");
+            assert_eq!(order_book.cancel_order(0, 4).map(|order| order.price).ok(), Some(price_to_u64(101.0)), "{order_book_type}");
+            assert_eq!(order_book.get_best_prices(0), Some((Some(price_to_u64(100.0)), None)), "{order_book_type}");
+            assert!(matches!(order_book.cancel_order(0, 2), Err(OrderBookError::OrderNotFound)), "{order_book_type}");
+            assert!(matches!(order_book.cancel_order(1, 1), Err(OrderBookError::OrderNotFound)), "{order_book_type}");
+            assert!(matches!(order_book.cancel_order(7, 1), Err(OrderBookError::InvalidSymbol)), "{order_book_type}");
+
+            // A cancelled id can rest again, and cancelled orders never trade
+            order_book.add_order_fast(new_order(2, 0, 10, 99.0, OrderSide::Buy));
+            order_book.add_order_fast(new_order(5, 0, 30, 99.0, OrderSide::Sell));
+            let mut filled: Vec<_> = order_book.match_orders().iter().map(|trade| trade.maker_order_id).collect();
+            filled.sort_unstable();
+            assert_eq!(filled, vec![1, 2, 3], "{order_book_type}");
+            assert!(order_book.resting_orders(0).unwrap().is_empty(), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_match_stats_consistent_across_types() {
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            for (id, quantity, price, side) in [
+                (1, 10, 101.0, OrderSide::Buy), (2, 5, 101.0, OrderSide::Buy), (3, 10, 100.0, OrderSide::Buy),
+                (4, 8, 99.0, OrderSide::Sell), (5, 10, 100.0, OrderSide::Sell), (6, 10, 102.0, OrderSide::Sell),
+            ] {
+                order_book.add_order_fast(new_order(id, 0, quantity, price, side));
+            }
+            assert!(order_book.last_match_stats(0).is_none(), "{order_book_type}");
+            order_book.match_orders();
+
+            let stats = order_book.last_match_stats(0).unwrap();
+            assert_eq!((stats.trades, stats.volume, stats.levels_cleared), (4, 18, 3), "{order_book_type}");
+
+            // A cycle with nothing to cross leaves the last stats in place
+            order_book.match_orders();
+            assert_eq!(order_book.last_match_stats(0), Some(stats), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_level_journal_consistent_across_types() {
+        use crate::types::order::price_to_u64;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.add_order_fast(new_order(1, 0, 10, 100.0, OrderSide::Buy));
+            order_book.set_level_journal(true);
+            order_book.add_order_fast(new_order(2, 0, 5, 100.0, OrderSide::Buy));
+            order_book.reduce_order(2, 2).unwrap();
+            order_book.add_order_fast(new_order(3, 0, 10, 100.0, OrderSide::Sell));
+            order_book.match_orders();
+            order_book.cancel_orders(&FxHashSet::from_iter([2]));
+
+            let changes: Vec<_> = order_book.take_level_changes(0).unwrap().iter()
+                .map(|change| (change.side, change.old_quantity, change.new_quantity))
+                .collect();
+            assert_eq!(changes, vec![
+                (OrderSide::Buy, 10, 15), (OrderSide::Buy, 15, 13), (OrderSide::Sell, 0, 10),
+                (OrderSide::Buy, 13, 3), (OrderSide::Sell, 10, 0), (OrderSide::Buy, 3, 0),
+            ], "{order_book_type}");
+            assert!(order_book.take_level_changes(0).unwrap().is_empty(), "{order_book_type}");
+            assert!(order_book.take_level_changes(1).is_none(), "{order_book_type}");
+
+            order_book.set_level_journal(false);
+            order_book.add_order_fast(new_order(4, 0, 10, 99.0, OrderSide::Buy));
+            assert!(order_book.take_level_changes(0).unwrap().is_empty(), "{order_book_type}");
+            assert_eq!(order_book.get_best_prices(0), Some((Some(price_to_u64(99.0)), None)), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_tie_break_consistent_across_types() {
+        use crate::engine::TieBreak;
+
+        let remaining_bids = |order_book_type, tie_break| {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0]));
+            order_book.set_tie_break(tie_break);
+            for (id, quantity) in [(1, 5), (2, 20), (3, 10), (4, 20)] {
+                order_book.add_order_fast(new_order(id, 0, quantity, 100.0, OrderSide::Buy));
+            }
+            order_book.add_order_fast(new_order(5, 0, 20, 100.0, OrderSide::Sell));
+            order_book.match_orders();
+            assert_eq!(order_book.tie_break(), tie_break, "{order_book_type}");
+            order_book.resting_orders(0).unwrap().iter().map(|order| order.id).collect::<Vec<_>>()
+        };
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            assert_eq!(remaining_bids(order_book_type, TieBreak::Fifo), vec![2, 3, 4], "{order_book_type}");
+            assert_eq!(remaining_bids(order_book_type, TieBreak::SizePriority), vec![1, 3, 4], "{order_book_type}");
+
+            let lottery = remaining_bids(order_book_type, TieBreak::Lottery { seed: 7 });
+            assert_eq!(lottery.len(), 3, "{order_book_type}");
+            assert_eq!(lottery, remaining_bids(order_book_type, TieBreak::Lottery { seed: 7 }), "{order_book_type}");
+        }
+    }
+
+    #[test]
+    fn test_match_orders_returns_trades_across_types() {
+        use crate::types::trade::Trade;
+
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut order_book = create_order_book(order_book_type, FxHashSet::from_iter([0, 1]));
+            order_book.add_order_fast(new_order(1, 0, 10, 101.0, OrderSide::Sell));
+            order_book.add_order_fast(new_order(2, 0, 4, 101.5, OrderSide::Buy));
+            order_book.add_order_fast(new_order(3, 1, 7, 50.0, OrderSide::Buy));
+            assert!(order_book.match_orders().iter().all(|trade| trade.symbol == 0), "{order_book_type}");
+            assert!(order_book.match_orders().is_empty(), "{order_book_type}");
+
+            order_book.add_order_fast(new_order(4, 1, 9, 49.0, OrderSide::Sell));
+            assert_eq!(order_book.match_orders(), vec![Trade {
+                symbol: 1,
+                taker_order_id: 4,
+                maker_order_id: 3,
+                price: 50_000,
+                quantity: 7,
+                side: OrderSide::Sell,
+            }], "{order_book_type}");
+        }
+    }
+
     #[test]
     fn test_factory_functions_work() {
         let symbols = FxHashSet::from_iter([0]);
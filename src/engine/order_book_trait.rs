@@ -1,37 +1,189 @@
-use crate::{engine::OrderBookType, types::{order::Order, symbol_mapping::SymbolId}};
+use crate::{engine::{AggregatedDepth, BookDepth, LevelChange, LotSizes, MatchStats, OrderBookType, ParticipantLimits, PriceLevelIter, price_level_view, TickSizes, TieBreak}, types::{order::{Order, OrderSide}, symbol_mapping::SymbolId, trade::{Trade, TradePriceRule}}};
 use rustc_hash::FxHashSet;
 
 #[derive(Debug, Clone, Copy)]
 pub enum OrderBookError {
     InvalidSymbol,
+    ParticipantLimitExceeded,
+    /// A fixed-capacity book has no free price level or order slot.
+    CapacityExceeded,
+    OrderNotFound,
+    /// A reduction of zero, or of the order's whole quantity.
+    InvalidQuantity,
+    /// The price is not a multiple of the symbol's tick size.
+    OffTick,
+    /// The quantity is not a multiple of the symbol's lot size.
+    OddLot,
+    /// The quantity is below the symbol's minimum order quantity.
+    BelowMinQuantity,
+    /// Price times quantity is below the symbol's minimum notional.
+    BelowMinNotional,
 }
 
-pub trait OrderBookTrait: Send + Sync {
+/// Order entry and matching. The minimum a book needs to be benchmarked or driven directly.
+pub trait OrderBookCore: Send + Sync {
     fn new(symbols: FxHashSet<SymbolId>) -> Self where Self: Sized;
-    
+
+    /// Rests the order. An immediate-or-cancel order is matched against its symbol on
+    /// arrival instead, and whatever is left of it is cancelled into
+    /// `take_evicted_orders`; its trades come out of the next `match_orders`.
     fn add_order(&mut self, order: Order) -> Result<bool, OrderBookError>;
-    
+
     fn add_order_fast(&mut self, order: Order) -> bool;
-    
+
     /// # Safety
     /// Caller must guarantee that the symbol is valid.
     unsafe fn add_order_unchecked(&mut self, order: Order);
-    
-    fn match_orders(&mut self);
-    
+
+    /// Matches every crossed symbol and returns the executions, in order within each
+    /// symbol.
+    fn match_orders(&mut self) -> Vec<Trade>;
+
     fn add_orders_batch_fast(&mut self, orders: &[Order]) -> (u32, u32);
-    
+
     /// # Safety
     /// Caller must guarantee that all symbols are valid.
     unsafe fn add_orders_batch_unchecked(&mut self, orders: &[Order]) -> u32;
-    
+
     fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)>;
-    
+
     fn can_match(&self, symbol: SymbolId) -> bool;
-    
+
     fn is_valid_symbol(&self, symbol: SymbolId) -> bool;
-    
+
     fn get_symbols(&self) -> &FxHashSet<SymbolId>;
 
     fn order_book_type(&self) -> OrderBookType;
-}
\ No newline at end of file
+}
+
+/// Resting order state and the policies applied to it, needed for snapshots, session
+/// rolls and router-level configuration.
+pub trait OrderBookLifecycle: OrderBookCore {
+    /// Resting orders for a symbol, bids then asks, each side in matching priority order.
+    fn resting_orders(&self, symbol: SymbolId) -> Option<Vec<Order>>;
+
+    fn set_trade_price_rule(&mut self, rule: TradePriceRule);
+
+    fn trade_price_rule(&self) -> TradePriceRule;
+
+    /// How each price level picks the next of its orders to match. The trade price
+    /// still follows the trade price rule.
+    fn set_tie_break(&mut self, tie_break: TieBreak);
+
+    fn tie_break(&self) -> TieBreak;
+
+    /// Rebuilds the per-participant counts from the orders already resting.
+    fn set_participant_limits(&mut self, limits: ParticipantLimits);
+
+    fn participant_limits(&self) -> ParticipantLimits;
+
+    /// Replaces the tick table every added order is checked against. Orders already
+    /// resting keep their prices.
+    fn set_tick_sizes(&mut self, tick_sizes: TickSizes);
+
+    fn tick_sizes(&self) -> &TickSizes;
+
+    /// Replaces the lot size and minimum quantity and notional rules every added order
+    /// is checked against, after its tick.
+    fn set_lot_sizes(&mut self, lot_sizes: LotSizes);
+
+    fn lot_sizes(&self) -> &LotSizes;
+
+    /// Caps the price levels kept per side. A new level beyond the cap evicts the level
+    /// farthest from the touch, which may be the new order's own; `None` lifts the cap.
+    fn set_max_depth(&mut self, max_levels: Option<usize>);
+
+    fn max_depth(&self) -> Option<usize>;
+
+    /// Orders cancelled by the depth cap, and immediate-or-cancel remainders, since the
+    /// last call, for returning to their owners.
+    fn take_evicted_orders(&mut self) -> Vec<Order>;
+
+    /// Starts or stops journaling each change to a price level's resting quantity, per
+    /// symbol. Off by default; disabling drops changes not yet taken.
+    fn set_level_journal(&mut self, enabled: bool);
+
+    fn level_journal(&self) -> bool;
+
+    /// Level changes journaled for `symbol` since the last call, oldest first.
+    fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>>;
+
+    /// Takes `quantity_delta` off a resting order without moving it in its queue and
+    /// returns the order as it now rests. Removing the whole quantity is a cancel and is
+    /// rejected with `InvalidQuantity`.
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Result<Order, OrderBookError>;
+
+    /// Removes every resting order `predicate` accepts in a single pass over each
+    /// symbol's book and returns them.
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool) -> Vec<Order>;
+
+    /// Removes one resting order from `symbol`'s book and returns it. The default is a
+    /// full scan through `cancel_where`; every built-in book overrides it to find the
+    /// order through an id index instead.
+    fn cancel_order(&mut self, symbol: SymbolId, order_id: u64) -> Result<Order, OrderBookError> {
+        if !self.is_valid_symbol(symbol) {
+            return Err(OrderBookError::InvalidSymbol);
+        }
+        self.cancel_where(&|order| order.symbol == symbol && order.id == order_id)
+            .pop()
+            .ok_or(OrderBookError::OrderNotFound)
+    }
+
+    /// Removes every resting order whose id is in `order_ids`. Ids with nothing resting
+    /// are ignored. The default is a full scan through `cancel_where`; every built-in
+    /// book overrides it to look each id up in its id index instead.
+    fn cancel_orders(&mut self, order_ids: &FxHashSet<u64>) -> Vec<Order> {
+        if order_ids.is_empty() {
+            return Vec::new();
+        }
+        self.cancel_where(&|order| order_ids.contains(&order.id))
+    }
+
+    /// Removes every resting order whose `expires_at` is at or before `now` and returns
+    /// them. The default scans the whole book, and every book but the HashMap one uses
+    /// it; the HashMap book walks an expiry-ordered index and only touches the orders
+    /// that expire.
+    fn take_expired_orders(&mut self, now: u64) -> Vec<Order> {
+        self.cancel_where(&|order| order.expires_at.is_some_and(|expires_at| expires_at <= now))
+    }
+
+    /// Cancels every good-till-date order that has expired by `now` and returns their ids.
+    fn expire_orders(&mut self, now: u64) -> Vec<u64> {
+        self.take_expired_orders(now).iter().map(|order| order.id).collect()
+    }
+}
+
+/// Read-only views for market data consumers.
+pub trait OrderBookMarketData: OrderBookCore {
+    /// Price levels on one side, best price first.
+    fn price_levels(&self, symbol: SymbolId, side: OrderSide, with_order_ids: bool) -> Option<PriceLevelIter<'_>>;
+
+    fn last_trade_price(&self, symbol: SymbolId) -> Option<u64>;
+
+    /// Stats from the symbol's most recent matching cycle that found a cross.
+    fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats>;
+
+    /// The best `levels` price levels on each side, with each level's total quantity
+    /// and order count. `None` for an unknown symbol.
+    fn get_depth(&self, symbol: SymbolId, levels: usize) -> Option<BookDepth> {
+        let side = |side| self.price_levels(symbol, side, false).map(|iter| iter.take(levels).collect());
+        Some(BookDepth { bids: side(OrderSide::Buy)?, asks: side(OrderSide::Sell)? })
+    }
+
+    /// Depth grouped into bands of `band_size` price units, at most `levels` bands per side.
+    fn aggregated_depth(&self, symbol: SymbolId, band_size: u64, levels: usize) -> Option<AggregatedDepth> {
+        let bids = self.price_levels(symbol, OrderSide::Buy, false)?;
+        let asks = self.price_levels(symbol, OrderSide::Sell, false)?;
+        Some(AggregatedDepth {
+            bids: price_level_view::band_levels(bids, OrderSide::Buy, band_size, levels),
+            asks: price_level_view::band_levels(asks, OrderSide::Sell, band_size, levels),
+        })
+    }
+}
+
+/// Every capability the routers rely on. Implemented automatically for any book that
+/// implements the capability traits, so new methods land on the narrowest trait that
+/// fits and generic code can bound on just what it uses.
+pub trait OrderBookTrait: OrderBookCore + OrderBookLifecycle + OrderBookMarketData {}
+
+impl<T: OrderBookCore + OrderBookLifecycle + OrderBookMarketData + ?Sized> OrderBookTrait for T {}
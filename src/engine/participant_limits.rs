@@ -0,0 +1,139 @@
+use rustc_hash::FxHashMap;
+
+use crate::types::order::{Order, OrderSide, ParticipantId};
+use crate::types::validation;
+
+/// Fair-access caps on resting orders per participant within one symbol's book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ParticipantLimits {
+    pub max_orders_per_level: Option<usize>,
+    pub max_orders_per_book: Option<usize>,
+}
+
+impl ParticipantLimits {
+    #[inline(always)]
+    pub fn with_max_orders_per_level(mut self, max_orders: usize) -> Self {
+        self.max_orders_per_level = Some(max_orders);
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_max_orders_per_book(mut self, max_orders: usize) -> Self {
+        self.max_orders_per_book = Some(max_orders);
+        self
+    }
+
+    #[inline(always)]
+    pub fn is_unlimited(&self) -> bool {
+        self.max_orders_per_level.is_none() && self.max_orders_per_book.is_none()
+    }
+}
+
+/// Resting order counts backing `ParticipantLimits`. Nothing is tracked while
+/// unlimited or with validation stripped, so neither adds work to the hot path.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ParticipantOrderCounts {
+    limits: ParticipantLimits,
+    per_book: FxHashMap<ParticipantId, usize>,
+    per_level: FxHashMap<(ParticipantId, OrderSide, u64), usize>,
+}
+
+impl ParticipantOrderCounts {
+    pub(crate) fn set_limits<'a>(&mut self, limits: ParticipantLimits, resting: impl IntoIterator<Item = &'a Order>) {
+        self.limits = limits;
+        self.per_book.clear();
+        self.per_level.clear();
+        if self.enforced() {
+            for order in resting {
+                self.record(order);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn enforced(&self) -> bool {
+        validation::ENABLED && !self.limits.is_unlimited()
+    }
+
+    #[inline(always)]
+    pub(crate) fn admits(&self, order: &Order) -> bool {
+        if !self.enforced() {
+            return true;
+        }
+
+        let within = |limit: Option<usize>, count: Option<&usize>| {
+            limit.is_none_or(|limit| count.copied().unwrap_or(0) < limit)
+        };
+        within(self.limits.max_orders_per_book, self.per_book.get(&order.participant))
+            && within(self.limits.max_orders_per_level, self.per_level.get(&Self::level_key(order)))
+    }
+
+    #[inline(always)]
+    pub(crate) fn record(&mut self, order: &Order) {
+        if !self.enforced() {
+            return;
+        }
+        *self.per_book.entry(order.participant).or_default() += 1;
+        *self.per_level.entry(Self::level_key(order)).or_default() += 1;
+    }
+
+    #[inline(always)]
+    pub(crate) fn release(&mut self, order: &Order) {
+        if !self.enforced() {
+            return;
+        }
+        Self::decrement(&mut self.per_book, order.participant);
+        Self::decrement(&mut self.per_level, Self::level_key(order));
+    }
+
+    #[inline(always)]
+    fn level_key(order: &Order) -> (ParticipantId, OrderSide, u64) {
+        (order.participant, order.order_type, order.price)
+    }
+
+    #[inline(always)]
+    fn decrement<K: std::hash::Hash + Eq>(counts: &mut FxHashMap<K, usize>, key: K) {
+        if let Some(count) = counts.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_counts_enforce_level_and_book_caps() {
+        let mut counts = ParticipantOrderCounts::default();
+        let resting = [new_order(1, 0, 100, 10.0, OrderSide::Buy).with_participant(7)];
+        counts.set_limits(ParticipantLimits::default().with_max_orders_per_level(1).with_max_orders_per_book(2), &resting);
+
+        let same_level = new_order(2, 0, 100, 10.0, OrderSide::Buy).with_participant(7);
+        let other_level = new_order(3, 0, 100, 11.0, OrderSide::Buy).with_participant(7);
+        assert!(!counts.admits(&same_level));
+        assert!(counts.admits(&other_level));
+        assert!(counts.admits(&same_level.clone().with_participant(8)));
+
+        counts.record(&other_level);
+        assert!(!counts.admits(&new_order(4, 0, 100, 12.0, OrderSide::Sell).with_participant(7)));
+
+        counts.release(&resting[0]);
+        assert!(counts.admits(&same_level));
+    }
+
+    #[test]
+    #[cfg(feature = "strip-validation")]
+    fn test_stripped_validation_admits_everything() {
+        let mut counts = ParticipantOrderCounts::default();
+        let order = new_order(1, 0, 100, 10.0, OrderSide::Buy).with_participant(7);
+        counts.set_limits(ParticipantLimits::default().with_max_orders_per_book(1), [&order]);
+        assert!(counts.admits(&order));
+        assert!(counts.per_book.is_empty());
+    }
+}
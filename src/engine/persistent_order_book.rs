@@ -0,0 +1,665 @@
+use im::{OrdMap, Vector};
+use rustc_hash::FxHashSet;
+
+use crate::engine::order_book_trait::{OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData};
+use crate::engine::OrderBookType;
+use crate::engine::price_level_view::{PriceLevelIter, PriceLevelView};
+use crate::engine::participant_limits::{ParticipantLimits, ParticipantOrderCounts};
+use crate::engine::lot_size::LotSizes;
+use crate::engine::symbol_map::{FxMaps, IdMap, MapBackend, SymbolMap};
+use crate::engine::tick_size::TickSizes;
+use crate::engine::depth_limit::DepthLimit;
+use crate::engine::match_stats::{self, MatchCycle, MatchStats, TakeOnArrival};
+use crate::engine::level_journal::{LevelChange, LevelJournal};
+use crate::engine::tie_break::{TieBreak, TieBreaker};
+use crate::engine::reduction;
+use crate::engine::lazy_matchers;
+use crate::types::{order::{Order, OrderSide, OrderFlags}, symbol_mapping::SymbolId, trade::{Trade, TradePriceRule}};
+
+type Levels = OrdMap<u64, Vector<Order>>;
+
+#[derive(Debug)]
+struct PersistentMatcher<B: MapBackend> {
+    bid_levels: Levels,
+    ask_levels: Levels,
+    // Order id to the level it rests in, so cancels and reductions search one level
+    ids: B::Ids<(OrderSide, u64)>,
+    last_trade_price: Option<u64>,
+    participants: ParticipantOrderCounts,
+    depth: DepthLimit,
+    evicted: Vec<Order>,
+    arrival_trades: Vec<Trade>,
+    match_stats: Option<MatchStats>,
+    level_changes: LevelJournal,
+    tie_breaker: TieBreaker,
+}
+
+impl<B: MapBackend> Default for PersistentMatcher<B> {
+    fn default() -> Self {
+        Self {
+            bid_levels: Levels::default(),
+            ask_levels: Levels::default(),
+            ids: Default::default(),
+            last_trade_price: None,
+            participants: ParticipantOrderCounts::default(),
+            depth: DepthLimit::default(),
+            evicted: Vec::new(),
+            arrival_trades: Vec::new(),
+            match_stats: None,
+            level_changes: LevelJournal::default(),
+            tie_breaker: TieBreaker::default(),
+        }
+    }
+}
+
+impl<B: MapBackend> PersistentMatcher<B> {
+    #[inline(always)]
+    fn levels_mut(&mut self, side: OrderSide) -> &mut Levels {
+        match side {
+            OrderSide::Buy => &mut self.bid_levels,
+            OrderSide::Sell => &mut self.ask_levels,
+        }
+    }
+
+    #[inline(always)]
+    fn add_order(&mut self, order: Order) {
+        self.participants.record(&order);
+        self.level_changes.record(order.order_type, order.price, order.quantity);
+        let (side, price) = (order.order_type, order.price);
+        let evict = self.depth.record(side, price);
+        self.ids.insert(order.id, (side, price));
+        self.levels_mut(side).entry(price).or_default().push_back(order);
+        if let Some(far_price) = evict {
+            self.evict_level(side, far_price);
+        }
+    }
+
+    fn evict_level(&mut self, side: OrderSide, price: u64) {
+        for order in self.levels_mut(side).remove(&price).into_iter().flatten() {
+            self.ids.remove(order.id);
+            self.participants.release(&order);
+            self.level_changes.release(&order);
+            self.evicted.push(order);
+        }
+    }
+
+    fn match_orders(&mut self, rule: TradePriceRule, trades: &mut Vec<Trade>) {
+        if !self.can_match() {
+            return;
+        }
+        let mut cycle = MatchCycle::start();
+        while self.can_match() {
+            let bid_price = self.bid_levels.get_max().map(|(price, _)| *price);
+            let ask_price = self.ask_levels.get_min().map(|(price, _)| *price);
+            let (Some(bid_price), Some(ask_price)) = (bid_price, ask_price) else { break };
+
+            let bid = Self::peek_next(&self.bid_levels, bid_price, &mut self.tie_breaker);
+            let ask = Self::peek_next(&self.ask_levels, ask_price, &mut self.tie_breaker);
+            match (bid, ask) {
+                (Some((bid_index, bid)), Some((ask_index, ask))) => {
+                    self.last_trade_price = Some(cycle.record_trade(&bid, &ask, rule));
+                    let quantity = bid.quantity.min(ask.quantity);
+                    self.settle_fill(&bid, quantity);
+                    self.settle_fill(&ask, quantity);
+                    Self::fill(&mut self.bid_levels, bid_price, bid_index, quantity);
+                    Self::fill(&mut self.ask_levels, ask_price, ask_index, quantity);
+                    if !self.bid_levels.contains_key(&bid_price) {
+                        cycle.level_cleared();
+                    }
+                    if !self.ask_levels.contains_key(&ask_price) {
+                        cycle.level_cleared();
+                    }
+                }
+                _ => break,
+            }
+        }
+        self.match_stats = Some(cycle.finish(trades));
+    }
+
+    #[inline(always)]
+    fn peek_next(levels: &Levels, price: u64, tie_breaker: &mut TieBreaker) -> Option<(usize, Order)> {
+        let level = levels.get(&price)?;
+        let index = tie_breaker.pick(level.len(), level.iter().map(|order| order.quantity))?;
+        level.get(index).map(|order| (index, order.clone()))
+    }
+
+    /// Fills `quantity` of the order at `index`, which keeps its place in the level
+    /// unless that fills it completely.
+    #[inline(always)]
+    fn fill(levels: &mut Levels, price: u64, index: usize, quantity: u64) {
+        let Some(level) = levels.get_mut(&price) else { return };
+        match level.get_mut(index) {
+            Some(order) if order.quantity > quantity => order.quantity -= quantity,
+            Some(_) => {
+                level.remove(index);
+                if level.is_empty() {
+                    levels.remove(&price);
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Books a fill of `quantity` against `order` as it stood before the trade.
+    #[inline(always)]
+    fn settle_fill(&mut self, order: &Order, quantity: u64) {
+        if quantity == order.quantity {
+            self.ids.remove(order.id);
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        } else {
+            self.level_changes.reduce(order, quantity);
+        }
+    }
+
+    #[inline(always)]
+    fn get_best_prices(&self) -> (Option<u64>, Option<u64>) {
+        best_prices(&self.bid_levels, &self.ask_levels)
+    }
+
+    #[inline(always)]
+    fn can_match(&self) -> bool {
+        match self.get_best_prices() {
+            (Some(bid), Some(ask)) => bid >= ask,
+            _ => false,
+        }
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Option<Result<Order, OrderBookError>> {
+        let &(side, price) = self.ids.get(order_id)?;
+        let order = self.levels_mut(side).get_mut(&price)?.iter_mut().find(|order| order.id == order_id)?;
+        let reduced = reduction::reduce(order, quantity_delta);
+        if let Ok(order) = &reduced {
+            self.level_changes.reduce(order, quantity_delta);
+        }
+        Some(reduced)
+    }
+
+    fn cancel_order(&mut self, order_id: u64) -> Option<Order> {
+        let (side, price) = self.ids.remove(order_id)?;
+        let levels = self.levels_mut(side);
+        let level = levels.get_mut(&price)?;
+        let index = level.iter().position(|order| order.id == order_id)?;
+        let order = level.remove(index);
+        if level.is_empty() {
+            levels.remove(&price);
+        }
+        self.participants.release(&order);
+        self.depth.release(&order);
+        self.level_changes.release(&order);
+        Some(order)
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool, cancelled: &mut Vec<Order>) {
+        let start = cancelled.len();
+        for levels in [&mut self.bid_levels, &mut self.ask_levels] {
+            let prices: Vec<u64> = levels.iter()
+                .filter(|(_, level)| level.iter().any(predicate))
+                .map(|(&price, _)| price)
+                .collect();
+            for price in prices {
+                let Some(level) = levels.get_mut(&price) else { continue };
+                cancelled.extend(level.iter().filter(|order| predicate(order)).cloned());
+                level.retain(|order| !predicate(order));
+                if level.is_empty() {
+                    levels.remove(&price);
+                }
+            }
+        }
+        for order in &cancelled[start..] {
+            self.ids.remove(order.id);
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        }
+    }
+
+    fn resting_orders(&self) -> Vec<Order> {
+        resting_orders(&self.bid_levels, &self.ask_levels)
+    }
+}
+
+#[inline(always)]
+fn best_prices(bid_levels: &Levels, ask_levels: &Levels) -> (Option<u64>, Option<u64>) {
+    (
+        bid_levels.get_max().map(|(price, _)| *price),
+        ask_levels.get_min().map(|(price, _)| *price),
+    )
+}
+
+fn resting_orders(bid_levels: &Levels, ask_levels: &Levels) -> Vec<Order> {
+    bid_levels.values().rev()
+        .chain(ask_levels.values())
+        .flat_map(|level| level.iter().cloned())
+        .collect()
+}
+
+/// Point-in-time view of one symbol's book. Taking it is O(1): the price level
+/// trees are structurally shared with the live book until the matcher mutates them.
+#[derive(Debug, Clone)]
+pub struct PersistentBookView {
+    symbol: SymbolId,
+    bid_levels: Levels,
+    ask_levels: Levels,
+}
+
+impl PersistentBookView {
+    #[inline(always)]
+    pub fn symbol(&self) -> SymbolId {
+        self.symbol
+    }
+
+    #[inline(always)]
+    pub fn get_best_prices(&self) -> (Option<u64>, Option<u64>) {
+        best_prices(&self.bid_levels, &self.ask_levels)
+    }
+
+    pub fn resting_orders(&self) -> Vec<Order> {
+        resting_orders(&self.bid_levels, &self.ask_levels)
+    }
+
+    /// (price, total quantity, order count) per bid level, best first.
+    pub fn bid_levels(&self) -> impl Iterator<Item = (u64, u64, usize)> + '_ {
+        self.bid_levels.iter().rev().map(level_summary)
+    }
+
+    /// (price, total quantity, order count) per ask level, best first.
+    pub fn ask_levels(&self) -> impl Iterator<Item = (u64, u64, usize)> + '_ {
+        self.ask_levels.iter().map(level_summary)
+    }
+}
+
+#[inline(always)]
+fn level_summary((price, orders): (&u64, &Vector<Order>)) -> (u64, u64, usize) {
+    (*price, orders.iter().map(|order| order.quantity).sum(), orders.len())
+}
+
+impl<B: MapBackend> TakeOnArrival for PersistentMatcher<B> {
+    type Slot = usize;
+
+    fn next_resting(&mut self, taker: &Order) -> Option<(Self::Slot, Order)> {
+        let (best, levels) = match taker.order_type {
+            OrderSide::Buy => (self.ask_levels.get_min(), &self.ask_levels),
+            OrderSide::Sell => (self.bid_levels.get_max(), &self.bid_levels),
+        };
+        let price = best.map(|(price, _)| *price).filter(|&price| taker.order_type.crosses(taker.price, price))?;
+        Self::peek_next(levels, price, &mut self.tie_breaker)
+    }
+
+    fn fill_resting(&mut self, index: Self::Slot, resting: &Order, quantity: u64) -> bool {
+        self.settle_fill(resting, quantity);
+        let levels = self.levels_mut(resting.order_type);
+        Self::fill(levels, resting.price, index, quantity);
+        !levels.contains_key(&resting.price)
+    }
+
+    fn arrived(&mut self, mut trades: Vec<Trade>, last_trade_price: Option<u64>, remainder: Option<Order>) {
+        self.arrival_trades.append(&mut trades);
+        self.last_trade_price = last_trade_price.or(self.last_trade_price);
+        self.evicted.extend(remainder);
+    }
+}
+
+#[repr(align(64))]
+pub struct PersistentOrderBook<B: MapBackend = FxMaps> {
+    symbols: FxHashSet<SymbolId>,
+    matchers: B::Matchers<PersistentMatcher<B>>,
+    trade_price_rule: TradePriceRule,
+    tie_break: TieBreak,
+    participant_limits: ParticipantLimits,
+    tick_sizes: TickSizes,
+    lot_sizes: LotSizes,
+    max_depth: Option<usize>,
+    level_journal: bool,
+}
+
+impl<B: MapBackend> PersistentOrderBook<B> {
+    #[inline(always)]
+    pub fn snapshot(&self, symbol: SymbolId) -> Option<PersistentBookView> {
+        let (bid_levels, ask_levels) = match self.matchers.get(symbol) {
+            Some(matcher) => (matcher.bid_levels.clone(), matcher.ask_levels.clone()),
+            None if self.symbols.contains(&symbol) => Default::default(),
+            None => return None,
+        };
+        Some(PersistentBookView { symbol, bid_levels, ask_levels })
+    }
+
+    #[inline(always)]
+    fn matcher_mut(&mut self, symbol: SymbolId) -> Option<&mut PersistentMatcher<B>> {
+        let (limits, max_depth, level_journal, tie_break) = (self.participant_limits, self.max_depth, self.level_journal, self.tie_break);
+        lazy_matchers::matcher_entry(&mut self.matchers, &self.symbols, symbol, || {
+            let mut matcher = PersistentMatcher::default();
+            matcher.participants.set_limits(limits, []);
+            let _ = matcher.depth.set_max_levels(max_depth, []);
+            matcher.level_changes.set_enabled(level_journal, []);
+            matcher.tie_breaker = TieBreaker::new(tie_break);
+            matcher
+        })
+    }
+}
+
+impl PersistentOrderBook {
+    pub fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        <Self as OrderBookCore>::new(symbols)
+    }
+}
+
+impl<B: MapBackend> OrderBookCore for PersistentOrderBook<B> {
+    fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        Self {
+            symbols,
+            matchers: Default::default(),
+            trade_price_rule: TradePriceRule::default(),
+            tie_break: TieBreak::default(),
+            participant_limits: ParticipantLimits::default(),
+            tick_sizes: TickSizes::default(),
+            lot_sizes: LotSizes::default(),
+            max_depth: None,
+            level_journal: false,
+        }
+    }
+
+    #[inline(always)]
+    fn add_order(&mut self, order: Order) -> Result<bool, OrderBookError> {
+        let order = self.tick_sizes.conform(order)?;
+        self.lot_sizes.check(&order)?;
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return Err(OrderBookError::ParticipantLimitExceeded);
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+            } else {
+                matcher.add_order(order);
+            }
+            Ok(true)
+        } else {
+            Err(OrderBookError::InvalidSymbol)
+        }
+    }
+
+    #[inline(always)]
+    fn add_order_fast(&mut self, order: Order) -> bool {
+        let Ok(order) = self.tick_sizes.conform(order) else { return false };
+        if self.lot_sizes.check(&order).is_err() {
+            return false;
+        }
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return false;
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+            } else {
+                matcher.add_order(order);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn add_order_unchecked(&mut self, order: Order) {
+        let rule = self.trade_price_rule;
+        let matcher = unsafe { self.matcher_mut(order.symbol).unwrap_unchecked() };
+        if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+            match_stats::take_on_arrival(matcher, order, rule);
+        } else {
+            matcher.add_order(order);
+        }
+    }
+
+    #[inline(always)]
+    fn match_orders(&mut self) -> Vec<Trade> {
+        let mut trades = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            trades.append(&mut matcher.arrival_trades);
+            matcher.match_orders(self.trade_price_rule, &mut trades);
+        }
+        trades
+    }
+
+    #[inline(always)]
+    fn add_orders_batch_fast(&mut self, orders: &[Order]) -> (u32, u32) {
+        let mut successful = 0;
+        let mut failed = 0;
+
+        for order in orders {
+            if self.add_order_fast(order.clone()) {
+                successful += 1;
+            } else {
+                failed += 1;
+            }
+        }
+
+        (successful, failed)
+    }
+
+    #[inline(always)]
+    unsafe fn add_orders_batch_unchecked(&mut self, orders: &[Order]) -> u32 {
+        for order in orders {
+            unsafe { self.add_order_unchecked(order.clone()); }
+        }
+        orders.len() as u32
+    }
+
+    #[inline(always)]
+    fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.get_best_prices()),
+            None => self.symbols.contains(&symbol).then_some((None, None)),
+        }
+    }
+
+    #[inline(always)]
+    fn can_match(&self, symbol: SymbolId) -> bool {
+        self.matchers.get(symbol)
+            .is_some_and(|matcher| matcher.can_match())
+    }
+
+    #[inline(always)]
+    fn is_valid_symbol(&self, symbol: SymbolId) -> bool {
+        self.symbols.contains(&symbol)
+    }
+
+    #[inline(always)]
+    fn get_symbols(&self) -> &FxHashSet<SymbolId> {
+        &self.symbols
+    }
+
+    #[inline(always)]
+    fn order_book_type(&self) -> OrderBookType {
+        OrderBookType::Persistent
+    }
+}
+
+impl<B: MapBackend> OrderBookLifecycle for PersistentOrderBook<B> {
+    fn resting_orders(&self, symbol: SymbolId) -> Option<Vec<Order>> {
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.resting_orders()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    #[inline(always)]
+    fn set_trade_price_rule(&mut self, rule: TradePriceRule) {
+        self.trade_price_rule = rule;
+    }
+
+    #[inline(always)]
+    fn trade_price_rule(&self) -> TradePriceRule {
+        self.trade_price_rule
+    }
+
+    fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+        for matcher in self.matchers.values_mut() {
+            matcher.tie_breaker = TieBreaker::new(tie_break);
+        }
+    }
+
+    #[inline(always)]
+    fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    fn set_participant_limits(&mut self, limits: ParticipantLimits) {
+        self.participant_limits = limits;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.participants.set_limits(limits, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn participant_limits(&self) -> ParticipantLimits {
+        self.participant_limits
+    }
+
+    fn set_tick_sizes(&mut self, tick_sizes: TickSizes) {
+        self.tick_sizes = tick_sizes;
+    }
+
+    #[inline(always)]
+    fn tick_sizes(&self) -> &TickSizes {
+        &self.tick_sizes
+    }
+
+    fn set_lot_sizes(&mut self, lot_sizes: LotSizes) {
+        self.lot_sizes = lot_sizes;
+    }
+
+    #[inline(always)]
+    fn lot_sizes(&self) -> &LotSizes {
+        &self.lot_sizes
+    }
+
+    fn set_max_depth(&mut self, max_levels: Option<usize>) {
+        self.max_depth = max_levels;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            for (side, price) in matcher.depth.set_max_levels(max_levels, &resting) {
+                matcher.evict_level(side, price);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    fn take_evicted_orders(&mut self) -> Vec<Order> {
+        self.matchers.values_mut().flat_map(|matcher| matcher.evicted.drain(..)).collect()
+    }
+
+    fn set_level_journal(&mut self, enabled: bool) {
+        self.level_journal = enabled;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.level_changes.set_enabled(enabled, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn level_journal(&self) -> bool {
+        self.level_journal
+    }
+
+    fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>> {
+        match self.matchers.get_mut(symbol) {
+            Some(matcher) => Some(matcher.level_changes.take()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Result<Order, OrderBookError> {
+        self.matchers.values_mut()
+            .find_map(|matcher| matcher.reduce_order(order_id, quantity_delta))
+            .unwrap_or(Err(OrderBookError::OrderNotFound))
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool) -> Vec<Order> {
+        let mut cancelled = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            matcher.cancel_where(predicate, &mut cancelled);
+        }
+        cancelled
+    }
+
+    fn cancel_order(&mut self, symbol: SymbolId, order_id: u64) -> Result<Order, OrderBookError> {
+        lazy_matchers::cancel_in(&mut self.matchers, &self.symbols, symbol, |matcher| matcher.cancel_order(order_id))
+    }
+
+    fn cancel_orders(&mut self, order_ids: &FxHashSet<u64>) -> Vec<Order> {
+        lazy_matchers::cancel_each_in(&mut self.matchers, order_ids, |matcher, order_id| matcher.cancel_order(order_id))
+    }
+}
+
+impl<B: MapBackend> OrderBookMarketData for PersistentOrderBook<B> {
+    fn price_levels(&self, symbol: SymbolId, side: OrderSide, with_order_ids: bool) -> Option<PriceLevelIter<'_>> {
+        let Some(matcher) = self.matchers.get(symbol) else {
+            return self.symbols.contains(&symbol).then(|| Box::new(std::iter::empty()) as PriceLevelIter<'_>);
+        };
+        let view = move |(&price, level): (&u64, &Vector<Order>)| {
+            PriceLevelView::from_orders(price, level.iter(), with_order_ids)
+        };
+        Some(match side {
+            OrderSide::Buy => Box::new(matcher.bid_levels.iter().rev().map(view)),
+            OrderSide::Sell => Box::new(matcher.ask_levels.iter().map(view)),
+        })
+    }
+
+    #[inline(always)]
+    fn last_trade_price(&self, symbol: SymbolId) -> Option<u64> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.last_trade_price)
+    }
+
+    #[inline(always)]
+    fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.match_stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, price_to_u64};
+
+    const APPLE_SYMBOL: SymbolId = 0;
+
+    #[test]
+    fn test_persistent_order_book_matching() {
+        let mut order_book = PersistentOrderBook::new(FxHashSet::from_iter([APPLE_SYMBOL]));
+        order_book.add_order(new_order(1, APPLE_SYMBOL, 100, 150.0, OrderSide::Buy)).unwrap();
+        order_book.add_order(new_order(2, APPLE_SYMBOL, 100, 149.0, OrderSide::Buy)).unwrap();
+        order_book.add_order(new_order(3, APPLE_SYMBOL, 100, 150.0, OrderSide::Sell)).unwrap();
+
+        assert!(order_book.can_match(APPLE_SYMBOL));
+        order_book.match_orders();
+
+        assert_eq!(order_book.get_best_prices(APPLE_SYMBOL), Some((Some(price_to_u64(149.0)), None)));
+        assert_eq!(order_book.order_book_type(), OrderBookType::Persistent);
+    }
+
+    #[test]
+    fn test_snapshot_is_isolated_from_later_mutation() {
+        let mut order_book = PersistentOrderBook::new(FxHashSet::from_iter([APPLE_SYMBOL]));
+        order_book.add_order(new_order(1, APPLE_SYMBOL, 100, 150.0, OrderSide::Buy)).unwrap();
+        order_book.add_order(new_order(2, APPLE_SYMBOL, 40, 150.0, OrderSide::Buy)).unwrap();
+
+        let view = order_book.snapshot(APPLE_SYMBOL).unwrap();
+        order_book.add_order(new_order(3, APPLE_SYMBOL, 100, 150.0, OrderSide::Sell)).unwrap();
+        order_book.match_orders();
+
+        assert_eq!(view.bid_levels().collect::<Vec<_>>(), vec![(price_to_u64(150.0), 140, 2)]);
+        assert_eq!(view.ask_levels().count(), 0);
+        assert_eq!(order_book.resting_orders(APPLE_SYMBOL).unwrap().len(), 1);
+    }
+}
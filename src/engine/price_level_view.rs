@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use crate::types::order::{Order, OrderSide};
+
+/// Read-only summary of one price level, as returned by `OrderBookTrait::price_levels`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceLevelView {
+    pub price: u64,
+    pub total_quantity: u64,
+    pub order_count: usize,
+    /// Ids in the level's queue order, only filled when requested.
+    pub order_ids: Option<Vec<u64>>,
+}
+
+impl PriceLevelView {
+    pub(crate) fn from_orders<'a>(price: u64, orders: impl Iterator<Item = &'a Order>, with_order_ids: bool) -> Self {
+        let mut view = PriceLevelView {
+            price,
+            total_quantity: 0,
+            order_count: 0,
+            order_ids: with_order_ids.then(Vec::new),
+        };
+        for order in orders {
+            view.total_quantity += order.quantity;
+            view.order_count += 1;
+            if let Some(order_ids) = &mut view.order_ids {
+                order_ids.push(order.id);
+            }
+        }
+        view
+    }
+}
+
+pub type PriceLevelIter<'a> = Box<dyn Iterator<Item = PriceLevelView> + 'a>;
+
+/// Groups one side of a book into levels, best price first. For books without
+/// price-indexed storage; arrival order within a level is preserved.
+pub(crate) fn aggregate_levels<'a>(
+    orders: impl Iterator<Item = &'a Order>,
+    side: OrderSide,
+    with_order_ids: bool,
+) -> Vec<PriceLevelView> {
+    let mut levels: BTreeMap<u64, Vec<&Order>> = BTreeMap::new();
+    for order in orders.filter(|order| order.order_type == side) {
+        levels.entry(order.price).or_default().push(order);
+    }
+
+    let views = levels.into_iter()
+        .map(|(price, orders)| PriceLevelView::from_orders(price, orders.into_iter(), with_order_ids));
+    match side {
+        OrderSide::Buy => views.rev().collect(),
+        OrderSide::Sell => views.collect(),
+    }
+}
+
+/// The best price levels on each side of one symbol's book, as returned by
+/// `OrderBookMarketData::get_depth`. Order ids are not filled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookDepth {
+    /// Highest price first.
+    pub bids: Vec<PriceLevelView>,
+    /// Lowest price first.
+    pub asks: Vec<PriceLevelView>,
+}
+
+/// The best `levels` levels per side, for books without price-indexed storage. Keeps
+/// at most `levels` prices per side while scanning: a price that falls off is worse
+/// than every kept one, and the kept ones only improve, so it never comes back.
+pub(crate) fn top_levels<'a>(orders: impl Iterator<Item = &'a Order>, levels: usize) -> BookDepth {
+    let mut bids: BTreeMap<u64, (u64, usize)> = BTreeMap::new();
+    let mut asks: BTreeMap<u64, (u64, usize)> = BTreeMap::new();
+    for order in orders {
+        let side = match order.order_type {
+            OrderSide::Buy => &mut bids,
+            OrderSide::Sell => &mut asks,
+        };
+        let level = side.entry(order.price).or_default();
+        level.0 += order.quantity;
+        level.1 += 1;
+        if side.len() > levels {
+            match order.order_type {
+                OrderSide::Buy => side.pop_first(),
+                OrderSide::Sell => side.pop_last(),
+            };
+        }
+    }
+
+    let view = |(price, (total_quantity, order_count))| PriceLevelView { price, total_quantity, order_count, order_ids: None };
+    BookDepth {
+        bids: bids.into_iter().rev().map(view).collect(),
+        asks: asks.into_iter().map(view).collect(),
+    }
+}
+
+/// Several price levels folded into one fixed-width price band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceBand {
+    /// Band edge: rounded down for bids and up for asks, so a band never quotes better
+    /// than the levels inside it.
+    pub price: u64,
+    pub total_quantity: u64,
+    pub order_count: usize,
+    pub level_count: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AggregatedDepth {
+    pub bids: Vec<PriceBand>,
+    pub asks: Vec<PriceBand>,
+}
+
+/// Folds best-first levels into bands of `band_size` price units, keeping the best
+/// `max_bands`. Stops pulling levels once the next band would be one too many.
+pub fn band_levels(levels: impl Iterator<Item = PriceLevelView>, side: OrderSide, band_size: u64, max_bands: usize) -> Vec<PriceBand> {
+    let band_size = band_size.max(1);
+    let mut bands: Vec<PriceBand> = Vec::with_capacity(max_bands);
+    for level in levels {
+        let price = match side {
+            OrderSide::Buy => level.price - level.price % band_size,
+            OrderSide::Sell => level.price.div_ceil(band_size) * band_size,
+        };
+        if let Some(band) = bands.last_mut().filter(|band| band.price == price) {
+            band.total_quantity += level.total_quantity;
+            band.order_count += level.order_count;
+            band.level_count += 1;
+            continue;
+        }
+        if bands.len() == max_bands {
+            break;
+        }
+        bands.push(PriceBand {
+            price,
+            total_quantity: level.total_quantity,
+            order_count: level.order_count,
+            level_count: 1,
+        });
+    }
+    bands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, price_to_u64};
+
+    #[test]
+    fn test_band_levels_rounds_away_from_touch() {
+        let orders = [
+            new_order(1, 0, 10, 100.004, OrderSide::Sell),
+            new_order(2, 0, 20, 100.09, OrderSide::Sell),
+            new_order(3, 0, 30, 100.1, OrderSide::Sell),
+            new_order(4, 0, 40, 100.15, OrderSide::Sell),
+            new_order(5, 0, 50, 100.35, OrderSide::Sell),
+        ];
+        let levels = aggregate_levels(orders.iter(), OrderSide::Sell, false);
+        let bands = band_levels(levels.into_iter(), OrderSide::Sell, price_to_u64(0.1), 2);
+
+        assert_eq!(bands.len(), 2);
+        assert_eq!((bands[0].price, bands[0].total_quantity, bands[0].level_count), (price_to_u64(100.1), 60, 3));
+        assert_eq!((bands[1].price, bands[1].total_quantity), (price_to_u64(100.2), 40));
+
+        let bids = aggregate_levels([new_order(6, 0, 5, 99.99, OrderSide::Buy)].iter(), OrderSide::Buy, false);
+        assert_eq!(band_levels(bids.into_iter(), OrderSide::Buy, price_to_u64(0.1), 5)[0].price, price_to_u64(99.9));
+    }
+
+    #[test]
+    fn test_top_levels_matches_full_aggregation() {
+        let orders: Vec<Order> = (0..200u64)
+            .map(|id| {
+                let side = if id % 3 == 0 { OrderSide::Sell } else { OrderSide::Buy };
+                new_order(id, 0, id % 7 + 1, 100.0 + ((id * 37) % 23) as f64 * if side == OrderSide::Sell { 1.0 } else { -1.0 }, side)
+            })
+            .collect();
+        for levels in [0, 1, 5, 100] {
+            let depth = top_levels(orders.iter(), levels);
+            for (side, top) in [(OrderSide::Buy, &depth.bids), (OrderSide::Sell, &depth.asks)] {
+                let mut full = aggregate_levels(orders.iter(), side, false);
+                full.truncate(levels);
+                assert_eq!(top, &full, "{side:?} top {levels}");
+            }
+        }
+    }
+}
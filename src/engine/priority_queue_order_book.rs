@@ -1,29 +1,45 @@
 use std::collections::BinaryHeap;
 use std::cmp::Ordering;
-use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_hash::FxHashSet;
 
-use crate::engine::order_book_trait::{OrderBookTrait, OrderBookError};
+use crate::engine::order_book_trait::{OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData};
 use crate::engine::OrderBookType;
-use crate::types::{order::Order, symbol_mapping::SymbolId};
-
-#[derive(Debug, Clone)]
-struct BidOrder(Order);
+use crate::engine::price_level_view::{self, BookDepth, PriceLevelIter};
+use crate::engine::participant_limits::{ParticipantLimits, ParticipantOrderCounts};
+use crate::engine::lot_size::LotSizes;
+use crate::engine::symbol_map::{FxMaps, IdMap, MapBackend, SymbolMap};
+use crate::engine::tick_size::TickSizes;
+use crate::engine::depth_limit::DepthLimit;
+use crate::engine::match_stats::{self, MatchCycle, MatchStats, TakeOnArrival};
+use crate::engine::level_journal::{LevelChange, LevelJournal};
+use crate::engine::tie_break::{TieBreak, TieBreaker};
+use crate::engine::reduction;
+use crate::engine::lazy_matchers;
+use crate::types::{order::{Order, OrderSide, OrderFlags}, symbol_mapping::SymbolId, trade::{Trade, TradePriceRule}};
+
+/// Where a resting order ranks: all a heap holds. The order itself lives in the
+/// matcher's `orders`, so fills and reductions never touch the heaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Key {
+    price: u64,
+    id: u64,
+}
 
-impl PartialEq for BidOrder {
-    fn eq(&self, other: &Self) -> bool {
-        self.0.price == other.0.price && self.0.id == other.0.id
-    }
+trait HeapKey: Ord + Copy {
+    fn key(&self) -> Key;
 }
 
-impl Eq for BidOrder {}
+/// Highest price first, then lowest id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BidKey(Key);
 
-impl PartialOrd for BidOrder {
+impl PartialOrd for BidKey {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for BidOrder {
+impl Ord for BidKey {
     fn cmp(&self, other: &Self) -> Ordering {
         match self.0.price.cmp(&other.0.price) {
             Ordering::Equal => other.0.id.cmp(&self.0.id),
@@ -32,24 +48,24 @@ impl Ord for BidOrder {
     }
 }
 
-#[derive(Debug, Clone)]
-struct AskOrder(Order);
-
-impl PartialEq for AskOrder {
-    fn eq(&self, other: &Self) -> bool {
-        self.0.price == other.0.price && self.0.id == other.0.id
+impl HeapKey for BidKey {
+    #[inline(always)]
+    fn key(&self) -> Key {
+        self.0
     }
 }
 
-impl Eq for AskOrder {}
+/// Lowest price first, then lowest id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AskKey(Key);
 
-impl PartialOrd for AskOrder {
+impl PartialOrd for AskKey {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for AskOrder {
+impl Ord for AskKey {
     fn cmp(&self, other: &Self) -> Ordering {
         match other.0.price.cmp(&self.0.price) {
             Ordering::Equal => other.0.id.cmp(&self.0.id),
@@ -58,38 +74,139 @@ impl Ord for AskOrder {
     }
 }
 
+impl HeapKey for AskKey {
+    #[inline(always)]
+    fn key(&self) -> Key {
+        self.0
+    }
+}
+
 #[derive(Debug)]
-struct PriorityQueueMatcher {
-    bids: BinaryHeap<BidOrder>,
-    asks: BinaryHeap<AskOrder>,
+struct PriorityQueueMatcher<B: MapBackend> {
+    bids: BinaryHeap<BidKey>,
+    asks: BinaryHeap<AskKey>,
+    orders: B::Ids<Order>,
+    // Ids cancelled while their key is still in a heap. A heap cannot remove from the
+    // middle, so the key stays until it reaches the top or the heaps are compacted
+    tombstones: FxHashSet<u64>,
     best_bid: Option<u64>,
     best_ask: Option<u64>,
+    last_trade_price: Option<u64>,
+    participants: ParticipantOrderCounts,
+    depth: DepthLimit,
+    evicted: Vec<Order>,
+    arrival_trades: Vec<Trade>,
+    match_stats: Option<MatchStats>,
+    level_changes: LevelJournal,
+    tie_breaker: TieBreaker,
 }
 
-impl PriorityQueueMatcher {
+impl<B: MapBackend> PriorityQueueMatcher<B> {
     fn new() -> Self {
         Self {
             bids: BinaryHeap::new(),
             asks: BinaryHeap::new(),
+            orders: Default::default(),
+            tombstones: FxHashSet::default(),
             best_bid: None,
             best_ask: None,
+            last_trade_price: None,
+            participants: ParticipantOrderCounts::default(),
+            depth: DepthLimit::default(),
+            evicted: Vec::new(),
+            arrival_trades: Vec::new(),
+            match_stats: None,
+            level_changes: LevelJournal::default(),
+            tie_breaker: TieBreaker::default(),
         }
     }
 
     #[inline(always)]
     fn add_order(&mut self, order: Order) {
-        match order.order_type {
-            crate::types::order::OrderSide::Buy => {
-                let price = order.price;
-                self.bids.push(BidOrder(order));
-                self.best_bid = Some(self.best_bid.map_or(price, |current| current.max(price)));
+        // A tombstone would also hide the new key, so clear it out first
+        if self.tombstones.contains(&order.id) {
+            self.compact();
+        }
+        self.participants.record(&order);
+        self.level_changes.record(order.order_type, order.price, order.quantity);
+        let side = order.order_type;
+        let key = Key { price: order.price, id: order.id };
+        let evict = self.depth.record(side, order.price);
+        match side {
+            OrderSide::Buy => {
+                self.bids.push(BidKey(key));
+                self.best_bid = Some(self.best_bid.map_or(key.price, |current| current.max(key.price)));
+            }
+            OrderSide::Sell => {
+                self.asks.push(AskKey(key));
+                self.best_ask = Some(self.best_ask.map_or(key.price, |current| current.min(key.price)));
+            }
+        }
+        self.orders.insert(order.id, order);
+        if let Some(far_price) = evict {
+            self.evict_level(side, far_price);
+        }
+    }
+
+    /// The order `key` ranks, or `None` for a tombstone.
+    #[inline(always)]
+    fn resting(&self, key: Key) -> Option<&Order> {
+        if self.tombstones.contains(&key.id) {
+            return None;
+        }
+        self.orders.get(key.id).filter(|order| order.price == key.price)
+    }
+
+    /// Drops tombstones from the top of both heaps, so the top of each is always a
+    /// resting order, and refreshes the cached best prices from them.
+    #[inline(always)]
+    fn purge_tops(&mut self) {
+        if !self.tombstones.is_empty() {
+            while let Some(top) = self.bids.peek() && self.tombstones.remove(&top.0.id) {
+                self.bids.pop();
             }
-            crate::types::order::OrderSide::Sell => {
-                let price = order.price;
-                self.asks.push(AskOrder(order));
-                self.best_ask = Some(self.best_ask.map_or(price, |current| current.min(price)));
+            while let Some(top) = self.asks.peek() && self.tombstones.remove(&top.0.id) {
+                self.asks.pop();
             }
         }
+        self.best_bid = self.bids.peek().map(|top| top.0.price);
+        self.best_ask = self.asks.peek().map(|top| top.0.price);
+    }
+
+    /// Rebuilds both heaps from the keys of resting orders, dropping every tombstone.
+    fn compact(&mut self) {
+        let tombstones = std::mem::take(&mut self.tombstones);
+        self.bids.retain(|key| !tombstones.contains(&key.0.id));
+        self.asks.retain(|key| !tombstones.contains(&key.0.id));
+    }
+
+    fn evict_level(&mut self, side: OrderSide, price: u64) {
+        self.compact();
+        let evicted: Vec<Key> = match side {
+            OrderSide::Buy => Self::take_keys(&mut self.bids, |key| key.price == price),
+            OrderSide::Sell => Self::take_keys(&mut self.asks, |key| key.price == price),
+        };
+        for key in evicted {
+            if let Some(order) = self.orders.remove(key.id) {
+                self.participants.release(&order);
+                self.level_changes.release(&order);
+                self.evicted.push(order);
+            }
+        }
+        self.purge_tops();
+    }
+
+    /// Removes the keys `predicate` accepts from `heap` and returns them.
+    fn take_keys<T: HeapKey>(heap: &mut BinaryHeap<T>, predicate: impl Fn(Key) -> bool) -> Vec<Key> {
+        let mut taken = Vec::new();
+        heap.retain(|key| {
+            let take = predicate(key.key());
+            if take {
+                taken.push(key.key());
+            }
+            !take
+        });
+        taken
     }
 
     #[inline(always)]
@@ -101,48 +218,245 @@ impl PriorityQueueMatcher {
     }
 
     #[inline(always)]
-    fn match_orders(&mut self) {
+    fn match_orders(&mut self, rule: TradePriceRule, trades: &mut Vec<Trade>) {
+        if !self.can_match() {
+            return;
+        }
+        let mut cycle = MatchCycle::start();
         while self.can_match() {
-            let bid = self.bids.pop();
-            let ask = self.asks.pop();
-
-            match (bid, ask) {
-                (Some(_), Some(_)) => {
-                    self.best_bid = self.bids.peek().map(|order| order.0.price);
-                    self.best_ask = self.asks.peek().map(|order| order.0.price);
-                }
-                _ => break,
+            let bid = Self::pop_next(&mut self.bids, &self.orders, &mut self.tombstones, &mut self.tie_breaker);
+            let ask = Self::pop_next(&mut self.asks, &self.orders, &mut self.tombstones, &mut self.tie_breaker);
+            let (Some(bid), Some(ask)) = (bid, ask) else {
+                break;
+            };
+            let (Some(bid_order), Some(ask_order)) = (self.orders.get(bid.id).cloned(), self.orders.get(ask.id).cloned()) else {
+                break;
+            };
+
+            self.last_trade_price = Some(cycle.record_trade(&bid_order, &ask_order, rule));
+            let quantity = bid_order.quantity.min(ask_order.quantity);
+            self.settle_fill(&bid_order, quantity);
+            self.settle_fill(&ask_order, quantity);
+            // Ids order a level, so a key pushed back returns to its place
+            if bid_order.quantity > quantity {
+                self.bids.push(BidKey(bid));
+            }
+            if ask_order.quantity > quantity {
+                self.asks.push(AskKey(ask));
             }
+            self.purge_tops();
+            if self.best_bid != Some(bid.price) {
+                cycle.level_cleared();
+            }
+            if self.best_ask != Some(ask.price) {
+                cycle.level_cleared();
+            }
+        }
+        self.match_stats = Some(cycle.finish(trades));
+    }
+
+    /// Books a fill of `quantity` against `order` as it stood before the trade.
+    #[inline(always)]
+    fn settle_fill(&mut self, order: &Order, quantity: u64) {
+        if quantity == order.quantity {
+            self.orders.remove(order.id);
+            self.participants.release(order);
+            self.depth.release(order);
+            self.level_changes.release(order);
+        } else {
+            if let Some(resting) = self.orders.get_mut(order.id) {
+                resting.quantity -= quantity;
+            }
+            self.level_changes.reduce(order, quantity);
         }
     }
 
+    // The heap orders a level by id alone, so any other tie-break draws from the whole
+    // best level and pushes the rest back. The top is never a tombstone, but keys
+    // further into the level may be.
+    #[inline(always)]
+    fn pop_next<T: HeapKey>(heap: &mut BinaryHeap<T>, orders: &B::Ids<Order>, tombstones: &mut FxHashSet<u64>, tie_breaker: &mut TieBreaker) -> Option<Key> {
+        if tie_breaker.is_fifo() {
+            return heap.pop().map(|key| key.key());
+        }
+        let price = heap.peek()?.key().price;
+        let mut level = Vec::new();
+        while let Some(next) = heap.peek() && next.key().price == price {
+            let next = heap.pop()?;
+            if !tombstones.remove(&next.key().id) {
+                level.push(next);
+            }
+        }
+        let quantities = level.iter().map(|next| orders.get(next.key().id).map_or(0, |order| order.quantity));
+        let index = tie_breaker.pick(level.len(), quantities)?;
+        let picked = level.swap_remove(index);
+        heap.extend(level);
+        Some(picked.key())
+    }
+
     #[inline(always)]
     fn get_best_prices(&self) -> (Option<u64>, Option<u64>) {
-        let best_bid = self.bids.peek().map(|order| order.0.price);
-        let best_ask = self.asks.peek().map(|order| order.0.price);
-        (best_bid, best_ask)
+        (self.best_bid, self.best_ask)
+    }
+
+    // Quantity plays no part in heap order, so only the order itself changes
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Option<Result<Order, OrderBookError>> {
+        let reduced = reduction::reduce(self.orders.get_mut(order_id)?, quantity_delta);
+        if let Ok(order) = &reduced {
+            self.level_changes.reduce(order, quantity_delta);
+        }
+        Some(reduced)
+    }
+
+    /// Removes the order and tombstones its key, compacting once tombstones outnumber
+    /// resting orders so the heaps stay within twice the book.
+    fn cancel_order(&mut self, order_id: u64) -> Option<Order> {
+        let order = self.orders.remove(order_id)?;
+        self.tombstones.insert(order_id);
+        if self.tombstones.len() > self.orders.len() {
+            self.compact();
+        }
+        self.purge_tops();
+        self.participants.release(&order);
+        self.depth.release(&order);
+        self.level_changes.release(&order);
+        Some(order)
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool, cancelled: &mut Vec<Order>) {
+        self.compact();
+        let orders = &self.orders;
+        let matches = |key: Key| orders.get(key.id).is_some_and(predicate);
+        let keys: Vec<Key> = Self::take_keys(&mut self.bids, matches).into_iter()
+            .chain(Self::take_keys(&mut self.asks, matches))
+            .collect();
+        for key in keys {
+            if let Some(order) = self.orders.remove(key.id) {
+                self.participants.release(&order);
+                self.depth.release(&order);
+                self.level_changes.release(&order);
+                cancelled.push(order);
+            }
+        }
+        self.purge_tops();
+    }
+
+    fn resting_orders(&self) -> Vec<Order> {
+        let mut bids: Vec<&BidKey> = self.bids.iter().collect();
+        let mut asks: Vec<&AskKey> = self.asks.iter().collect();
+        bids.sort_unstable_by(|a, b| b.cmp(a));
+        asks.sort_unstable_by(|a, b| b.cmp(a));
+
+        bids.into_iter().map(|key| key.0)
+            .chain(asks.into_iter().map(|key| key.0))
+            .filter_map(|key| self.resting(key).cloned())
+            .collect()
+    }
+}
+
+impl<B: MapBackend> TakeOnArrival for PriorityQueueMatcher<B> {
+    type Slot = Key;
+
+    fn next_resting(&mut self, taker: &Order) -> Option<(Self::Slot, Order)> {
+        let key = match taker.order_type {
+            OrderSide::Buy if taker.order_type.crosses(taker.price, self.best_ask?) => {
+                Self::pop_next(&mut self.asks, &self.orders, &mut self.tombstones, &mut self.tie_breaker)?
+            }
+            OrderSide::Sell if taker.order_type.crosses(taker.price, self.best_bid?) => {
+                Self::pop_next(&mut self.bids, &self.orders, &mut self.tombstones, &mut self.tie_breaker)?
+            }
+            _ => return None,
+        };
+        Some((key, self.orders.get(key.id)?.clone()))
+    }
+
+    fn fill_resting(&mut self, key: Self::Slot, resting: &Order, quantity: u64) -> bool {
+        self.settle_fill(resting, quantity);
+        // Ids order a level, so a key pushed back returns to its place
+        if resting.quantity > quantity {
+            match resting.order_type {
+                OrderSide::Buy => self.bids.push(BidKey(key)),
+                OrderSide::Sell => self.asks.push(AskKey(key)),
+            }
+        }
+        self.purge_tops();
+        let best = match resting.order_type {
+            OrderSide::Buy => self.best_bid,
+            OrderSide::Sell => self.best_ask,
+        };
+        best != Some(key.price)
+    }
+
+    fn arrived(&mut self, mut trades: Vec<Trade>, last_trade_price: Option<u64>, remainder: Option<Order>) {
+        self.arrival_trades.append(&mut trades);
+        self.last_trade_price = last_trade_price.or(self.last_trade_price);
+        self.evicted.extend(remainder);
     }
 }
 
 #[repr(align(64))]
-pub struct PriorityQueueOrderBook {
+pub struct PriorityQueueOrderBook<B: MapBackend = FxMaps> {
     symbols: FxHashSet<SymbolId>,
-    matchers: FxHashMap<SymbolId, PriorityQueueMatcher>,
+    matchers: B::Matchers<PriorityQueueMatcher<B>>,
+    trade_price_rule: TradePriceRule,
+    tie_break: TieBreak,
+    participant_limits: ParticipantLimits,
+    tick_sizes: TickSizes,
+    lot_sizes: LotSizes,
+    max_depth: Option<usize>,
+    level_journal: bool,
+}
+
+impl<B: MapBackend> PriorityQueueOrderBook<B> {
+    #[inline(always)]
+    fn matcher_mut(&mut self, symbol: SymbolId) -> Option<&mut PriorityQueueMatcher<B>> {
+        let (limits, max_depth, level_journal, tie_break) = (self.participant_limits, self.max_depth, self.level_journal, self.tie_break);
+        lazy_matchers::matcher_entry(&mut self.matchers, &self.symbols, symbol, || {
+            let mut matcher = PriorityQueueMatcher::new();
+            matcher.participants.set_limits(limits, []);
+            let _ = matcher.depth.set_max_levels(max_depth, []);
+            matcher.level_changes.set_enabled(level_journal, []);
+            matcher.tie_breaker = TieBreaker::new(tie_break);
+            matcher
+        })
+    }
+}
+
+impl PriorityQueueOrderBook {
+    pub fn new(symbols: FxHashSet<SymbolId>) -> Self {
+        <Self as OrderBookCore>::new(symbols)
+    }
 }
 
-impl OrderBookTrait for PriorityQueueOrderBook {
+impl<B: MapBackend> OrderBookCore for PriorityQueueOrderBook<B> {
     fn new(symbols: FxHashSet<SymbolId>) -> Self {
-        let mut matchers = FxHashMap::with_capacity_and_hasher(symbols.len(), Default::default());
-        for &symbol in &symbols {
-            matchers.insert(symbol, PriorityQueueMatcher::new());
+        Self {
+            symbols,
+            matchers: Default::default(),
+            trade_price_rule: TradePriceRule::default(),
+            tie_break: TieBreak::default(),
+            participant_limits: ParticipantLimits::default(),
+            tick_sizes: TickSizes::default(),
+            lot_sizes: LotSizes::default(),
+            max_depth: None,
+            level_journal: false,
         }
-        Self { symbols, matchers }
     }
 
     #[inline(always)]
     fn add_order(&mut self, order: Order) -> Result<bool, OrderBookError> {
-        if let Some(matcher) = self.matchers.get_mut(&order.symbol) {
-            matcher.add_order(order);
+        let order = self.tick_sizes.conform(order)?;
+        self.lot_sizes.check(&order)?;
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return Err(OrderBookError::ParticipantLimitExceeded);
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+            } else {
+                matcher.add_order(order);
+            }
             Ok(true)
         } else {
             Err(OrderBookError::InvalidSymbol)
@@ -151,8 +465,20 @@ impl OrderBookTrait for PriorityQueueOrderBook {
 
     #[inline(always)]
     fn add_order_fast(&mut self, order: Order) -> bool {
-        if let Some(matcher) = self.matchers.get_mut(&order.symbol) {
-            matcher.add_order(order);
+        let Ok(order) = self.tick_sizes.conform(order) else { return false };
+        if self.lot_sizes.check(&order).is_err() {
+            return false;
+        }
+        let rule = self.trade_price_rule;
+        if let Some(matcher) = self.matcher_mut(order.symbol) {
+            if !matcher.participants.admits(&order) {
+                return false;
+            }
+            if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                match_stats::take_on_arrival(matcher, order, rule);
+            } else {
+                matcher.add_order(order);
+            }
             true
         } else {
             false
@@ -161,17 +487,23 @@ impl OrderBookTrait for PriorityQueueOrderBook {
 
     #[inline(always)]
     unsafe fn add_order_unchecked(&mut self, order: Order) {
-        unsafe {
-            let matcher = self.matchers.get_mut(&order.symbol).unwrap_unchecked();
+        let rule = self.trade_price_rule;
+        let matcher = unsafe { self.matcher_mut(order.symbol).unwrap_unchecked() };
+        if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+            match_stats::take_on_arrival(matcher, order, rule);
+        } else {
             matcher.add_order(order);
         }
     }
 
     #[inline(always)]
-    fn match_orders(&mut self) {
+    fn match_orders(&mut self) -> Vec<Trade> {
+        let mut trades = Vec::new();
         for matcher in self.matchers.values_mut() {
-            matcher.match_orders();
+            trades.append(&mut matcher.arrival_trades);
+            matcher.match_orders(self.trade_price_rule, &mut trades);
         }
+        trades
     }
 
     #[inline(always)]
@@ -204,19 +536,22 @@ impl OrderBookTrait for PriorityQueueOrderBook {
 
     #[inline(always)]
     fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
-        self.matchers.get(&symbol).map(|matcher| matcher.get_best_prices())
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.get_best_prices()),
+            None => self.symbols.contains(&symbol).then_some((None, None)),
+        }
     }
 
     #[inline(always)]
     fn can_match(&self, symbol: SymbolId) -> bool {
-        self.matchers.get(&symbol)
+        self.matchers.get(symbol)
             .map(|matcher| matcher.can_match())
             .unwrap_or(false)
     }
 
     #[inline(always)]
     fn is_valid_symbol(&self, symbol: SymbolId) -> bool {
-        self.matchers.contains_key(&symbol)
+        self.symbols.contains(&symbol)
     }
 
     #[inline(always)]
@@ -230,11 +565,158 @@ impl OrderBookTrait for PriorityQueueOrderBook {
     }
 }
 
+impl<B: MapBackend> OrderBookLifecycle for PriorityQueueOrderBook<B> {
+    fn resting_orders(&self, symbol: SymbolId) -> Option<Vec<Order>> {
+        match self.matchers.get(symbol) {
+            Some(matcher) => Some(matcher.resting_orders()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    #[inline(always)]
+    fn set_trade_price_rule(&mut self, rule: TradePriceRule) {
+        self.trade_price_rule = rule;
+    }
+
+    #[inline(always)]
+    fn trade_price_rule(&self) -> TradePriceRule {
+        self.trade_price_rule
+    }
+
+    fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+        for matcher in self.matchers.values_mut() {
+            matcher.tie_breaker = TieBreaker::new(tie_break);
+        }
+    }
+
+    #[inline(always)]
+    fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    fn set_participant_limits(&mut self, limits: ParticipantLimits) {
+        self.participant_limits = limits;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.participants.set_limits(limits, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn participant_limits(&self) -> ParticipantLimits {
+        self.participant_limits
+    }
+
+    fn set_tick_sizes(&mut self, tick_sizes: TickSizes) {
+        self.tick_sizes = tick_sizes;
+    }
+
+    #[inline(always)]
+    fn tick_sizes(&self) -> &TickSizes {
+        &self.tick_sizes
+    }
+
+    fn set_lot_sizes(&mut self, lot_sizes: LotSizes) {
+        self.lot_sizes = lot_sizes;
+    }
+
+    #[inline(always)]
+    fn lot_sizes(&self) -> &LotSizes {
+        &self.lot_sizes
+    }
+
+    fn set_max_depth(&mut self, max_levels: Option<usize>) {
+        self.max_depth = max_levels;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            for (side, price) in matcher.depth.set_max_levels(max_levels, &resting) {
+                matcher.evict_level(side, price);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    fn take_evicted_orders(&mut self) -> Vec<Order> {
+        self.matchers.values_mut().flat_map(|matcher| matcher.evicted.drain(..)).collect()
+    }
+
+    fn set_level_journal(&mut self, enabled: bool) {
+        self.level_journal = enabled;
+        for matcher in self.matchers.values_mut() {
+            let resting = matcher.resting_orders();
+            matcher.level_changes.set_enabled(enabled, &resting);
+        }
+    }
+
+    #[inline(always)]
+    fn level_journal(&self) -> bool {
+        self.level_journal
+    }
+
+    fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>> {
+        match self.matchers.get_mut(symbol) {
+            Some(matcher) => Some(matcher.level_changes.take()),
+            None => self.symbols.contains(&symbol).then(Vec::new),
+        }
+    }
+
+    fn reduce_order(&mut self, order_id: u64, quantity_delta: u64) -> Result<Order, OrderBookError> {
+        self.matchers.values_mut()
+            .find_map(|matcher| matcher.reduce_order(order_id, quantity_delta))
+            .unwrap_or(Err(OrderBookError::OrderNotFound))
+    }
+
+    fn cancel_where(&mut self, predicate: &dyn Fn(&Order) -> bool) -> Vec<Order> {
+        let mut cancelled = Vec::new();
+        for matcher in self.matchers.values_mut() {
+            matcher.cancel_where(predicate, &mut cancelled);
+        }
+        cancelled
+    }
+
+    fn cancel_order(&mut self, symbol: SymbolId, order_id: u64) -> Result<Order, OrderBookError> {
+        lazy_matchers::cancel_in(&mut self.matchers, &self.symbols, symbol, |matcher| matcher.cancel_order(order_id))
+    }
+
+    fn cancel_orders(&mut self, order_ids: &FxHashSet<u64>) -> Vec<Order> {
+        lazy_matchers::cancel_each_in(&mut self.matchers, order_ids, |matcher, order_id| matcher.cancel_order(order_id))
+    }
+}
+
+impl<B: MapBackend> OrderBookMarketData for PriorityQueueOrderBook<B> {
+    fn price_levels(&self, symbol: SymbolId, side: OrderSide, with_order_ids: bool) -> Option<PriceLevelIter<'_>> {
+        let resting = self.resting_orders(symbol)?;
+        let levels = price_level_view::aggregate_levels(resting.iter(), side, with_order_ids);
+        Some(Box::new(levels.into_iter()))
+    }
+
+    fn get_depth(&self, symbol: SymbolId, levels: usize) -> Option<BookDepth> {
+        let resting = self.resting_orders(symbol)?;
+        Some(price_level_view::top_levels(resting.iter(), levels))
+    }
+
+    #[inline(always)]
+    fn last_trade_price(&self, symbol: SymbolId) -> Option<u64> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.last_trade_price)
+    }
+
+    #[inline(always)]
+    fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats> {
+        self.matchers.get(symbol)
+            .and_then(|matcher| matcher.match_stats)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::order::{new_order, OrderSide};
-    use crate::engine::order_book_trait::OrderBookTrait;
 
     const APPLE_SYMBOL: SymbolId = 0;
 
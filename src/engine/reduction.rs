@@ -0,0 +1,13 @@
+use crate::engine::OrderBookError;
+use crate::types::order::Order;
+
+/// Takes `quantity_delta` off `order` where it rests and returns it as it now stands.
+/// Taking the whole quantity would be a cancel, so at least one unit must remain.
+#[inline(always)]
+pub(crate) fn reduce(order: &mut Order, quantity_delta: u64) -> Result<Order, OrderBookError> {
+    if quantity_delta == 0 || quantity_delta >= order.quantity {
+        return Err(OrderBookError::InvalidQuantity);
+    }
+    order.quantity -= quantity_delta;
+    Ok(order.clone())
+}
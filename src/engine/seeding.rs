@@ -0,0 +1,120 @@
+use crate::engine::{OrderBookCore, OrderBookError};
+use crate::types::order::{Order, OrderKind, OrderSide, TimeInForce};
+use crate::types::symbol_mapping::SymbolId;
+
+/// How much a seeded book rests at each level and how far apart the levels sit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantityProfile {
+    /// Quantity at the best bid and the best ask.
+    pub touch_quantity: u64,
+    /// Each level further from the mid holds this share of the one inside it, so
+    /// 0.8 shrinks sizes by a fifth per level. Levels never drop below 1.
+    pub decay: f64,
+    /// Resting orders each level's quantity is split across.
+    pub orders_per_level: usize,
+    /// Price distance between levels, and from the mid to the best bid and ask.
+    pub level_spacing: u64,
+    /// Seeded ids count up from here. The default sits far above the ids tests and
+    /// replays use, so seeded orders don't collide with flow.
+    pub first_order_id: u64,
+}
+
+impl Default for QuantityProfile {
+    fn default() -> Self {
+        Self { touch_quantity: 1_000, decay: 0.8, orders_per_level: 1, level_spacing: 10, first_order_id: 1 << 62 }
+    }
+}
+
+impl QuantityProfile {
+    /// Quantity resting at `level`, 0 being the level nearest the mid.
+    pub fn level_quantity(&self, level: usize) -> u64 {
+        let decayed = self.touch_quantity as f64 * self.decay.powi(level as i32);
+        (decayed.round() as u64).max(1)
+    }
+}
+
+/// Builds `levels` bid and `levels` ask levels around `mid_price`, innermost first,
+/// with sizes decaying away from the mid as `profile` describes. Bid levels that would
+/// fall to a price of 0 or below are left out. Orders are `GoodTillCancel` so a warm
+/// book survives session rolls.
+pub fn seed_orders(symbol: SymbolId, mid_price: u64, levels: usize, profile: &QuantityProfile) -> Vec<Order> {
+    let per_level = profile.orders_per_level.max(1);
+    let mut orders = Vec::with_capacity(levels * 2 * per_level);
+    let mut id = profile.first_order_id;
+    for level in 0..levels {
+        let offset = profile.level_spacing.saturating_mul(level as u64 + 1);
+        let quantity = profile.level_quantity(level);
+        let bid = mid_price.checked_sub(offset).filter(|&price| price > 0);
+        let ask = Some(mid_price.saturating_add(offset));
+        for (side, price) in [(OrderSide::Buy, bid), (OrderSide::Sell, ask)] {
+            let Some(price) = price else { continue };
+            // Split evenly, with the remainder on the first (oldest) order
+            let count = (per_level as u64).min(quantity);
+            for slot in 0..count {
+                let share = quantity / count + if slot == 0 { quantity % count } else { 0 };
+                orders.push(Order {
+                    id,
+                    symbol,
+                    quantity: share,
+                    price,
+                    order_type: side,
+                    time_in_force: TimeInForce::GoodTillCancel,
+                    timestamp: 0,
+                    participant: 0,
+                    expires_at: None,
+                    kind: OrderKind::Limit,
+                });
+                id += 1;
+            }
+        }
+    }
+    orders
+}
+
+/// Rests the orders from `seed_orders` on `book`. Returns the id after the last one
+/// seeded, for the caller to continue numbering from.
+pub fn seed_book<B: OrderBookCore + ?Sized>(
+    book: &mut B,
+    symbol: SymbolId,
+    mid_price: u64,
+    levels: usize,
+    profile: &QuantityProfile,
+) -> Result<u64, OrderBookError> {
+    let mut next_id = profile.first_order_id;
+    for order in seed_orders(symbol, mid_price, levels, profile) {
+        next_id = order.id + 1;
+        book.add_order(order)?;
+    }
+    Ok(next_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{create_order_book, OrderBookType};
+    use crate::types::order::price_to_u64;
+
+    #[test]
+    fn test_seeded_book_shape() {
+        let profile = QuantityProfile { touch_quantity: 100, decay: 0.5, orders_per_level: 3, first_order_id: 1, ..Default::default() };
+        assert_eq!((0..4).map(|level| profile.level_quantity(level)).collect::<Vec<_>>(), vec![100, 50, 25, 13]);
+
+        let mid = price_to_u64(100.0);
+        let orders = seed_orders(0, mid, 4, &profile);
+        let bid_total: u64 = orders.iter().filter(|order| order.order_type == OrderSide::Buy).map(|order| order.quantity).sum();
+        assert_eq!(bid_total, 188);
+        assert_eq!(orders.len(), 24);
+        assert_eq!(orders[..3].iter().map(|order| order.quantity).collect::<Vec<_>>(), vec![34, 33, 33]);
+
+        for book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue] {
+            let mut book = create_order_book(book_type, [0].into_iter().collect());
+            assert_eq!(seed_book(book.as_mut(), 0, mid, 4, &profile).unwrap(), 25);
+            assert_eq!(book.get_best_prices(0), Some((Some(mid - 10), Some(mid + 10))), "{book_type:?}");
+            assert!(book.match_orders().is_empty());
+        }
+
+        // Bids stop short of a zero price
+        let near_zero = seed_orders(0, 25, 4, &QuantityProfile::default());
+        assert_eq!(near_zero.iter().filter(|order| order.order_type == OrderSide::Buy).count(), 2);
+    }
+}
@@ -0,0 +1,354 @@
+use rustc_hash::FxHashMap;
+
+use crate::types::symbol_mapping::SymbolId;
+
+/// Where a book keeps its per-symbol matchers. The hot path looks a matcher up on
+/// every order, so this is the container `MapBackend`s swap out.
+pub trait SymbolMap<M>: Default {
+    fn get(&self, symbol: SymbolId) -> Option<&M>;
+
+    fn get_mut(&mut self, symbol: SymbolId) -> Option<&mut M>;
+
+    /// Returns the matcher for `symbol`, calling `create` if there is none. `create`
+    /// returning `None` leaves the symbol absent.
+    fn get_or_create(&mut self, symbol: SymbolId, create: impl FnOnce() -> Option<M>) -> Option<&mut M>;
+
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a M>
+    where
+        M: 'a;
+
+    fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut M>
+    where
+        M: 'a;
+}
+
+/// Order id to value, for books that find resting orders by id.
+pub trait IdMap<V>: Default {
+    fn get(&self, id: u64) -> Option<&V>;
+
+    fn get_mut(&mut self, id: u64) -> Option<&mut V>;
+
+    fn insert(&mut self, id: u64, value: V) -> Option<V>;
+
+    fn remove(&mut self, id: u64) -> Option<V>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn ids(&self) -> impl Iterator<Item = u64>;
+}
+
+/// Picks the map implementations a book is built on. Every book takes one as a type
+/// parameter defaulting to `FxMaps`, e.g. `HashMapOrderBook::<DenseMaps>::new(symbols)`
+/// through `OrderBookCore::new`. Each book also has an inherent `new` building it on
+/// `FxMaps`, so the default backend need not be named.
+/// Books are shared across threads, so the maps must be too.
+pub trait MapBackend: 'static {
+    type Matchers<M: Send + Sync>: SymbolMap<M> + Send + Sync;
+    type Ids<V: Send + Sync>: IdMap<V> + Send + Sync;
+}
+
+/// `FxHashMap` for both symbols and order ids.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxMaps;
+
+impl MapBackend for FxMaps {
+    type Matchers<M: Send + Sync> = FxHashMap<SymbolId, M>;
+    type Ids<V: Send + Sync> = FxHashMap<u64, V>;
+}
+
+/// `DenseSymbolMap` for symbols and `FxHashMap` for order ids.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenseMaps;
+
+impl MapBackend for DenseMaps {
+    type Matchers<M: Send + Sync> = DenseSymbolMap<M>;
+    type Ids<V: Send + Sync> = FxHashMap<u64, V>;
+}
+
+impl<M> SymbolMap<M> for FxHashMap<SymbolId, M> {
+    #[inline(always)]
+    fn get(&self, symbol: SymbolId) -> Option<&M> {
+        FxHashMap::get(self, &symbol)
+    }
+
+    #[inline(always)]
+    fn get_mut(&mut self, symbol: SymbolId) -> Option<&mut M> {
+        FxHashMap::get_mut(self, &symbol)
+    }
+
+    #[inline(always)]
+    fn get_or_create(&mut self, symbol: SymbolId, create: impl FnOnce() -> Option<M>) -> Option<&mut M> {
+        use std::collections::hash_map::Entry;
+        match self.entry(symbol) {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => create().map(|matcher| entry.insert(matcher)),
+        }
+    }
+
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a M>
+    where
+        M: 'a,
+    {
+        FxHashMap::values(self)
+    }
+
+    fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut M>
+    where
+        M: 'a,
+    {
+        FxHashMap::values_mut(self)
+    }
+}
+
+impl<V> IdMap<V> for FxHashMap<u64, V> {
+    #[inline(always)]
+    fn get(&self, id: u64) -> Option<&V> {
+        FxHashMap::get(self, &id)
+    }
+
+    #[inline(always)]
+    fn get_mut(&mut self, id: u64) -> Option<&mut V> {
+        FxHashMap::get_mut(self, &id)
+    }
+
+    #[inline(always)]
+    fn insert(&mut self, id: u64, value: V) -> Option<V> {
+        FxHashMap::insert(self, id, value)
+    }
+
+    #[inline(always)]
+    fn remove(&mut self, id: u64) -> Option<V> {
+        FxHashMap::remove(self, &id)
+    }
+
+    fn len(&self) -> usize {
+        FxHashMap::len(self)
+    }
+
+    fn ids(&self) -> impl Iterator<Item = u64> {
+        self.keys().copied()
+    }
+}
+
+/// Matchers in a `Vec` indexed by symbol id: a bounds check and a load instead of a
+/// hash, at the cost of a slot for every id up to the largest one seen. Suits small,
+/// densely numbered symbol universes.
+#[derive(Debug, Clone)]
+pub struct DenseSymbolMap<M> {
+    slots: Vec<Option<M>>,
+}
+
+impl<M> Default for DenseSymbolMap<M> {
+    fn default() -> Self {
+        Self { slots: Vec::new() }
+    }
+}
+
+impl<M> SymbolMap<M> for DenseSymbolMap<M> {
+    #[inline(always)]
+    fn get(&self, symbol: SymbolId) -> Option<&M> {
+        self.slots.get(symbol as usize)?.as_ref()
+    }
+
+    #[inline(always)]
+    fn get_mut(&mut self, symbol: SymbolId) -> Option<&mut M> {
+        self.slots.get_mut(symbol as usize)?.as_mut()
+    }
+
+    #[inline(always)]
+    fn get_or_create(&mut self, symbol: SymbolId, create: impl FnOnce() -> Option<M>) -> Option<&mut M> {
+        let index = symbol as usize;
+        if self.slots.get(index).is_none_or(Option::is_none) {
+            let matcher = create()?;
+            if index >= self.slots.len() {
+                self.slots.resize_with(index + 1, || None);
+            }
+            self.slots[index] = Some(matcher);
+        }
+        self.slots[index].as_mut()
+    }
+
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a M>
+    where
+        M: 'a,
+    {
+        self.slots.iter().flatten()
+    }
+
+    fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut M>
+    where
+        M: 'a,
+    {
+        self.slots.iter_mut().flatten()
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+pub use raw_table::{RawTableMap, RawTableMaps};
+
+#[cfg(feature = "hashbrown")]
+mod raw_table {
+    use std::hash::Hasher;
+
+    use hashbrown::hash_table::{Entry, HashTable};
+    use rustc_hash::FxHasher;
+
+    use super::{IdMap, MapBackend, SymbolMap};
+    use crate::types::symbol_mapping::SymbolId;
+
+    /// `RawTableMap` for both symbols and order ids.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct RawTableMaps;
+
+    impl MapBackend for RawTableMaps {
+        type Matchers<M: Send + Sync> = RawTableMap<SymbolId, M>;
+        type Ids<V: Send + Sync> = RawTableMap<u64, V>;
+    }
+
+    /// hashbrown's `HashTable` keyed by integers, hashing each key once with Fx and
+    /// comparing keys directly instead of going through `Hash` and `BuildHasher`.
+    #[derive(Debug, Clone)]
+    pub struct RawTableMap<K, V> {
+        table: HashTable<(K, V)>,
+    }
+
+    impl<K, V> Default for RawTableMap<K, V> {
+        fn default() -> Self {
+            Self { table: HashTable::new() }
+        }
+    }
+
+    #[inline(always)]
+    fn hash(key: u64) -> u64 {
+        let mut hasher = FxHasher::default();
+        hasher.write_u64(key);
+        hasher.finish()
+    }
+
+    impl<K: Copy + Eq + Into<u64>, V> RawTableMap<K, V> {
+        #[inline(always)]
+        fn get(&self, key: K) -> Option<&V> {
+            self.table.find(hash(key.into()), |(other, _)| *other == key).map(|(_, value)| value)
+        }
+
+        #[inline(always)]
+        fn get_mut(&mut self, key: K) -> Option<&mut V> {
+            self.table.find_mut(hash(key.into()), |(other, _)| *other == key).map(|(_, value)| value)
+        }
+
+        #[inline(always)]
+        fn insert(&mut self, key: K, value: V) -> Option<V> {
+            match self.table.entry(hash(key.into()), |(other, _)| *other == key, |(other, _)| hash((*other).into())) {
+                Entry::Occupied(mut entry) => Some(std::mem::replace(&mut entry.get_mut().1, value)),
+                Entry::Vacant(entry) => {
+                    entry.insert((key, value));
+                    None
+                }
+            }
+        }
+    }
+
+    impl<M> SymbolMap<M> for RawTableMap<SymbolId, M> {
+        #[inline(always)]
+        fn get(&self, symbol: SymbolId) -> Option<&M> {
+            RawTableMap::get(self, symbol)
+        }
+
+        #[inline(always)]
+        fn get_mut(&mut self, symbol: SymbolId) -> Option<&mut M> {
+            RawTableMap::get_mut(self, symbol)
+        }
+
+        #[inline(always)]
+        fn get_or_create(&mut self, symbol: SymbolId, create: impl FnOnce() -> Option<M>) -> Option<&mut M> {
+            let entry = self.table.entry(hash(symbol.into()), |(other, _)| *other == symbol, |(other, _)| hash((*other).into()));
+            match entry {
+                Entry::Occupied(entry) => Some(&mut entry.into_mut().1),
+                Entry::Vacant(entry) => create().map(|matcher| &mut entry.insert((symbol, matcher)).into_mut().1),
+            }
+        }
+
+        fn values<'a>(&'a self) -> impl Iterator<Item = &'a M>
+    where
+        M: 'a,
+    {
+            self.table.iter().map(|(_, matcher)| matcher)
+        }
+
+        fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut M>
+    where
+        M: 'a,
+    {
+            self.table.iter_mut().map(|(_, matcher)| matcher)
+        }
+    }
+
+    impl<V> IdMap<V> for RawTableMap<u64, V> {
+        #[inline(always)]
+        fn get(&self, id: u64) -> Option<&V> {
+            RawTableMap::get(self, id)
+        }
+
+        #[inline(always)]
+        fn get_mut(&mut self, id: u64) -> Option<&mut V> {
+            RawTableMap::get_mut(self, id)
+        }
+
+        #[inline(always)]
+        fn insert(&mut self, id: u64, value: V) -> Option<V> {
+            RawTableMap::insert(self, id, value)
+        }
+
+        #[inline(always)]
+        fn remove(&mut self, id: u64) -> Option<V> {
+            self.table.find_entry(hash(id), |(other, _)| *other == id).ok().map(|entry| entry.remove().0.1)
+        }
+
+        fn len(&self) -> usize {
+            self.table.len()
+        }
+
+        fn ids(&self) -> impl Iterator<Item = u64> {
+            self.table.iter().map(|(id, _)| *id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise<B: MapBackend>() {
+        let mut matchers = B::Matchers::<u32>::default();
+        assert!(matchers.get_or_create(5, || None).is_none());
+        *matchers.get_or_create(5, || Some(1)).unwrap() += 1;
+        *matchers.get_or_create(5, || Some(10)).unwrap() += 1;
+        matchers.get_or_create(70_000, || Some(7));
+        assert_eq!((matchers.get(5), matchers.get(4), matchers.get(70_000)), (Some(&3), None, Some(&7)));
+        matchers.values_mut().for_each(|matcher| *matcher *= 2);
+        let mut values: Vec<u32> = matchers.values().copied().collect();
+        values.sort_unstable();
+        assert_eq!(values, vec![6, 14]);
+
+        let mut ids = B::Ids::<u8>::default();
+        assert_eq!(ids.insert(9, 1), None);
+        assert_eq!(ids.insert(9, 2), Some(1));
+        ids.insert(u64::MAX, 3);
+        assert_eq!((ids.get(9), ids.len()), (Some(&2), 2));
+        assert_eq!(ids.remove(9), Some(2));
+        assert_eq!(ids.ids().collect::<Vec<_>>(), vec![u64::MAX]);
+        assert_eq!(ids.remove(9), None);
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        exercise::<FxMaps>();
+        exercise::<DenseMaps>();
+        #[cfg(feature = "hashbrown")]
+        exercise::<RawTableMaps>();
+    }
+}
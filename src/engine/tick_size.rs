@@ -0,0 +1,104 @@
+use rustc_hash::FxHashMap;
+
+use crate::engine::OrderBookError;
+use crate::types::order::{Order, OrderSide};
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::validation;
+
+/// What a book does with an order priced between ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+pub enum OffTickPolicy {
+    /// Refuse it with `OrderBookError::OffTick`.
+    #[default]
+    Reject,
+    /// Move it to the nearest tick that is no more aggressive: bids round down and
+    /// asks round up.
+    RoundPassive,
+}
+
+/// Minimum price increment per symbol, in scaled price units. Symbols without an
+/// entry accept any price.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct TickSizes {
+    ticks: FxHashMap<SymbolId, u64>,
+    policy: OffTickPolicy,
+}
+
+impl TickSizes {
+    /// A tick of 0 or 1 removes the symbol's entry, since every price is on it.
+    pub fn with_tick(mut self, symbol: SymbolId, tick: u64) -> Self {
+        self.set_tick(symbol, tick);
+        self
+    }
+
+    pub fn with_policy(mut self, policy: OffTickPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn set_tick(&mut self, symbol: SymbolId, tick: u64) {
+        if tick > 1 {
+            self.ticks.insert(symbol, tick);
+        } else {
+            self.ticks.remove(&symbol);
+        }
+    }
+
+    #[inline(always)]
+    pub fn tick(&self, symbol: SymbolId) -> Option<u64> {
+        self.ticks.get(&symbol).copied()
+    }
+
+    #[inline(always)]
+    pub fn policy(&self) -> OffTickPolicy {
+        self.policy
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    /// Returns the order on its symbol's tick under the policy. Nothing is checked with
+    /// validation stripped.
+    #[inline(always)]
+    pub fn conform(&self, mut order: Order) -> Result<Order, OrderBookError> {
+        if !validation::ENABLED || self.ticks.is_empty() {
+            return Ok(order);
+        }
+        let Some(tick) = self.tick(order.symbol) else { return Ok(order) };
+        let offset = order.price % tick;
+        if offset == 0 {
+            return Ok(order);
+        }
+        match (self.policy, order.order_type) {
+            (OffTickPolicy::Reject, _) => return Err(OrderBookError::OffTick),
+            (OffTickPolicy::RoundPassive, OrderSide::Buy) => order.price -= offset,
+            (OffTickPolicy::RoundPassive, OrderSide::Sell) => {
+                order.price = order.price.checked_add(tick - offset).ok_or(OrderBookError::OffTick)?;
+            }
+        }
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_conform_rejects_or_rounds_passively() {
+        let ticks = TickSizes::default().with_tick(0, 50).with_tick(1, 1);
+        assert_eq!(ticks.tick(1), None);
+        let bid = new_order(1, 0, 10, 100.02, OrderSide::Buy);
+        let ask = new_order(2, 0, 10, 100.02, OrderSide::Sell);
+        assert!(matches!(ticks.conform(bid.clone()), Err(OrderBookError::OffTick)));
+        assert_eq!(ticks.conform(new_order(3, 0, 10, 100.05, OrderSide::Buy)).unwrap().price, 100_050);
+
+        let rounding = ticks.with_policy(OffTickPolicy::RoundPassive);
+        assert_eq!(rounding.conform(bid).unwrap().price, 100_000);
+        assert_eq!(rounding.conform(ask).unwrap().price, 100_050);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::rng::SplitMix64;
+
+/// How a price level picks the next of its resting orders to match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+pub enum TieBreak {
+    /// Price-time priority: the earliest order at the level matches first.
+    #[default]
+    Fifo,
+    /// Largest resting quantity first, the earliest among equal sizes.
+    SizePriority,
+    /// A random draw among the level's orders. Draws come from `seed`, so a run is
+    /// reproducible.
+    Lottery { seed: u64 },
+}
+
+/// Applies a `TieBreak` for one symbol's matcher, holding the lottery's random stream.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TieBreaker {
+    rule: TieBreak,
+    rng: SplitMix64,
+}
+
+impl TieBreaker {
+    pub(crate) fn new(rule: TieBreak) -> Self {
+        let seed = match rule {
+            TieBreak::Lottery { seed } => seed,
+            TieBreak::Fifo | TieBreak::SizePriority => 0,
+        };
+        Self { rule, rng: SplitMix64::new(seed) }
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_fifo(&self) -> bool {
+        self.rule == TieBreak::Fifo
+    }
+
+    /// Position of the next order to match among a level's `len` orders, given their
+    /// quantities in arrival order. `None` for an empty level.
+    #[inline(always)]
+    pub(crate) fn pick(&mut self, len: usize, quantities: impl Iterator<Item = u64>) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        match self.rule {
+            TieBreak::Fifo => Some(0),
+            TieBreak::SizePriority => quantities.enumerate()
+                .fold(None, |best: Option<(usize, u64)>, (index, quantity)| match best {
+                    Some((_, largest)) if largest >= quantity => best,
+                    _ => Some((index, quantity)),
+                })
+                .map(|(index, _)| index),
+            TieBreak::Lottery { .. } => Some((self.rng.next_u64() % len as u64) as usize),
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_per_rule() {
+        let quantities = [5, 20, 7, 20];
+        assert_eq!(TieBreaker::new(TieBreak::Fifo).pick(quantities.len(), quantities.into_iter()), Some(0));
+        assert_eq!(TieBreaker::new(TieBreak::SizePriority).pick(quantities.len(), quantities.into_iter()), Some(1));
+        assert_eq!(TieBreaker::new(TieBreak::SizePriority).pick(0, std::iter::empty()), None);
+
+        let draws = |seed| {
+            let mut tie_breaker = TieBreaker::new(TieBreak::Lottery { seed });
+            (0..16).map(|_| tie_breaker.pick(quantities.len(), quantities.into_iter()).unwrap()).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(3), draws(3));
+        assert!(draws(3).iter().all(|&index| index < quantities.len()));
+        assert_ne!(draws(3), draws(4));
+    }
+}
@@ -0,0 +1,207 @@
+use std::io::{self, Write};
+
+use crate::router::OrderRouter;
+use crate::types::order::{decode_order, encode_order, Order, ORDER_WIRE_SIZE};
+
+const JOURNAL_MAGIC: u64 = 0x4f52_4442_4a4e_4c31;
+
+// Header: magic, then the record size so a reader built with another layout refuses the file
+const HEADER_SIZE: usize = 16;
+
+/// Writes accepted orders as a binary journal: a 16-byte header followed by one
+/// `encode_order` record per order. Fixed-size records can be replayed straight out of
+/// a memory map; they do not carry `expires_at` or the order kind.
+pub struct JournalWriter<W: Write> {
+    writer: W,
+    records: u64,
+}
+
+impl<W: Write> JournalWriter<W> {
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&JOURNAL_MAGIC.to_le_bytes())?;
+        writer.write_all(&(ORDER_WIRE_SIZE as u64).to_le_bytes())?;
+        Ok(Self { writer, records: 0 })
+    }
+
+    #[inline(always)]
+    pub fn append(&mut self, order: &Order) -> io::Result<()> {
+        self.writer.write_all(&encode_order(order))?;
+        self.records += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Records of a binary journal, decoded in place from the bytes they are stored in.
+#[derive(Debug, Clone, Copy)]
+pub struct JournalRecords<'a> {
+    records: &'a [u8],
+}
+
+impl<'a> JournalRecords<'a> {
+    /// Checks the header and that the body holds whole records.
+    pub fn parse(bytes: &'a [u8]) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let Some((header, records)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+            return Err(invalid("journal shorter than its header"));
+        };
+        // The header is a fixed-size array, so these conversions cannot fail
+        if u64::from_le_bytes(header[0..8].try_into().unwrap()) != JOURNAL_MAGIC {
+            return Err(invalid("not a binary order journal"));
+        }
+        if u64::from_le_bytes(header[8..16].try_into().unwrap()) != ORDER_WIRE_SIZE as u64 {
+            return Err(invalid("journal written with a different record layout"));
+        }
+        if records.len() % ORDER_WIRE_SIZE != 0 {
+            return Err(invalid("journal ends in a partial record"));
+        }
+        Ok(Self { records })
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.records.len() / ORDER_WIRE_SIZE
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Decodes each record as it is reached. Nothing is allocated per record.
+    pub fn iter(&self) -> impl Iterator<Item = io::Result<Order>> + 'a {
+        self.records.chunks_exact(ORDER_WIRE_SIZE).enumerate().map(|(index, record)| {
+            decode_order(record).map_err(|error| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("record {index}: {error:?}"))
+            })
+        })
+    }
+}
+
+/// A binary journal file mapped into memory, so replay reads records straight from
+/// the page cache instead of copying the file into a buffer first.
+#[cfg(feature = "mmap-replay")]
+pub struct MappedJournal {
+    mmap: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap-replay")]
+impl MappedJournal {
+    pub fn open(path: &std::path::Path) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // Journals are append-only and not truncated while replayed
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        #[cfg(unix)]
+        mmap.advise(memmap2::Advice::Sequential)?;
+        JournalRecords::parse(&mmap)?;
+        Ok(Self { mmap })
+    }
+
+    pub fn records(&self) -> JournalRecords<'_> {
+        // Validated in `open`
+        JournalRecords::parse(&self.mmap).expect("journal checked on open")
+    }
+}
+
+/// What a `replay` did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ReplayStats {
+    pub records: u64,
+    pub routed: u64,
+    pub rejected: u64,
+    pub trades: u64,
+}
+
+/// Routes every record of `journal` through `router`, matching after every
+/// `match_interval` records (at least 1) and once more at the end. Rejected orders are
+/// counted and skipped; a record that fails to decode stops the replay.
+pub fn replay(router: &mut OrderRouter, journal: JournalRecords<'_>, match_interval: usize) -> io::Result<ReplayStats> {
+    let match_interval = match_interval.max(1) as u64;
+    let mut stats = ReplayStats::default();
+    for order in journal.iter() {
+        match router.route_order(order?) {
+            Ok(()) => stats.routed += 1,
+            Err(_) => stats.rejected += 1,
+        }
+        stats.records += 1;
+        if stats.records % match_interval == 0 {
+            stats.trades += router.match_all_orders().len() as u64;
+        }
+    }
+    if stats.records % match_interval != 0 {
+        stats.trades += router.match_all_orders().len() as u64;
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashSet;
+    use crate::engine::OrderBookType;
+    use crate::types::order::{new_order, OrderSide, TimeInForce};
+
+    fn flow() -> Vec<Order> {
+        (1..=40u64)
+            .map(|id| {
+                let side = if id % 2 == 0 { OrderSide::Buy } else { OrderSide::Sell };
+                new_order(id, (id % 3) as u32, 10 + id, 100.0 + (id % 5) as f64 - 2.0, side)
+                    .with_time_in_force(if id % 7 == 0 { TimeInForce::GoodTillCancel } else { TimeInForce::Day })
+                    .with_participant(id as u32 % 4)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_matches_routing_directly() {
+        let mut writer = JournalWriter::new(Vec::new()).unwrap();
+        flow().iter().for_each(|order| writer.append(order).unwrap());
+        assert_eq!(writer.records(), 40);
+        let bytes = writer.into_inner().unwrap();
+
+        let symbols = FxHashSet::from_iter([0, 1]);
+        let mut direct = OrderRouter::new_direct(symbols.clone(), OrderBookType::HashMap);
+        let mut trades = 0;
+        for order in flow() {
+            let _ = direct.route_order(order);
+            trades += direct.match_all_orders().len() as u64;
+        }
+
+        let journal = JournalRecords::parse(&bytes).unwrap();
+        let mut replayed = OrderRouter::new_direct(symbols, OrderBookType::HashMap);
+        let stats = replay(&mut replayed, journal, 1).unwrap();
+        assert_eq!(stats, ReplayStats { records: 40, routed: 27, rejected: 13, trades });
+        assert_eq!(replayed.snapshot_all().checksum(), direct.snapshot_all().checksum());
+
+        assert!(JournalRecords::parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(JournalRecords::parse(&bytes[1..]).is_err());
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER_SIZE + 28] = 9;
+        let error = replay(&mut OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap), JournalRecords::parse(&corrupt).unwrap(), 1);
+        assert!(error.unwrap_err().to_string().starts_with("record 0"));
+    }
+
+    #[cfg(feature = "mmap-replay")]
+    #[test]
+    fn test_replay_from_mapped_file() {
+        let path = std::env::temp_dir().join(format!("rust-order-book-journal-{}.bin", std::process::id()));
+        let mut writer = JournalWriter::new(std::io::BufWriter::new(std::fs::File::create(&path).unwrap())).unwrap();
+        flow().iter().for_each(|order| writer.append(order).unwrap());
+        writer.into_inner().unwrap();
+
+        let journal = MappedJournal::open(&path).unwrap();
+        assert_eq!(journal.records().len(), 40);
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1, 2]), OrderBookType::HashMap);
+        assert_eq!(replay(&mut router, journal.records(), 8).unwrap().routed, 40);
+        let _ = std::fs::remove_file(&path);
+    }
+}
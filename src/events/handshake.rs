@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::schema::{SchemaError, CURRENT_VERSION, OLDEST_SUPPORTED_VERSION};
+
+/// First message each side of a gateway or replication link sends: the range of
+/// event schema versions it can read and write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hello {
+    pub min_version: u16,
+    pub max_version: u16,
+}
+
+impl Hello {
+    /// The range this build supports.
+    pub fn local() -> Self {
+        Self { min_version: OLDEST_SUPPORTED_VERSION, max_version: CURRENT_VERSION }
+    }
+
+    /// Highest version both sides support, which the link then uses in both
+    /// directions.
+    pub fn negotiate(&self, peer: &Hello) -> Result<u16, SchemaError> {
+        let version = self.max_version.min(peer.max_version);
+        if version < self.min_version.max(peer.min_version) {
+            return Err(SchemaError::NoCommonVersion);
+        }
+        Ok(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_highest_common_version() {
+        let local = Hello::local();
+        assert_eq!(local.negotiate(&local), Ok(CURRENT_VERSION));
+        assert_eq!(local.negotiate(&Hello { min_version: 1, max_version: 1 }), Ok(1));
+        assert_eq!(local.negotiate(&Hello { min_version: 1, max_version: 5 }), Ok(CURRENT_VERSION));
+        assert_eq!(local.negotiate(&Hello { min_version: 3, max_version: 5 }), Err(SchemaError::NoCommonVersion));
+    }
+}
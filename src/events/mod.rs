@@ -0,0 +1,11 @@
+pub mod binary_journal;
+pub mod handshake;
+pub mod schema;
+pub mod shrink;
+
+#[cfg(feature = "mmap-replay")]
+pub use binary_journal::MappedJournal;
+pub use binary_journal::{replay, JournalRecords, JournalWriter, ReplayStats};
+pub use handshake::Hello;
+pub use schema::{decode, decode_journal, encode, BookEvent, SchemaError, CURRENT_VERSION, OLDEST_SUPPORTED_VERSION};
+pub use shrink::{check_invariants, shrink_journal, Violation};
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::engine::LevelChange;
+use crate::types::order::Order;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// Version this build writes.
+pub const CURRENT_VERSION: u16 = 2;
+/// Oldest version this build still reads.
+pub const OLDEST_SUPPORTED_VERSION: u16 = 1;
+
+/// One book event as it is journalled or sent to a peer.
+///
+/// Fields added in later versions must carry `#[serde(default)]` so older records
+/// still decode, and decoding ignores fields it does not know so newer records do too.
+/// Event types this build does not know decode as `Unknown` rather than failing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BookEvent {
+    Accepted {
+        order: Order,
+        /// Router sequence assigned to the order, 0 in version 1 records.
+        #[serde(default)]
+        sequence: u64,
+    },
+    Traded(Trade),
+    Cancelled {
+        symbol: SymbolId,
+        order_id: u64,
+        /// Quantity that was still resting when the order was cancelled.
+        quantity: u64,
+    },
+    LevelUpdate {
+        symbol: SymbolId,
+        #[serde(flatten)]
+        change: LevelChange,
+    },
+    /// An event type from a newer version, kept so a replay can skip it.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// Not valid JSON, or not an event of the version it claims.
+    Malformed(String),
+    /// Older than `OLDEST_SUPPORTED_VERSION`.
+    UnsupportedVersion(u16),
+    /// The event has no encoding in the requested version.
+    NotRepresentable { version: u16 },
+    /// Two peers' version ranges do not overlap.
+    NoCommonVersion,
+}
+
+#[derive(Serialize)]
+struct EnvelopeRef<'a> {
+    version: u16,
+    event: &'a BookEvent,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    version: u16,
+    event: BookEvent,
+}
+
+/// Encodes `event` as one JSON line in `version`.
+///
+/// Version 1 is the original journal format: a bare `Order` per line, which can
+/// only express accepted orders. Version 2 wraps every event in a
+/// `{"version":2,"event":{"type":...}}` envelope.
+pub fn encode(event: &BookEvent, version: u16) -> Result<String, SchemaError> {
+    let encoded = match (version, event) {
+        (1, BookEvent::Accepted { order, .. }) => serde_json::to_string(order),
+        (1, _) => return Err(SchemaError::NotRepresentable { version }),
+        (2, _) => serde_json::to_string(&EnvelopeRef { version, event }),
+        _ => return Err(SchemaError::UnsupportedVersion(version)),
+    };
+    encoded.map_err(|error| SchemaError::Malformed(error.to_string()))
+}
+
+/// Decodes one line written by any supported version, returning the version it was
+/// written in alongside the event. Lines from versions newer than this build are
+/// decoded on a best-effort basis.
+pub fn decode(line: &str) -> Result<(u16, BookEvent), SchemaError> {
+    let value: serde_json::Value = serde_json::from_str(line.trim())
+        .map_err(|error| SchemaError::Malformed(error.to_string()))?;
+    let malformed = |error: serde_json::Error| SchemaError::Malformed(error.to_string());
+
+    let Some(version) = value.get("version").and_then(serde_json::Value::as_u64) else {
+        let order: Order = serde_json::from_value(value).map_err(malformed)?;
+        return Ok((1, BookEvent::Accepted { order, sequence: 0 }));
+    };
+    let version = u16::try_from(version).unwrap_or(u16::MAX);
+    if version < OLDEST_SUPPORTED_VERSION {
+        return Err(SchemaError::UnsupportedVersion(version));
+    }
+    let envelope: Envelope = serde_json::from_value(value).map_err(malformed)?;
+    Ok((envelope.version, envelope.event))
+}
+
+/// Decodes a journal line by line, skipping blank lines. Fails with the 1-based line
+/// number of the first line that does not decode.
+pub fn decode_journal(journal: &str) -> Result<Vec<BookEvent>, (usize, SchemaError)> {
+    journal.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| decode(line).map(|(_, event)| event).map_err(|error| (index + 1, error)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, OrderSide};
+
+    #[test]
+    fn test_round_trip_and_compatibility() {
+        let order = new_order(7, 1, 10, 100.0, OrderSide::Buy);
+        let accepted = BookEvent::Accepted { order: order.clone(), sequence: 3 };
+        let cancelled = BookEvent::Cancelled { symbol: 1, order_id: 7, quantity: 10 };
+
+        let line = encode(&cancelled, CURRENT_VERSION).unwrap();
+        assert!(matches!(decode(&line), Ok((2, BookEvent::Cancelled { order_id: 7, quantity: 10, .. }))));
+        assert_eq!(encode(&cancelled, 1), Err(SchemaError::NotRepresentable { version: 1 }));
+        assert_eq!(encode(&accepted, 9), Err(SchemaError::UnsupportedVersion(9)));
+
+        // Version 1 journals are bare orders
+        let legacy = encode(&accepted, 1).unwrap();
+        assert_eq!(legacy, serde_json::to_string(&order).unwrap());
+        assert!(matches!(decode(&legacy), Ok((1, BookEvent::Accepted { order, sequence: 0 })) if order.id == 7));
+
+        // Newer records: unknown fields are ignored and unknown event types are kept
+        let newer = r#"{"version":3,"event":{"type":"cancelled","symbol":1,"order_id":7,"quantity":10,"reason":"x"}}"#;
+        assert!(matches!(decode(newer), Ok((3, BookEvent::Cancelled { .. }))));
+        assert!(matches!(decode(r#"{"version":3,"event":{"type":"halted","symbol":1}}"#), Ok((3, BookEvent::Unknown))));
+        assert_eq!(decode(r#"{"version":0,"event":{"type":"unknown"}}"#).err(), Some(SchemaError::UnsupportedVersion(0)));
+        assert!(matches!(decode("not json"), Err(SchemaError::Malformed(_))));
+
+        let trade = BookEvent::Traded(Trade::between(&order, &new_order(8, 1, 4, 100.0, OrderSide::Sell), 100_000));
+        let change = LevelChange { timestamp: 1, side: OrderSide::Buy, price: 100_000, old_quantity: 10, new_quantity: 6 };
+        let level_update = BookEvent::LevelUpdate { symbol: 1, change };
+        assert!(matches!(decode(&encode(&trade, 2).unwrap()), Ok((2, BookEvent::Traded(Trade { taker_order_id: 8, quantity: 4, .. })))));
+        assert!(matches!(decode(&encode(&level_update, 2).unwrap()), Ok((2, BookEvent::LevelUpdate { symbol: 1, change: decoded })) if decoded == change));
+
+        let journal = format!("{legacy}\n\n{line}\nnot json\n");
+        assert!(matches!(decode_journal(&journal), Err((4, SchemaError::Malformed(_)))));
+        assert_eq!(decode_journal(&format!("{legacy}\n\n{line}\n")).unwrap().len(), 2);
+    }
+}
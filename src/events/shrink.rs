@@ -0,0 +1,236 @@
+//! Shrinks a journal that breaks a router invariant down to a short sequence of
+//! commands that still breaks it, by delta debugging (ddmin) over the journal's
+//! accepted and cancelled events.
+
+use std::fmt;
+
+use rustc_hash::FxHashSet;
+
+use crate::engine::OrderBookType;
+use crate::events::BookEvent;
+use crate::router::OrderRouter;
+use crate::types::order::OrderSide;
+
+/// An invariant that stopped holding while replaying a journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the command after which the invariant was checked and failed.
+    pub step: usize,
+    pub invariant: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} violated after command {}: {}", self.invariant, self.step, self.message)
+    }
+}
+
+/// The commands a replay applies: `Accepted` and `Cancelled` events. Trades and level
+/// updates are what the engine produced, so they are dropped.
+pub fn commands(events: &[BookEvent]) -> Vec<BookEvent> {
+    events.iter()
+        .filter(|event| matches!(event, BookEvent::Accepted { .. } | BookEvent::Cancelled { .. }))
+        .cloned()
+        .collect()
+}
+
+/// Checks every book in `router` once matching has run: the best-price cache agrees
+/// with the resting orders, no book is left crossed, and, with the ledger enabled,
+/// every order's quantity is accounted for. Returns the broken invariant's name.
+pub fn check_invariants(router: &OrderRouter) -> Result<(), (&'static str, String)> {
+    for book in router.snapshot_books() {
+        let side_prices = |side| book.orders.iter().filter(move |order| order.order_type == side).map(|order| order.price);
+        let (best_bid, best_ask) = (side_prices(OrderSide::Buy).max(), side_prices(OrderSide::Sell).min());
+        let cached = router.get_best_prices(book.symbol);
+        if cached != Some((best_bid, best_ask)) {
+            return Err(("best_prices", format!("symbol {} caches {cached:?} but rests {:?}", book.symbol, (best_bid, best_ask))));
+        }
+        if let (Some(bid), Some(ask)) = (best_bid, best_ask) && bid >= ask {
+            return Err(("crossed_book", format!("symbol {} left crossed at bid {bid} ask {ask}", book.symbol)));
+        }
+    }
+    match router.reconcile() {
+        Some(discrepancies) if !discrepancies.is_empty() => {
+            Err(("quantity_ledger", format!("{} orders unaccounted for, first {:?}", discrepancies.len(), discrepancies[0])))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Replays `commands` through a fresh ledgered router over `order_book_type`, matching
+/// and calling `check` after every command. Rejected orders and unknown cancels are
+/// no-ops, as they would be live, so any subsequence of a journal replays.
+pub fn replay_checked(
+    order_book_type: OrderBookType,
+    commands: &[BookEvent],
+    mut check: impl FnMut(&OrderRouter) -> Result<(), (&'static str, String)>,
+) -> Result<(), Violation> {
+    let symbols: FxHashSet<_> = commands.iter()
+        .filter_map(|command| match command {
+            BookEvent::Accepted { order, .. } => Some(order.symbol),
+            BookEvent::Cancelled { symbol, .. } => Some(*symbol),
+            _ => None,
+        })
+        .collect();
+    let mut router = OrderRouter::new_direct(symbols, order_book_type);
+    router.enable_ledger();
+
+    for (step, command) in commands.iter().enumerate() {
+        match command {
+            BookEvent::Accepted { order, .. } => {
+                let _ = router.route_order(order.clone());
+            }
+            BookEvent::Cancelled { order_id, .. } => {
+                router.cancel_orders_batch(&[*order_id]);
+            }
+            _ => continue,
+        }
+        router.match_all_orders();
+        check(&router).map_err(|(invariant, message)| Violation { step, invariant, message })?;
+    }
+    Ok(())
+}
+
+/// Minimises `items` while `fails` keeps returning true, by ddmin: try each of `n`
+/// chunks and each complement on its own, keep the first that still fails, and split
+/// finer when none does. The result is 1-minimal: removing any single item makes
+/// `fails` return false. `fails(items)` must be true.
+pub fn shrink<T: Clone>(items: &[T], mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut current = items.to_vec();
+    let mut granularity = 2;
+    while current.len() >= 2 {
+        let chunk = current.len().div_ceil(granularity);
+        let ranges: Vec<_> = (0..current.len()).step_by(chunk).map(|start| start..(start + chunk).min(current.len())).collect();
+
+        let subset = ranges.iter().map(|range| current[range.clone()].to_vec()).find(|subset| fails(subset));
+        if let Some(subset) = subset {
+            current = subset;
+            granularity = 2;
+            continue;
+        }
+        let complement = ranges.iter()
+            .map(|range| current[..range.start].iter().chain(&current[range.end..]).cloned().collect::<Vec<_>>())
+            .find(|complement| fails(complement));
+        if let Some(complement) = complement {
+            current = complement;
+            granularity = (granularity - 1).max(2);
+            continue;
+        }
+        if granularity >= current.len() {
+            break;
+        }
+        granularity = (granularity * 2).min(current.len());
+    }
+    current
+}
+
+/// Shrinks `events` to the fewest commands that still break the same invariant as the
+/// full journal under `check_invariants`. `None` when the journal breaks nothing.
+pub fn shrink_journal(order_book_type: OrderBookType, events: &[BookEvent]) -> Option<(Vec<BookEvent>, Violation)> {
+    shrink_journal_with(order_book_type, events, check_invariants)
+}
+
+/// `shrink_journal` against a caller's check, e.g. a stricter one reproducing a bug
+/// report.
+pub fn shrink_journal_with(
+    order_book_type: OrderBookType,
+    events: &[BookEvent],
+    mut check: impl FnMut(&OrderRouter) -> Result<(), (&'static str, String)>,
+) -> Option<(Vec<BookEvent>, Violation)> {
+    let commands = commands(events);
+    let invariant = replay_checked(order_book_type, &commands, &mut check).err()?.invariant;
+    let shrunk = shrink(&commands, |candidate| {
+        matches!(replay_checked(order_book_type, candidate, &mut check), Err(violation) if violation.invariant == invariant)
+    });
+    let violation = replay_checked(order_book_type, &shrunk, &mut check).expect_err("shrunk journal still fails");
+    Some((shrunk, violation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    fn test_shrink_is_one_minimal() {
+        let items: Vec<u32> = (0..100).collect();
+        let shrunk = shrink(&items, |candidate| candidate.contains(&17) && candidate.contains(&63));
+        assert_eq!(shrunk, vec![17, 63]);
+
+        let shrunk = shrink(&items, |candidate| candidate.iter().filter(|&&item| item % 10 == 0).count() >= 3);
+        assert_eq!(shrunk.len(), 3);
+        assert!(shrunk.iter().all(|item| item % 10 == 0));
+    }
+
+    #[test]
+    fn test_clean_journals_do_not_shrink() {
+        let events = vec![
+            BookEvent::Accepted { order: new_order(1, 0, 10, 100.0, OrderSide::Buy), sequence: 1 },
+            BookEvent::Accepted { order: new_order(2, 0, 4, 99.0, OrderSide::Sell), sequence: 2 },
+            BookEvent::Cancelled { symbol: 0, order_id: 1, quantity: 6 },
+        ];
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::Persistent] {
+            assert!(shrink_journal(order_book_type, &events).is_none());
+        }
+    }
+
+    #[test]
+    fn test_corpus_journal_shrinks_under_a_caller_check() {
+        // Stand-in bug: the check fails once 100.0 has traded while the best bid rests
+        // below it
+        let traded_above_bid = |router: &OrderRouter| -> Result<(), (&'static str, String)> {
+            match router.get_best_prices(0) {
+                Some((Some(bid), _)) if bid < 100_000 && router.last_trade_price(0) == Some(100_000) => {
+                    Err(("traded_above_bid", format!("last trade 100.0 above best bid {bid}")))
+                }
+                _ => Ok(()),
+            }
+        };
+        let events = crate::events::decode_journal(include_str!("../../tests/corpus/deep_level_sweep.jsonl")).unwrap();
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            assert!(shrink_journal(order_book_type, &events).is_none(), "{order_book_type:?}");
+        }
+
+        let (shrunk, violation) = shrink_journal_with(OrderBookType::HashMap, &events, traded_above_bid).unwrap();
+        let ids: Vec<u64> = shrunk.iter()
+            .map(|event| match event {
+                BookEvent::Accepted { order, .. } => order.id,
+                _ => unreachable!("only commands are kept"),
+            })
+            .collect();
+        assert_eq!(ids, vec![5, 11, 12]);
+        assert_eq!((violation.step, violation.invariant), (2, "traded_above_bid"));
+    }
+
+    #[test]
+    fn test_shrink_journal_keeps_the_failing_invariant() {
+        // Stand-in bug: the check fails once more than 2 bid units rest above 100.0
+        let bids_above = |router: &OrderRouter| -> Result<(), (&'static str, String)> {
+            let quantity: u64 = router.snapshot_books().iter()
+                .flat_map(|book| &book.orders)
+                .filter(|order| order.order_type == OrderSide::Buy && order.price > 100_000)
+                .map(|order| order.quantity)
+                .sum();
+            if quantity > 2 { Err(("high_bids", format!("{quantity} units bid above 100"))) } else { Ok(()) }
+        };
+        let mut events: Vec<BookEvent> = (1..=40)
+            .map(|id| {
+                let side = if id % 2 == 0 { OrderSide::Buy } else { OrderSide::Sell };
+                let price = if id == 12 || id == 30 { 101.0 } else { 90.0 + (id % 7) as f64 + if side == OrderSide::Sell { 20.0 } else { 0.0 } };
+                BookEvent::Accepted { order: new_order(id, 0, 2, price, side), sequence: id }
+            })
+            .collect();
+        events.insert(20, BookEvent::Unknown);
+
+        let (shrunk, violation) = shrink_journal_with(OrderBookType::HashMap, &events, bids_above).unwrap();
+        let ids: Vec<u64> = shrunk.iter()
+            .map(|event| match event {
+                BookEvent::Accepted { order, .. } => order.id,
+                _ => unreachable!("only commands are kept"),
+            })
+            .collect();
+        assert_eq!(ids, vec![12, 30]);
+        assert_eq!((violation.step, violation.invariant), (1, "high_bids"));
+    }
+}
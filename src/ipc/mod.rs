@@ -0,0 +1,3 @@
+pub mod shm_ring;
+
+pub use shm_ring::{ShmOrderClient, ShmOrderIntake};
\ No newline at end of file
@@ -0,0 +1,217 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use memmap2::MmapMut;
+
+use crate::router::OrderRouter;
+use crate::types::order::{Order, ORDER_WIRE_SIZE, decode_order, encode_order};
+
+const RING_MAGIC: u64 = 0x4f52_4442_4b52_4e47;
+
+// Header: magic + capacity on the first cache line, then head and tail on their own lines
+const MAGIC_OFFSET: usize = 0;
+const CAPACITY_OFFSET: usize = 8;
+const HEAD_OFFSET: usize = 64;
+const TAIL_OFFSET: usize = 128;
+const HEADER_SIZE: usize = 192;
+
+struct ShmRing {
+    mmap: MmapMut,
+    capacity: u64,
+}
+
+impl ShmRing {
+    fn create(path: &Path, capacity: usize) -> io::Result<Self> {
+        if capacity == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "ring capacity must be non-zero"));
+        }
+
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len((HEADER_SIZE + capacity * ORDER_WIRE_SIZE) as u64)?;
+
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        mmap[MAGIC_OFFSET..MAGIC_OFFSET + 8].copy_from_slice(&RING_MAGIC.to_le_bytes());
+        mmap[CAPACITY_OFFSET..CAPACITY_OFFSET + 8].copy_from_slice(&(capacity as u64).to_le_bytes());
+        mmap.flush()?;
+
+        Ok(Self { mmap, capacity: capacity as u64 })
+    }
+
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        Self::validate(&file, mmap)
+    }
+
+    fn validate(file: &File, mmap: MmapMut) -> io::Result<Self> {
+        let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        if mmap.len() < HEADER_SIZE {
+            return Err(invalid("file too small for ring header"));
+        }
+        let magic = u64::from_le_bytes(mmap[MAGIC_OFFSET..MAGIC_OFFSET + 8].try_into().unwrap());
+        if magic != RING_MAGIC {
+            return Err(invalid("not an order ring file"));
+        }
+        let capacity = u64::from_le_bytes(mmap[CAPACITY_OFFSET..CAPACITY_OFFSET + 8].try_into().unwrap());
+        let expected_len = HEADER_SIZE as u64 + capacity * ORDER_WIRE_SIZE as u64;
+        if capacity == 0 || file.metadata()?.len() < expected_len {
+            return Err(invalid("ring capacity does not match file size"));
+        }
+
+        Ok(Self { mmap, capacity })
+    }
+
+    #[inline(always)]
+    fn index(&self, offset: usize) -> &AtomicU64 {
+        // mmap is page aligned and the header offsets are multiples of 8
+        unsafe { &*(self.mmap.as_ptr().add(offset) as *const AtomicU64) }
+    }
+
+    #[inline(always)]
+    fn slot_offset(&self, position: u64) -> usize {
+        HEADER_SIZE + (position % self.capacity) as usize * ORDER_WIRE_SIZE
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        let head = self.index(HEAD_OFFSET).load(Ordering::Acquire);
+        let tail = self.index(TAIL_OFFSET).load(Ordering::Acquire);
+        head.wrapping_sub(tail) as usize
+    }
+}
+
+pub struct ShmOrderIntake {
+    ring: ShmRing,
+}
+
+impl ShmOrderIntake {
+    pub fn create(path: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+        Ok(Self { ring: ShmRing::create(path.as_ref(), capacity)? })
+    }
+
+    #[inline(always)]
+    pub fn try_recv(&mut self) -> Option<Order> {
+        loop {
+            let tail = self.ring.index(TAIL_OFFSET).load(Ordering::Relaxed);
+            let head = self.ring.index(HEAD_OFFSET).load(Ordering::Acquire);
+            if tail == head {
+                return None;
+            }
+
+            let offset = self.ring.slot_offset(tail);
+            let order = decode_order(&self.ring.mmap[offset..offset + ORDER_WIRE_SIZE]).ok();
+            self.ring.index(TAIL_OFFSET).store(tail + 1, Ordering::Release);
+
+            // Malformed records are dropped rather than stalling the ring
+            if order.is_some() {
+                return order;
+            }
+        }
+    }
+
+    pub fn drain_into(&mut self, router: &mut OrderRouter) -> usize {
+        let mut routed = 0;
+        while let Some(order) = self.try_recv() {
+            if router.route_order(order).is_ok() {
+                routed += 1;
+            }
+        }
+        routed
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity as usize
+    }
+}
+
+pub struct ShmOrderClient {
+    ring: ShmRing,
+}
+
+impl ShmOrderClient {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self { ring: ShmRing::open(path.as_ref())? })
+    }
+
+    /// Returns false when the ring is full; the order is not written.
+    #[inline(always)]
+    pub fn try_send(&mut self, order: &Order) -> bool {
+        let head = self.ring.index(HEAD_OFFSET).load(Ordering::Relaxed);
+        let tail = self.ring.index(TAIL_OFFSET).load(Ordering::Acquire);
+        if head.wrapping_sub(tail) >= self.ring.capacity {
+            return false;
+        }
+
+        let offset = self.ring.slot_offset(head);
+        self.ring.mmap[offset..offset + ORDER_WIRE_SIZE].copy_from_slice(&encode_order(order));
+        self.ring.index(HEAD_OFFSET).store(head + 1, Ordering::Release);
+        true
+    }
+
+    pub fn send(&mut self, order: &Order) {
+        while !self.try_send(order) {
+            std::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::OrderBookType;
+    use crate::types::order::{new_order, OrderSide};
+    use rustc_hash::FxHashSet;
+
+    fn ring_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rust-order-book-{name}-{}.ring", std::process::id()))
+    }
+
+    #[test]
+    fn test_client_to_intake_round_trip() {
+        let path = ring_path("round-trip");
+        let mut intake = ShmOrderIntake::create(&path, 2).unwrap();
+        let mut client = ShmOrderClient::open(&path).unwrap();
+
+        let order = new_order(7, 1, 250, 101.25, OrderSide::Sell);
+        assert!(client.try_send(&order));
+        assert!(client.try_send(&order));
+        assert!(!client.try_send(&order));
+        assert_eq!(intake.len(), 2);
+
+        let received = intake.try_recv().unwrap();
+        assert_eq!((received.id, received.symbol, received.quantity, received.price), (7, 1, 250, 101250));
+        assert_eq!(received.order_type, OrderSide::Sell);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_drain_into_router() {
+        let path = ring_path("drain");
+        let mut intake = ShmOrderIntake::create(&path, 8).unwrap();
+        let mut client = ShmOrderClient::open(&path).unwrap();
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+
+        client.send(&new_order(1, 0, 100, 150.0, OrderSide::Buy));
+        client.send(&new_order(2, 5, 100, 150.0, OrderSide::Buy));
+
+        assert_eq!(intake.drain_into(&mut router), 1);
+        assert!(intake.is_empty());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
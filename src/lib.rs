@@ -1,3 +1,16 @@
 pub mod types;
 pub mod engine;
-pub mod router;
\ No newline at end of file
+pub mod router;
+pub mod snapshot;
+pub mod market_data;
+pub mod storage;
+pub mod cli;
+pub mod events;
+pub mod paper;
+#[cfg(unix)]
+pub mod admin;
+#[cfg(feature = "shm")]
+pub mod ipc;
+#[cfg(feature = "napi")]
+pub mod node;
+mod rng;
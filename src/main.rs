@@ -1,5 +1,7 @@
 use rust_order_book::{
+    cli::stdin_stream,
     engine::OrderBookType,
+    events,
     types::{order::{new_order, OrderSide}, symbol_mapping::SymbolId},
     router::OrderRouter,
 };
@@ -8,7 +10,76 @@ use rustc_hash::FxHashSet;
 const APPLE_SYMBOL: SymbolId = 0;
 const GOOGLE_SYMBOL: SymbolId = 1;
 
+const USAGE: &str = "usage: rust-order-book [--stdin [--book hashmap|priorityqueue|arrayqueue|persistent|fixedcapacity] [--symbols 0,1,..] | --shrink <journal> [--book ..]]";
+
+/// `--stdin`: applies newline-delimited commands from stdin and writes acks and match
+/// trades to stdout as JSON lines, so the engine can sit in a shell pipeline.
+fn run_stdin(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut order_book_type = OrderBookType::HashMap;
+    let mut symbols = FxHashSet::from_iter([APPLE_SYMBOL, GOOGLE_SYMBOL]);
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or_else(|| USAGE.to_string())?;
+        match arg.as_str() {
+            "--book" => order_book_type = OrderBookType::from_name(&value).ok_or_else(|| format!("unknown book '{value}'"))?,
+            "--symbols" => {
+                symbols = value.split(',')
+                    .map(|symbol| symbol.trim().parse::<SymbolId>().map_err(|_| format!("invalid symbol '{symbol}'")))
+                    .collect::<Result<_, _>>()?;
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let mut router = OrderRouter::new_direct(symbols, order_book_type);
+    let stdin = std::io::stdin();
+    stdin_stream::run(&mut router, stdin.lock(), std::io::stdout().lock()).map_err(|error| error.to_string())
+}
+
+/// `--shrink <journal>`: replays a journal and, if it breaks a router invariant, prints
+/// the smallest sub-journal that still breaks it, one current-version event per line.
+fn run_shrink(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let path = args.next().ok_or_else(|| USAGE.to_string())?;
+    let order_book_type = match (args.next().as_deref(), args.next()) {
+        (None, _) => OrderBookType::HashMap,
+        (Some("--book"), Some(value)) => OrderBookType::from_name(&value).ok_or_else(|| format!("unknown book '{value}'"))?,
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let journal = std::fs::read_to_string(&path).map_err(|error| format!("{path}: {error}"))?;
+    let journal = events::decode_journal(&journal).map_err(|(line, error)| format!("{path}:{line}: {error:?}"))?;
+    let (shrunk, violation) = events::shrink_journal(order_book_type, &journal)
+        .ok_or_else(|| format!("{path} breaks no invariant on the {order_book_type} book"))?;
+    for event in &shrunk {
+        println!("{}", events::encode(event, events::CURRENT_VERSION).map_err(|error| format!("{error:?}"))?);
+    }
+    eprintln!("{} of {} events: {violation}", shrunk.len(), journal.len());
+    Ok(())
+}
+
 fn main() {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("--stdin") => {
+            if let Err(error) = run_stdin(args) {
+                eprintln!("{error}");
+                std::process::exit(2);
+            }
+            return;
+        }
+        Some("--shrink") => {
+            if let Err(error) = run_shrink(args) {
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(_) => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+        None => {}
+    }
+
     let symbols = FxHashSet::from_iter([APPLE_SYMBOL, GOOGLE_SYMBOL]);
     let mut router = OrderRouter::new_direct(symbols.clone(), OrderBookType::HashMap);
     
@@ -24,4 +95,4 @@ fn main() {
     }
     
     router.match_all_orders();
-}
\ No newline at end of file
+}
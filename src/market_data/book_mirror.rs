@@ -0,0 +1,202 @@
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+
+use crossbeam::channel::{self, Receiver, Sender};
+use rustc_hash::FxHashMap;
+
+use crate::market_data::IndicativeOpen;
+use crate::types::symbol_mapping::SymbolId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MirrorUpdate {
+    pub symbol: SymbolId,
+    pub sequence: u64,
+    pub best_bid: Option<u64>,
+    pub best_ask: Option<u64>,
+}
+
+/// Pre-open equilibrium for a symbol; `indicative: None` clears it once the book opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndicativeUpdate {
+    pub symbol: SymbolId,
+    pub sequence: u64,
+    pub indicative: Option<IndicativeOpen>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SymbolView {
+    pub best_bid: Option<u64>,
+    pub best_ask: Option<u64>,
+    pub indicative: Option<IndicativeOpen>,
+    pub last_sequence: u64,
+    pub updates: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MirrorMessage {
+    TopOfBook(MirrorUpdate),
+    Indicative(IndicativeUpdate),
+}
+
+impl MirrorMessage {
+    #[inline(always)]
+    fn symbol(&self) -> SymbolId {
+        match self {
+            MirrorMessage::TopOfBook(update) => update.symbol,
+            MirrorMessage::Indicative(update) => update.symbol,
+        }
+    }
+}
+
+type MirrorState = Arc<RwLock<FxHashMap<SymbolId, SymbolView>>>;
+
+/// Matching-side handle. Publishing never blocks or takes the mirror's lock.
+#[derive(Clone)]
+pub struct MirrorPublisher {
+    sender: Sender<MirrorMessage>,
+}
+
+impl MirrorPublisher {
+    #[inline(always)]
+    pub fn publish(&self, update: MirrorUpdate) {
+        let _ = self.sender.send(MirrorMessage::TopOfBook(update));
+    }
+
+    #[inline(always)]
+    pub fn publish_indicative(&self, update: IndicativeUpdate) {
+        let _ = self.sender.send(MirrorMessage::Indicative(update));
+    }
+}
+
+/// Query-side handle, cheap to clone into REST handlers or dashboard threads.
+#[derive(Clone)]
+pub struct MirrorReader {
+    state: MirrorState,
+}
+
+impl MirrorReader {
+    pub fn best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
+        self.view(symbol).map(|view| (view.best_bid, view.best_ask))
+    }
+
+    pub fn view(&self, symbol: SymbolId) -> Option<SymbolView> {
+        self.state.read().ok()?.get(&symbol).copied()
+    }
+
+    pub fn symbols(&self) -> Vec<SymbolId> {
+        self.state.read().map(|state| state.keys().copied().collect()).unwrap_or_default()
+    }
+}
+
+/// Read-optimised copy of top-of-book state, maintained on its own thread from
+/// updates published by the matching side.
+pub struct BookMirror {
+    reader: MirrorReader,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl BookMirror {
+    pub fn start() -> (Self, MirrorPublisher) {
+        let (sender, receiver) = channel::unbounded();
+        let state: MirrorState = Arc::default();
+        let mirror_state = state.clone();
+
+        let handle = thread::Builder::new()
+            .name("order-book-mirror".into())
+            .spawn(move || run_mirror(receiver, mirror_state))
+            .expect("failed to spawn mirror thread");
+
+        let mirror = Self {
+            reader: MirrorReader { state },
+            handle: Some(handle),
+        };
+        (mirror, MirrorPublisher { sender })
+    }
+
+    pub fn reader(&self) -> MirrorReader {
+        self.reader.clone()
+    }
+
+    /// Waits for every publisher to be dropped and the remaining updates to be applied.
+    pub fn join(mut self) -> MirrorReader {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        self.reader.clone()
+    }
+}
+
+fn run_mirror(receiver: Receiver<MirrorMessage>, state: MirrorState) {
+    while let Ok(first) = receiver.recv() {
+        // Apply everything already queued under one write lock
+        let Ok(mut views) = state.write() else { return };
+        for message in std::iter::once(first).chain(receiver.try_iter()) {
+            let view = views.entry(message.symbol()).or_default();
+            match message {
+                MirrorMessage::TopOfBook(update) if update.sequence >= view.last_sequence => {
+                    view.best_bid = update.best_bid;
+                    view.best_ask = update.best_ask;
+                    view.last_sequence = update.sequence;
+                }
+                MirrorMessage::Indicative(update) if update.sequence >= view.last_sequence => {
+                    view.indicative = update.indicative;
+                    view.last_sequence = update.sequence;
+                }
+                _ => {}
+            }
+            view.updates += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::OrderBookType;
+    use crate::router::OrderRouter;
+    use crate::types::order::{new_order, price_to_u64, OrderSide};
+    use crate::types::trade;
+    use rustc_hash::FxHashSet;
+
+    #[test]
+    fn test_mirror_tracks_router_best_prices() {
+        let (mirror, publisher) = BookMirror::start();
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.attach_mirror(publisher);
+
+        router.route_order(new_order(1, 0, 100, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 100, 101.0, OrderSide::Sell)).unwrap();
+        router.route_order(new_order(3, 1, 100, 50.0, OrderSide::Sell)).unwrap();
+        drop(router);
+
+        let reader = mirror.join();
+        assert_eq!(reader.best_prices(0), Some((Some(price_to_u64(99.0)), Some(price_to_u64(101.0)))));
+        assert_eq!(reader.best_prices(1), Some((None, Some(price_to_u64(50.0)))));
+        assert_eq!(reader.view(0).unwrap().last_sequence, 2);
+    }
+
+    #[test]
+    fn test_mirror_streams_indicative_open_during_pre_open() {
+        let (mirror, publisher) = BookMirror::start();
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.attach_mirror(publisher);
+        router.roll_session();
+
+        router.route_order(new_order(1, 0, 100, 101.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 60, 100.0, OrderSide::Sell)).unwrap();
+        assert_eq!(router.indicative_open(0).map(|open| open.surplus), Some(40));
+
+        let reader = mirror.reader();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while reader.view(0).and_then(|view| view.indicative).is_none() && std::time::Instant::now() < deadline {
+            std::thread::yield_now();
+        }
+        let indicative = reader.view(0).unwrap().indicative.unwrap();
+        assert_eq!(indicative.price, trade::midpoint(price_to_u64(100.0), price_to_u64(101.0)));
+        assert_eq!(indicative.matched_quantity, 60);
+
+        router.open_session().unwrap();
+        drop(router);
+        assert_eq!(mirror.join().view(0).unwrap().indicative, None);
+    }
+}
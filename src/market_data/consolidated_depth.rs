@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::OrderBookMarketData;
+use crate::types::order::OrderSide;
+use crate::types::symbol_mapping::SymbolId;
+
+pub type VenueId = u16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenueQuantity {
+    pub venue: VenueId,
+    pub quantity: u64,
+    pub order_count: usize,
+}
+
+/// One price across every venue quoting it, with each venue's share.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsolidatedLevel {
+    pub price: u64,
+    pub total_quantity: u64,
+    pub order_count: usize,
+    /// In the order the venues were given.
+    pub venues: Vec<VenueQuantity>,
+}
+
+/// Merges one side of `symbol` across venue books, best price first, keeping at most
+/// `levels` prices. Venues that do not list the symbol are skipped.
+pub fn consolidated_depth<B: OrderBookMarketData + ?Sized>(
+    venues: &[(VenueId, &B)],
+    symbol: SymbolId,
+    side: OrderSide,
+    levels: usize,
+) -> Vec<ConsolidatedLevel> {
+    let mut merged: BTreeMap<u64, ConsolidatedLevel> = BTreeMap::new();
+    for &(venue, book) in venues {
+        let Some(book_levels) = book.price_levels(symbol, side, false) else { continue };
+        // Each book is best first, so its levels past the `levels` best are never needed
+        for level in book_levels.take(levels) {
+            let consolidated = merged.entry(level.price).or_insert_with(|| ConsolidatedLevel {
+                price: level.price,
+                total_quantity: 0,
+                order_count: 0,
+                venues: Vec::new(),
+            });
+            consolidated.total_quantity += level.total_quantity;
+            consolidated.order_count += level.order_count;
+            consolidated.venues.push(VenueQuantity {
+                venue,
+                quantity: level.total_quantity,
+                order_count: level.order_count,
+            });
+        }
+    }
+
+    let merged = merged.into_values();
+    match side {
+        OrderSide::Buy => merged.rev().take(levels).collect(),
+        OrderSide::Sell => merged.take(levels).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashSet;
+
+    use crate::engine::{OrderBookCore, OrderBookType, create_any_order_book};
+    use crate::types::order::{new_order, price_to_u64};
+
+    #[test]
+    fn test_consolidated_depth_attributes_venues() {
+        let mut primary = create_any_order_book(OrderBookType::HashMap, FxHashSet::from_iter([0]));
+        let mut secondary = create_any_order_book(OrderBookType::PriorityQueue, FxHashSet::from_iter([0]));
+        primary.add_order_fast(new_order(1, 0, 100, 100.0, OrderSide::Buy));
+        primary.add_order_fast(new_order(2, 0, 50, 99.0, OrderSide::Buy));
+        secondary.add_order_fast(new_order(3, 0, 30, 100.0, OrderSide::Buy));
+        secondary.add_order_fast(new_order(4, 0, 20, 101.0, OrderSide::Buy));
+
+        let depth = consolidated_depth(&[(1, &primary), (2, &secondary)], 0, OrderSide::Buy, 2);
+        assert_eq!(depth.iter().map(|level| level.price).collect::<Vec<_>>(), vec![price_to_u64(101.0), price_to_u64(100.0)]);
+        assert_eq!(depth[1].total_quantity, 130);
+        assert_eq!(depth[1].venues, vec![
+            VenueQuantity { venue: 1, quantity: 100, order_count: 1 },
+            VenueQuantity { venue: 2, quantity: 30, order_count: 1 },
+        ]);
+        assert!(consolidated_depth(&[(1, &primary)], 9, OrderSide::Sell, 5).is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::order::{Order, OrderSide};
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthMetricsConfig {
+    /// Width in price units of each distance-from-mid bucket.
+    pub bucket_width: u64,
+    /// Orders further out than the last bucket are counted in it.
+    pub buckets: usize,
+}
+
+impl Default for DepthMetricsConfig {
+    fn default() -> Self {
+        Self { bucket_width: 100, buckets: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SideDepthMetrics {
+    pub levels: usize,
+    pub orders: usize,
+    pub quantity: u64,
+    pub max_orders_per_level: usize,
+    /// Resting quantity per distance-from-mid bucket, nearest first.
+    pub quantity_by_distance: Vec<u64>,
+}
+
+impl SideDepthMetrics {
+    #[inline(always)]
+    pub fn mean_orders_per_level(&self) -> f64 {
+        if self.levels == 0 {
+            0.0
+        } else {
+            self.orders as f64 / self.levels as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthMetrics {
+    pub symbol: SymbolId,
+    /// Reference price for the distance buckets: the mid when both sides are
+    /// present, otherwise the best price of the side that is.
+    pub reference_price: Option<u64>,
+    pub bids: SideDepthMetrics,
+    pub asks: SideDepthMetrics,
+}
+
+impl DepthMetrics {
+    pub fn from_orders(symbol: SymbolId, orders: &[Order], config: DepthMetricsConfig) -> Self {
+        let mut bid_levels: BTreeMap<u64, (usize, u64)> = BTreeMap::new();
+        let mut ask_levels: BTreeMap<u64, (usize, u64)> = BTreeMap::new();
+        for order in orders {
+            let levels = match order.order_type {
+                OrderSide::Buy => &mut bid_levels,
+                OrderSide::Sell => &mut ask_levels,
+            };
+            let level = levels.entry(order.price).or_default();
+            level.0 += 1;
+            level.1 += order.quantity;
+        }
+
+        let best_bid = bid_levels.keys().next_back().copied();
+        let best_ask = ask_levels.keys().next().copied();
+        let reference_price = match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) => Some(trade::midpoint(bid, ask)),
+            (bid, ask) => bid.or(ask),
+        };
+
+        Self {
+            symbol,
+            reference_price,
+            bids: side_metrics(&bid_levels, reference_price, config),
+            asks: side_metrics(&ask_levels, reference_price, config),
+        }
+    }
+}
+
+fn side_metrics(
+    levels: &BTreeMap<u64, (usize, u64)>,
+    reference_price: Option<u64>,
+    config: DepthMetricsConfig,
+) -> SideDepthMetrics {
+    let mut metrics = SideDepthMetrics {
+        levels: levels.len(),
+        quantity_by_distance: vec![0; config.buckets],
+        ..Default::default()
+    };
+
+    for (&price, &(orders, quantity)) in levels {
+        metrics.orders += orders;
+        metrics.quantity += quantity;
+        metrics.max_orders_per_level = metrics.max_orders_per_level.max(orders);
+
+        if let (Some(reference), Some(last_bucket)) = (reference_price, config.buckets.checked_sub(1)) {
+            let distance = price.abs_diff(reference) / config.bucket_width.max(1);
+            let bucket = usize::try_from(distance).unwrap_or(usize::MAX).min(last_bucket);
+            metrics.quantity_by_distance[bucket] += quantity;
+        }
+    }
+
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    fn test_depth_metrics_shape() {
+        let orders = vec![
+            new_order(1, 0, 100, 99.9, OrderSide::Buy),
+            new_order(2, 0, 50, 99.9, OrderSide::Buy),
+            new_order(3, 0, 10, 99.0, OrderSide::Buy),
+            new_order(4, 0, 70, 100.1, OrderSide::Sell),
+        ];
+        let metrics = DepthMetrics::from_orders(0, &orders, DepthMetricsConfig { bucket_width: 500, buckets: 2 });
+
+        assert_eq!(metrics.reference_price, Some(100_000));
+        assert_eq!(metrics.bids.levels, 2);
+        assert_eq!(metrics.bids.orders, 3);
+        assert_eq!(metrics.bids.max_orders_per_level, 2);
+        assert_eq!(metrics.bids.mean_orders_per_level(), 1.5);
+        assert_eq!(metrics.bids.quantity_by_distance, vec![150, 10]);
+        assert_eq!(metrics.asks.quantity_by_distance, vec![70, 0]);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeatMapConfig {
+    /// Length of each time slice, in the units of the timestamps passed to `record`.
+    pub slice_width: u64,
+    /// Width in price units of each price bucket.
+    pub bucket_width: u64,
+}
+
+impl Default for HeatMapConfig {
+    fn default() -> Self {
+        Self { slice_width: 1_000_000_000, bucket_width: 100 }
+    }
+}
+
+/// Executed volume of one symbol as a dense matrix, one row per time slice and one
+/// column per price bucket, both ascending and spanning every slice and bucket between
+/// the first and last that traded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeatMap {
+    pub symbol: SymbolId,
+    /// Start of each row's slice.
+    pub slice_starts: Vec<u64>,
+    /// Lowest price of each column's bucket.
+    pub price_buckets: Vec<u64>,
+    pub volume: Vec<Vec<u64>>,
+}
+
+impl HeatMap {
+    /// A header row of bucket prices, then one row per slice starting with its start.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("slice_start");
+        for price in &self.price_buckets {
+            let _ = write!(csv, ",{price}");
+        }
+        csv.push('\n');
+        for (start, row) in self.slice_starts.iter().zip(&self.volume) {
+            let _ = write!(csv, "{start}");
+            for volume in row {
+                let _ = write!(csv, ",{volume}");
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Buckets executed volume by (time slice, price bucket) per symbol, from the trades
+/// `OrderRouter::match_all_orders` returns. The caller supplies the time, so
+/// simulations can record against their own clock.
+#[derive(Debug, Clone, Default)]
+pub struct HeatMapRecorder {
+    config: HeatMapConfig,
+    cells: FxHashMap<SymbolId, BTreeMap<(u64, u64), u64>>,
+}
+
+impl HeatMapRecorder {
+    /// Widths of 0 are treated as 1.
+    pub fn new(config: HeatMapConfig) -> Self {
+        let config = HeatMapConfig { slice_width: config.slice_width.max(1), bucket_width: config.bucket_width.max(1) };
+        Self { config, cells: FxHashMap::default() }
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> HeatMapConfig {
+        self.config
+    }
+
+    /// Adds every trade's quantity to the cell of `timestamp`'s slice and its price's bucket.
+    pub fn record(&mut self, timestamp: u64, trades: &[Trade]) {
+        let slice = timestamp / self.config.slice_width;
+        for trade in trades {
+            let bucket = trade.price / self.config.bucket_width;
+            *self.cells.entry(trade.symbol).or_default().entry((slice, bucket)).or_default() += trade.quantity;
+        }
+    }
+
+    /// Symbols with recorded volume, ascending.
+    pub fn symbols(&self) -> Vec<SymbolId> {
+        let mut symbols: Vec<SymbolId> = self.cells.keys().copied().collect();
+        symbols.sort_unstable();
+        symbols
+    }
+
+    /// The symbol's matrix, or `None` when it has not traded.
+    pub fn heat_map(&self, symbol: SymbolId) -> Option<HeatMap> {
+        let cells = self.cells.get(&symbol)?;
+        let (first_slice, _) = *cells.keys().next()?;
+        let (last_slice, _) = *cells.keys().next_back()?;
+        let first_bucket = cells.keys().map(|&(_, bucket)| bucket).min()?;
+        let last_bucket = cells.keys().map(|&(_, bucket)| bucket).max()?;
+
+        let columns = (last_bucket - first_bucket + 1) as usize;
+        let mut volume = vec![vec![0; columns]; (last_slice - first_slice + 1) as usize];
+        for (&(slice, bucket), &quantity) in cells {
+            volume[(slice - first_slice) as usize][(bucket - first_bucket) as usize] = quantity;
+        }
+        Some(HeatMap {
+            symbol,
+            slice_starts: (first_slice..=last_slice).map(|slice| slice * self.config.slice_width).collect(),
+            price_buckets: (first_bucket..=last_bucket).map(|bucket| bucket * self.config.bucket_width).collect(),
+            volume,
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::OrderSide;
+
+    fn trade(symbol: SymbolId, price: u64, quantity: u64) -> Trade {
+        Trade { symbol, taker_order_id: 2, maker_order_id: 1, price, quantity, side: OrderSide::Buy }
+    }
+
+    #[test]
+    fn test_buckets_volume_by_slice_and_price() {
+        let mut recorder = HeatMapRecorder::new(HeatMapConfig { slice_width: 10, bucket_width: 100 });
+        recorder.record(3, &[trade(0, 100_050, 5), trade(0, 100_099, 2), trade(1, 5, 1)]);
+        recorder.record(27, &[trade(0, 100_210, 4)]);
+        recorder.record(29, &[trade(0, 100_000, 1)]);
+
+        assert_eq!(recorder.symbols(), vec![0, 1]);
+        let heat_map = recorder.heat_map(0).unwrap();
+        assert_eq!(heat_map.slice_starts, vec![0, 10, 20]);
+        assert_eq!(heat_map.price_buckets, vec![100_000, 100_100, 100_200]);
+        assert_eq!(heat_map.volume, vec![vec![7, 0, 0], vec![0, 0, 0], vec![1, 0, 4]]);
+        assert_eq!(heat_map.to_csv(), "slice_start,100000,100100,100200\n0,7,0,0\n10,0,0,0\n20,1,0,4\n");
+        assert!(recorder.heat_map(2).is_none());
+
+        recorder.clear();
+        assert!(recorder.heat_map(0).is_none());
+    }
+}
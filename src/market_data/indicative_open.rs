@@ -0,0 +1,105 @@
+use crate::types::order::{Order, OrderSide};
+use crate::types::trade;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct IndicativeOpen {
+    pub price: u64,
+    pub matched_quantity: u64,
+    /// Quantity left unmatched at `price` on the heavier side.
+    pub surplus: u64,
+    pub surplus_side: Option<OrderSide>,
+}
+
+/// Equilibrium price an uncross would use right now: the price maximising executable
+/// volume, then minimising surplus, with remaining ties resolved to the middle of the
+/// tied range. `None` when the book is not crossed.
+pub fn indicative_open<'a>(orders: impl IntoIterator<Item = &'a Order>) -> Option<IndicativeOpen> {
+    let mut bids = Vec::new();
+    let mut asks = Vec::new();
+    for order in orders {
+        match order.order_type {
+            OrderSide::Buy => bids.push((order.price, order.quantity)),
+            OrderSide::Sell => asks.push((order.price, order.quantity)),
+        }
+    }
+    bids.sort_unstable_by_key(|&(price, _)| std::cmp::Reverse(price));
+    asks.sort_unstable_by_key(|&(price, _)| price);
+
+    let mut candidates: Vec<u64> = bids.iter().chain(&asks).map(|&(price, _)| price).collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    // Candidates ascend, so demand at or above the price shrinks while supply grows
+    let total_demand: u64 = bids.iter().map(|&(_, quantity)| quantity).sum();
+    let mut demand = total_demand;
+    let mut supply = 0;
+    let mut bid_index = bids.len();
+    let mut ask_index = 0;
+
+    let mut best: Option<(u64, u64, u64, u64)> = None;
+    let mut best_range = (0, 0);
+    for price in candidates {
+        while bid_index > 0 && bids[bid_index - 1].0 < price {
+            bid_index -= 1;
+            demand -= bids[bid_index].1;
+        }
+        while ask_index < asks.len() && asks[ask_index].0 <= price {
+            supply += asks[ask_index].1;
+            ask_index += 1;
+        }
+
+        let matched = demand.min(supply);
+        if matched == 0 {
+            continue;
+        }
+        let surplus = demand.abs_diff(supply);
+        match best {
+            Some((_, best_matched, best_surplus, _))
+                if (matched, std::cmp::Reverse(surplus)) < (best_matched, std::cmp::Reverse(best_surplus)) => {}
+            Some((_, best_matched, best_surplus, _)) if matched == best_matched && surplus == best_surplus => {
+                best_range.1 = price;
+            }
+            _ => {
+                best = Some((price, matched, surplus, demand));
+                best_range = (price, price);
+            }
+        }
+    }
+
+    let (_, matched_quantity, surplus, demand) = best?;
+    let surplus_side = match (surplus, demand > matched_quantity) {
+        (0, _) => None,
+        (_, true) => Some(OrderSide::Buy),
+        (_, false) => Some(OrderSide::Sell),
+    };
+    Some(IndicativeOpen {
+        price: trade::midpoint(best_range.0, best_range.1),
+        matched_quantity,
+        surplus,
+        surplus_side,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, price_to_u64};
+
+    #[test]
+    fn test_indicative_open_maximises_volume() {
+        let orders = [
+            new_order(1, 0, 300, 101.0, OrderSide::Buy),
+            new_order(2, 0, 200, 100.0, OrderSide::Buy),
+            new_order(3, 0, 100, 99.0, OrderSide::Sell),
+            new_order(4, 0, 250, 100.0, OrderSide::Sell),
+            new_order(5, 0, 500, 102.0, OrderSide::Sell),
+        ];
+        let open = indicative_open(&orders).unwrap();
+
+        assert_eq!(open.price, price_to_u64(100.0));
+        assert_eq!(open.matched_quantity, 350);
+        assert_eq!(open.surplus, 150);
+        assert_eq!(open.surplus_side, Some(OrderSide::Buy));
+        assert_eq!(indicative_open(&orders[..2]), None);
+    }
+}
@@ -0,0 +1,15 @@
+pub mod book_mirror;
+pub mod depth_metrics;
+pub mod heat_map;
+pub mod consolidated_depth;
+pub mod indicative_open;
+#[cfg(feature = "redis")]
+pub mod redis_bridge;
+
+pub use book_mirror::{BookMirror, IndicativeUpdate, MirrorPublisher, MirrorReader, MirrorUpdate, SymbolView};
+pub use consolidated_depth::{consolidated_depth, ConsolidatedLevel, VenueId, VenueQuantity};
+pub use depth_metrics::{DepthMetrics, DepthMetricsConfig, SideDepthMetrics};
+pub use heat_map::{HeatMap, HeatMapConfig, HeatMapRecorder};
+pub use indicative_open::{indicative_open, IndicativeOpen};
+#[cfg(feature = "redis")]
+pub use redis_bridge::{DepthLevels, RedisBridge, RedisBridgeConfig, RedisBridgeStats, RedisPublisher};
\ No newline at end of file
@@ -0,0 +1,332 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crossbeam::channel::{self, Receiver, Sender};
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+
+use crate::market_data::MirrorUpdate;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedisBridgeConfig {
+    /// `host:port` of the Redis server.
+    pub address: String,
+    /// Start of every channel and key name: `<prefix>:bbo:<symbol>`,
+    /// `<prefix>:trades:<symbol>` and `<prefix>:depth:<symbol>`.
+    pub prefix: String,
+    /// Keep `<prefix>:depth:<symbol>` set to this many levels per side. `None` skips
+    /// the depth keys.
+    pub depth_levels: Option<usize>,
+    pub connect_timeout: Duration,
+}
+
+impl Default for RedisBridgeConfig {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:6379".to_string(),
+            prefix: "book".to_string(),
+            depth_levels: None,
+            connect_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Best levels on each side as `[price, quantity]`, best first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DepthLevels {
+    pub symbol: SymbolId,
+    pub sequence: u64,
+    pub bids: Vec<[u64; 2]>,
+    pub asks: Vec<[u64; 2]>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedisBridgeStats {
+    /// Commands Redis accepted.
+    pub published: u64,
+    /// Commands lost because Redis was unreachable or refused them.
+    pub dropped: u64,
+    pub reconnects: u64,
+}
+
+#[derive(Debug, Clone)]
+enum BridgeMessage {
+    TopOfBook(MirrorUpdate),
+    Trade(Trade),
+    Depth(DepthLevels),
+}
+
+/// Matching-side handle. Publishing never blocks on Redis.
+#[derive(Clone)]
+pub struct RedisPublisher {
+    sender: Sender<BridgeMessage>,
+    depth_levels: Option<usize>,
+}
+
+impl RedisPublisher {
+    #[inline(always)]
+    pub fn publish_top_of_book(&self, update: MirrorUpdate) {
+        let _ = self.sender.send(BridgeMessage::TopOfBook(update));
+    }
+
+    pub fn publish_trades(&self, trades: &[Trade]) {
+        for trade in trades {
+            let _ = self.sender.send(BridgeMessage::Trade(*trade));
+        }
+    }
+
+    #[inline(always)]
+    pub fn publish_depth(&self, depth: DepthLevels) {
+        let _ = self.sender.send(BridgeMessage::Depth(depth));
+    }
+
+    /// Levels per side the bridge keeps in the depth keys, if any.
+    #[inline(always)]
+    pub fn depth_levels(&self) -> Option<usize> {
+        self.depth_levels
+    }
+}
+
+/// Publishes best bid/offer changes and trades to Redis channels, and optionally keeps
+/// the latest depth in Redis keys, from its own thread. Values are JSON.
+///
+/// Redis being down never stalls matching: commands are dropped while it is
+/// unreachable and the connection is retried on the next update.
+pub struct RedisBridge {
+    handle: Option<JoinHandle<RedisBridgeStats>>,
+}
+
+impl RedisBridge {
+    pub fn start(config: RedisBridgeConfig) -> (Self, RedisPublisher) {
+        let (sender, receiver) = channel::unbounded();
+        let depth_levels = config.depth_levels;
+        let handle = thread::Builder::new()
+            .name("order-book-redis".into())
+            .spawn(move || run_bridge(receiver, config))
+            .expect("failed to spawn redis bridge thread");
+        (Self { handle: Some(handle) }, RedisPublisher { sender, depth_levels })
+    }
+
+    /// Waits for every publisher to be dropped and the remaining updates to be sent.
+    pub fn join(mut self) -> RedisBridgeStats {
+        self.handle.take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+struct BestBidOffer {
+    symbol: SymbolId,
+    sequence: u64,
+    best_bid: Option<u64>,
+    best_ask: Option<u64>,
+}
+
+struct Connection {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Connection {
+    fn open(config: &RedisBridgeConfig) -> io::Result<Self> {
+        let address = config.address.to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing"))?;
+        let writer = TcpStream::connect_timeout(&address, config.connect_timeout)?;
+        writer.set_nodelay(true)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { writer, reader })
+    }
+
+    /// Sends pipelined commands and returns how many Redis accepted.
+    fn send(&mut self, commands: &[u8], count: usize) -> io::Result<u64> {
+        self.writer.write_all(commands)?;
+        let mut accepted = 0;
+        let mut line = String::new();
+        for _ in 0..count {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            // PUBLISH answers with an integer and SET with +OK; errors start with '-'
+            if !line.starts_with('-') {
+                accepted += 1;
+            }
+        }
+        Ok(accepted)
+    }
+}
+
+// RESP array of bulk strings, the form Redis expects every command in
+fn encode_command(out: &mut Vec<u8>, arguments: &[&[u8]]) {
+    out.extend_from_slice(format!("*{}\r\n", arguments.len()).as_bytes());
+    for argument in arguments {
+        out.extend_from_slice(format!("${}\r\n", argument.len()).as_bytes());
+        out.extend_from_slice(argument);
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+fn run_bridge(receiver: Receiver<BridgeMessage>, config: RedisBridgeConfig) -> RedisBridgeStats {
+    let mut stats = RedisBridgeStats::default();
+    let mut connection: Option<Connection> = None;
+    let mut connected_before = false;
+    let mut last_top_of_book: FxHashMap<SymbolId, (Option<u64>, Option<u64>)> = FxHashMap::default();
+    let mut commands = Vec::new();
+
+    while let Ok(first) = receiver.recv() {
+        // Pipeline everything already queued into one write
+        commands.clear();
+        let mut count = 0;
+        for message in std::iter::once(first).chain(receiver.try_iter()) {
+            let (command, name, payload) = match message {
+                BridgeMessage::TopOfBook(update) => {
+                    let top = (update.best_bid, update.best_ask);
+                    if last_top_of_book.insert(update.symbol, top) == Some(top) {
+                        continue;
+                    }
+                    let message = BestBidOffer {
+                        symbol: update.symbol,
+                        sequence: update.sequence,
+                        best_bid: update.best_bid,
+                        best_ask: update.best_ask,
+                    };
+                    ("PUBLISH", format!("{}:bbo:{}", config.prefix, update.symbol), serde_json::to_vec(&message))
+                }
+                BridgeMessage::Trade(trade) => {
+                    ("PUBLISH", format!("{}:trades:{}", config.prefix, trade.symbol), serde_json::to_vec(&trade))
+                }
+                BridgeMessage::Depth(depth) => {
+                    ("SET", format!("{}:depth:{}", config.prefix, depth.symbol), serde_json::to_vec(&depth))
+                }
+            };
+            let Ok(payload) = payload else {
+                stats.dropped += 1;
+                continue;
+            };
+            encode_command(&mut commands, &[command.as_bytes(), name.as_bytes(), &payload]);
+            count += 1;
+        }
+        if count == 0 {
+            continue;
+        }
+
+        if connection.is_none() {
+            match Connection::open(&config) {
+                Ok(opened) => {
+                    connection = Some(opened);
+                    stats.reconnects += connected_before as u64;
+                    connected_before = true;
+                }
+                Err(_) => {
+                    stats.dropped += count as u64;
+                    continue;
+                }
+            }
+        }
+        let sent = connection.as_mut().map(|connection| connection.send(&commands, count));
+        match sent {
+            Some(Ok(accepted)) => {
+                stats.published += accepted;
+                stats.dropped += count as u64 - accepted;
+            }
+            _ => {
+                connection = None;
+                stats.dropped += count as u64;
+            }
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use crate::engine::OrderBookType;
+    use crate::router::OrderRouter;
+    use crate::types::order::{new_order, price_to_u64, OrderSide};
+    use rustc_hash::FxHashSet;
+
+    // Accepts one connection, answers every command like Redis would and returns the
+    // commands once the bridge disconnects
+    fn fake_redis(listener: TcpListener) -> JoinHandle<Vec<Vec<String>>> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut commands = Vec::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                let count: usize = line.trim()[1..].parse().unwrap();
+                let mut arguments = Vec::new();
+                for _ in 0..count {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    let mut argument = vec![0; line.trim()[1..].parse::<usize>().unwrap() + 2];
+                    reader.read_exact(&mut argument).unwrap();
+                    argument.truncate(argument.len() - 2);
+                    arguments.push(String::from_utf8(argument).unwrap());
+                }
+                writer.write_all(if arguments[0] == "SET" { b"+OK\r\n" } else { b":1\r\n" }).unwrap();
+                commands.push(arguments);
+                line.clear();
+            }
+            commands
+        })
+    }
+
+    #[test]
+    fn test_bridge_publishes_bbo_changes_trades_and_depth() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = RedisBridgeConfig {
+            address: listener.local_addr().unwrap().to_string(),
+            depth_levels: Some(1),
+            ..RedisBridgeConfig::default()
+        };
+        let server = fake_redis(listener);
+        let (bridge, publisher) = RedisBridge::start(config);
+
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.attach_redis_bridge(publisher);
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 10, 98.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(3, 0, 4, 99.0, OrderSide::Sell)).unwrap();
+        router.match_all_orders();
+        drop(router);
+
+        let stats = bridge.join();
+        let commands = server.join().unwrap();
+        assert_eq!(stats, RedisBridgeStats { published: commands.len() as u64, dropped: 0, reconnects: 0 });
+
+        // Attaching publishes the empty book. The second bid leaves the touch alone, so it
+        // updates depth but not the BBO
+        let channels: Vec<&str> = commands.iter()
+            .filter(|command| command[0] == "PUBLISH")
+            .map(|command| command[1].as_str())
+            .collect();
+        assert_eq!(channels, vec!["book:bbo:0", "book:bbo:0", "book:bbo:0", "book:trades:0", "book:bbo:0"]);
+
+        let trade: Trade = serde_json::from_str(&commands.iter().find(|command| command[1] == "book:trades:0").unwrap()[2]).unwrap();
+        assert_eq!((trade.taker_order_id, trade.maker_order_id, trade.quantity), (3, 1, 4));
+        let depth = &commands.iter().rev().find(|command| command[0] == "SET").unwrap()[2];
+        assert_eq!(depth, &format!(r#"{{"symbol":0,"sequence":3,"bids":[[{},6]],"asks":[]}}"#, price_to_u64(99.0)));
+    }
+
+    #[test]
+    fn test_bridge_drops_updates_while_redis_is_down() {
+        let address = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+        let (bridge, publisher) = RedisBridge::start(RedisBridgeConfig { address, ..RedisBridgeConfig::default() });
+        publisher.publish_top_of_book(MirrorUpdate { symbol: 0, sequence: 1, best_bid: Some(1), best_ask: None });
+        drop(publisher);
+        assert_eq!(bridge.join(), RedisBridgeStats { published: 0, dropped: 1, reconnects: 0 });
+    }
+}
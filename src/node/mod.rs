@@ -0,0 +1,3 @@
+pub mod router;
+
+pub use router::{NodeDepth, NodeLevel, NodeOrder, NodeRouter, NodeTrade};
@@ -0,0 +1,222 @@
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction};
+use napi_derive::napi;
+use rustc_hash::FxHashSet;
+
+use crate::engine::OrderBookType;
+use crate::router::{OrderRouter, RejectCode};
+use crate::types::order::{Order, OrderKind, OrderSide, TimeInForce};
+use crate::types::price_scale::PriceScale;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// Ids and quantities cross into JavaScript as numbers, exact up to 2^53.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// An order as JavaScript submits it. Prices are decimal and scaled on the way in, at
+/// the symbol's price scale.
+#[napi(object)]
+pub struct NodeOrder {
+    pub id: i64,
+    pub symbol: u32,
+    pub quantity: i64,
+    pub price: f64,
+    /// `"buy"` or `"sell"`.
+    pub side: String,
+    /// `"day"` (the default), `"gtc"` or `"ioc"`.
+    pub time_in_force: Option<String>,
+    pub participant: Option<u32>,
+    /// Makes the order an iceberg showing at most this much at a time.
+    pub display_quantity: Option<i64>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct NodeTrade {
+    pub symbol: u32,
+    pub taker_order_id: i64,
+    pub maker_order_id: i64,
+    pub price: f64,
+    pub quantity: i64,
+    /// The taker's side, `"buy"` or `"sell"`.
+    pub side: String,
+}
+
+#[napi(object)]
+pub struct NodeLevel {
+    pub price: f64,
+    pub quantity: i64,
+    pub orders: u32,
+}
+
+/// Best-first levels on each side.
+#[napi(object)]
+pub struct NodeDepth {
+    pub bids: Vec<NodeLevel>,
+    pub asks: Vec<NodeLevel>,
+}
+
+fn integer(value: i64, field: &str) -> Result<u64> {
+    if (0..=MAX_SAFE_INTEGER).contains(&value) {
+        Ok(value as u64)
+    } else {
+        Err(Error::new(Status::InvalidArg, format!("{field} must be a non-negative safe integer")))
+    }
+}
+
+// Engine quantities only exceed 2^53 if a caller outside JavaScript put them there
+fn number(value: u64) -> i64 {
+    value.min(MAX_SAFE_INTEGER as u64) as i64
+}
+
+fn side_name(side: OrderSide) -> String {
+    match side {
+        OrderSide::Buy => "buy",
+        OrderSide::Sell => "sell",
+    }
+    .to_string()
+}
+
+impl NodeOrder {
+    fn into_order(self, scale: PriceScale) -> Result<Order> {
+        let side = match self.side.to_ascii_lowercase().as_str() {
+            "buy" => OrderSide::Buy,
+            "sell" => OrderSide::Sell,
+            side => return Err(Error::new(Status::InvalidArg, format!("unknown side '{side}'"))),
+        };
+        let time_in_force = match self.time_in_force.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None | Some("day") => TimeInForce::Day,
+            Some("gtc") => TimeInForce::GoodTillCancel,
+            Some("ioc") => TimeInForce::ImmediateOrCancel,
+            Some(time_in_force) => return Err(Error::new(Status::InvalidArg, format!("unknown time in force '{time_in_force}'"))),
+        };
+        if !self.price.is_finite() || self.price < 0.0 {
+            return Err(Error::new(Status::InvalidArg, "price must be a non-negative number".to_string()));
+        }
+        let kind = match self.display_quantity {
+            Some(display_quantity) => OrderKind::Iceberg { display_quantity: integer(display_quantity, "displayQuantity")? },
+            None => OrderKind::Limit,
+        };
+        Ok(Order {
+            id: integer(self.id, "id")?,
+            symbol: self.symbol,
+            quantity: integer(self.quantity, "quantity")?,
+            price: scale.to_u64(self.price),
+            order_type: side,
+            time_in_force,
+            timestamp: 0,
+            participant: self.participant.unwrap_or(0),
+            expires_at: None,
+            kind,
+        })
+    }
+}
+
+impl NodeTrade {
+    fn new(trade: &Trade, scale: PriceScale) -> Self {
+        Self {
+            symbol: trade.symbol,
+            taker_order_id: number(trade.taker_order_id),
+            maker_order_id: number(trade.maker_order_id),
+            price: scale.to_price(trade.price),
+            quantity: number(trade.quantity),
+            side: side_name(trade.side),
+        }
+    }
+}
+
+/// `OrderRouter` for Node.js, exported as `OrderRouter`. Calls run on the JavaScript
+/// thread; nothing matches until `matchOrders`, which returns the trades and also
+/// streams each one to the `onTrade` callback.
+#[napi(js_name = "OrderRouter")]
+pub struct NodeRouter {
+    router: OrderRouter,
+    trade_listener: Option<ThreadsafeFunction<NodeTrade, ErrorStrategy::Fatal>>,
+}
+
+#[napi]
+impl NodeRouter {
+    /// `bookType` is a book name such as `"hashmap"`, HashMap when omitted.
+    #[napi(constructor)]
+    pub fn new(symbols: Vec<u32>, book_type: Option<String>) -> Result<Self> {
+        let order_book_type = match book_type {
+            Some(name) => OrderBookType::from_name(&name)
+                .ok_or_else(|| Error::new(Status::InvalidArg, format!("unknown book '{name}'")))?,
+            None => OrderBookType::HashMap,
+        };
+        let symbols: FxHashSet<SymbolId> = symbols.into_iter().collect();
+        Ok(Self { router: OrderRouter::new_direct(symbols, order_book_type), trade_listener: None })
+    }
+
+    /// Routes an order. A refusal throws an error whose message is the numeric
+    /// `RejectCode` and the router's reason, such as `"1: Invalid symbol"`.
+    #[napi]
+    pub fn submit(&mut self, order: NodeOrder) -> Result<()> {
+        let scale = self.router.price_scale(order.symbol);
+        let order = order.into_order(scale)?;
+        self.router.route_order(order).map_err(|reason| {
+            Error::new(Status::GenericFailure, format!("{}: {reason}", RejectCode::from_reason(reason).code()))
+        })
+    }
+
+    /// Cancels the listed orders and returns the ids that were resting or held.
+    #[napi]
+    pub fn cancel(&mut self, order_ids: Vec<i64>) -> Result<Vec<i64>> {
+        let order_ids = order_ids.into_iter().map(|id| integer(id, "order id")).collect::<Result<Vec<u64>>>()?;
+        let result = self.router.cancel_orders_batch(&order_ids);
+        Ok(result.cancelled.iter().map(|order| number(order.id)).collect())
+    }
+
+    /// Up to `levels` levels per side, or `null` for an unknown symbol.
+    #[napi]
+    pub fn depth(&self, symbol: u32, levels: u32) -> Option<NodeDepth> {
+        let depth = self.router.get_depth(symbol, levels as usize)?;
+        let scale = self.router.price_scale(symbol);
+        let convert = |levels: Vec<crate::engine::PriceLevelView>| levels.into_iter()
+            .map(|level| NodeLevel { price: scale.to_price(level.price), quantity: number(level.total_quantity), orders: level.order_count as u32 })
+            .collect();
+        Some(NodeDepth { bids: convert(depth.bids), asks: convert(depth.asks) })
+    }
+
+    /// Matches every book and returns the trades, grouped by symbol.
+    #[napi]
+    pub fn match_orders(&mut self) -> Vec<NodeTrade> {
+        let trades = self.router.match_all_orders();
+        let trades: Vec<NodeTrade> = trades.iter().map(|trade| NodeTrade::new(trade, self.router.price_scale(trade.symbol))).collect();
+        if let Some(listener) = &self.trade_listener {
+            for trade in &trades {
+                listener.call(trade.clone(), ThreadsafeFunctionCallMode::NonBlocking);
+            }
+        }
+        trades
+    }
+
+    /// Streams every trade from `matchOrders` to `callback`, replacing any earlier one.
+    /// The callback does not keep the process alive.
+    #[napi]
+    pub fn on_trade(&mut self, env: Env, callback: JsFunction) -> Result<()> {
+        let mut listener: ThreadsafeFunction<NodeTrade, ErrorStrategy::Fatal> =
+            callback.create_threadsafe_function(0, |context| Ok(vec![context.value]))?;
+        listener.unref(&env)?;
+        self.trade_listener = Some(listener);
+        Ok(())
+    }
+
+    #[napi]
+    pub fn best_prices(&self, symbol: u32) -> Option<Vec<Option<f64>>> {
+        let (bid, ask) = self.router.get_best_prices(symbol)?;
+        let scale = self.router.price_scale(symbol);
+        Some(vec![bid.map(|price| scale.to_price(price)), ask.map(|price| scale.to_price(price))])
+    }
+
+    /// Sets how many decimals `symbol`'s prices carry, 3 unless set. Call it before
+    /// submitting the symbol's orders, since resting prices are not rescaled.
+    #[napi]
+    pub fn set_price_scale(&mut self, symbol: u32, decimals: u32) -> Result<()> {
+        let scale = PriceScale::new(decimals)
+            .ok_or_else(|| Error::new(Status::InvalidArg, format!("price scale must be at most {} decimals", PriceScale::MAX_DECIMALS)))?;
+        self.router.set_price_scale(symbol, scale);
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::router::OrderRouter;
+use crate::types::order::{Order, OrderSide, PRICE_SCALE};
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// Basis points in a whole, so a `margin_rate_bps` of 10_000 means fully funded.
+pub const BPS: i128 = 10_000;
+
+/// Money amounts are price times quantity at the price scale, so with `PRICE_DECIMALS`
+/// of 3 a `starting_cash` of 1_000 is one unit of cash. Symbols traded from one account
+/// should share a `PriceScale`, or their money amounts are in different units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountConfig {
+    pub starting_cash: i128,
+    /// Share of the value of positions and open orders held as margin.
+    pub margin_rate_bps: u32,
+}
+
+impl Default for AccountConfig {
+    fn default() -> Self {
+        Self { starting_cash: 100_000 * PRICE_SCALE as i128, margin_rate_bps: BPS as u32 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    /// Positive when long, negative when short.
+    pub quantity: i64,
+    /// Average price the open quantity was entered at, 0 when flat.
+    pub average_price: u64,
+    pub realized_pnl: i128,
+}
+
+impl Position {
+    /// Books a fill, realizing P&L on whatever part of it closes the position.
+    fn fill(&mut self, side: OrderSide, price: u64, quantity: u64) {
+        let signed = match side {
+            OrderSide::Buy => quantity as i64,
+            OrderSide::Sell => -(quantity as i64),
+        };
+        if self.quantity == 0 || self.quantity.signum() == signed.signum() {
+            let open = self.quantity.unsigned_abs() as u128;
+            let total = open + quantity as u128;
+            self.average_price = ((self.average_price as u128 * open + price as u128 * quantity as u128) / total) as u64;
+            self.quantity += signed;
+            return;
+        }
+        let closed = self.quantity.unsigned_abs().min(quantity) as i128;
+        self.realized_pnl += (price as i128 - self.average_price as i128) * closed * self.quantity.signum() as i128;
+        self.quantity += signed;
+        if self.quantity == 0 {
+            self.average_price = 0;
+        } else if self.quantity.signum() == signed.signum() {
+            // Flipped through flat: the remainder opens at the fill price
+            self.average_price = price;
+        }
+    }
+
+    fn unrealized_pnl(&self, mark: u64) -> i128 {
+        (mark as i128 - self.average_price as i128) * self.quantity as i128
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionSummary {
+    pub symbol: SymbolId,
+    pub position: Position,
+    /// Mid when both sides are quoted, else the last trade, else the average price.
+    pub mark_price: u64,
+    pub unrealized_pnl: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountSummary {
+    pub cash: i128,
+    /// Cash plus the marked value of every position.
+    pub equity: i128,
+    pub realized_pnl: i128,
+    pub unrealized_pnl: i128,
+    /// Margin held against positions at their mark.
+    pub margin_used: i128,
+    /// Margin held against open orders at their limit price.
+    pub margin_reserved: i128,
+    /// Equity not yet committed to margin.
+    pub buying_power: i128,
+    /// Symbols with a position or realized P&L, ascending.
+    pub positions: Vec<PositionSummary>,
+}
+
+/// A simulated account that a strategy trades through against an `OrderRouter`.
+///
+/// Orders go through `submit` so the account knows which executions are its own;
+/// feed it the trades from every `match_all_orders` call with `apply_trades`.
+#[derive(Debug, Clone)]
+pub struct PaperAccount {
+    config: AccountConfig,
+    cash: i128,
+    positions: FxHashMap<SymbolId, Position>,
+    /// Open orders by id, with their unfilled quantity.
+    open_orders: FxHashMap<u64, Order>,
+}
+
+impl PaperAccount {
+    pub fn new(config: AccountConfig) -> Self {
+        Self { config, cash: config.starting_cash, positions: FxHashMap::default(), open_orders: FxHashMap::default() }
+    }
+
+    pub fn config(&self) -> AccountConfig {
+        self.config
+    }
+
+    pub fn cash(&self) -> i128 {
+        self.cash
+    }
+
+    pub fn position(&self, symbol: SymbolId) -> Position {
+        self.positions.get(&symbol).copied().unwrap_or_default()
+    }
+
+    pub fn open_orders(&self) -> impl Iterator<Item = &Order> {
+        self.open_orders.values()
+    }
+
+    /// Routes `order` for the account, rejecting it when its margin would exceed the
+    /// buying power left.
+    pub fn submit(&mut self, router: &mut OrderRouter, order: Order) -> Result<(), &'static str> {
+        if self.open_orders.contains_key(&order.id) {
+            return Err("Duplicate order id");
+        }
+        if self.margin(order.price, order.quantity) > self.summary(router).buying_power {
+            return Err("Insufficient buying power");
+        }
+        router.route_order(order.clone())?;
+        self.open_orders.insert(order.id, order);
+        Ok(())
+    }
+
+    /// Cancels the account's orders among `order_ids` and stops tracking them. Returns
+    /// the orders that were still resting.
+    pub fn cancel(&mut self, router: &mut OrderRouter, order_ids: &[u64]) -> Vec<Order> {
+        let own: Vec<u64> = order_ids.iter().copied().filter(|id| self.open_orders.contains_key(id)).collect();
+        let result = router.cancel_orders_batch(&own);
+        for id in own {
+            self.open_orders.remove(&id);
+        }
+        result.cancelled
+    }
+
+    /// Stops tracking an order the router removed on its own, such as one that expired
+    /// or was evicted.
+    pub fn release_order(&mut self, order_id: u64) -> Option<Order> {
+        self.open_orders.remove(&order_id)
+    }
+
+    /// Books the account's side of every trade that involves one of its orders.
+    pub fn apply_trades(&mut self, trades: &[Trade]) {
+        for trade in trades {
+            for order_id in [trade.taker_order_id, trade.maker_order_id] {
+                self.fill(order_id, trade);
+            }
+        }
+    }
+
+    fn fill(&mut self, order_id: u64, trade: &Trade) {
+        let Some(order) = self.open_orders.get_mut(&order_id).filter(|order| order.symbol == trade.symbol) else {
+            return;
+        };
+        let side = order.order_type;
+        order.quantity = order.quantity.saturating_sub(trade.quantity);
+        if order.quantity == 0 {
+            self.open_orders.remove(&order_id);
+        }
+        let notional = trade.price as i128 * trade.quantity as i128;
+        match side {
+            OrderSide::Buy => self.cash -= notional,
+            OrderSide::Sell => self.cash += notional,
+        }
+        self.positions.entry(trade.symbol).or_default().fill(side, trade.price, trade.quantity);
+    }
+
+    fn margin(&self, price: u64, quantity: u64) -> i128 {
+        price as i128 * quantity as i128 * self.config.margin_rate_bps as i128 / BPS
+    }
+
+    fn mark_price(router: &OrderRouter, symbol: SymbolId, position: &Position) -> u64 {
+        match router.get_best_prices(symbol) {
+            Some((Some(bid), Some(ask))) => bid / 2 + ask / 2 + (bid % 2 + ask % 2) / 2,
+            _ => router.last_trade_price(symbol).unwrap_or(position.average_price),
+        }
+    }
+
+    /// Cash, equity, margin and P&L with positions marked against `router`'s books.
+    pub fn summary(&self, router: &OrderRouter) -> AccountSummary {
+        let mut positions: Vec<PositionSummary> = self.positions.iter()
+            .map(|(&symbol, position)| {
+                let mark_price = Self::mark_price(router, symbol, position);
+                PositionSummary { symbol, position: *position, mark_price, unrealized_pnl: position.unrealized_pnl(mark_price) }
+            })
+            .collect();
+        positions.sort_unstable_by_key(|summary| summary.symbol);
+
+        let market_value: i128 = positions.iter()
+            .map(|summary| summary.mark_price as i128 * summary.position.quantity as i128)
+            .sum();
+        let margin_used = positions.iter()
+            .map(|summary| self.margin(summary.mark_price, summary.position.quantity.unsigned_abs()))
+            .sum();
+        let margin_reserved = self.open_orders.values().map(|order| self.margin(order.price, order.quantity)).sum();
+        let equity = self.cash + market_value;
+        AccountSummary {
+            cash: self.cash,
+            equity,
+            realized_pnl: positions.iter().map(|summary| summary.position.realized_pnl).sum(),
+            unrealized_pnl: positions.iter().map(|summary| summary.unrealized_pnl).sum(),
+            margin_used,
+            margin_reserved,
+            buying_power: equity - margin_used - margin_reserved,
+            positions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashSet;
+    use crate::engine::OrderBookType;
+    use crate::types::order::{new_order, price_to_u64};
+
+    #[test]
+    fn test_positions_pnl_and_margin() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        let config = AccountConfig { starting_cash: price_to_u64(10_000.0) as i128, margin_rate_bps: 5_000 };
+        let mut account = PaperAccount::new(config);
+
+        // Buy 100 at 50 against someone else's offer
+        router.route_order(new_order(1, 0, 100, 50.0, OrderSide::Sell)).unwrap();
+        account.submit(&mut router, new_order(2, 0, 100, 50.0, OrderSide::Buy)).unwrap();
+        account.apply_trades(&router.match_all_orders());
+        assert_eq!(account.position(0), Position { quantity: 100, average_price: price_to_u64(50.0), realized_pnl: 0 });
+        assert_eq!(account.cash(), price_to_u64(5_000.0) as i128);
+
+        // Quotes at 54/56 mark the position at 55
+        router.route_order(new_order(3, 0, 10, 54.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(4, 0, 10, 56.0, OrderSide::Sell)).unwrap();
+        let summary = account.summary(&router);
+        assert_eq!(summary.positions[0].mark_price, price_to_u64(55.0));
+        assert_eq!(summary.unrealized_pnl, price_to_u64(500.0) as i128);
+        assert_eq!(summary.equity, price_to_u64(10_500.0) as i128);
+        assert_eq!(summary.margin_used, price_to_u64(2_750.0) as i128);
+        assert_eq!(summary.buying_power, price_to_u64(7_750.0) as i128);
+
+        // Sell 40 into the bid: 10 fill at 54 and 30 rest at 54
+        account.submit(&mut router, new_order(5, 0, 40, 54.0, OrderSide::Sell)).unwrap();
+        account.apply_trades(&router.match_all_orders());
+        assert_eq!(account.position(0).quantity, 90);
+        assert_eq!(account.position(0).realized_pnl, price_to_u64(40.0) as i128);
+        assert_eq!(account.open_orders().map(|order| (order.id, order.quantity)).collect::<Vec<_>>(), vec![(5, 30)]);
+        let summary = account.summary(&router);
+        assert_eq!(summary.margin_reserved, price_to_u64(810.0) as i128);
+
+        assert_eq!(account.cancel(&mut router, &[5, 4]).iter().map(|order| order.id).collect::<Vec<_>>(), vec![5]);
+        assert_eq!(account.open_orders().count(), 0);
+        assert_eq!(router.get_best_prices(0), Some((None, Some(price_to_u64(56.0)))));
+
+        // Orders beyond the buying power are refused before they reach the router
+        assert_eq!(account.submit(&mut router, new_order(6, 0, 1_000, 56.0, OrderSide::Buy)), Err("Insufficient buying power"));
+        assert_eq!(router.session_stats().orders_routed, 5);
+    }
+
+    #[test]
+    fn test_position_flips_through_flat() {
+        let mut position = Position::default();
+        position.fill(OrderSide::Sell, 100, 10);
+        position.fill(OrderSide::Buy, 90, 15);
+        assert_eq!(position, Position { quantity: 5, average_price: 90, realized_pnl: 100 });
+        position.fill(OrderSide::Sell, 95, 5);
+        assert_eq!(position, Position { quantity: 0, average_price: 0, realized_pnl: 125 });
+    }
+}
@@ -0,0 +1,3 @@
+pub mod account;
+
+pub use account::{AccountConfig, AccountSummary, PaperAccount, Position, PositionSummary};
@@ -0,0 +1,59 @@
+//! splitmix64, for the few places that want cheap, seedable randomness without
+//! pulling in `rand`: consistent-hash points, tie-break lotteries and simulated
+//! network delays.
+
+/// splitmix64's increment, the 64-bit golden ratio.
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// splitmix64's output for `state`: a cheap, well-mixed 64-bit hash, and the draw a
+/// generator in that state makes.
+#[inline(always)]
+pub(crate) fn mix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(GOLDEN_GAMMA);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A splitmix64 stream: each draw mixes the state, then steps it by `GOLDEN_GAMMA`.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Stream `index` of `seed`, e.g. one per shard, so shards sharing a seed still
+    /// draw differently.
+    pub(crate) const fn stream(seed: u64, index: usize) -> Self {
+        Self::new(seed ^ (index as u64).wrapping_mul(GOLDEN_GAMMA))
+    }
+
+    #[inline(always)]
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let draw = mix64(self.state);
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        draw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_matches_reference_splitmix64() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!([rng.next_u64(), rng.next_u64()], [0xE220_A839_7B1D_CDAF, 0x6E78_9E6A_A1B9_65F4]);
+
+        let draws = |index| {
+            let mut rng = SplitMix64::stream(42, index);
+            (0..4).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(1), draws(1));
+        assert_ne!(draws(0), draws(1));
+    }
+}
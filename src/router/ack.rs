@@ -0,0 +1,24 @@
+use crate::types::order::{Order, ParticipantId, TimeInForce};
+use crate::types::symbol_mapping::SymbolId;
+
+/// Sent for every order the router accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct OrderAck {
+    pub order_id: u64,
+    pub symbol: SymbolId,
+    pub participant: ParticipantId,
+    /// Effective time in force, after `SymbolDefault` was resolved.
+    pub time_in_force: TimeInForce,
+    /// Router sequence assigned to the order.
+    pub sequence: u64,
+}
+
+/// Sent when an owner reduces a resting order in place.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OrderReduced {
+    /// The order as it rests after the reduction, priority unchanged.
+    pub order: Order,
+    pub reduced_by: u64,
+    /// Router sequence assigned to the reduction.
+    pub sequence: u64,
+}
@@ -0,0 +1,202 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::PriceLevelView;
+use crate::types::order::{Order, OrderSide};
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// The liquidity one aggressive order could see when it arrived and the prices it got
+/// as the taker. Prices are in the symbol's scaled units.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    pub symbol: SymbolId,
+    pub order_id: u64,
+    pub side: OrderSide,
+    pub limit_price: u64,
+    pub quantity: u64,
+    /// Best opposite price when the order arrived.
+    pub arrival_best: u64,
+    /// Opposite quantity at prices no worse than the limit when the order arrived.
+    pub available_quantity: u64,
+    /// Average price of sweeping the arrival book for the order's quantity, within
+    /// its limit.
+    pub expected_price: f64,
+    pub filled_quantity: u64,
+    pub filled_notional: u128,
+}
+
+impl ExecutionReport {
+    pub fn average_price(&self) -> Option<f64> {
+        (self.filled_quantity > 0).then(|| self.filled_notional as f64 / self.filled_quantity as f64)
+    }
+
+    /// How far past the arrival best the order filled on average, in price units;
+    /// negative when it filled better.
+    pub fn slippage(&self) -> Option<f64> {
+        let average = self.average_price()?;
+        Some(match self.side {
+            OrderSide::Buy => average - self.arrival_best as f64,
+            OrderSide::Sell => self.arrival_best as f64 - average,
+        })
+    }
+
+    /// How far inside its limit the order filled on average, in price units.
+    pub fn price_improvement(&self) -> Option<f64> {
+        let average = self.average_price()?;
+        Some(match self.side {
+            OrderSide::Buy => self.limit_price as f64 - average,
+            OrderSide::Sell => average - self.limit_price as f64,
+        })
+    }
+
+    /// Captures the order's view of the opposite side, best level first, or `None`
+    /// when the order does not cross it.
+    pub(crate) fn arrive(order: &Order, opposite: impl Iterator<Item = PriceLevelView>) -> Option<Self> {
+        let within_limit = |price: u64| match order.order_type {
+            OrderSide::Buy => price <= order.price,
+            OrderSide::Sell => price >= order.price,
+        };
+        let mut opposite = opposite.take_while(|level| within_limit(level.price)).peekable();
+        let arrival_best = opposite.peek()?.price;
+
+        let (mut available_quantity, mut swept, mut swept_notional) = (0u64, 0u64, 0u128);
+        for level in opposite {
+            available_quantity += level.total_quantity;
+            let take = level.total_quantity.min(order.quantity - swept);
+            swept += take;
+            swept_notional += level.price as u128 * take as u128;
+        }
+        Some(Self {
+            symbol: order.symbol,
+            order_id: order.id,
+            side: order.order_type,
+            limit_price: order.price,
+            quantity: order.quantity,
+            arrival_best,
+            available_quantity,
+            expected_price: swept_notional as f64 / swept.max(1) as f64,
+            filled_quantity: 0,
+            filled_notional: 0,
+        })
+    }
+}
+
+/// Quantity-weighted execution quality over a set of reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BestExecutionSummary {
+    pub orders: usize,
+    pub filled_orders: usize,
+    /// Filled over submitted quantity.
+    pub fill_rate: f64,
+    pub mean_slippage: f64,
+    pub mean_price_improvement: f64,
+}
+
+impl BestExecutionSummary {
+    pub fn from_reports(reports: &[ExecutionReport]) -> Self {
+        let (mut quantity, mut filled, mut slippage, mut improvement, mut filled_orders) = (0u64, 0u64, 0.0, 0.0, 0);
+        for report in reports {
+            quantity += report.quantity;
+            if let (Some(order_slippage), Some(order_improvement)) = (report.slippage(), report.price_improvement()) {
+                filled_orders += 1;
+                filled += report.filled_quantity;
+                slippage += order_slippage * report.filled_quantity as f64;
+                improvement += order_improvement * report.filled_quantity as f64;
+            }
+        }
+        let per_unit = |total: f64, units: u64| if units == 0 { 0.0 } else { total / units as f64 };
+        Self {
+            orders: reports.len(),
+            filled_orders,
+            fill_rate: per_unit(filled as f64, quantity),
+            mean_slippage: per_unit(slippage, filled),
+            mean_price_improvement: per_unit(improvement, filled),
+        }
+    }
+}
+
+/// Reports for the aggressive orders routed since it was enabled, keyed by order id.
+#[derive(Debug, Default)]
+pub(crate) struct BestExecutionAudit {
+    reports: FxHashMap<u64, ExecutionReport>,
+}
+
+impl BestExecutionAudit {
+    pub(crate) fn record(&mut self, report: ExecutionReport) {
+        self.reports.insert(report.order_id, report);
+    }
+
+    /// Adds each trade to the report of its taker.
+    pub(crate) fn fill(&mut self, trades: &[Trade]) {
+        for trade in trades {
+            if let Some(report) = self.reports.get_mut(&trade.taker_order_id).filter(|report| report.symbol == trade.symbol) {
+                report.filled_quantity += trade.quantity;
+                report.filled_notional += trade.price as u128 * trade.quantity as u128;
+            }
+        }
+    }
+
+    /// Every report, by symbol then order id.
+    pub(crate) fn reports(&self) -> Vec<ExecutionReport> {
+        let mut reports: Vec<ExecutionReport> = self.reports.values().cloned().collect();
+        reports.sort_unstable_by_key(|report| (report.symbol, report.order_id));
+        reports
+    }
+
+    /// Removes and returns the reports of orders `is_open` says have left the router,
+    /// by symbol then order id.
+    pub(crate) fn take_closed(&mut self, is_open: impl Fn(&ExecutionReport) -> bool) -> Vec<ExecutionReport> {
+        let mut closed = Vec::new();
+        self.reports.retain(|_, report| {
+            let open = is_open(report);
+            if !open {
+                closed.push(report.clone());
+            }
+            open
+        });
+        closed.sort_unstable_by_key(|report| (report.symbol, report.order_id));
+        closed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    fn level(price: u64, total_quantity: u64) -> PriceLevelView {
+        PriceLevelView { price, total_quantity, order_count: 1, order_ids: None }
+    }
+
+    #[test]
+    fn test_report_measures_slippage_against_arrival_book() {
+        let mut audit = BestExecutionAudit::default();
+        let mut buy = new_order(7, 0, 30, 0.0, OrderSide::Buy);
+        buy.price = 103;
+        audit.record(ExecutionReport::arrive(&buy, [level(100, 10), level(102, 10), level(104, 50)].into_iter()).unwrap());
+        let mut passive = new_order(8, 0, 5, 0.0, OrderSide::Buy);
+        passive.price = 99;
+        assert!(ExecutionReport::arrive(&passive, [level(100, 10)].into_iter()).is_none());
+
+        audit.fill(&[
+            Trade { symbol: 0, taker_order_id: 7, maker_order_id: 1, price: 100, quantity: 10, side: OrderSide::Buy },
+            Trade { symbol: 0, taker_order_id: 7, maker_order_id: 2, price: 102, quantity: 5, side: OrderSide::Buy },
+            Trade { symbol: 0, taker_order_id: 9, maker_order_id: 7, price: 103, quantity: 5, side: OrderSide::Sell },
+        ]);
+
+        let reports = audit.reports();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!((report.arrival_best, report.available_quantity, report.expected_price), (100, 20, 101.0));
+        assert_eq!((report.filled_quantity, report.average_price()), (15, Some(302.0 / 3.0)));
+        assert!((report.slippage().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((report.price_improvement().unwrap() - 7.0 / 3.0).abs() < 1e-9);
+
+        let summary = BestExecutionSummary::from_reports(&reports);
+        assert_eq!((summary.orders, summary.filled_orders, summary.fill_rate), (1, 1, 0.5));
+        assert!(audit.take_closed(|_| true).is_empty());
+        assert_eq!(audit.take_closed(|_| false), reports);
+        assert!(audit.reports().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::types::{order::Order, symbol_mapping::SymbolId};
+use crate::types::{order::{Order, OrderFlags}, symbol_mapping::SymbolId, trade::Trade};
 use crate::engine::OrderBookTrait;
 
 pub struct BookRoute {
@@ -14,8 +14,12 @@ impl BookRoute {
         }
     }
 
-    pub fn process_order(&mut self, order: Order) {
+    pub fn process_order(&mut self, order: Order) -> Vec<Trade> {
+        let immediate_or_cancel = order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL);
         let _ = self.order_book.add_order_fast(order);
-        self.order_book.match_orders();
+        if immediate_or_cancel {
+            self.order_book.take_evicted_orders();
+        }
+        self.order_book.match_orders()
     }
 }
\ No newline at end of file
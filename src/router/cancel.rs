@@ -0,0 +1,109 @@
+use rustc_hash::FxHashMap;
+
+use crate::types::order::{Order, ParticipantId};
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// Outcome of `OrderRouter::cancel_orders_batch`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct BatchCancelResult {
+    /// Cancelled orders, grouped by symbol in ascending order.
+    pub cancelled: Vec<Order>,
+    /// Requested ids with nothing resting, in request order.
+    pub not_found: Vec<u64>,
+}
+
+impl BatchCancelResult {
+    #[inline(always)]
+    pub fn cancelled_count(&self) -> usize {
+        self.cancelled.len()
+    }
+}
+
+/// Symbol, participant and unfilled quantity of every order open in an `OrderRouter`,
+/// by id, so a cancel by id goes straight to its book's id index and a trade can be
+/// attributed. Icebergs count their reserve.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OrderSymbols {
+    open: FxHashMap<u64, (SymbolId, ParticipantId, u64)>,
+}
+
+impl OrderSymbols {
+    #[inline(always)]
+    pub(crate) fn symbol(&self, order_id: u64) -> Option<SymbolId> {
+        self.open.get(&order_id).map(|&(symbol, _, _)| symbol)
+    }
+
+    #[inline(always)]
+    pub(crate) fn participant(&self, order_id: u64) -> Option<ParticipantId> {
+        self.open.get(&order_id).map(|&(_, participant, _)| participant)
+    }
+
+    #[inline(always)]
+    pub(crate) fn accept(&mut self, symbol: SymbolId, order_id: u64, participant: ParticipantId, quantity: u64) {
+        self.open.insert(order_id, (symbol, participant, quantity));
+    }
+
+    /// Starts afresh from `orders`.
+    pub(crate) fn rebase<'a>(&mut self, orders: impl IntoIterator<Item = &'a Order>) {
+        self.open.clear();
+        for order in orders {
+            self.accept(order.symbol, order.id, order.participant, order.quantity);
+        }
+    }
+
+    pub(crate) fn fill(&mut self, trades: &[Trade]) {
+        for trade in trades {
+            for order_id in [trade.taker_order_id, trade.maker_order_id] {
+                self.fill_order(trade.symbol, order_id, trade.quantity);
+            }
+        }
+    }
+
+    /// Takes a fill or reduction off the order, forgetting it once nothing is left.
+    pub(crate) fn fill_order(&mut self, symbol: SymbolId, order_id: u64, quantity: u64) {
+        let Some((open_symbol, _, remaining)) = self.open.get_mut(&order_id) else { return };
+        if *open_symbol != symbol {
+            return;
+        }
+        *remaining = remaining.saturating_sub(quantity);
+        if *remaining == 0 {
+            self.open.remove(&order_id);
+        }
+    }
+
+    pub(crate) fn forget(&mut self, orders: &[Order]) {
+        for order in orders {
+            if self.symbol(order.id) == Some(order.symbol) {
+                self.open.remove(&order.id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, OrderSide};
+
+    #[test]
+    fn test_order_symbols_forget_filled_and_cancelled_orders() {
+        let mut order_symbols = OrderSymbols::default();
+        order_symbols.accept(0, 1, 0, 10);
+        order_symbols.accept(1, 2, 7, 10);
+        order_symbols.accept(1, 3, 0, 5);
+
+        let bid = new_order(2, 1, 10, 100.0, OrderSide::Buy);
+        let ask = new_order(3, 1, 5, 100.0, OrderSide::Sell);
+        order_symbols.fill(&[Trade::between(&bid, &ask, ask.price)]);
+        assert_eq!(order_symbols.symbol(2), Some(1));
+        assert_eq!(order_symbols.participant(2), Some(7));
+        assert_eq!(order_symbols.symbol(3), None);
+
+        // Only the symbol an id was accepted under can remove it
+        order_symbols.forget(&[new_order(1, 1, 10, 100.0, OrderSide::Buy)]);
+        assert_eq!(order_symbols.symbol(1), Some(0));
+        order_symbols.fill_order(0, 1, 10);
+        assert_eq!(order_symbols.symbol(1), None);
+    }
+}
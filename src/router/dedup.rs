@@ -0,0 +1,149 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use rustc_hash::FxHashMap;
+
+use crate::types::order::ParticipantId;
+
+/// Which remembered order id a full dedup window forgets first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupEviction {
+    /// The id seen longest ago.
+    #[default]
+    OldestFirst,
+    /// The lowest id, for clients whose ids increase but may arrive out of order.
+    LowestIdFirst,
+}
+
+/// How many order ids are remembered per participant to catch retransmits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DedupWindow {
+    pub capacity: usize,
+    pub eviction: DedupEviction,
+}
+
+impl DedupWindow {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), eviction: DedupEviction::default() }
+    }
+
+    pub fn with_eviction(mut self, eviction: DedupEviction) -> Self {
+        self.eviction = eviction;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DedupStats {
+    /// Orders rejected as duplicates.
+    pub hits: u64,
+    /// Ids forgotten to keep the window within capacity.
+    pub evictions: u64,
+    pub remembered: usize,
+}
+
+#[derive(Debug, Default)]
+struct ParticipantIds {
+    ids: BTreeSet<u64>,
+    /// Arrival order, only kept for `OldestFirst`.
+    arrivals: VecDeque<u64>,
+    hits: u64,
+    evictions: u64,
+}
+
+/// Accepted order ids per participant, bounded by each participant's window.
+#[derive(Debug)]
+pub(crate) struct DedupFilter {
+    default_window: DedupWindow,
+    windows: FxHashMap<ParticipantId, DedupWindow>,
+    participants: FxHashMap<ParticipantId, ParticipantIds>,
+}
+
+impl DedupFilter {
+    pub(crate) fn new(default_window: DedupWindow) -> Self {
+        Self { default_window, windows: FxHashMap::default(), participants: FxHashMap::default() }
+    }
+
+    /// Applies from the next accepted order; a shrunk window is trimmed then.
+    pub(crate) fn set_window(&mut self, participant: ParticipantId, window: DedupWindow) {
+        self.windows.insert(participant, window);
+        if let Some(seen) = self.participants.get_mut(&participant) {
+            if window.eviction == DedupEviction::LowestIdFirst {
+                seen.arrivals.clear();
+            } else if seen.arrivals.is_empty() {
+                seen.arrivals.extend(seen.ids.iter().copied());
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn window(&self, participant: ParticipantId) -> DedupWindow {
+        self.windows.get(&participant).copied().unwrap_or(self.default_window)
+    }
+
+    /// Counts a hit when `order_id` is still remembered for `participant`.
+    #[inline(always)]
+    pub(crate) fn is_duplicate(&mut self, participant: ParticipantId, order_id: u64) -> bool {
+        match self.participants.get_mut(&participant) {
+            Some(seen) if seen.ids.contains(&order_id) => {
+                seen.hits += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn record(&mut self, participant: ParticipantId, order_id: u64) {
+        let window = self.window(participant);
+        let seen = self.participants.entry(participant).or_default();
+        if !seen.ids.insert(order_id) {
+            return;
+        }
+        if window.eviction == DedupEviction::OldestFirst {
+            seen.arrivals.push_back(order_id);
+        }
+        while seen.ids.len() > window.capacity {
+            let forgotten = match window.eviction {
+                DedupEviction::OldestFirst => seen.arrivals.pop_front(),
+                DedupEviction::LowestIdFirst => seen.ids.first().copied(),
+            };
+            let Some(forgotten) = forgotten else { break };
+            seen.ids.remove(&forgotten);
+            seen.evictions += 1;
+        }
+    }
+
+    pub(crate) fn stats(&self, participant: ParticipantId) -> DedupStats {
+        self.participants.get(&participant).map_or_else(DedupStats::default, |seen| DedupStats {
+            hits: seen.hits,
+            evictions: seen.evictions,
+            remembered: seen.ids.len(),
+        })
+    }
+
+    pub(crate) fn total_hits(&self) -> u64 {
+        self.participants.values().map(|seen| seen.hits).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_evict_per_policy() {
+        let mut filter = DedupFilter::new(DedupWindow::new(2));
+        filter.set_window(7, DedupWindow::new(2).with_eviction(DedupEviction::LowestIdFirst));
+        for id in [5, 3, 9] {
+            filter.record(1, id);
+            filter.record(7, id);
+        }
+
+        // Oldest-first forgot 5; lowest-first forgot 3
+        assert!(!filter.is_duplicate(1, 5) && filter.is_duplicate(1, 3) && filter.is_duplicate(1, 9));
+        assert!(filter.is_duplicate(7, 5) && !filter.is_duplicate(7, 3));
+        assert_eq!(filter.stats(1), DedupStats { hits: 2, evictions: 1, remembered: 2 });
+        assert_eq!(filter.total_hits(), 3);
+        assert_eq!(filter.stats(2), DedupStats::default());
+    }
+}
@@ -0,0 +1,36 @@
+use std::io;
+
+use crate::engine::MatchStats;
+use crate::snapshot::BookSnapshot;
+use crate::storage::Storage;
+use crate::types::order::Order;
+use crate::types::symbol_mapping::SymbolId;
+
+/// Storage keys of delisting archives start with this.
+pub const DELISTING_PREFIX: &str = "delisted-";
+
+/// Everything left of a symbol that `OrderRouter::delist_symbol` took off the venue.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SymbolDelisted {
+    pub symbol: SymbolId,
+    /// Router sequence of the delisting.
+    pub sequence: u64,
+    /// The book as it rested when the symbol was halted.
+    pub final_snapshot: BookSnapshot,
+    /// Resting, carried and imbalance-only orders cancelled as `Delisted`.
+    pub cancelled: Vec<Order>,
+    pub last_trade_price: Option<u64>,
+    pub last_match_stats: Option<MatchStats>,
+    /// Nanoseconds since the Unix epoch.
+    pub delisted_at: u64,
+}
+
+impl SymbolDelisted {
+    /// Stores the record as JSON and returns the key it was written under. Keys sort
+    /// by symbol, then by sequence.
+    pub fn archive_to(&self, storage: &dyn Storage) -> io::Result<String> {
+        let key = format!("{DELISTING_PREFIX}{:010}-{:020}.json", self.symbol, self.sequence);
+        storage.put(&key, &serde_json::to_vec(self)?)?;
+        Ok(key)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::types::order::Order;
+
+/// Why the venue, rather than the owner, took an order off the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ExpiryReason {
+    /// Day order still resting when the session rolled.
+    SessionEnd,
+    /// Its price level was the farthest from the touch when the book exceeded its max depth.
+    DepthEviction,
+    /// Immediate-or-cancel order's remainder after matching on arrival.
+    ImmediateOrCancel,
+    /// Imbalance-only order with no opening imbalance to offset.
+    AuctionUnfilled,
+    /// Quote whose TTL ran out without a refresh.
+    QuoteExpired,
+    /// Participant with cancel-on-disconnect set lost its connection.
+    Disconnected,
+    /// Pulled by an operator, e.g. through the admin shell.
+    OperatorCancel,
+    /// Good-till-date order whose expiry time passed.
+    GoodTillDate,
+    /// Its symbol was delisted.
+    Delisted,
+    /// Iceberg whose next slice the book refused, such as a full fixed-capacity book.
+    /// Carries the slice and the reserve behind it.
+    RefillRefused,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OrderExpired {
+    pub order: Order,
+    pub reason: ExpiryReason,
+    /// Router sequence when the order expired.
+    pub sequence: u64,
+}
@@ -0,0 +1,180 @@
+use rustc_hash::FxHashMap;
+
+use crate::types::order::{Order, OrderSide, ParticipantId};
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// A participant's net fills in one symbol since position tracking was enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct AccountPosition {
+    pub symbol: SymbolId,
+    /// Positive when net long, negative when net short.
+    pub quantity: i64,
+    /// Last trade price, the average fill price when the symbol has none.
+    pub mark_price: u64,
+    /// `quantity` at `mark_price`, signed like the quantity.
+    pub market_value: i128,
+}
+
+/// What one participant has at risk across every book in an `OrderRouter`. Money
+/// amounts are price times quantity at the price scale, as in `PaperAccount`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct AccountExposure {
+    pub participant: ParticipantId,
+    /// Resting, carried and imbalance-only orders, iceberg reserves included.
+    pub open_orders: usize,
+    pub open_bid_quantity: u64,
+    pub open_ask_quantity: u64,
+    /// Open bids at their limit prices.
+    pub open_bid_notional: i128,
+    pub open_ask_notional: i128,
+    /// Symbols with a non-zero position, ascending. Empty unless position tracking is
+    /// enabled.
+    pub positions: Vec<AccountPosition>,
+}
+
+impl AccountExposure {
+    /// Open order notional on both sides plus the absolute market value of every
+    /// position.
+    pub fn gross_exposure(&self) -> i128 {
+        self.open_bid_notional + self.open_ask_notional
+            + self.positions.iter().map(|position| position.market_value.abs()).sum::<i128>()
+    }
+
+    pub(crate) fn from_orders<'a>(participant: ParticipantId, orders: impl IntoIterator<Item = &'a Order>) -> Self {
+        let mut exposure = Self { participant, ..Default::default() };
+        for order in orders {
+            let notional = order.price as i128 * order.quantity as i128;
+            exposure.open_orders += 1;
+            match order.order_type {
+                OrderSide::Buy => {
+                    exposure.open_bid_quantity += order.quantity;
+                    exposure.open_bid_notional += notional;
+                }
+                OrderSide::Sell => {
+                    exposure.open_ask_quantity += order.quantity;
+                    exposure.open_ask_notional += notional;
+                }
+            }
+        }
+        exposure
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct NetFills {
+    quantity: i64,
+    /// Quantity-weighted sum of fill prices, for the fallback mark.
+    bought: (u128, u64),
+    sold: (u128, u64),
+}
+
+impl NetFills {
+    fn average_price(&self) -> u64 {
+        let (notional, quantity) = if self.quantity >= 0 { self.bought } else { self.sold };
+        if quantity == 0 { 0 } else { (notional / quantity as u128) as u64 }
+    }
+}
+
+/// Net fills per participant and symbol. Trades only name orders, so the owner and
+/// unfilled quantity of every live order are kept until it fills or leaves the router.
+#[derive(Debug, Default)]
+pub(crate) struct PositionTracker {
+    owners: FxHashMap<(SymbolId, u64), (ParticipantId, OrderSide, u64)>,
+    positions: FxHashMap<(ParticipantId, SymbolId), NetFills>,
+}
+
+impl PositionTracker {
+    #[inline(always)]
+    pub(crate) fn accept(&mut self, symbol: SymbolId, order_id: u64, participant: ParticipantId, side: OrderSide, quantity: u64) {
+        self.owners.insert((symbol, order_id), (participant, side, quantity));
+    }
+
+    /// Starts tracking the owners of `orders` afresh, keeping positions.
+    pub(crate) fn rebase<'a>(&mut self, orders: impl IntoIterator<Item = &'a Order>) {
+        self.owners.clear();
+        for order in orders {
+            self.accept(order.symbol, order.id, order.participant, order.order_type, order.quantity);
+        }
+    }
+
+    pub(crate) fn fill(&mut self, trades: &[Trade]) {
+        for trade in trades {
+            for order_id in [trade.taker_order_id, trade.maker_order_id] {
+                self.fill_order(trade.symbol, order_id, trade.price, trade.quantity);
+            }
+        }
+    }
+
+    pub(crate) fn fill_order(&mut self, symbol: SymbolId, order_id: u64, price: u64, quantity: u64) {
+        let key = (symbol, order_id);
+        let Some((participant, side, remaining)) = self.owners.get_mut(&key) else { return };
+        let fills = self.positions.entry((*participant, symbol)).or_default();
+        let notional = price as u128 * quantity as u128;
+        match side {
+            OrderSide::Buy => {
+                fills.quantity += quantity as i64;
+                fills.bought = (fills.bought.0 + notional, fills.bought.1 + quantity);
+            }
+            OrderSide::Sell => {
+                fills.quantity -= quantity as i64;
+                fills.sold = (fills.sold.0 + notional, fills.sold.1 + quantity);
+            }
+        }
+        *remaining = remaining.saturating_sub(quantity);
+        if *remaining == 0 {
+            self.owners.remove(&key);
+        }
+    }
+
+    pub(crate) fn reduce(&mut self, symbol: SymbolId, order_id: u64, quantity: u64) {
+        if let Some((_, _, remaining)) = self.owners.get_mut(&(symbol, order_id)) {
+            *remaining = remaining.saturating_sub(quantity);
+        }
+    }
+
+    pub(crate) fn forget(&mut self, orders: &[Order]) {
+        for order in orders {
+            self.owners.remove(&(order.symbol, order.id));
+        }
+    }
+
+    /// Non-zero positions of `participant`, marked by `last_trade_price`.
+    pub(crate) fn positions(&self, participant: ParticipantId, last_trade_price: impl Fn(SymbolId) -> Option<u64>) -> Vec<AccountPosition> {
+        let mut positions: Vec<AccountPosition> = self.positions.iter()
+            .filter(|((owner, _), fills)| *owner == participant && fills.quantity != 0)
+            .map(|(&(_, symbol), fills)| {
+                let mark_price = last_trade_price(symbol).unwrap_or_else(|| fills.average_price());
+                AccountPosition { symbol, quantity: fills.quantity, mark_price, market_value: mark_price as i128 * fills.quantity as i128 }
+            })
+            .collect();
+        positions.sort_unstable_by_key(|position| position.symbol);
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    fn test_positions_net_fills_by_owner() {
+        let mut tracker = PositionTracker::default();
+        let bid = new_order(1, 0, 100, 10.0, OrderSide::Buy).with_participant(7);
+        let ask = new_order(2, 0, 40, 10.0, OrderSide::Sell).with_participant(8);
+        let untracked = new_order(3, 0, 60, 10.0, OrderSide::Sell);
+        tracker.rebase([&bid, &ask]);
+        tracker.fill(&[Trade::between(&bid, &ask, 10_000), Trade::between(&bid, &untracked, 10_000)]);
+
+        assert_eq!(tracker.positions(7, |_| Some(11_000)), vec![AccountPosition { symbol: 0, quantity: 100, mark_price: 11_000, market_value: 1_100_000 }]);
+        assert_eq!(tracker.positions(8, |_| None)[0].quantity, -40);
+        assert_eq!(tracker.positions(8, |_| None)[0].mark_price, 10_000);
+        // Both orders filled completely, so nothing is left to follow
+        assert!(tracker.owners.is_empty());
+
+        let exposure = AccountExposure { positions: tracker.positions(8, |_| None), ..AccountExposure::from_orders(8, [&new_order(4, 1, 5, 2.0, OrderSide::Buy)]) };
+        assert_eq!((exposure.open_orders, exposure.open_bid_notional), (1, 10_000));
+        assert_eq!(exposure.gross_exposure(), 10_000 + 400_000);
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Liveness of one `ShardedRouter` shard as of a `shard_health` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardHealth {
+    pub shard: usize,
+    /// Orders the shard has taken off its queue, executed or timed out.
+    pub processed: u64,
+    pub queue_depth: usize,
+    /// Orders are queued but `processed` has not moved for at least the stall threshold.
+    pub stalled: bool,
+    pub alive: bool,
+}
+
+impl ShardHealth {
+    #[inline(always)]
+    pub fn is_healthy(&self) -> bool {
+        self.alive && !self.stalled
+    }
+}
+
+/// Intake queue occupancy for one `ShardedRouter` shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueDepth {
+    pub shard: usize,
+    pub depth: usize,
+    pub capacity: usize,
+    pub high_water: usize,
+}
+
+/// Matching passes of one `ShardedRouter` shard configured with adaptive batching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchStats {
+    pub shard: usize,
+    pub passes: u64,
+    /// Orders executed across all passes.
+    pub orders: u64,
+    /// Batch size the shard will drain up to on its next pass.
+    pub target: usize,
+    pub largest: usize,
+}
+
+impl BatchStats {
+    #[inline(always)]
+    pub fn mean_batch_size(&self) -> f64 {
+        if self.passes == 0 { 0.0 } else { self.orders as f64 / self.passes as f64 }
+    }
+}
+
+/// Shared between a shard's thread, which records each pass, and `batch_stats`.
+#[derive(Debug, Default)]
+pub(crate) struct BatchCounters {
+    passes: AtomicU64,
+    orders: AtomicU64,
+    target: AtomicUsize,
+    largest: AtomicUsize,
+}
+
+impl BatchCounters {
+    #[inline(always)]
+    pub(crate) fn record(&self, size: usize, next_target: usize) {
+        self.passes.fetch_add(1, Ordering::Relaxed);
+        self.orders.fetch_add(size as u64, Ordering::Relaxed);
+        self.largest.fetch_max(size, Ordering::Relaxed);
+        self.target.store(next_target, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_target(&self, target: usize) {
+        self.target.store(target, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self, shard: usize) -> BatchStats {
+        BatchStats {
+            shard,
+            passes: self.passes.load(Ordering::Relaxed),
+            orders: self.orders.load(Ordering::Relaxed),
+            target: self.target.load(Ordering::Relaxed),
+            largest: self.largest.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Readiness {
+    /// False after a snapshot restore failed, until one succeeds.
+    pub recovery_complete: bool,
+    pub session_open: bool,
+}
+
+impl Readiness {
+    #[inline(always)]
+    pub fn is_ready(&self) -> bool {
+        self.recovery_complete && self.session_open
+    }
+}
+
+/// Tracks when a shard's processed count last moved, across `shard_health` calls.
+#[derive(Debug)]
+pub(crate) struct ProgressWatch {
+    processed: u64,
+    changed_at: Instant,
+}
+
+impl ProgressWatch {
+    pub(crate) fn new() -> Self {
+        Self { processed: 0, changed_at: Instant::now() }
+    }
+
+    /// Records `processed`, returning how long it has gone without changing.
+    pub(crate) fn observe(&mut self, processed: u64) -> Duration {
+        if processed != self.processed {
+            self.processed = processed;
+            self.changed_at = Instant::now();
+        }
+        self.changed_at.elapsed()
+    }
+}
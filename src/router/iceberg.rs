@@ -0,0 +1,146 @@
+use rustc_hash::FxHashMap;
+
+use crate::types::order::{Order, OrderFlags, OrderKind};
+use crate::types::trade::Trade;
+
+/// Hidden reserves of the iceberg orders in an `OrderRouter`. Books only ever hold an
+/// iceberg's current slice; when trades use it up, the next slice is cut from the
+/// reserve and added at the back of its price level.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IcebergReserves {
+    /// By order id, the reserve as an order whose quantity is the hidden remainder.
+    reserves: FxHashMap<u64, Order>,
+    /// Unfilled quantity of the slice each iceberg with a reserve has resting.
+    displayed: FxHashMap<u64, u64>,
+}
+
+impl IcebergReserves {
+    #[inline(always)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.reserves.is_empty()
+    }
+
+    /// Cuts the first slice off an iceberg and holds back the rest. Other orders, and
+    /// icebergs that fit in their display size or never rest, pass through whole.
+    pub(crate) fn split(&mut self, mut order: Order) -> Order {
+        let OrderKind::Iceberg { display_quantity } = order.kind else { return order };
+        if display_quantity == 0 || order.quantity <= display_quantity || order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) {
+            return order;
+        }
+        let mut reserve = order.clone();
+        reserve.quantity = order.quantity - display_quantity;
+        order.quantity = display_quantity;
+        self.displayed.insert(order.id, display_quantity);
+        self.reserves.insert(order.id, reserve);
+        order
+    }
+
+    /// Books `trades` against the resting slices and returns the next slice of every
+    /// iceberg whose slice they used up, in the order the slices ran out.
+    pub(crate) fn refill(&mut self, trades: &[Trade]) -> Vec<Order> {
+        if self.reserves.is_empty() {
+            return Vec::new();
+        }
+        let mut slices = Vec::new();
+        for trade in trades {
+            for order_id in [trade.taker_order_id, trade.maker_order_id] {
+                slices.extend(self.fill(order_id, trade.quantity));
+            }
+        }
+        slices
+    }
+
+    /// Books one fill of `quantity` against the resting slice of `order_id` and returns
+    /// the next slice if it used the slice up.
+    pub(crate) fn fill(&mut self, order_id: u64, quantity: u64) -> Option<Order> {
+        let displayed = self.displayed.get_mut(&order_id)?;
+        *displayed = displayed.saturating_sub(quantity);
+        if *displayed != 0 {
+            return None;
+        }
+        self.next_slice(order_id)
+    }
+
+    fn next_slice(&mut self, order_id: u64) -> Option<Order> {
+        let reserve = self.reserves.get_mut(&order_id)?;
+        let OrderKind::Iceberg { display_quantity } = reserve.kind else { return None };
+        let mut slice = reserve.clone();
+        slice.quantity = display_quantity.min(reserve.quantity);
+        reserve.quantity -= slice.quantity;
+        if reserve.quantity == 0 {
+            // The last slice rests like any other order
+            self.reserves.remove(&order_id);
+            self.displayed.remove(&order_id);
+        } else {
+            self.displayed.insert(order_id, slice.quantity);
+        }
+        Some(slice)
+    }
+
+    /// Forgets the reserve of an iceberg whose first slice the book refused.
+    pub(crate) fn discard(&mut self, order_id: u64) {
+        self.reserves.remove(&order_id);
+        self.displayed.remove(&order_id);
+    }
+
+    /// Takes `quantity` off the resting slice of `order_id`, after a reduction.
+    pub(crate) fn reduce(&mut self, order_id: u64, quantity: u64) {
+        if let Some(displayed) = self.displayed.get_mut(&order_id) {
+            *displayed = displayed.saturating_sub(quantity);
+        }
+    }
+
+    /// Folds the reserve of each order leaving the books back into it, so callers see
+    /// the whole unfilled quantity.
+    pub(crate) fn release(&mut self, orders: &mut [Order]) {
+        if self.reserves.is_empty() {
+            return;
+        }
+        for order in orders {
+            if let Some(reserve) = self.reserves.remove(&order.id) {
+                self.displayed.remove(&order.id);
+                order.quantity += reserve.quantity;
+            }
+        }
+    }
+
+    pub(crate) fn reserves(&self) -> impl Iterator<Item = &Order> {
+        self.reserves.values()
+    }
+
+    /// Replaces every reserve, with each displayed quantity taken from the matching
+    /// slice in `resting`.
+    pub(crate) fn restore<'a>(&mut self, reserves: Vec<Order>, resting: impl IntoIterator<Item = &'a Order>) {
+        self.reserves = reserves.into_iter().map(|reserve| (reserve.id, reserve)).collect();
+        self.displayed = resting.into_iter()
+            .filter(|order| self.reserves.contains_key(&order.id))
+            .map(|order| (order.id, order.quantity))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, OrderSide};
+
+    #[test]
+    fn test_reserve_refills_as_slices_fill() {
+        let mut icebergs = IcebergReserves::default();
+        let order = new_order(1, 0, 250, 100.0, OrderSide::Sell).with_kind(OrderKind::Iceberg { display_quantity: 100 });
+        let slice = icebergs.split(order);
+        assert_eq!(slice.quantity, 100);
+
+        let trade = |quantity| Trade { quantity, maker_order_id: 1, ..Trade::between(&new_order(2, 0, quantity, 100.0, OrderSide::Buy), &slice, slice.price) };
+        assert!(icebergs.refill(&[trade(60)]).is_empty());
+        assert_eq!(icebergs.refill(&[trade(40)]).iter().map(|slice| slice.quantity).collect::<Vec<_>>(), vec![100]);
+        assert_eq!(icebergs.refill(&[trade(100)]).iter().map(|slice| slice.quantity).collect::<Vec<_>>(), vec![50]);
+        assert!(icebergs.is_empty());
+
+        // A cancelled iceberg hands back its reserve with the resting slice
+        let mut resting = [icebergs.split(new_order(3, 0, 300, 100.0, OrderSide::Buy).with_kind(OrderKind::Iceberg { display_quantity: 100 }))];
+        icebergs.release(&mut resting);
+        assert_eq!(resting[0].quantity, 300);
+        assert!(icebergs.is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::types::order::OrderSide;
+use crate::types::symbol_mapping::SymbolId;
+
+pub type BestPrices = (Option<u64>, Option<u64>);
+
+/// Calendar-style spread instrument priced as `front - back`. Prices are
+/// unsigned, so an implied spread price below zero is not quoted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ImpliedSpread {
+    pub spread: SymbolId,
+    pub front: SymbolId,
+    pub back: SymbolId,
+}
+
+impl ImpliedSpread {
+    #[inline(always)]
+    pub fn legs(&self) -> [SymbolId; 3] {
+        [self.spread, self.front, self.back]
+    }
+
+    #[inline(always)]
+    pub fn involves(&self, symbol: SymbolId) -> bool {
+        self.legs().contains(&symbol)
+    }
+
+    /// Implied-in: spread prices from the two outright books.
+    #[inline(always)]
+    pub fn implied_spread(&self, (front_bid, front_ask): BestPrices, (back_bid, back_ask): BestPrices) -> BestPrices {
+        (
+            front_bid.zip(back_ask).and_then(|(bid, ask)| bid.checked_sub(ask)),
+            front_ask.zip(back_bid).and_then(|(ask, bid)| ask.checked_sub(bid)),
+        )
+    }
+
+    /// Implied-out: front leg prices from the spread and back books.
+    #[inline(always)]
+    pub fn implied_front(&self, (spread_bid, spread_ask): BestPrices, (back_bid, back_ask): BestPrices) -> BestPrices {
+        (
+            spread_bid.zip(back_bid).and_then(|(spread, back)| spread.checked_add(back)),
+            spread_ask.zip(back_ask).and_then(|(spread, back)| spread.checked_add(back)),
+        )
+    }
+
+    /// Implied-out: back leg prices from the spread and front books.
+    #[inline(always)]
+    pub fn implied_back(&self, (spread_bid, spread_ask): BestPrices, (front_bid, front_ask): BestPrices) -> BestPrices {
+        (
+            front_bid.zip(spread_ask).and_then(|(front, spread)| front.checked_sub(spread)),
+            front_ask.zip(spread_bid).and_then(|(front, spread)| front.checked_sub(spread)),
+        )
+    }
+}
+
+/// `quantity` of one resting order filled against prices implied from the other two
+/// legs of a spread rather than against an order in its own book. Outright legs fill
+/// at their own price and the spread leg at `front - back`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ImpliedFill {
+    pub symbol: SymbolId,
+    pub order_id: u64,
+    pub side: OrderSide,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+/// Highest bid and lowest ask across two quotes.
+#[inline(always)]
+pub fn best_of((bid_a, ask_a): BestPrices, (bid_b, ask_b): BestPrices) -> BestPrices {
+    (
+        bid_a.max(bid_b),
+        match (ask_a, ask_b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (ask, None) | (None, ask) => ask,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_implied_prices() {
+        let spread = ImpliedSpread { spread: 2, front: 0, back: 1 };
+        let front = (Some(1_050), Some(1_060));
+        let back = (Some(1_000), Some(1_005));
+
+        assert_eq!(spread.implied_spread(front, back), (Some(45), Some(60)));
+        assert_eq!(spread.implied_front((Some(40), Some(70)), back), (Some(1_040), Some(1_075)));
+        assert_eq!(spread.implied_back((Some(40), Some(70)), front), (Some(980), Some(1_020)));
+        assert_eq!(spread.implied_spread((Some(900), None), back), (None, None));
+        assert_eq!(best_of((Some(10), None), (Some(9), Some(12))), (Some(10), Some(12)));
+    }
+}
@@ -0,0 +1,116 @@
+use rustc_hash::FxHashMap;
+
+use crate::types::{order::Order, symbol_mapping::SymbolId};
+
+#[derive(Debug, Clone, Copy, Default)]
+struct LedgerEntry {
+    submitted: u64,
+    filled: u64,
+    cancelled: u64,
+    expired: u64,
+}
+
+/// An order whose submitted quantity is not covered by what happened to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuantityDiscrepancy {
+    pub symbol: SymbolId,
+    pub order_id: u64,
+    pub submitted: u64,
+    pub filled: u64,
+    pub cancelled: u64,
+    pub expired: u64,
+    pub resting: u64,
+}
+
+/// Per-order quantity accounting: everything submitted must end up filled, cancelled,
+/// expired or still resting. Keeps one entry per order ever submitted.
+#[derive(Debug, Default)]
+pub(crate) struct QuantityLedger {
+    entries: FxHashMap<(SymbolId, u64), LedgerEntry>,
+}
+
+impl QuantityLedger {
+    #[inline(always)]
+    fn entry(&mut self, order: &Order) -> &mut LedgerEntry {
+        self.entries.entry((order.symbol, order.id)).or_default()
+    }
+
+    #[inline(always)]
+    pub(crate) fn submit(&mut self, symbol: SymbolId, order_id: u64, quantity: u64) {
+        self.entries.entry((symbol, order_id)).or_default().submitted += quantity;
+    }
+
+    #[inline(always)]
+    pub(crate) fn fill(&mut self, symbol: SymbolId, order_id: u64, quantity: u64) {
+        self.entries.entry((symbol, order_id)).or_default().filled += quantity;
+    }
+
+    #[inline(always)]
+    pub(crate) fn cancel(&mut self, order: &Order) {
+        self.entry(order).cancelled += order.quantity;
+    }
+
+    #[inline(always)]
+    pub(crate) fn reduce(&mut self, symbol: SymbolId, order_id: u64, quantity: u64) {
+        self.entries.entry((symbol, order_id)).or_default().cancelled += quantity;
+    }
+
+    #[inline(always)]
+    pub(crate) fn expire(&mut self, order: &Order) {
+        self.entry(order).expired += order.quantity;
+    }
+
+    /// Discrepancies against the orders resting now, ordered by symbol then order id.
+    pub(crate) fn reconcile<'a>(&self, resting: impl IntoIterator<Item = &'a Order>) -> Vec<QuantityDiscrepancy> {
+        let mut resting_quantities: FxHashMap<(SymbolId, u64), u64> = FxHashMap::default();
+        for order in resting {
+            *resting_quantities.entry((order.symbol, order.id)).or_default() += order.quantity;
+        }
+
+        let mut discrepancies: Vec<QuantityDiscrepancy> = self.entries.iter()
+            .map(|(&key, entry)| (key, *entry))
+            .chain(resting_quantities.keys()
+                .filter(|key| !self.entries.contains_key(key))
+                .map(|&key| (key, LedgerEntry::default())))
+            .filter_map(|((symbol, order_id), entry)| {
+                let resting = resting_quantities.get(&(symbol, order_id)).copied().unwrap_or(0);
+                let accounted = entry.filled + entry.cancelled + entry.expired + resting;
+                (accounted != entry.submitted).then_some(QuantityDiscrepancy {
+                    symbol,
+                    order_id,
+                    submitted: entry.submitted,
+                    filled: entry.filled,
+                    cancelled: entry.cancelled,
+                    expired: entry.expired,
+                    resting,
+                })
+            })
+            .collect();
+        discrepancies.sort_unstable_by_key(|discrepancy| (discrepancy.symbol, discrepancy.order_id));
+        discrepancies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, OrderSide};
+
+    #[test]
+    fn test_ledger_flags_unaccounted_quantity() {
+        let mut ledger = QuantityLedger::default();
+        let filled = new_order(1, 0, 100, 100.0, OrderSide::Buy);
+        let resting = new_order(2, 0, 50, 101.0, OrderSide::Sell);
+        let lost = new_order(3, 0, 70, 99.0, OrderSide::Buy);
+        let phantom = new_order(4, 0, 10, 99.0, OrderSide::Buy);
+        for order in [&filled, &resting, &lost] {
+            ledger.submit(order.symbol, order.id, order.quantity);
+        }
+        ledger.fill(filled.symbol, filled.id, filled.quantity);
+
+        let discrepancies = ledger.reconcile([&resting, &phantom]);
+        assert_eq!(discrepancies.iter().map(|discrepancy| discrepancy.order_id).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!((discrepancies[0].submitted, discrepancies[0].resting), (70, 0));
+        assert_eq!((discrepancies[1].submitted, discrepancies[1].resting), (0, 10));
+    }
+}
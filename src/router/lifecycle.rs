@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rustc_hash::FxHashMap;
+
+use crate::types::order::Order;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+/// When an order live in the router reached each stage, in nanoseconds since the Unix
+/// epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct OrderTimeline {
+    /// `route_order` was called.
+    pub received_at: u64,
+    /// The book or the imbalance hold took the order.
+    pub accepted_at: u64,
+    pub first_fill_at: Option<u64>,
+    /// Submitted quantity not yet filled, reduced or cancelled.
+    pub remaining: u64,
+}
+
+/// Spread of one lifecycle interval over a symbol's most recent orders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct LatencyDistribution {
+    pub samples: usize,
+    pub min: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+}
+
+impl LatencyDistribution {
+    fn from_samples(samples: &VecDeque<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted: Vec<u64> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+        // Nearest rank: the smallest sample at or above the given share of the rest
+        let rank = |percentile: usize| Duration::from_nanos(sorted[(sorted.len() * percentile).div_ceil(100).max(1) - 1]);
+        let total: u128 = sorted.iter().map(|&nanos| nanos as u128).sum();
+        Self {
+            samples: sorted.len(),
+            min: Duration::from_nanos(sorted[0]),
+            p50: rank(50),
+            p90: rank(90),
+            p99: rank(99),
+            max: Duration::from_nanos(sorted[sorted.len() - 1]),
+            mean: Duration::from_nanos((total / sorted.len() as u128) as u64),
+        }
+    }
+}
+
+/// Execution quality for one symbol, over the last `window` samples of each interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct LifecycleMetrics {
+    pub symbol: SymbolId,
+    /// Received to accepted.
+    pub time_to_ack: LatencyDistribution,
+    /// Accepted to first fill.
+    pub time_to_first_fill: LatencyDistribution,
+    /// Accepted to fully filled.
+    pub time_to_fill: LatencyDistribution,
+    /// Accepted to leaving the router unfilled or partly filled, whether cancelled,
+    /// expired or evicted.
+    pub resting_before_cancel: LatencyDistribution,
+}
+
+#[derive(Debug, Default)]
+struct SymbolSamples {
+    time_to_ack: VecDeque<u64>,
+    time_to_first_fill: VecDeque<u64>,
+    time_to_fill: VecDeque<u64>,
+    resting_before_cancel: VecDeque<u64>,
+}
+
+/// Per-order timelines for orders still live in the router, and per-symbol samples of
+/// the intervals between their stages, each capped at `window`.
+#[derive(Debug)]
+pub(crate) struct LifecycleTracker {
+    window: usize,
+    timelines: FxHashMap<(SymbolId, u64), OrderTimeline>,
+    samples: FxHashMap<SymbolId, SymbolSamples>,
+}
+
+#[inline(always)]
+fn push_sample(samples: &mut VecDeque<u64>, window: usize, nanos: u64) {
+    if samples.len() == window {
+        samples.pop_front();
+    }
+    samples.push_back(nanos);
+}
+
+impl LifecycleTracker {
+    pub(crate) fn new(window: usize) -> Self {
+        Self { window: window.max(1), timelines: FxHashMap::default(), samples: FxHashMap::default() }
+    }
+
+    pub(crate) fn accept(&mut self, symbol: SymbolId, order_id: u64, quantity: u64, received_at: u64, accepted_at: u64) {
+        let window = self.window;
+        let samples = self.samples.entry(symbol).or_default();
+        push_sample(&mut samples.time_to_ack, window, accepted_at.saturating_sub(received_at));
+        self.timelines.insert((symbol, order_id), OrderTimeline { received_at, accepted_at, first_fill_at: None, remaining: quantity });
+    }
+
+    pub(crate) fn fill(&mut self, trades: &[Trade], now: u64) {
+        for trade in trades {
+            for order_id in [trade.taker_order_id, trade.maker_order_id] {
+                self.fill_order(trade.symbol, order_id, trade.quantity, now);
+            }
+        }
+    }
+
+    pub(crate) fn fill_order(&mut self, symbol: SymbolId, order_id: u64, quantity: u64, now: u64) {
+        let window = self.window;
+        let Some(timeline) = self.timelines.get_mut(&(symbol, order_id)) else { return };
+        let samples = self.samples.entry(symbol).or_default();
+        if timeline.first_fill_at.is_none() {
+            timeline.first_fill_at = Some(now);
+            push_sample(&mut samples.time_to_first_fill, window, now.saturating_sub(timeline.accepted_at));
+        }
+        timeline.remaining = timeline.remaining.saturating_sub(quantity);
+        if timeline.remaining == 0 {
+            push_sample(&mut samples.time_to_fill, window, now.saturating_sub(timeline.accepted_at));
+            self.timelines.remove(&(symbol, order_id));
+        }
+    }
+
+    pub(crate) fn reduce(&mut self, symbol: SymbolId, order_id: u64, quantity: u64) {
+        if let Some(timeline) = self.timelines.get_mut(&(symbol, order_id)) {
+            timeline.remaining = timeline.remaining.saturating_sub(quantity);
+        }
+    }
+
+    /// Ends the timeline of every order leaving the router without filling.
+    pub(crate) fn cancel(&mut self, orders: &[Order], now: u64) {
+        let window = self.window;
+        for order in orders {
+            let Some(timeline) = self.timelines.remove(&(order.symbol, order.id)) else { continue };
+            let samples = self.samples.entry(order.symbol).or_default();
+            push_sample(&mut samples.resting_before_cancel, window, now.saturating_sub(timeline.accepted_at));
+        }
+    }
+
+    /// Forgets live timelines, e.g. when a restore replaces the orders they belong to.
+    pub(crate) fn clear_timelines(&mut self) {
+        self.timelines.clear();
+    }
+
+    pub(crate) fn timeline(&self, symbol: SymbolId, order_id: u64) -> Option<OrderTimeline> {
+        self.timelines.get(&(symbol, order_id)).copied()
+    }
+
+    pub(crate) fn metrics(&self, symbol: SymbolId) -> LifecycleMetrics {
+        let Some(samples) = self.samples.get(&symbol) else {
+            return LifecycleMetrics { symbol, ..Default::default() };
+        };
+        LifecycleMetrics {
+            symbol,
+            time_to_ack: LatencyDistribution::from_samples(&samples.time_to_ack),
+            time_to_first_fill: LatencyDistribution::from_samples(&samples.time_to_first_fill),
+            time_to_fill: LatencyDistribution::from_samples(&samples.time_to_fill),
+            resting_before_cancel: LatencyDistribution::from_samples(&samples.resting_before_cancel),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, OrderSide};
+
+    #[test]
+    fn test_intervals_follow_each_order() {
+        let mut tracker = LifecycleTracker::new(3);
+        let bid = new_order(1, 0, 100, 100.0, OrderSide::Buy);
+        let ask = new_order(2, 0, 60, 100.0, OrderSide::Sell);
+        tracker.accept(0, 1, 100, 1_000, 1_010);
+        tracker.accept(0, 2, 60, 2_000, 2_040);
+
+        tracker.fill(&[Trade::between(&bid, &ask, bid.price)], 5_000);
+        assert_eq!(tracker.timeline(0, 1).map(|timeline| (timeline.first_fill_at, timeline.remaining)), Some((Some(5_000), 40)));
+        assert!(tracker.timeline(0, 2).is_none());
+        tracker.cancel(&[bid], 9_010);
+
+        let metrics = tracker.metrics(0);
+        assert_eq!((metrics.time_to_ack.min, metrics.time_to_ack.max), (Duration::from_nanos(10), Duration::from_nanos(40)));
+        assert_eq!(metrics.time_to_first_fill.samples, 2);
+        assert_eq!(metrics.time_to_fill.p50, Duration::from_nanos(2_960));
+        assert_eq!(metrics.resting_before_cancel.mean, Duration::from_nanos(8_000));
+        assert_eq!(tracker.metrics(7).time_to_ack.samples, 0);
+
+        // Only the last `window` samples count
+        (0..5).for_each(|id| tracker.accept(0, 10 + id, 1, 0, id * 100));
+        let time_to_ack = tracker.metrics(0).time_to_ack;
+        assert_eq!((time_to_ack.samples, time_to_ack.min, time_to_ack.p99), (3, Duration::from_nanos(200), Duration::from_nanos(400)));
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::types::order::ParticipantId;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MessageRateConfig {
+    /// Messages per trade above which a participant is alerted, `None` for no limit.
+    pub max_order_to_trade: Option<f64>,
+    /// Messages a participant must have sent before its ratio is checked, so a few
+    /// orders early in the day do not alert.
+    pub min_messages: u64,
+    /// Messages per second over `rate_window` above which a participant is alerted,
+    /// `None` for no limit.
+    pub max_messages_per_second: Option<f64>,
+    pub rate_window: Duration,
+}
+
+impl Default for MessageRateConfig {
+    fn default() -> Self {
+        Self { max_order_to_trade: None, min_messages: 100, max_messages_per_second: None, rate_window: Duration::from_secs(1) }
+    }
+}
+
+/// One participant's messages and executions since analytics were enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageStats {
+    pub participant: ParticipantId,
+    /// Orders routed, whether accepted or rejected.
+    pub adds: u64,
+    /// Orders cancelled by id.
+    pub cancels: u64,
+    /// Reductions.
+    pub modifies: u64,
+    /// Fills on either side of a trade, implied fills included.
+    pub trades: u64,
+    /// Messages over the last `rate_window`, per second.
+    pub messages_per_second: f64,
+}
+
+impl MessageStats {
+    #[inline(always)]
+    pub fn messages(&self) -> u64 {
+        self.adds + self.cancels + self.modifies
+    }
+
+    /// Messages per trade, counting a participant without trades as having one.
+    #[inline(always)]
+    pub fn order_to_trade_ratio(&self) -> f64 {
+        self.messages() as f64 / self.trades.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MessageAlertKind {
+    OrderToTradeRatio,
+    MessageRate,
+}
+
+/// A participant crossed a `MessageRateConfig` threshold. Sent once per crossing; the
+/// participant must drop back under the threshold before it alerts again.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MessageAlert {
+    pub participant: ParticipantId,
+    pub kind: MessageAlertKind,
+    pub value: f64,
+    pub threshold: f64,
+    /// Nanoseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Default)]
+struct ParticipantMessages {
+    stats: MessageStats,
+    /// Times of the messages inside the rate window, oldest first.
+    recent: VecDeque<u64>,
+    ratio_alerted: bool,
+    rate_alerted: bool,
+}
+
+/// Per-participant message counts and rates, checked against the configured
+/// thresholds after every message and trade.
+#[derive(Debug)]
+pub(crate) struct MessageRateTracker {
+    config: MessageRateConfig,
+    participants: FxHashMap<ParticipantId, ParticipantMessages>,
+    alerts: Vec<MessageAlert>,
+}
+
+impl MessageRateTracker {
+    pub(crate) fn new(config: MessageRateConfig) -> Self {
+        Self { config, participants: FxHashMap::default(), alerts: Vec::new() }
+    }
+
+    pub(crate) fn add(&mut self, participant: ParticipantId, now: u64) {
+        self.message(participant, now, |stats| stats.adds += 1);
+    }
+
+    pub(crate) fn cancel(&mut self, participant: ParticipantId, now: u64) {
+        self.message(participant, now, |stats| stats.cancels += 1);
+    }
+
+    pub(crate) fn modify(&mut self, participant: ParticipantId, now: u64) {
+        self.message(participant, now, |stats| stats.modifies += 1);
+    }
+
+    pub(crate) fn trade(&mut self, participant: ParticipantId, now: u64) {
+        let entry = self.participants.entry(participant).or_default();
+        entry.stats.participant = participant;
+        entry.stats.trades += 1;
+        self.check(participant, now);
+    }
+
+    fn message(&mut self, participant: ParticipantId, now: u64, count: impl FnOnce(&mut MessageStats)) {
+        let window = self.config.rate_window.as_nanos() as u64;
+        let entry = self.participants.entry(participant).or_default();
+        entry.stats.participant = participant;
+        count(&mut entry.stats);
+        entry.recent.push_back(now);
+        while entry.recent.front().is_some_and(|&at| at.saturating_add(window) <= now) {
+            entry.recent.pop_front();
+        }
+        self.check(participant, now);
+    }
+
+    // Raises an alert for each threshold the participant has just crossed
+    fn check(&mut self, participant: ParticipantId, now: u64) {
+        let config = self.config;
+        let Some(entry) = self.participants.get_mut(&participant) else { return };
+        let stats = Self::stats_of(config, entry, now);
+        if let Some(threshold) = config.max_order_to_trade {
+            let ratio = stats.order_to_trade_ratio();
+            let breached = stats.messages() >= config.min_messages && ratio > threshold;
+            if breached && !entry.ratio_alerted {
+                self.alerts.push(MessageAlert { participant, kind: MessageAlertKind::OrderToTradeRatio, value: ratio, threshold, timestamp: now });
+            }
+            entry.ratio_alerted = breached;
+        }
+        if let Some(threshold) = config.max_messages_per_second {
+            let breached = stats.messages_per_second > threshold;
+            if breached && !entry.rate_alerted {
+                self.alerts.push(MessageAlert { participant, kind: MessageAlertKind::MessageRate, value: stats.messages_per_second, threshold, timestamp: now });
+            }
+            entry.rate_alerted = breached;
+        }
+    }
+
+    fn stats_of(config: MessageRateConfig, entry: &ParticipantMessages, now: u64) -> MessageStats {
+        let window = config.rate_window.as_nanos() as u64;
+        let recent = entry.recent.iter().filter(|&&at| at.saturating_add(window) > now).count();
+        let seconds = config.rate_window.as_secs_f64();
+        MessageStats { messages_per_second: if seconds > 0.0 { recent as f64 / seconds } else { 0.0 }, ..entry.stats }
+    }
+
+    pub(crate) fn stats(&self, participant: ParticipantId, now: u64) -> Option<MessageStats> {
+        Some(Self::stats_of(self.config, self.participants.get(&participant)?, now))
+    }
+
+    /// Every participant's stats, by participant.
+    pub(crate) fn all_stats(&self, now: u64) -> Vec<MessageStats> {
+        let mut stats: Vec<MessageStats> = self.participants.values().map(|entry| Self::stats_of(self.config, entry, now)).collect();
+        stats.sort_unstable_by_key(|stats| stats.participant);
+        stats
+    }
+
+    pub(crate) fn take_alerts(&mut self) -> Vec<MessageAlert> {
+        std::mem::take(&mut self.alerts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alerts_once_per_crossing() {
+        let config = MessageRateConfig {
+            max_order_to_trade: Some(2.0),
+            min_messages: 3,
+            max_messages_per_second: Some(3.0),
+            rate_window: Duration::from_secs(1),
+        };
+        let mut tracker = MessageRateTracker::new(config);
+        let second = 1_000_000_000;
+        tracker.add(7, 0);
+        tracker.add(7, 1);
+        assert!(tracker.take_alerts().is_empty());
+        tracker.cancel(7, 2);
+        let alerts = tracker.take_alerts();
+        assert_eq!(alerts.iter().map(|alert| (alert.kind, alert.value)).collect::<Vec<_>>(), vec![(MessageAlertKind::OrderToTradeRatio, 3.0)]);
+
+        tracker.modify(7, 3);
+        assert_eq!(tracker.take_alerts().iter().map(|alert| (alert.kind, alert.value)).collect::<Vec<_>>(), vec![(MessageAlertKind::MessageRate, 4.0)]);
+        tracker.trade(7, 4);
+        tracker.trade(7, 5);
+        tracker.trade(7, 6);
+        tracker.add(7, 2 * second);
+        tracker.add(7, 2 * second + 1);
+        assert!(tracker.take_alerts().is_empty());
+        tracker.add(7, 2 * second + 2);
+        assert_eq!(tracker.take_alerts().iter().map(|alert| (alert.kind, alert.value)).collect::<Vec<_>>(), vec![(MessageAlertKind::OrderToTradeRatio, 7.0 / 3.0)]);
+
+        let stats = tracker.stats(7, 2 * second + 2).unwrap();
+        assert_eq!((stats.adds, stats.cancels, stats.modifies, stats.trades, stats.messages_per_second), (5, 1, 1, 3, 3.0));
+        assert_eq!(tracker.all_stats(3 * second + 2)[0].messages_per_second, 0.0);
+        assert!(tracker.stats(8, 0).is_none());
+    }
+}
@@ -1,5 +1,43 @@
 pub mod order_router;
 pub mod book_route;
+pub mod sharded_router;
+pub mod session;
+pub mod health;
+pub mod implied;
+pub mod expiry;
+pub mod ledger;
+pub mod lifecycle;
+pub mod best_execution;
+pub mod message_rate;
+pub mod exposure;
+pub mod dedup;
+pub mod ack;
+pub mod cancel;
+pub(crate) mod quote_guard;
+pub mod multi_tenant;
+pub mod report;
+pub mod reject;
+pub mod obligations;
+pub mod delisting;
+pub(crate) mod iceberg;
 
 pub use order_router::OrderRouter;
-pub use book_route::BookRoute;
\ No newline at end of file
+pub use book_route::BookRoute;
+pub use sharded_router::{AckDelay, AckDelayConfig, AdaptiveBatching, JitterConfig, ShardAssignment, ShardedRouter, ShardConfig, TimedOutOrder, TryRouteError, WaitStrategy};
+pub use session::{SessionState, SessionStats, SessionSummary};
+pub use implied::{ImpliedFill, ImpliedSpread};
+pub use health::{BatchStats, QueueDepth, Readiness, ShardHealth};
+pub use expiry::{ExpiryReason, OrderExpired};
+pub use ledger::QuantityDiscrepancy;
+pub use lifecycle::{LatencyDistribution, LifecycleMetrics, OrderTimeline};
+pub use best_execution::{BestExecutionSummary, ExecutionReport};
+pub use message_rate::{MessageAlert, MessageAlertKind, MessageRateConfig, MessageStats};
+pub use exposure::{AccountExposure, AccountPosition};
+pub use dedup::{DedupEviction, DedupStats, DedupWindow};
+pub use ack::{OrderAck, OrderReduced};
+pub use cancel::BatchCancelResult;
+pub use report::{BookReport, BookReportConfig, ReportLevel};
+pub use reject::{OrderRejected, RejectCode};
+pub use delisting::SymbolDelisted;
+pub use obligations::{BreachKind, ObligationBreach, ObligationCompliance, ObligationMonitor, QuoteFault, QuoteObligation};
+pub use multi_tenant::{MultiTenantEngine, TenantConfig, TenantId, TenantQuota};
\ No newline at end of file
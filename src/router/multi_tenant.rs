@@ -0,0 +1,209 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::engine::{OrderBookType, ParticipantLimits};
+use crate::router::OrderRouter;
+use crate::types::order::Order;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::Trade;
+
+pub type TenantId = u32;
+
+/// Resource caps for one tenant. `None` leaves that resource uncapped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantQuota {
+    pub max_symbols: Option<usize>,
+    /// Orders accepted per session, counted from the tenant router's session stats.
+    pub max_orders_per_session: Option<u64>,
+}
+
+impl TenantQuota {
+    pub fn with_max_symbols(mut self, max_symbols: usize) -> Self {
+        self.max_symbols = Some(max_symbols);
+        self
+    }
+
+    pub fn with_max_orders_per_session(mut self, max_orders: u64) -> Self {
+        self.max_orders_per_session = Some(max_orders);
+        self
+    }
+}
+
+/// Everything a tenant's router is built from. Symbol ids are the tenant's own: the
+/// same id in two tenants names two unrelated books.
+#[derive(Debug, Clone)]
+pub struct TenantConfig {
+    pub symbols: FxHashSet<SymbolId>,
+    pub order_book_type: OrderBookType,
+    pub participant_limits: ParticipantLimits,
+    pub max_depth: Option<usize>,
+    pub quota: TenantQuota,
+}
+
+impl TenantConfig {
+    pub fn new(symbols: FxHashSet<SymbolId>, order_book_type: OrderBookType) -> Self {
+        Self {
+            symbols,
+            order_book_type,
+            participant_limits: ParticipantLimits::default(),
+            max_depth: None,
+            quota: TenantQuota::default(),
+        }
+    }
+
+    pub fn with_participant_limits(mut self, limits: ParticipantLimits) -> Self {
+        self.participant_limits = limits;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_levels: usize) -> Self {
+        self.max_depth = Some(max_levels);
+        self
+    }
+
+    pub fn with_quota(mut self, quota: TenantQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+}
+
+struct Tenant {
+    router: OrderRouter,
+    quota: TenantQuota,
+}
+
+/// Isolated `OrderRouter`s, one per tenant, in one process. Tenants share nothing:
+/// each has its own symbol namespace, risk configuration, listeners and snapshots,
+/// and is held to its own `TenantQuota`.
+#[derive(Default)]
+pub struct MultiTenantEngine {
+    tenants: FxHashMap<TenantId, Tenant>,
+}
+
+impl MultiTenantEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_tenant(&mut self, tenant: TenantId, config: TenantConfig) -> Result<(), &'static str> {
+        if self.tenants.contains_key(&tenant) {
+            return Err("Tenant already exists");
+        }
+        if config.quota.max_symbols.is_some_and(|max_symbols| config.symbols.len() > max_symbols) {
+            return Err("Tenant symbol quota exceeded");
+        }
+
+        let mut router = OrderRouter::new_direct(config.symbols, config.order_book_type);
+        router.set_participant_limits(config.participant_limits);
+        router.set_max_depth(config.max_depth);
+        self.tenants.insert(tenant, Tenant { router, quota: config.quota });
+        Ok(())
+    }
+
+    /// Removes the tenant and hands back its router with everything still in it.
+    pub fn remove_tenant(&mut self, tenant: TenantId) -> Option<OrderRouter> {
+        self.tenants.remove(&tenant).map(|tenant| tenant.router)
+    }
+
+    #[inline(always)]
+    pub fn route_order(&mut self, tenant: TenantId, order: Order) -> Result<(), &'static str> {
+        let tenant = self.tenants.get_mut(&tenant).ok_or("Unknown tenant")?;
+        if tenant.quota.max_orders_per_session.is_some_and(|max_orders| tenant.router.session_stats().orders_routed >= max_orders) {
+            return Err("Tenant order quota exceeded");
+        }
+        tenant.router.route_order(order)
+    }
+
+    /// Matches every tenant's books and returns each tenant's trades, by tenant id.
+    pub fn match_all_orders(&mut self) -> Vec<(TenantId, Vec<Trade>)> {
+        let mut trades: Vec<_> = self.tenants.iter_mut()
+            .map(|(&tenant_id, tenant)| (tenant_id, tenant.router.match_all_orders()))
+            .filter(|(_, trades)| !trades.is_empty())
+            .collect();
+        trades.sort_unstable_by_key(|(tenant_id, _)| *tenant_id);
+        trades
+    }
+
+    /// The tenant's router, for its listeners, snapshots and session control.
+    pub fn tenant(&self, tenant: TenantId) -> Option<&OrderRouter> {
+        self.tenants.get(&tenant).map(|tenant| &tenant.router)
+    }
+
+    pub fn tenant_mut(&mut self, tenant: TenantId) -> Option<&mut OrderRouter> {
+        self.tenants.get_mut(&tenant).map(|tenant| &mut tenant.router)
+    }
+
+    pub fn quota(&self, tenant: TenantId) -> Option<TenantQuota> {
+        self.tenants.get(&tenant).map(|tenant| tenant.quota)
+    }
+
+    pub fn set_quota(&mut self, tenant: TenantId, quota: TenantQuota) -> Result<(), &'static str> {
+        let tenant = self.tenants.get_mut(&tenant).ok_or("Unknown tenant")?;
+        if quota.max_symbols.is_some_and(|max_symbols| tenant.router.get_symbols().len() > max_symbols) {
+            return Err("Tenant symbol quota exceeded");
+        }
+        tenant.quota = quota;
+        Ok(())
+    }
+
+    /// Tenant ids in ascending order.
+    pub fn tenant_ids(&self) -> Vec<TenantId> {
+        let mut tenants: Vec<TenantId> = self.tenants.keys().copied().collect();
+        tenants.sort_unstable();
+        tenants
+    }
+
+    #[inline(always)]
+    pub fn tenant_count(&self) -> usize {
+        self.tenants.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, price_to_u64, OrderSide};
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_tenants_are_isolated() {
+        let mut engine = MultiTenantEngine::new();
+        engine.add_tenant(1, TenantConfig::new(FxHashSet::from_iter([0]), OrderBookType::HashMap)).unwrap();
+        let limits = ParticipantLimits::default().with_max_orders_per_book(1);
+        engine.add_tenant(2, TenantConfig::new(FxHashSet::from_iter([0, 5]), OrderBookType::Persistent).with_participant_limits(limits)).unwrap();
+        assert_eq!(engine.add_tenant(2, TenantConfig::new(FxHashSet::default(), OrderBookType::HashMap)), Err("Tenant already exists"));
+
+        engine.route_order(1, new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        engine.route_order(2, new_order(1, 0, 10, 100.0, OrderSide::Sell)).unwrap();
+        engine.route_order(1, new_order(2, 0, 10, 101.0, OrderSide::Buy)).unwrap();
+        assert!(engine.route_order(2, new_order(2, 0, 10, 101.0, OrderSide::Sell)).is_err());
+        assert_eq!(engine.route_order(1, new_order(3, 5, 10, 100.0, OrderSide::Buy)), Err("Invalid symbol"));
+        assert!(engine.match_all_orders().is_empty());
+
+        // Same symbol id, separate books: nothing crossed between the tenants
+        assert_eq!(engine.tenant(1).unwrap().get_best_prices(0), Some((Some(price_to_u64(101.0)), None)));
+        assert_eq!(engine.tenant(2).unwrap().get_best_prices(0), Some((None, Some(price_to_u64(100.0)))));
+        assert_eq!(engine.tenant_ids(), vec![1, 2]);
+        assert!(engine.remove_tenant(1).is_some());
+        assert_eq!(engine.route_order(1, new_order(4, 0, 10, 100.0, OrderSide::Buy)), Err("Unknown tenant"));
+    }
+
+    #[test]
+    fn test_tenant_quotas() {
+        let mut engine = MultiTenantEngine::new();
+        let quota = TenantQuota::default().with_max_symbols(1).with_max_orders_per_session(2);
+        let config = TenantConfig::new(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap).with_quota(quota);
+        assert_eq!(engine.add_tenant(1, config.clone()), Err("Tenant symbol quota exceeded"));
+
+        engine.add_tenant(1, config.with_quota(TenantQuota::default().with_max_orders_per_session(2))).unwrap();
+        engine.route_order(1, new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        engine.route_order(1, new_order(2, 1, 10, 100.0, OrderSide::Buy)).unwrap();
+        assert_eq!(engine.route_order(1, new_order(3, 0, 10, 100.0, OrderSide::Buy)), Err("Tenant order quota exceeded"));
+        assert_eq!(engine.set_quota(1, quota), Err("Tenant symbol quota exceeded"));
+
+        // A new session starts a fresh order allowance
+        let router = engine.tenant_mut(1).unwrap();
+        router.roll_session();
+        router.open_session().unwrap();
+        assert!(engine.route_order(1, new_order(3, 0, 10, 100.0, OrderSide::Buy)).is_ok());
+    }
+}
@@ -0,0 +1,311 @@
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::Sender;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::router::OrderRouter;
+use crate::types::order::{Order, OrderSide, ParticipantId};
+use crate::types::symbol_mapping::SymbolId;
+
+/// Basis points in a whole, so a `min_presence_bps` of 10_000 means the whole session.
+pub const BPS: u32 = 10_000;
+
+/// What a registered market maker must show in a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct QuoteObligation {
+    /// Widest allowed distance between its own best bid and best ask.
+    pub max_spread: u64,
+    /// Smallest quantity it must show at its best price on each side.
+    pub min_quantity: u64,
+    /// Share of the session, in basis points, its quote must meet both limits.
+    pub min_presence_bps: u32,
+}
+
+/// Why a market maker's quote does not meet its obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum QuoteFault {
+    NoQuote,
+    /// Only one side is quoted; `missing` is the side without an order.
+    OneSided { missing: OrderSide },
+    SpreadTooWide { spread: u64 },
+    /// Less than the minimum quantity at its best price on at least one side.
+    TooSmall { bid_quantity: u64, ask_quantity: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum BreachKind {
+    /// A compliant quote stopped meeting the obligation.
+    Quote(QuoteFault),
+    /// The session ended with the quote compliant for less than `min_presence_bps`.
+    Presence { presence_bps: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ObligationBreach {
+    pub participant: ParticipantId,
+    pub symbol: SymbolId,
+    pub kind: BreachKind,
+    /// Session time monitored when the breach was detected.
+    pub session_elapsed: Duration,
+}
+
+/// One market maker's record in one symbol for the session so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ObligationCompliance {
+    pub participant: ParticipantId,
+    pub symbol: SymbolId,
+    pub obligation: QuoteObligation,
+    /// Time the quote met the obligation.
+    pub compliant_time: Duration,
+    pub session_elapsed: Duration,
+    pub presence_bps: u32,
+    /// The quote's state at the last observation.
+    pub fault: Option<QuoteFault>,
+}
+
+impl ObligationCompliance {
+    #[inline(always)]
+    pub fn is_met(&self) -> bool {
+        self.presence_bps >= self.obligation.min_presence_bps
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Tracked {
+    obligation: QuoteObligation,
+    compliant_time: Duration,
+    session_elapsed: Duration,
+    // When the quote was last checked and what it looked like then
+    last: Option<(Instant, Result<(), QuoteFault>)>,
+}
+
+impl Tracked {
+    fn new(obligation: QuoteObligation) -> Self {
+        Self { obligation, compliant_time: Duration::ZERO, session_elapsed: Duration::ZERO, last: None }
+    }
+
+    // Credits the time since the last check to the state seen then
+    fn advance(&mut self, now: Instant) {
+        if let Some((at, state)) = self.last {
+            let elapsed = now.saturating_duration_since(at);
+            self.session_elapsed += elapsed;
+            if state.is_ok() {
+                self.compliant_time += elapsed;
+            }
+        }
+    }
+
+    fn presence_bps(&self) -> u32 {
+        if self.session_elapsed.is_zero() {
+            return 0;
+        }
+        (self.compliant_time.as_nanos() * BPS as u128 / self.session_elapsed.as_nanos()) as u32
+    }
+}
+
+/// Checks that registered market makers keep two-sided quotes within a maximum spread
+/// and at a minimum size for a minimum share of the session, per symbol.
+///
+/// The monitor samples: call `observe` whenever the books may have changed, and each
+/// quote is taken to have held its observed state until the next call.
+#[derive(Debug, Default)]
+pub struct ObligationMonitor {
+    tracked: FxHashMap<(ParticipantId, SymbolId), Tracked>,
+    breach_listener: Option<Sender<ObligationBreach>>,
+}
+
+impl ObligationMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn attach_breach_listener(&mut self, listener: Sender<ObligationBreach>) {
+        self.breach_listener = Some(listener);
+    }
+
+    /// Registers `participant` as a market maker in `symbol`, replacing any earlier
+    /// obligation and its record. Time counts from the next observation.
+    pub fn register(&mut self, participant: ParticipantId, symbol: SymbolId, obligation: QuoteObligation) {
+        self.tracked.insert((participant, symbol), Tracked::new(obligation));
+    }
+
+    pub fn deregister(&mut self, participant: ParticipantId, symbol: SymbolId) -> Option<ObligationCompliance> {
+        let tracked = self.tracked.remove(&(participant, symbol))?;
+        Some(Self::compliance(participant, symbol, &tracked))
+    }
+
+    /// Clears every record and checks the quotes as they stand at the start of a
+    /// session.
+    pub fn start_session(&mut self, router: &OrderRouter, now: Instant) {
+        for tracked in self.tracked.values_mut() {
+            *tracked = Tracked::new(tracked.obligation);
+        }
+        self.observe(router, now);
+    }
+
+    /// Checks every registered quote against `router`'s books. Sends a breach for each
+    /// quote that was compliant at the last observation and no longer is.
+    pub fn observe(&mut self, router: &OrderRouter, now: Instant) {
+        let symbols: FxHashSet<SymbolId> = self.tracked.keys().map(|&(_, symbol)| symbol).collect();
+        let books: FxHashMap<SymbolId, Vec<Order>> = symbols.into_iter()
+            .map(|symbol| (symbol, router.snapshot_book(symbol).map(|snapshot| snapshot.orders).unwrap_or_default()))
+            .collect();
+
+        let mut breaches = Vec::new();
+        for (&(participant, symbol), tracked) in &mut self.tracked {
+            tracked.advance(now);
+            let state = evaluate(&books[&symbol], participant, &tracked.obligation);
+            if let (Some((_, Ok(()))), Err(fault)) = (tracked.last, state) {
+                breaches.push(ObligationBreach {
+                    participant,
+                    symbol,
+                    kind: BreachKind::Quote(fault),
+                    session_elapsed: tracked.session_elapsed,
+                });
+            }
+            tracked.last = Some((now, state));
+        }
+        self.notify(breaches);
+    }
+
+    /// Closes the session at `now` and returns every record, ordered by participant then
+    /// symbol. Sends a presence breach for each one below its minimum. Records are kept
+    /// until the next `start_session`.
+    pub fn end_session(&mut self, now: Instant) -> Vec<ObligationCompliance> {
+        let mut records: Vec<ObligationCompliance> = self.tracked.iter_mut()
+            .map(|(&(participant, symbol), tracked)| {
+                tracked.advance(now);
+                tracked.last = None;
+                Self::compliance(participant, symbol, tracked)
+            })
+            .collect();
+        records.sort_unstable_by_key(|record| (record.participant, record.symbol));
+
+        let breaches = records.iter()
+            .filter(|record| !record.is_met())
+            .map(|record| ObligationBreach {
+                participant: record.participant,
+                symbol: record.symbol,
+                kind: BreachKind::Presence { presence_bps: record.presence_bps },
+                session_elapsed: record.session_elapsed,
+            })
+            .collect();
+        self.notify(breaches);
+        records
+    }
+
+    pub fn compliance_of(&self, participant: ParticipantId, symbol: SymbolId) -> Option<ObligationCompliance> {
+        self.tracked.get(&(participant, symbol)).map(|tracked| Self::compliance(participant, symbol, tracked))
+    }
+
+    fn compliance(participant: ParticipantId, symbol: SymbolId, tracked: &Tracked) -> ObligationCompliance {
+        ObligationCompliance {
+            participant,
+            symbol,
+            obligation: tracked.obligation,
+            compliant_time: tracked.compliant_time,
+            session_elapsed: tracked.session_elapsed,
+            presence_bps: tracked.presence_bps(),
+            fault: tracked.last.and_then(|(_, state)| state.err()),
+        }
+    }
+
+    fn notify(&self, breaches: Vec<ObligationBreach>) {
+        if let Some(listener) = &self.breach_listener {
+            for breach in breaches {
+                let _ = listener.send(breach);
+            }
+        }
+    }
+}
+
+// The participant's best price on one side and its total quantity there
+fn best_quote(orders: &[Order], participant: ParticipantId, side: OrderSide) -> Option<(u64, u64)> {
+    let own = || orders.iter().filter(move |order| order.participant == participant && order.order_type == side);
+    let best = match side {
+        OrderSide::Buy => own().map(|order| order.price).max()?,
+        OrderSide::Sell => own().map(|order| order.price).min()?,
+    };
+    Some((best, own().filter(|order| order.price == best).map(|order| order.quantity).sum()))
+}
+
+fn evaluate(orders: &[Order], participant: ParticipantId, obligation: &QuoteObligation) -> Result<(), QuoteFault> {
+    let bid = best_quote(orders, participant, OrderSide::Buy);
+    let ask = best_quote(orders, participant, OrderSide::Sell);
+    let ((bid_price, bid_quantity), (ask_price, ask_quantity)) = match (bid, ask) {
+        (Some(bid), Some(ask)) => (bid, ask),
+        (None, None) => return Err(QuoteFault::NoQuote),
+        (None, Some(_)) => return Err(QuoteFault::OneSided { missing: OrderSide::Buy }),
+        (Some(_), None) => return Err(QuoteFault::OneSided { missing: OrderSide::Sell }),
+    };
+    let spread = ask_price.saturating_sub(bid_price);
+    if spread > obligation.max_spread {
+        return Err(QuoteFault::SpreadTooWide { spread });
+    }
+    if bid_quantity < obligation.min_quantity || ask_quantity < obligation.min_quantity {
+        return Err(QuoteFault::TooSmall { bid_quantity, ask_quantity });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::OrderBookType;
+    use crate::types::order::{new_order, price_to_u64};
+
+    #[test]
+    fn test_presence_and_breaches_per_market_maker() {
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        let mut monitor = ObligationMonitor::new();
+        monitor.attach_breach_listener(sender);
+        let obligation = QuoteObligation { max_spread: price_to_u64(1.0), min_quantity: 10, min_presence_bps: 5_000 };
+        monitor.register(7, 0, obligation);
+        monitor.register(8, 0, obligation);
+
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        monitor.start_session(&router, at(0));
+
+        // Participant 7 quotes 99.5 / 100.5 from 10s; participant 8 only ever quotes 2 wide
+        router.route_order(new_order(1, 0, 10, 99.5, OrderSide::Buy).with_participant(7)).unwrap();
+        router.route_order(new_order(2, 0, 10, 100.5, OrderSide::Sell).with_participant(7)).unwrap();
+        router.route_order(new_order(3, 0, 10, 99.0, OrderSide::Buy).with_participant(8)).unwrap();
+        router.route_order(new_order(4, 0, 10, 101.0, OrderSide::Sell).with_participant(8)).unwrap();
+        monitor.observe(&router, at(10));
+        assert_eq!(monitor.compliance_of(8, 0).unwrap().fault, Some(QuoteFault::SpreadTooWide { spread: price_to_u64(2.0) }));
+
+        // An aggressive buyer lifts 7's offer at 40s
+        router.route_order(new_order(5, 0, 10, 100.5, OrderSide::Buy)).unwrap();
+        router.match_all_orders();
+        monitor.observe(&router, at(40));
+        monitor.observe(&router, at(50));
+
+        let records = monitor.end_session(at(60));
+        let summary: Vec<_> = records.iter()
+            .map(|record| (record.participant, record.compliant_time.as_secs(), record.presence_bps, record.is_met()))
+            .collect();
+        assert_eq!(summary, vec![(7, 30, 5_000, true), (8, 0, 0, false)]);
+
+        let breaches: Vec<_> = receiver.try_iter().map(|breach| (breach.participant, breach.kind)).collect();
+        assert_eq!(breaches, vec![
+            (7, BreachKind::Quote(QuoteFault::OneSided { missing: OrderSide::Sell })),
+            (8, BreachKind::Presence { presence_bps: 0 }),
+        ]);
+    }
+
+    #[test]
+    fn test_quote_must_show_minimum_size_on_both_sides() {
+        let obligation = QuoteObligation { max_spread: 1_000, min_quantity: 10, min_presence_bps: BPS };
+        let orders = [
+            new_order(1, 0, 4, 99.0, OrderSide::Buy).with_participant(1),
+            new_order(2, 0, 6, 99.0, OrderSide::Buy).with_participant(1),
+            new_order(3, 0, 50, 99.0, OrderSide::Buy).with_participant(2),
+            new_order(4, 0, 5, 100.0, OrderSide::Sell).with_participant(1),
+        ];
+        assert_eq!(evaluate(&orders, 1, &obligation), Err(QuoteFault::TooSmall { bid_quantity: 10, ask_quantity: 5 }));
+        assert_eq!(evaluate(&orders[..3], 1, &obligation), Err(QuoteFault::OneSided { missing: OrderSide::Sell }));
+        assert_eq!(evaluate(&orders, 3, &obligation), Err(QuoteFault::NoQuote));
+    }
+}
@@ -1,12 +1,87 @@
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::Sender;
 use rustc_hash::{FxHashSet, FxHashMap};
 
-use crate::engine::{OrderBookType, create_order_book, OrderBookTrait};
-use crate::types::order::Order;
+use crate::engine::seeding::{self, QuantityProfile};
+use crate::engine::{AggregatedDepth, AnyOrderBook, BookDepth, LevelChange, MatchStats, OrderBookType, create_any_order_book, OrderBookCore, OrderBookError, OrderBookLifecycle, OrderBookMarketData, LotSize, LotSizes, ParticipantLimits, TickSizes, TieBreak};
+#[cfg(feature = "redis")]
+use crate::market_data::{DepthLevels, RedisPublisher};
+use crate::market_data::{self, DepthMetrics, DepthMetricsConfig, IndicativeOpen, IndicativeUpdate, MirrorPublisher, MirrorUpdate};
+use crate::router::ack::{OrderAck, OrderReduced};
+use crate::router::best_execution::{BestExecutionAudit, ExecutionReport};
+use crate::router::cancel::{BatchCancelResult, OrderSymbols};
+use crate::router::delisting::SymbolDelisted;
+use crate::router::dedup::{DedupFilter, DedupStats, DedupWindow};
+use crate::router::expiry::{ExpiryReason, OrderExpired};
+use crate::router::health::Readiness;
+use crate::router::iceberg::IcebergReserves;
+use crate::router::quote_guard::QuoteGuard;
+use crate::router::reject::{OrderRejected, RejectCode};
+use crate::router::report::{BookReport, BookReportConfig, ReportContext};
+use crate::router::ledger::{QuantityDiscrepancy, QuantityLedger};
+use crate::router::lifecycle::{LifecycleMetrics, LifecycleTracker, OrderTimeline};
+use crate::router::message_rate::{MessageAlert, MessageRateConfig, MessageRateTracker, MessageStats};
+use crate::router::exposure::{AccountExposure, PositionTracker};
+use crate::router::implied::{self, BestPrices, ImpliedFill, ImpliedSpread};
+use crate::router::session::{self, SessionState, SessionStats, SessionSummary};
+use crate::snapshot::{BookSnapshot, RouterSnapshot};
+use crate::types::order::{Order, OrderFlags, OrderSide, ParticipantId, TimeInForce};
+use crate::types::price_scale::{PriceScale, PriceScales};
+use crate::types::trade::{Trade, TradePriceRule};
 use crate::types::symbol_mapping::SymbolId;
+use crate::types::timestamp;
+use crate::types::validation;
+
+// One leg of an implied cross: its symbol, the side of the resting orders it fills and
+// their fill price
+type ImpliedLeg = (SymbolId, OrderSide, u64);
 
 pub struct OrderRouter {
-    direct_order_books: FxHashMap<SymbolId, Box<dyn OrderBookTrait + Send + Sync>>,
+    direct_order_books: FxHashMap<SymbolId, AnyOrderBook>,
     order_book_type: OrderBookType,
+    trade_price_rule: TradePriceRule,
+    sequence: u64,
+    session_state: SessionState,
+    session_id: u64,
+    session_stats: SessionStats,
+    session_history: Vec<SessionSummary>,
+    carried_orders: Vec<Order>,
+    imbalance_orders: Vec<Order>,
+    mirror: Option<MirrorPublisher>,
+    #[cfg(feature = "redis")]
+    redis: Option<RedisPublisher>,
+    expiry_listener: Option<Sender<OrderExpired>>,
+    ack_listener: Option<Sender<OrderAck>>,
+    reduction_listener: Option<Sender<OrderReduced>>,
+    reject_listener: Option<Sender<OrderRejected>>,
+    delisting_listener: Option<Sender<SymbolDelisted>>,
+    message_alert_listener: Option<Sender<MessageAlert>>,
+    default_time_in_force: FxHashMap<SymbolId, TimeInForce>,
+    tie_breaks: FxHashMap<SymbolId, TieBreak>,
+    implied_spreads: Vec<ImpliedSpread>,
+    implied_fills: Vec<ImpliedFill>,
+    participant_limits: ParticipantLimits,
+    tick_sizes: TickSizes,
+    lot_sizes: LotSizes,
+    price_scales: PriceScales,
+    max_depth: Option<usize>,
+    evicted_orders: Vec<Order>,
+    level_journal: bool,
+    ledger: Option<QuantityLedger>,
+    lifecycle: Option<LifecycleTracker>,
+    best_execution: Option<BestExecutionAudit>,
+    message_rates: Option<MessageRateTracker>,
+    positions: Option<PositionTracker>,
+    dedup: Option<DedupFilter>,
+    quote_guard: QuoteGuard,
+    indicative_interval: Duration,
+    indicative_published: FxHashMap<SymbolId, Instant>,
+    indicative_pending: FxHashSet<SymbolId>,
+    recovery_complete: bool,
+    freezes: FxHashMap<SymbolId, u32>,
+    icebergs: IcebergReserves,
+    order_symbols: OrderSymbols,
 }
 
 impl OrderRouter {
@@ -14,48 +89,2309 @@ impl OrderRouter {
         let mut direct_order_books = FxHashMap::default();
         for &symbol in &symbols {
             let symbol_set = FxHashSet::from_iter([symbol]);
-            direct_order_books.insert(symbol, create_order_book(order_book_type, symbol_set));
+            direct_order_books.insert(symbol, create_any_order_book(order_book_type, symbol_set));
         }
         
         Self {
             direct_order_books,
             order_book_type,
+            trade_price_rule: TradePriceRule::default(),
+            sequence: 0,
+            session_state: SessionState::default(),
+            session_id: 0,
+            session_stats: SessionStats::default(),
+            session_history: Vec::new(),
+            carried_orders: Vec::new(),
+            imbalance_orders: Vec::new(),
+            mirror: None,
+            #[cfg(feature = "redis")]
+            redis: None,
+            expiry_listener: None,
+            ack_listener: None,
+            reduction_listener: None,
+            reject_listener: None,
+            delisting_listener: None,
+            message_alert_listener: None,
+            default_time_in_force: FxHashMap::default(),
+            tie_breaks: FxHashMap::default(),
+            implied_spreads: Vec::new(),
+            implied_fills: Vec::new(),
+            participant_limits: ParticipantLimits::default(),
+            tick_sizes: TickSizes::default(),
+            lot_sizes: LotSizes::default(),
+            price_scales: PriceScales::default(),
+            max_depth: None,
+            evicted_orders: Vec::new(),
+            level_journal: false,
+            ledger: None,
+            lifecycle: None,
+            best_execution: None,
+            message_rates: None,
+            positions: None,
+            dedup: None,
+            quote_guard: QuoteGuard::default(),
+            indicative_interval: Duration::ZERO,
+            indicative_published: FxHashMap::default(),
+            indicative_pending: FxHashSet::default(),
+            recovery_complete: true,
+            freezes: FxHashMap::default(),
+            icebergs: IcebergReserves::default(),
+            order_symbols: OrderSymbols::default(),
         }
     }
     
     #[inline(always)]
-    pub fn route_order(&mut self, order: Order) -> Result<(), &'static str> {
-        if let Some(order_book) = self.direct_order_books.get_mut(&order.symbol) {
-            order_book.add_order_fast(order);
+    pub fn route_order(&mut self, mut order: Order) -> Result<(), &'static str> {
+        let received_at = if self.lifecycle.is_some() { timestamp::now_nanos() } else { 0 };
+        if let Some(message_rates) = &mut self.message_rates {
+            message_rates.add(order.participant, timestamp::now_nanos());
+            self.publish_message_alerts();
+        }
+        if self.session_state == SessionState::Closed {
+            return self.reject(&order, "Session closed");
+        }
+        if order.time_in_force == TimeInForce::SymbolDefault {
+            order.time_in_force = self.default_time_in_force(order.symbol);
+        }
+
+        let symbol = order.symbol;
+        if !self.freezes.is_empty() && self.freezes.contains_key(&symbol) {
+            return self.reject(&order, "Symbol frozen");
+        }
+        if validation::ENABLED && self.dedup.as_mut().is_some_and(|dedup| dedup.is_duplicate(order.participant, order.id)) {
+            return self.reject(&order, "Duplicate order");
+        }
+        if order.flags().contains(OrderFlags::AUCTION_ONLY) {
+            return self.hold_imbalance_order(order, received_at);
+        }
+        // Books match these on arrival, which would trade through the opening auction
+        if order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL) && self.session_state == SessionState::PreOpen {
+            return self.reject(&order, "Immediate-or-cancel order in pre-open");
+        }
+        if let Some(order_book) = self.direct_order_books.get_mut(&symbol) {
+            let (order_id, quantity, participant, time_in_force, flags, side) = (order.id, order.quantity, order.participant, order.time_in_force, order.flags(), order.order_type);
+            let arrival = self.best_execution.as_ref()
+                .and_then(|_| ExecutionReport::arrive(&order, order_book.price_levels(symbol, side.opposite(), false)?));
+            let order = if flags.contains(OrderFlags::HIDDEN_RESERVE) { self.icebergs.split(order) } else { order };
+            if let Err(error) = order_book.add_order(order) {
+                self.icebergs.discard(order_id);
+                self.session_stats.orders_rejected += 1;
+                self.notify_rejected(order_id, symbol, participant, error.into());
+                return Err(book_error(error));
+            }
+            self.sequence += 1;
+            self.session_stats.orders_routed += 1;
+            self.order_symbols.accept(symbol, order_id, participant, quantity);
+            if let Some(ledger) = &mut self.ledger {
+                ledger.submit(symbol, order_id, quantity);
+            }
+            if let Some(lifecycle) = &mut self.lifecycle {
+                lifecycle.accept(symbol, order_id, quantity, received_at, timestamp::now_nanos());
+            }
+            if let Some((best_execution, arrival)) = self.best_execution.as_mut().zip(arrival) {
+                best_execution.record(arrival);
+            }
+            if let Some(positions) = &mut self.positions {
+                positions.accept(symbol, order_id, participant, side, quantity);
+            }
+            if validation::ENABLED && let Some(dedup) = &mut self.dedup {
+                dedup.record(participant, order_id);
+            }
+            self.acknowledge(order_id, symbol, participant, time_in_force);
+            if self.max_depth.is_some() || flags.contains(OrderFlags::MATCH_ON_ARRIVAL) {
+                self.collect_evictions(Some(symbol));
+            }
+            if self.publishes_top_of_book() {
+                self.publish_top_of_book(symbol);
+                if self.session_state == SessionState::PreOpen {
+                    self.update_indicative(symbol);
+                }
+            }
             Ok(())
         } else {
-            Err("Invalid symbol")
+            self.reject(&order, "Invalid symbol")
+        }
+    }
+
+    // Imbalance-only orders stay out of the books and the indicative price until the open
+    fn hold_imbalance_order(&mut self, order: Order, received_at: u64) -> Result<(), &'static str> {
+        if self.session_state != SessionState::PreOpen {
+            return self.reject(&order, "Imbalance-only order outside pre-open");
+        }
+        if !self.supports_symbol(order.symbol) {
+            return self.reject(&order, "Invalid symbol");
+        }
+        self.order_symbols.accept(order.symbol, order.id, order.participant, order.quantity);
+        if let Some(ledger) = &mut self.ledger {
+            ledger.submit(order.symbol, order.id, order.quantity);
+        }
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.accept(order.symbol, order.id, order.quantity, received_at, timestamp::now_nanos());
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.accept(order.symbol, order.id, order.participant, order.order_type, order.quantity);
+        }
+        if validation::ENABLED && let Some(dedup) = &mut self.dedup {
+            dedup.record(order.participant, order.id);
+        }
+        self.sequence += 1;
+        self.session_stats.orders_routed += 1;
+        self.acknowledge(order.id, order.symbol, order.participant, order.time_in_force);
+        self.imbalance_orders.push(order);
+        Ok(())
+    }
+
+    /// Routes a warm resting book for `symbol` built by `seeding::seed_orders`, so it goes
+    /// through the same listeners, ledger and stats as any other flow. Returns the id
+    /// after the last one seeded.
+    pub fn seed_book(&mut self, symbol: SymbolId, mid_price: u64, levels: usize, profile: &QuantityProfile) -> Result<u64, &'static str> {
+        let mut next_id = profile.first_order_id;
+        for order in seeding::seed_orders(symbol, mid_price, levels, profile) {
+            next_id = order.id + 1;
+            self.route_order(order)?;
+        }
+        Ok(next_id)
+    }
+
+    /// Takes `quantity_delta` off a resting order without losing its queue position and
+    /// returns the order as it now rests. Unlike a cancel, at least one unit must remain.
+    pub fn reduce_order(&mut self, symbol: SymbolId, order_id: u64, quantity_delta: u64) -> Result<Order, &'static str> {
+        let order_book = self.direct_order_books.get_mut(&symbol).ok_or("Invalid symbol")?;
+        let order = order_book.reduce_order(order_id, quantity_delta).map_err(book_error)?;
+        self.icebergs.reduce(order_id, quantity_delta);
+        self.order_symbols.fill_order(symbol, order_id, quantity_delta);
+        self.sequence += 1;
+        if let Some(ledger) = &mut self.ledger {
+            ledger.reduce(symbol, order_id, quantity_delta);
+        }
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.reduce(symbol, order_id, quantity_delta);
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.reduce(symbol, order_id, quantity_delta);
+        }
+        if let Some(listener) = &self.reduction_listener {
+            let _ = listener.send(OrderReduced { order: order.clone(), reduced_by: quantity_delta, sequence: self.sequence });
+        }
+        if let Some(message_rates) = &mut self.message_rates {
+            message_rates.modify(order.participant, timestamp::now_nanos());
+            self.publish_message_alerts();
+        }
+        Ok(order)
+    }
+
+    /// Cancels every resting, carried or imbalance-only order whose id is listed. Each
+    /// id goes straight to its symbol's book and is found through the book's id index;
+    /// only ids not resting in a book are looked for among the held orders.
+    pub fn cancel_orders_batch(&mut self, order_ids: &[u64]) -> BatchCancelResult {
+        if order_ids.is_empty() {
+            return BatchCancelResult::default();
+        }
+
+        let mut cancelled = Vec::new();
+        let mut held = FxHashSet::default();
+        for &order_id in order_ids {
+            let resting = self.order_symbols.symbol(order_id)
+                .and_then(|symbol| self.direct_order_books.get_mut(&symbol)?.cancel_order(symbol, order_id).ok());
+            match resting {
+                Some(order) => cancelled.push(order),
+                None => {
+                    held.insert(order_id);
+                }
+            }
+        }
+        let cancelled = self.cancel_held_where(|order| held.contains(&order.id), cancelled);
+        if let Some(message_rates) = &mut self.message_rates {
+            let now = timestamp::now_nanos();
+            cancelled.iter().for_each(|order| message_rates.cancel(order.participant, now));
+            self.publish_message_alerts();
+        }
+        let found: FxHashSet<u64> = cancelled.iter().map(|order| order.id).collect();
+        let not_found = order_ids.iter().copied().filter(|id| !found.contains(id)).collect();
+        BatchCancelResult { cancelled, not_found }
+    }
+
+    // One pass over every book and the held orders, grouped by symbol, with the same
+    // accounting and market data updates whoever asked for the cancel
+    fn cancel_where(&mut self, predicate: impl Fn(&Order) -> bool) -> Vec<Order> {
+        let mut cancelled = Vec::new();
+        for order_book in self.direct_order_books.values_mut() {
+            cancelled.extend(order_book.cancel_where(&predicate));
+        }
+        self.cancel_held_where(predicate, cancelled)
+    }
+
+    // Adds the carried and imbalance-only orders `predicate` accepts to orders already
+    // taken out of the books, then settles them all
+    fn cancel_held_where(&mut self, predicate: impl Fn(&Order) -> bool, mut cancelled: Vec<Order>) -> Vec<Order> {
+        for held in [&mut self.carried_orders, &mut self.imbalance_orders] {
+            held.retain(|order| {
+                let keep = !predicate(order);
+                if !keep {
+                    cancelled.push(order.clone());
+                }
+                keep
+            });
+        }
+        if cancelled.is_empty() {
+            return cancelled;
+        }
+        cancelled.sort_by_key(|order| order.symbol);
+        self.icebergs.release(&mut cancelled);
+        self.order_symbols.forget(&cancelled);
+
+        self.sequence += 1;
+        if let Some(ledger) = &mut self.ledger {
+            cancelled.iter().for_each(|order| ledger.cancel(order));
+        }
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.cancel(&cancelled, timestamp::now_nanos());
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.forget(&cancelled);
+        }
+        if self.publishes_top_of_book() {
+            let mut symbols: Vec<SymbolId> = cancelled.iter().map(|order| order.symbol).collect();
+            symbols.dedup();
+            for symbol in symbols {
+                self.publish_top_of_book(symbol);
+                if self.session_state == SessionState::PreOpen {
+                    self.update_indicative(symbol);
+                }
+            }
+        }
+        cancelled
+    }
+
+    /// Cancels every resting or held good-till-date order whose `expires_at` is at or
+    /// before `now` (nanoseconds since the Unix epoch) and reports each to the expiry
+    /// listener as `GoodTillDate`. The HashMap book finds its expired orders through an
+    /// expiry-ordered index; the other books scan every resting order.
+    pub fn expire_orders(&mut self, now: u64) -> Vec<Order> {
+        let mut expired = Vec::new();
+        for order_book in self.direct_order_books.values_mut() {
+            expired.extend(order_book.take_expired_orders(now));
+        }
+        let expired = self.cancel_held_where(|order| order.expires_at.is_some_and(|expires_at| expires_at <= now), expired);
+        self.notify_expired(expired.iter().cloned(), ExpiryReason::GoodTillDate);
+        expired
+    }
+
+    /// Routes a market maker quote that `expire_stale_quotes` pulls unless the owner
+    /// calls `refresh_quotes` within `ttl`. Not kept in snapshots.
+    pub fn route_quote(&mut self, order: Order, ttl: Duration) -> Result<(), &'static str> {
+        let (participant, order_id) = (order.participant, order.id);
+        self.route_order(order)?;
+        self.quote_guard.track(participant, order_id, ttl, Instant::now());
+        Ok(())
+    }
+
+    /// Restarts the TTL on all of a participant's quotes and returns how many it had.
+    pub fn refresh_quotes(&mut self, participant: ParticipantId) -> usize {
+        self.quote_guard.refresh(participant, Instant::now())
+    }
+
+    /// Cancels every quote whose TTL has run out and reports each to the expiry listener
+    /// as `QuoteExpired`. Meant to be called on a timer, like `flush_indicative`.
+    pub fn expire_stale_quotes(&mut self) -> Vec<Order> {
+        let expired = self.quote_guard.take_expired(Instant::now());
+        if expired.is_empty() {
+            return Vec::new();
+        }
+        let cancelled = self.cancel_where(|order| expired.contains(&(order.participant, order.id)));
+        self.notify_expired(cancelled.iter().cloned(), ExpiryReason::QuoteExpired);
+        cancelled
+    }
+
+    /// Opts a participant in or out of having all its orders cancelled by `connection_lost`.
+    pub fn set_cancel_on_disconnect(&mut self, participant: ParticipantId, enabled: bool) {
+        self.quote_guard.set_cancel_on_disconnect(participant, enabled);
+    }
+
+    /// Called by the gateway when a participant's connection drops. If the participant
+    /// opted into cancel-on-disconnect, every order it has resting or held is cancelled
+    /// and reported as `Disconnected`; otherwise nothing happens.
+    pub fn connection_lost(&mut self, participant: ParticipantId) -> Vec<Order> {
+        if !self.quote_guard.cancels_on_disconnect(participant) {
+            return Vec::new();
         }
+        self.quote_guard.forget_participant(participant);
+        let cancelled = self.cancel_where(|order| order.participant == participant);
+        self.notify_expired(cancelled.iter().cloned(), ExpiryReason::Disconnected);
+        cancelled
+    }
+
+    /// Cancels every resting and held order, or only `symbol`'s, and reports each to
+    /// the expiry listener as `OperatorCancel`.
+    pub fn cancel_all(&mut self, symbol: Option<SymbolId>) -> Vec<Order> {
+        let cancelled = self.cancel_where(|order| symbol.is_none_or(|symbol| order.symbol == symbol));
+        self.notify_expired(cancelled.iter().cloned(), ExpiryReason::OperatorCancel);
+        cancelled
     }
 
+    /// Matches every book, then fills any crosses against implied spread prices, and
+    /// returns the direct executions, grouped by symbol in ascending order and in
+    /// execution order within each symbol. Implied fills come out of `take_implied_fills`.
     #[inline(always)]
-    pub fn match_all_orders(&mut self) {
+    pub fn match_all_orders(&mut self) -> Vec<Trade> {
+        let mut trades = Vec::new();
+        for order_book in self.direct_order_books.values_mut() {
+            trades.extend(order_book.match_orders());
+        }
+        if !self.icebergs.is_empty() {
+            self.refill_icebergs(&mut trades);
+        }
+        if !self.implied_spreads.is_empty() {
+            self.match_implied_spreads();
+        }
+        trades.sort_by_key(|trade| trade.symbol);
+        if let Some(message_rates) = &mut self.message_rates {
+            let now = timestamp::now_nanos();
+            for order_id in trades.iter().flat_map(|trade| [trade.taker_order_id, trade.maker_order_id]) {
+                if let Some(participant) = self.order_symbols.participant(order_id) {
+                    message_rates.trade(participant, now);
+                }
+            }
+            self.publish_message_alerts();
+        }
+        self.order_symbols.fill(&trades);
+        if let Some(ledger) = &mut self.ledger {
+            for trade in &trades {
+                ledger.fill(trade.symbol, trade.taker_order_id, trade.quantity);
+                ledger.fill(trade.symbol, trade.maker_order_id, trade.quantity);
+            }
+        }
+        if let Some(lifecycle) = &mut self.lifecycle && !trades.is_empty() {
+            lifecycle.fill(&trades, timestamp::now_nanos());
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.fill(&trades);
+        }
+        if let Some(best_execution) = &mut self.best_execution {
+            best_execution.fill(&trades);
+        }
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            redis.publish_trades(&trades);
+        }
+        if self.publishes_top_of_book() {
+            self.publish_all_top_of_book();
+        }
+        trades
+    }
+
+    // Adds the next slice of every iceberg `trades` used up to the back of its level and
+    // matches those books again, until no more slices run out
+    fn refill_icebergs(&mut self, trades: &mut Vec<Trade>) {
+        let mut checked = 0;
+        loop {
+            let slices = self.icebergs.refill(&trades[checked..]);
+            checked = trades.len();
+            if slices.is_empty() {
+                return;
+            }
+            let mut symbols = self.rest_slices(slices);
+            symbols.sort_unstable();
+            symbols.dedup();
+            for symbol in symbols {
+                if let Some(order_book) = self.direct_order_books.get_mut(&symbol) {
+                    trades.extend(order_book.match_orders());
+                }
+            }
+        }
+    }
+
+    // Adds iceberg slices to the back of their levels, returns the ones a book refuses
+    // to their owners, and returns the symbols of the slices added
+    fn rest_slices(&mut self, slices: Vec<Order>) -> Vec<SymbolId> {
+        let mut symbols = Vec::new();
+        let mut refused = Vec::new();
+        for slice in slices {
+            let Some(order_book) = self.direct_order_books.get_mut(&slice.symbol) else { continue };
+            symbols.push(slice.symbol);
+            if order_book.add_order(slice.clone()).is_err() {
+                refused.push(slice);
+            }
+        }
+        if !refused.is_empty() {
+            self.icebergs.release(&mut refused);
+            self.order_symbols.forget(&refused);
+            if let Some(ledger) = &mut self.ledger {
+                refused.iter().for_each(|order| ledger.cancel(order));
+            }
+            if let Some(lifecycle) = &mut self.lifecycle {
+                lifecycle.cancel(&refused, timestamp::now_nanos());
+            }
+            if let Some(positions) = &mut self.positions {
+                positions.forget(&refused);
+            }
+            self.notify_expired(refused, ExpiryReason::RefillRefused);
+        }
+        symbols
+    }
+
+    // Fills every spread's implied cross until none is left. Direct matching has run, so
+    // each cross needs orders from all three books
+    fn match_implied_spreads(&mut self) {
+        for definition in self.implied_spreads.clone() {
+            while let Some((quantity, legs)) = self.implied_cross(&definition) {
+                let mut filled = 0;
+                for (symbol, side, price) in legs {
+                    filled += self.fill_implied_leg(symbol, side, price, quantity);
+                }
+                if filled == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Buying the spread is buying the front and selling the back, so a spread bid crosses
+    // the front ask less the back bid, and a spread ask the front bid less the back ask.
+    // Returns the quantity all three best levels can fill and each leg's side and price
+    fn implied_cross(&self, definition: &ImpliedSpread) -> Option<(u64, [ImpliedLeg; 3])> {
+        let best = |symbol, side| self.direct_order_books.get(&symbol)?.price_levels(symbol, side, false)?.next();
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            let (Some(spread), Some(front), Some(back)) = (best(definition.spread, side), best(definition.front, side.opposite()), best(definition.back, side)) else { continue };
+            let Some(implied_price) = front.price.checked_sub(back.price) else { continue };
+            let crossed = match side {
+                OrderSide::Buy => spread.price >= implied_price,
+                OrderSide::Sell => spread.price <= implied_price,
+            };
+            if crossed {
+                let quantity = spread.total_quantity.min(front.total_quantity).min(back.total_quantity);
+                return Some((quantity, [
+                    (definition.spread, side, implied_price),
+                    (definition.front, side.opposite(), front.price),
+                    (definition.back, side, back.price),
+                ]));
+            }
+        }
+        None
+    }
+
+    // Takes `quantity` off the best `side` level of `symbol` in queue order: orders it
+    // uses up leave the book, and the last one it reaches is reduced in place. Returns
+    // the quantity filled
+    fn fill_implied_leg(&mut self, symbol: SymbolId, side: OrderSide, price: u64, quantity: u64) -> u64 {
+        let Some(order_book) = self.direct_order_books.get_mut(&symbol) else { return 0 };
+        let order_ids = order_book.price_levels(symbol, side, true)
+            .and_then(|mut levels| levels.next())
+            .and_then(|level| level.order_ids)
+            .unwrap_or_default();
+        let now = if self.lifecycle.is_some() { timestamp::now_nanos() } else { 0 };
+        let mut slices = Vec::new();
+        let mut remaining = quantity;
+        for order_id in order_ids {
+            if remaining == 0 {
+                break;
+            }
+            let filled = match order_book.reduce_order(order_id, remaining) {
+                Ok(_) => remaining,
+                Err(_) => match order_book.cancel_order(symbol, order_id) {
+                    Ok(order) => order.quantity,
+                    Err(_) => continue,
+                },
+            };
+            remaining -= filled;
+            slices.extend(self.icebergs.fill(order_id, filled));
+            if let Some((message_rates, participant)) = self.message_rates.as_mut().zip(self.order_symbols.participant(order_id)) {
+                message_rates.trade(participant, timestamp::now_nanos());
+            }
+            self.order_symbols.fill_order(symbol, order_id, filled);
+            if let Some(ledger) = &mut self.ledger {
+                ledger.fill(symbol, order_id, filled);
+            }
+            if let Some(lifecycle) = &mut self.lifecycle {
+                lifecycle.fill_order(symbol, order_id, filled, now);
+            }
+            if let Some(positions) = &mut self.positions {
+                positions.fill_order(symbol, order_id, price, filled);
+            }
+            self.implied_fills.push(ImpliedFill { symbol, order_id, side, price, quantity: filled });
+        }
+        if !slices.is_empty() {
+            self.rest_slices(slices);
+        }
+        quantity - remaining
+    }
+
+    /// Drains the fills `match_all_orders` made against implied spread prices, in the
+    /// order they happened. Each implied match fills the same quantity on all three
+    /// legs, taking each leg's best level in queue order whatever the tie break.
+    pub fn take_implied_fills(&mut self) -> Vec<ImpliedFill> {
+        std::mem::take(&mut self.implied_fills)
+    }
+
+    pub fn set_trade_price_rule(&mut self, rule: TradePriceRule) {
+        self.trade_price_rule = rule;
         for order_book in self.direct_order_books.values_mut() {
-            order_book.match_orders();
+            order_book.set_trade_price_rule(rule);
         }
     }
 
     #[inline(always)]
-    pub fn supports_symbol(&self, symbol: SymbolId) -> bool {
-        self.direct_order_books.contains_key(&symbol)
+    pub fn trade_price_rule(&self) -> TradePriceRule {
+        self.trade_price_rule
     }
 
     #[inline(always)]
-    pub fn get_implementation_name(&self) -> &'static str {
-        match self.order_book_type {
-            OrderBookType::HashMap => "HashMap",
-            OrderBookType::PriorityQueue => "PriorityQueue", 
-            OrderBookType::ArrayQueue => "ArrayQueue",
+    pub fn last_trade_price(&self, symbol: SymbolId) -> Option<u64> {
+        self.direct_order_books.get(&symbol)
+            .and_then(|order_book| order_book.last_trade_price(symbol))
+    }
+
+    /// Stats from the symbol's most recent matching cycle that found a cross.
+    #[inline(always)]
+    pub fn last_match_stats(&self, symbol: SymbolId) -> Option<MatchStats> {
+        self.direct_order_books.get(&symbol)
+            .and_then(|order_book| order_book.last_match_stats(symbol))
+    }
+
+    fn create_book(&self, symbol: SymbolId) -> AnyOrderBook {
+        let mut order_book = create_any_order_book(self.order_book_type, FxHashSet::from_iter([symbol]));
+        order_book.set_trade_price_rule(self.trade_price_rule);
+        order_book.set_participant_limits(self.participant_limits);
+        order_book.set_tick_sizes(self.tick_sizes.clone());
+        order_book.set_lot_sizes(self.lot_sizes.clone());
+        order_book.set_max_depth(self.max_depth);
+        order_book.set_level_journal(self.level_journal);
+        order_book.set_tie_break(self.tie_break(symbol));
+        order_book
+    }
+
+    pub fn set_participant_limits(&mut self, limits: ParticipantLimits) {
+        self.participant_limits = limits;
+        for order_book in self.direct_order_books.values_mut() {
+            order_book.set_participant_limits(limits);
         }
     }
 
     #[inline(always)]
-    pub fn get_symbols(&self) -> Vec<SymbolId> {
-        self.direct_order_books.keys().copied().collect()
+    pub fn participant_limits(&self) -> ParticipantLimits {
+        self.participant_limits
+    }
+
+    /// Checks every new order's price against `tick_sizes`, rejecting it as "Price off
+    /// tick" or rounding it as the table's policy says. Not enforced with validation
+    /// stripped.
+    pub fn set_tick_sizes(&mut self, tick_sizes: TickSizes) {
+        for order_book in self.direct_order_books.values_mut() {
+            order_book.set_tick_sizes(tick_sizes.clone());
+        }
+        self.tick_sizes = tick_sizes;
+    }
+
+    /// Sets one symbol's tick, keeping the rest of the table; 0 or 1 removes it.
+    pub fn set_tick_size(&mut self, symbol: SymbolId, tick: u64) {
+        let mut tick_sizes = self.tick_sizes.clone();
+        tick_sizes.set_tick(symbol, tick);
+        self.set_tick_sizes(tick_sizes);
+    }
+
+    #[inline(always)]
+    pub fn tick_sizes(&self) -> &TickSizes {
+        &self.tick_sizes
+    }
+
+    /// Checks every new order's quantity against `lot_sizes`, rejecting odd lots and
+    /// orders below the symbol's minimum quantity or notional. Not enforced with
+    /// validation stripped.
+    pub fn set_lot_sizes(&mut self, lot_sizes: LotSizes) {
+        for order_book in self.direct_order_books.values_mut() {
+            order_book.set_lot_sizes(lot_sizes.clone());
+        }
+        self.lot_sizes = lot_sizes;
+    }
+
+    /// Sets one symbol's rule, keeping the rest of the table.
+    pub fn set_lot_size(&mut self, symbol: SymbolId, rule: LotSize) {
+        let mut lot_sizes = self.lot_sizes.clone();
+        lot_sizes.set_rule(symbol, rule);
+        self.set_lot_sizes(lot_sizes);
+    }
+
+    #[inline(always)]
+    pub fn lot_sizes(&self) -> &LotSizes {
+        &self.lot_sizes
+    }
+
+    /// Decimals each symbol's prices carry, for callers converting prices to and from
+    /// decimals and for reports. Books hold prices as integers either way, so set a
+    /// symbol's scale before its orders, ticks, lot notionals and implied spreads,
+    /// which are all in its scaled units.
+    pub fn set_price_scales(&mut self, price_scales: PriceScales) {
+        self.price_scales = price_scales;
+    }
+
+    pub fn set_price_scale(&mut self, symbol: SymbolId, scale: PriceScale) {
+        self.price_scales.set_scale(symbol, scale);
+    }
+
+    #[inline(always)]
+    pub fn price_scale(&self, symbol: SymbolId) -> PriceScale {
+        self.price_scales.scale(symbol)
+    }
+
+    #[inline(always)]
+    pub fn price_scales(&self) -> &PriceScales {
+        &self.price_scales
+    }
+
+    /// Caps price levels per side on every book; see `OrderBookLifecycle::set_max_depth`.
+    pub fn set_max_depth(&mut self, max_levels: Option<usize>) {
+        self.max_depth = max_levels;
+        for order_book in self.direct_order_books.values_mut() {
+            order_book.set_max_depth(max_levels);
+        }
+        self.collect_evictions(None);
+    }
+
+    #[inline(always)]
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Journals level changes on every book; see `OrderBookLifecycle::set_level_journal`.
+    pub fn set_level_journal(&mut self, enabled: bool) {
+        self.level_journal = enabled;
+        for order_book in self.direct_order_books.values_mut() {
+            order_book.set_level_journal(enabled);
+        }
+    }
+
+    #[inline(always)]
+    pub fn level_journal(&self) -> bool {
+        self.level_journal
+    }
+
+    /// Level changes journaled for `symbol` since the last call, oldest first.
+    pub fn take_level_changes(&mut self, symbol: SymbolId) -> Option<Vec<LevelChange>> {
+        self.direct_order_books.get_mut(&symbol)
+            .and_then(|order_book| order_book.take_level_changes(symbol))
+    }
+
+    /// Drains the orders each book has cancelled on its own: levels evicted under the
+    /// depth cap and unfilled immediate-or-cancel remainders. With an expiry listener
+    /// attached they are sent there as they happen instead.
+    pub fn take_evicted_orders(&mut self) -> Vec<Order> {
+        std::mem::take(&mut self.evicted_orders)
+    }
+
+    // Drains evictions from one book, or every book for `None`, as soon as they happen
+    // so the ledger and listener see them in sequence
+    fn collect_evictions(&mut self, symbol: Option<SymbolId>) {
+        let mut evicted: Vec<Order> = match symbol {
+            Some(symbol) => self.direct_order_books.get_mut(&symbol)
+                .map(|order_book| order_book.take_evicted_orders())
+                .unwrap_or_default(),
+            None => self.direct_order_books.values_mut()
+                .flat_map(|order_book| order_book.take_evicted_orders())
+                .collect(),
+        };
+        if evicted.is_empty() {
+            return;
+        }
+        self.icebergs.release(&mut evicted);
+        self.order_symbols.forget(&evicted);
+        if let Some(ledger) = &mut self.ledger {
+            evicted.iter().for_each(|order| ledger.cancel(order));
+        }
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.cancel(&evicted, timestamp::now_nanos());
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.forget(&evicted);
+        }
+        if self.expiry_listener.is_some() {
+            let (unfilled, evicted): (Vec<Order>, Vec<Order>) = evicted.into_iter()
+                .partition(|order| order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL));
+            self.notify_expired(unfilled, ExpiryReason::ImmediateOrCancel);
+            self.notify_expired(evicted, ExpiryReason::DepthEviction);
+        } else {
+            self.evicted_orders.extend(evicted);
+        }
+    }
+
+    /// Starts quantity accounting from the orders resting now. Every later order must
+    /// end up filled, cancelled, expired or resting; see `reconcile`. Costs a pass over
+    /// the books per `match_all_orders` while enabled.
+    pub fn enable_ledger(&mut self) {
+        let mut ledger = QuantityLedger::default();
+        for order in self.resting_in_books().iter().chain(&self.carried_orders).chain(&self.imbalance_orders).chain(self.icebergs.reserves()) {
+            ledger.submit(order.symbol, order.id, order.quantity);
+        }
+        self.ledger = Some(ledger);
+    }
+
+    /// Orders whose submitted quantity does not equal filled + cancelled + expired +
+    /// resting, or `None` when the ledger is not enabled.
+    pub fn reconcile(&self) -> Option<Vec<QuantityDiscrepancy>> {
+        let ledger = self.ledger.as_ref()?;
+        let resting = self.resting_in_books();
+        Some(ledger.reconcile(resting.iter().chain(&self.carried_orders).chain(&self.imbalance_orders).chain(self.icebergs.reserves())))
+    }
+
+    /// Starts netting fills per participant and symbol, for `exposure`. Positions start
+    /// flat; orders already resting count from their next fill. Not kept in snapshots.
+    pub fn enable_position_tracking(&mut self) {
+        let mut positions = PositionTracker::default();
+        positions.rebase(&self.all_open_orders());
+        self.positions = Some(positions);
+    }
+
+    // Every order still open in the router, iceberg reserves folded into their slices
+    fn all_open_orders(&self) -> Vec<Order> {
+        let mut orders = self.resting_in_books();
+        orders.extend(self.carried_orders.iter().chain(&self.imbalance_orders).cloned());
+        let reserves: FxHashMap<u64, u64> = self.icebergs.reserves().map(|reserve| (reserve.id, reserve.quantity)).collect();
+        if !reserves.is_empty() {
+            for order in &mut orders {
+                order.quantity += reserves.get(&order.id).copied().unwrap_or(0);
+            }
+        }
+        orders
+    }
+
+    /// Every resting, carried and imbalance-only order of `participant`, by symbol then
+    /// id. Icebergs show their whole unfilled quantity.
+    pub fn open_orders(&self, participant: ParticipantId) -> Vec<Order> {
+        let mut orders = self.all_open_orders();
+        orders.retain(|order| order.participant == participant);
+        orders.sort_unstable_by_key(|order| (order.symbol, order.id));
+        orders
+    }
+
+    /// Open order quantity and notional per side for `participant`, plus its net
+    /// positions marked at each symbol's last trade when position tracking is enabled.
+    pub fn exposure(&self, participant: ParticipantId) -> AccountExposure {
+        let mut exposure = AccountExposure::from_orders(participant, &self.open_orders(participant));
+        if let Some(positions) = &self.positions {
+            exposure.positions = positions.positions(participant, |symbol| self.last_trade_price(symbol));
+        }
+        exposure
+    }
+
+    /// Starts timing each accepted order from intake through acceptance, first fill and
+    /// completion, keeping the last `window` samples of each interval per symbol. Orders
+    /// already resting are not timed. Not kept in snapshots.
+    pub fn enable_lifecycle_metrics(&mut self, window: usize) {
+        self.lifecycle = Some(LifecycleTracker::new(window));
+    }
+
+    /// Time-to-ack, time-to-fill and resting-time distributions for `symbol`, or `None`
+    /// when lifecycle metrics are not enabled.
+    pub fn lifecycle_metrics(&self, symbol: SymbolId) -> Option<LifecycleMetrics> {
+        Some(self.lifecycle.as_ref()?.metrics(symbol))
+    }
+
+    /// Stage timestamps of an order still resting or held, if it was timed.
+    pub fn order_timeline(&self, symbol: SymbolId, order_id: u64) -> Option<OrderTimeline> {
+        self.lifecycle.as_ref()?.timeline(symbol, order_id)
+    }
+
+    /// Starts a best-execution report for each order that crosses the book when it is
+    /// routed, holding the opposite side's liquidity at that moment and the prices the
+    /// order then trades at as the taker. Not kept in snapshots.
+    pub fn enable_best_execution(&mut self) {
+        self.best_execution = Some(BestExecutionAudit::default());
+    }
+
+    /// Reports for every aggressive order seen since `enable_best_execution`, by symbol
+    /// then order id, or `None` when it is not enabled.
+    pub fn best_execution_reports(&self) -> Option<Vec<ExecutionReport>> {
+        Some(self.best_execution.as_ref()?.reports())
+    }
+
+    /// Removes and returns the reports of aggressive orders no longer open in the router,
+    /// which can trade no further. Empty when best execution is not enabled.
+    pub fn take_best_execution_reports(&mut self) -> Vec<ExecutionReport> {
+        let order_symbols = &self.order_symbols;
+        self.best_execution.as_mut()
+            .map(|best_execution| best_execution.take_closed(|report| order_symbols.symbol(report.order_id) == Some(report.symbol)))
+            .unwrap_or_default()
+    }
+
+    /// Starts counting each participant's adds, cancels, reductions and fills, alerting
+    /// through `attach_message_alert_listener` when one crosses a threshold in `config`.
+    /// Not kept in snapshots.
+    pub fn enable_message_analytics(&mut self, config: MessageRateConfig) {
+        self.message_rates = Some(MessageRateTracker::new(config));
+    }
+
+    /// The participant's message counts, order-to-trade ratio and current message rate,
+    /// or `None` when analytics are off or it has sent nothing.
+    pub fn message_stats(&self, participant: ParticipantId) -> Option<MessageStats> {
+        self.message_rates.as_ref()?.stats(participant, timestamp::now_nanos())
+    }
+
+    /// `message_stats` for every participant seen, by participant.
+    pub fn all_message_stats(&self) -> Vec<MessageStats> {
+        self.message_rates.as_ref().map(|message_rates| message_rates.all_stats(timestamp::now_nanos())).unwrap_or_default()
+    }
+
+    // Hands alerts raised since the last call to the listener, dropping them without one
+    fn publish_message_alerts(&mut self) {
+        let Some(message_rates) = &mut self.message_rates else { return };
+        for alert in message_rates.take_alerts() {
+            if let Some(listener) = &self.message_alert_listener {
+                let _ = listener.send(alert);
+            }
+        }
+    }
+
+    /// Rejects an order whose (participant, id) was accepted recently, as remembered by
+    /// `default_window` unless the participant has its own. Not kept in snapshots.
+    pub fn enable_dedup(&mut self, default_window: DedupWindow) {
+        self.dedup = Some(DedupFilter::new(default_window));
+    }
+
+    /// Overrides the dedup window for one participant; no-op until dedup is enabled.
+    pub fn set_dedup_window(&mut self, participant: ParticipantId, window: DedupWindow) {
+        if let Some(dedup) = &mut self.dedup {
+            dedup.set_window(participant, window);
+        }
+    }
+
+    pub fn dedup_stats(&self, participant: ParticipantId) -> Option<DedupStats> {
+        self.dedup.as_ref().map(|dedup| dedup.stats(participant))
+    }
+
+    pub fn dedup_hits(&self) -> u64 {
+        self.dedup.as_ref().map_or(0, DedupFilter::total_hits)
+    }
+
+    fn resting_in_books(&self) -> Vec<Order> {
+        self.direct_order_books.iter()
+            .flat_map(|(&symbol, order_book)| order_book.resting_orders(symbol).unwrap_or_default())
+            .collect()
+    }
+
+    // Restored and carried orders were admitted once already, so they are re-loaded
+    // uncapped and only count towards the limits for new orders
+    fn load_uncapped(&mut self, orders: impl IntoIterator<Item = Order>) {
+        let limits = self.participant_limits;
+        self.set_participant_limits(ParticipantLimits::default());
+        for order in orders {
+            if let Some(order_book) = self.direct_order_books.get_mut(&order.symbol) {
+                order_book.add_order_fast(order);
+            }
+        }
+        self.set_participant_limits(limits);
+        self.collect_evictions(None);
+    }
+
+    /// Sends an `OrderExpired` for every order the router expires, cancels or evicts
+    /// on its own, so owners can tell those apart from their own cancels.
+    pub fn attach_expiry_listener(&mut self, listener: Sender<OrderExpired>) {
+        self.expiry_listener = Some(listener);
+    }
+
+    /// Sends an `OrderAck` for every accepted order.
+    pub fn attach_ack_listener(&mut self, listener: Sender<OrderAck>) {
+        self.ack_listener = Some(listener);
+    }
+
+    /// Sends an `OrderReduced` for every successful `reduce_order`.
+    pub fn attach_reduction_listener(&mut self, listener: Sender<OrderReduced>) {
+        self.reduction_listener = Some(listener);
+    }
+
+    /// Sends a `MessageAlert` each time a participant crosses a threshold set with
+    /// `enable_message_analytics`.
+    pub fn attach_message_alert_listener(&mut self, listener: Sender<MessageAlert>) {
+        self.message_alert_listener = Some(listener);
+    }
+
+    /// Reports every order the router refuses, with its reject code.
+    pub fn attach_reject_listener(&mut self, listener: Sender<OrderRejected>) {
+        self.reject_listener = Some(listener);
+    }
+
+    /// Sends the `SymbolDelisted` record of every `delist_symbol`.
+    pub fn attach_delisting_listener(&mut self, listener: Sender<SymbolDelisted>) {
+        self.delisting_listener = Some(listener);
+    }
+
+    #[inline(always)]
+    fn reject(&mut self, order: &Order, reason: &'static str) -> Result<(), &'static str> {
+        self.session_stats.orders_rejected += 1;
+        self.notify_rejected(order.id, order.symbol, order.participant, RejectCode::from_reason(reason));
+        Err(reason)
+    }
+
+    fn notify_rejected(&self, order_id: u64, symbol: SymbolId, participant: ParticipantId, code: RejectCode) {
+        if let Some(listener) = &self.reject_listener {
+            let _ = listener.send(OrderRejected { order_id, symbol, participant, code });
+        }
+    }
+
+    fn acknowledge(&self, order_id: u64, symbol: SymbolId, participant: ParticipantId, time_in_force: TimeInForce) {
+        if let Some(listener) = &self.ack_listener {
+            let _ = listener.send(OrderAck { order_id, symbol, participant, time_in_force, sequence: self.sequence });
+        }
+    }
+
+    /// Time in force applied to `symbol`'s orders that arrive as `SymbolDefault`. Only
+    /// `Day` and `GoodTillCancel` can be defaults.
+    pub fn set_default_time_in_force(&mut self, symbol: SymbolId, time_in_force: TimeInForce) -> Result<(), &'static str> {
+        if !self.supports_symbol(symbol) {
+            return Err("Invalid symbol");
+        }
+        if !matches!(time_in_force, TimeInForce::Day | TimeInForce::GoodTillCancel) {
+            return Err("Unsupported default time in force");
+        }
+        self.default_time_in_force.insert(symbol, time_in_force);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn default_time_in_force(&self, symbol: SymbolId) -> TimeInForce {
+        self.default_time_in_force.get(&symbol).copied().unwrap_or_default()
+    }
+
+    /// How `symbol`'s price levels pick the next order to match; see
+    /// `OrderBookLifecycle::set_tie_break`. `Fifo` unless set.
+    pub fn set_tie_break(&mut self, symbol: SymbolId, tie_break: TieBreak) -> Result<(), &'static str> {
+        let order_book = self.direct_order_books.get_mut(&symbol).ok_or("Invalid symbol")?;
+        order_book.set_tie_break(tie_break);
+        self.tie_breaks.insert(symbol, tie_break);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn tie_break(&self, symbol: SymbolId) -> TieBreak {
+        self.tie_breaks.get(&symbol).copied().unwrap_or_default()
+    }
+
+    fn notify_expired(&self, orders: impl IntoIterator<Item = Order>, reason: ExpiryReason) {
+        if let Some(listener) = &self.expiry_listener {
+            for order in orders {
+                let _ = listener.send(OrderExpired { order, reason, sequence: self.sequence });
+            }
+        }
+    }
+
+    pub fn attach_mirror(&mut self, publisher: MirrorPublisher) {
+        self.mirror = Some(publisher);
+        self.publish_all_top_of_book();
+    }
+
+    /// Publishes best bid/offer changes and trades to Redis through a running
+    /// `RedisBridge`, and the latest depth when the bridge keeps depth keys.
+    #[cfg(feature = "redis")]
+    pub fn attach_redis_bridge(&mut self, publisher: RedisPublisher) {
+        self.redis = Some(publisher);
+        self.publish_all_top_of_book();
+    }
+
+    #[inline(always)]
+    fn publishes_top_of_book(&self) -> bool {
+        #[cfg(feature = "redis")]
+        if self.redis.is_some() {
+            return true;
+        }
+        self.mirror.is_some()
+    }
+
+    fn publish_top_of_book(&self, symbol: SymbolId) {
+        if !self.publishes_top_of_book() {
+            return;
+        }
+        let Some((best_bid, best_ask)) = self.get_best_prices(symbol) else { return };
+        let update = MirrorUpdate { symbol, sequence: self.sequence, best_bid, best_ask };
+        if let Some(mirror) = &self.mirror {
+            mirror.publish(update);
+        }
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            redis.publish_top_of_book(update);
+            if let Some(levels) = redis.depth_levels() {
+                redis.publish_depth(self.depth_levels(symbol, levels));
+            }
+        }
+    }
+
+    #[cfg(feature = "redis")]
+    fn depth_levels(&self, symbol: SymbolId, levels: usize) -> DepthLevels {
+        let depth = self.get_depth(symbol, levels).unwrap_or_default();
+        let side = |levels: Vec<crate::engine::PriceLevelView>| levels.into_iter().map(|level| [level.price, level.total_quantity]).collect();
+        DepthLevels { symbol, sequence: self.sequence, bids: side(depth.bids), asks: side(depth.asks) }
+    }
+
+    /// Conflates pre-open indicative updates to at most one per symbol per interval.
+    /// Zero (the default) publishes after every order event.
+    pub fn set_indicative_interval(&mut self, interval: Duration) {
+        self.indicative_interval = interval;
+    }
+
+    /// Indicative opening price over the resting book and carried orders waiting for the open.
+    pub fn indicative_open(&self, symbol: SymbolId) -> Option<IndicativeOpen> {
+        let resting = self.direct_order_books.get(&symbol)?.resting_orders(symbol)?;
+        let carried = self.carried_orders.iter().filter(|order| order.symbol == symbol);
+        market_data::indicative_open(resting.iter().chain(carried))
+    }
+
+    fn update_indicative(&mut self, symbol: SymbolId) {
+        let now = Instant::now();
+        let due = self.indicative_published.get(&symbol)
+            .is_none_or(|published| now.duration_since(*published) >= self.indicative_interval);
+        if due {
+            self.publish_indicative(symbol, self.indicative_open(symbol));
+            self.indicative_published.insert(symbol, now);
+            self.indicative_pending.remove(&symbol);
+        } else {
+            self.indicative_pending.insert(symbol);
+        }
+    }
+
+    /// Publishes the latest indicative price for symbols held back by conflation.
+    pub fn flush_indicative(&mut self) {
+        if self.session_state != SessionState::PreOpen {
+            return;
+        }
+        let now = Instant::now();
+        for symbol in std::mem::take(&mut self.indicative_pending) {
+            self.publish_indicative(symbol, self.indicative_open(symbol));
+            self.indicative_published.insert(symbol, now);
+        }
+    }
+
+    fn publish_indicative(&self, symbol: SymbolId, indicative: Option<IndicativeOpen>) {
+        if let Some(mirror) = &self.mirror {
+            mirror.publish_indicative(IndicativeUpdate {
+                symbol,
+                sequence: self.sequence,
+                indicative,
+            });
+        }
+    }
+
+    fn publish_all_top_of_book(&self) {
+        for &symbol in self.direct_order_books.keys() {
+            self.publish_top_of_book(symbol);
+        }
+    }
+
+    #[inline(always)]
+    pub fn supports_symbol(&self, symbol: SymbolId) -> bool {
+        self.direct_order_books.contains_key(&symbol)
+    }
+
+    #[inline(always)]
+    pub fn get_implementation_name(&self) -> &'static str {
+        match self.order_book_type {
+            OrderBookType::HashMap => "HashMap",
+            OrderBookType::PriorityQueue => "PriorityQueue", 
+            OrderBookType::ArrayQueue => "ArrayQueue",
+            OrderBookType::Persistent => "Persistent",
+            OrderBookType::FixedCapacity => "FixedCapacity",
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_best_prices(&self, symbol: SymbolId) -> Option<(Option<u64>, Option<u64>)> {
+        self.direct_order_books.get(&symbol)
+            .and_then(|order_book| order_book.get_best_prices(symbol))
+    }
+
+    /// Registers a spread traded as `front - back`. Its implied prices are published
+    /// through `implied_best_prices`, and `match_all_orders` fills any cross against them.
+    pub fn add_implied_spread(&mut self, definition: ImpliedSpread) -> Result<(), &'static str> {
+        let [spread, front, back] = definition.legs();
+        if spread == front || spread == back || front == back {
+            return Err("Implied spread legs must be distinct");
+        }
+        // Implied prices add and subtract leg prices, which only works in one unit
+        if self.price_scale(front) != self.price_scale(spread) || self.price_scale(back) != self.price_scale(spread) {
+            return Err("Implied spread legs must share a price scale");
+        }
+        if !definition.legs().iter().all(|&symbol| self.supports_symbol(symbol)) {
+            return Err("Invalid symbol");
+        }
+        self.implied_spreads.push(definition);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn implied_spreads(&self) -> &[ImpliedSpread] {
+        &self.implied_spreads
+    }
+
+    /// Prices implied into `symbol` from resting orders in the related books.
+    pub fn implied_best_prices(&self, symbol: SymbolId) -> Option<BestPrices> {
+        if !self.supports_symbol(symbol) {
+            return None;
+        }
+
+        let direct = |symbol| self.get_best_prices(symbol).unwrap_or((None, None));
+        let mut implied_prices = (None, None);
+        for definition in self.implied_spreads.iter().filter(|definition| definition.involves(symbol)) {
+            let implied_prices_for_leg = if symbol == definition.spread {
+                definition.implied_spread(direct(definition.front), direct(definition.back))
+            } else if symbol == definition.front {
+                definition.implied_front(direct(definition.spread), direct(definition.back))
+            } else {
+                definition.implied_back(direct(definition.spread), direct(definition.front))
+            };
+            implied_prices = implied::best_of(implied_prices, implied_prices_for_leg);
+        }
+        Some(implied_prices)
+    }
+
+    /// Best of the direct book and any implied prices.
+    pub fn effective_best_prices(&self, symbol: SymbolId) -> Option<BestPrices> {
+        Some(implied::best_of(self.get_best_prices(symbol)?, self.implied_best_prices(symbol)?))
+    }
+
+    #[inline(always)]
+    pub fn get_symbols(&self) -> Vec<SymbolId> {
+        self.direct_order_books.keys().copied().collect()
+    }
+
+    /// Removes the symbol's book and hands back every order it held, including
+    /// good-till-cancel orders waiting for the next open.
+    pub fn drain_symbol(&mut self, symbol: SymbolId) -> Vec<Order> {
+        self.collect_evictions(Some(symbol));
+        let Some(order_book) = self.direct_order_books.remove(&symbol) else {
+            return Vec::new();
+        };
+
+        let mut orders = order_book.resting_orders(symbol).unwrap_or_default();
+        let (carried, remaining): (Vec<Order>, Vec<Order>) = std::mem::take(&mut self.carried_orders)
+            .into_iter()
+            .partition(|order| order.symbol == symbol);
+        orders.extend(carried);
+        self.carried_orders = remaining;
+        let (imbalance, remaining): (Vec<Order>, Vec<Order>) = std::mem::take(&mut self.imbalance_orders)
+            .into_iter()
+            .partition(|order| order.symbol == symbol);
+        orders.extend(imbalance);
+        self.imbalance_orders = remaining;
+        self.icebergs.release(&mut orders);
+        self.order_symbols.forget(&orders);
+        if let Some(ledger) = &mut self.ledger {
+            orders.iter().for_each(|order| ledger.cancel(order));
+        }
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.cancel(&orders, timestamp::now_nanos());
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.forget(&orders);
+        }
+
+        let cleared = MirrorUpdate { symbol, sequence: self.sequence, best_bid: None, best_ask: None };
+        if let Some(mirror) = &self.mirror {
+            mirror.publish(cleared);
+        }
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            redis.publish_top_of_book(cleared);
+            if redis.depth_levels().is_some() {
+                redis.publish_depth(DepthLevels { symbol, sequence: self.sequence, bids: Vec::new(), asks: Vec::new() });
+            }
+        }
+        orders
+    }
+
+    /// Takes `symbol` off the venue intraday. The book is captured as it rests, every
+    /// resting, carried and imbalance-only order is cancelled and reported to the expiry
+    /// listener as `Delisted`, market data is cleared, and the book is removed along
+    /// with the symbol's freezes, defaults and implied spreads, so later orders for it
+    /// are rejected as an invalid symbol. The returned record, also sent to the
+    /// delisting listener, can be kept with `SymbolDelisted::archive_to`.
+    pub fn delist_symbol(&mut self, symbol: SymbolId) -> Result<SymbolDelisted, &'static str> {
+        let final_snapshot = self.snapshot_book(symbol).ok_or("Invalid symbol")?;
+        let (last_trade_price, last_match_stats) = (self.last_trade_price(symbol), self.last_match_stats(symbol));
+
+        self.sequence += 1;
+        let cancelled = self.drain_symbol(symbol);
+        self.freezes.remove(&symbol);
+        self.default_time_in_force.remove(&symbol);
+        self.tie_breaks.remove(&symbol);
+        self.implied_spreads.retain(|definition| !definition.involves(symbol));
+        self.indicative_published.remove(&symbol);
+        self.indicative_pending.remove(&symbol);
+        self.notify_expired(cancelled.iter().cloned(), ExpiryReason::Delisted);
+
+        let delisted = SymbolDelisted {
+            symbol,
+            sequence: self.sequence,
+            final_snapshot,
+            cancelled,
+            last_trade_price,
+            last_match_stats,
+            delisted_at: timestamp::now_nanos(),
+        };
+        if let Some(listener) = &self.delisting_listener {
+            let _ = listener.send(delisted.clone());
+        }
+        Ok(delisted)
+    }
+
+    #[inline(always)]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn snapshot_book(&self, symbol: SymbolId) -> Option<BookSnapshot> {
+        let order_book = self.direct_order_books.get(&symbol)?;
+        Some(BookSnapshot {
+            symbol,
+            sequence: self.sequence,
+            orders: order_book.resting_orders(symbol)?,
+        })
+    }
+
+    /// The best `levels` price levels per side of `symbol`'s book.
+    pub fn get_depth(&self, symbol: SymbolId, levels: usize) -> Option<BookDepth> {
+        self.direct_order_books.get(&symbol)?.get_depth(symbol, levels)
+    }
+
+    pub fn aggregated_depth(&self, symbol: SymbolId, band_size: u64, levels: usize) -> Option<AggregatedDepth> {
+        self.direct_order_books.get(&symbol)?.aggregated_depth(symbol, band_size, levels)
+    }
+
+    pub fn depth_metrics(&self, symbol: SymbolId, config: DepthMetricsConfig) -> Option<DepthMetrics> {
+        let orders = self.direct_order_books.get(&symbol)?.resting_orders(symbol)?;
+        Some(DepthMetrics::from_orders(symbol, &orders, config))
+    }
+
+    /// Levels, session stats and notable resting orders for `symbol`, with the default
+    /// `BookReportConfig`.
+    pub fn export_report(&self, symbol: SymbolId) -> Option<BookReport> {
+        self.export_report_with(symbol, BookReportConfig::default())
+    }
+
+    pub fn export_report_with(&self, symbol: SymbolId, config: BookReportConfig) -> Option<BookReport> {
+        let order_book = self.direct_order_books.get(&symbol)?;
+        let context = ReportContext {
+            generated_at: timestamp::now_nanos(),
+            sequence: self.sequence,
+            session_id: self.session_id,
+            session_state: self.session_state,
+            session_stats: self.session_stats,
+            last_trade_price: order_book.last_trade_price(symbol),
+            trade_price_rule: order_book.trade_price_rule(),
+            price_scale: self.price_scale(symbol),
+            last_match_stats: order_book.last_match_stats(symbol),
+        };
+        Some(BookReport::from_orders(symbol, order_book.resting_orders(symbol)?, context, config))
+    }
+
+    pub fn snapshot_books(&self) -> Vec<BookSnapshot> {
+        let mut symbols = self.get_symbols();
+        symbols.sort_unstable();
+        symbols.into_iter()
+            .filter_map(|symbol| self.snapshot_book(symbol))
+            .collect()
+    }
+
+    pub fn snapshot_all(&self) -> RouterSnapshot {
+        RouterSnapshot {
+            sequence: self.sequence,
+            books: self.snapshot_books(),
+            session_state: self.session_state,
+            carried_orders: self.carried_orders.clone(),
+            imbalance_orders: self.imbalance_orders.clone(),
+            iceberg_reserves: {
+                let mut reserves: Vec<Order> = self.icebergs.reserves().cloned().collect();
+                reserves.sort_unstable_by_key(|reserve| reserve.id);
+                reserves
+            },
+        }
+    }
+
+    pub fn restore_all(&mut self, snapshot: RouterSnapshot) -> Result<(), &'static str> {
+        self.restore_books(snapshot.books)?;
+        self.sequence = self.sequence.max(snapshot.sequence);
+        self.session_state = snapshot.session_state;
+        self.carried_orders = snapshot.carried_orders;
+        self.imbalance_orders = snapshot.imbalance_orders;
+        let resting = self.resting_in_books();
+        self.icebergs.restore(snapshot.iceberg_reserves, resting.iter().chain(&self.carried_orders));
+        self.rebase_ledger();
+        Ok(())
+    }
+
+    pub fn restore_books(&mut self, snapshots: Vec<BookSnapshot>) -> Result<(), &'static str> {
+        if snapshots.iter().any(|snapshot| !self.supports_symbol(snapshot.symbol)) {
+            self.recovery_complete = false;
+            return Err("Invalid symbol");
+        }
+
+        // Reserves belong to the orders being replaced; `restore_all` brings back its own
+        self.icebergs = IcebergReserves::default();
+        let mut restored_orders = Vec::new();
+        for snapshot in snapshots {
+            let order_book = self.create_book(snapshot.symbol);
+            self.direct_order_books.insert(snapshot.symbol, order_book);
+            self.sequence = self.sequence.max(snapshot.sequence);
+            restored_orders.extend(snapshot.orders.into_iter().filter(|order| order.symbol == snapshot.symbol));
+        }
+        self.load_uncapped(restored_orders);
+        self.publish_all_top_of_book();
+        self.recovery_complete = true;
+        self.rebase_ledger();
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.clear_timelines();
+        }
+        Ok(())
+    }
+
+    // A restore replaces book contents wholesale, so accounting restarts from it
+    fn rebase_ledger(&mut self) {
+        if self.ledger.is_some() {
+            self.enable_ledger();
+        }
+        let open_orders = self.all_open_orders();
+        self.order_symbols.rebase(&open_orders);
+        if let Some(positions) = &mut self.positions {
+            positions.rebase(&open_orders);
+        }
+    }
+
+    /// Stops intake for all of `symbols` together, or for none if any is unknown.
+    /// Overlapping freezes nest; each needs its own `unfreeze_symbols`.
+    pub fn freeze_symbols(&mut self, symbols: &[SymbolId]) -> Result<(), &'static str> {
+        if !symbols.iter().all(|symbol| self.supports_symbol(*symbol)) {
+            return Err("Invalid symbol");
+        }
+        for &symbol in symbols {
+            *self.freezes.entry(symbol).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    pub fn unfreeze_symbols(&mut self, symbols: &[SymbolId]) -> Result<(), &'static str> {
+        if !symbols.iter().all(|symbol| self.freezes.contains_key(symbol)) {
+            return Err("Symbol not frozen");
+        }
+        for symbol in symbols {
+            if let Some(freezes) = self.freezes.get_mut(symbol) {
+                *freezes -= 1;
+                if *freezes == 0 {
+                    self.freezes.remove(symbol);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn is_frozen(&self, symbol: SymbolId) -> bool {
+        self.freezes.contains_key(&symbol)
+    }
+
+    #[inline(always)]
+    pub fn readiness(&self) -> Readiness {
+        Readiness {
+            recovery_complete: self.recovery_complete,
+            session_open: self.session_state == SessionState::Open,
+        }
+    }
+
+    #[inline(always)]
+    pub fn session_state(&self) -> SessionState {
+        self.session_state
+    }
+
+    #[inline(always)]
+    pub fn session_stats(&self) -> SessionStats {
+        self.session_stats
+    }
+
+    pub fn session_history(&self) -> &[SessionSummary] {
+        &self.session_history
+    }
+
+    /// Moves from `PreOpen` to `Open`, re-loading good-till-cancel orders carried over
+    /// from the previous session in their original priority order, then offsetting each
+    /// opening imbalance with imbalance-only orders. Returns the imbalance-only orders
+    /// that found no imbalance to offset, which are cancelled.
+    pub fn open_session(&mut self) -> Result<Vec<Order>, &'static str> {
+        if self.session_state != SessionState::PreOpen {
+            return Err("Session not in pre-open");
+        }
+
+        let carried_orders = std::mem::take(&mut self.carried_orders);
+        self.load_uncapped(carried_orders);
+        let (offsets, cancelled) = self.imbalance_offsets();
+        self.load_uncapped(offsets);
+        self.session_state = SessionState::Open;
+        self.indicative_pending.clear();
+        self.indicative_published.clear();
+        for &symbol in self.direct_order_books.keys() {
+            self.publish_indicative(symbol, None);
+        }
+        self.publish_all_top_of_book();
+        self.order_symbols.forget(&cancelled);
+        if let Some(ledger) = &mut self.ledger {
+            cancelled.iter().for_each(|order| ledger.cancel(order));
+        }
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.cancel(&cancelled, timestamp::now_nanos());
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.forget(&cancelled);
+        }
+        self.notify_expired(cancelled.iter().cloned(), ExpiryReason::AuctionUnfilled);
+        Ok(cancelled)
+    }
+
+    // Only orders opposite the surplus side and priced at or through the open qualify.
+    // They take the open price, most aggressive first, until the surplus is covered.
+    fn imbalance_offsets(&mut self) -> (Vec<Order>, Vec<Order>) {
+        let mut orders = std::mem::take(&mut self.imbalance_orders);
+        orders.sort_by_key(|order| {
+            let aggressiveness = match order.order_type {
+                OrderSide::Buy => u64::MAX - order.price,
+                OrderSide::Sell => order.price,
+            };
+            (order.symbol, aggressiveness, order.id)
+        });
+
+        let mut surpluses: FxHashMap<SymbolId, Option<(OrderSide, u64, u64)>> = FxHashMap::default();
+        let mut offsets = Vec::new();
+        let mut cancelled = Vec::new();
+        for order in orders {
+            let surplus = surpluses.entry(order.symbol).or_insert_with(|| {
+                self.indicative_open(order.symbol)
+                    .and_then(|open| Some((open.surplus_side?, open.price, open.surplus)))
+            });
+            match surplus {
+                Some((surplus_side, price, remaining))
+                    if *remaining > 0 && order.order_type != *surplus_side && match order.order_type {
+                        OrderSide::Buy => order.price >= *price,
+                        OrderSide::Sell => order.price <= *price,
+                    } =>
+                {
+                    *remaining = remaining.saturating_sub(order.quantity);
+                    offsets.push(Order { price: *price, ..order });
+                }
+                _ => cancelled.push(order),
+            }
+        }
+        (offsets, cancelled)
+    }
+
+    pub fn carried_orders(&self) -> &[Order] {
+        &self.carried_orders
+    }
+
+    /// Imbalance-only orders waiting for the opening auction.
+    pub fn imbalance_orders(&self) -> &[Order] {
+        &self.imbalance_orders
+    }
+
+    /// Closes the current session: records close prices, expires resting day orders,
+    /// holds good-till-cancel orders for the next open, archives the session
+    /// statistics and leaves the router in `PreOpen`.
+    pub fn roll_session(&mut self) -> SessionSummary {
+        self.session_state = SessionState::Closed;
+        self.collect_evictions(None);
+
+        let mut symbols = self.get_symbols();
+        symbols.sort_unstable();
+
+        let mut close_prices = FxHashMap::default();
+        let mut expired_orders = Vec::new();
+        for symbol in symbols {
+            let Some(order_book) = self.direct_order_books.get(&symbol) else { continue };
+            let (best_bid, best_ask) = order_book.get_best_prices(symbol).unwrap_or((None, None));
+            let last_trade_price = order_book.last_trade_price(symbol);
+            close_prices.insert(symbol, session::close_price(last_trade_price, best_bid, best_ask));
+            for order in order_book.resting_orders(symbol).unwrap_or_default() {
+                if order.flags().contains(OrderFlags::CARRIES_OVER) {
+                    self.carried_orders.push(order);
+                } else {
+                    expired_orders.push(order);
+                }
+            }
+
+            let fresh_book = self.create_book(symbol);
+            self.direct_order_books.insert(symbol, fresh_book);
+        }
+
+        self.icebergs.release(&mut expired_orders);
+        self.order_symbols.forget(&expired_orders);
+        let summary = SessionSummary {
+            session_id: self.session_id,
+            stats: std::mem::take(&mut self.session_stats),
+            close_prices,
+            expired_orders,
+            carried_orders: self.carried_orders.len(),
+        };
+        if let Some(ledger) = &mut self.ledger {
+            summary.expired_orders.iter().for_each(|order| ledger.expire(order));
+        }
+        if let Some(lifecycle) = &mut self.lifecycle {
+            lifecycle.cancel(&summary.expired_orders, timestamp::now_nanos());
+        }
+        if let Some(positions) = &mut self.positions {
+            positions.forget(&summary.expired_orders);
+        }
+        self.notify_expired(summary.expired_orders.iter().cloned(), ExpiryReason::SessionEnd);
+        self.session_history.push(summary.clone());
+        self.session_id += 1;
+        self.session_state = SessionState::PreOpen;
+        self.publish_all_top_of_book();
+        summary
+    }
+}
+
+#[inline(always)]
+fn book_error(error: OrderBookError) -> &'static str {
+    match error {
+        OrderBookError::InvalidSymbol => "Invalid symbol",
+        OrderBookError::ParticipantLimitExceeded => "Participant order limit exceeded",
+        OrderBookError::CapacityExceeded => "Book capacity exceeded",
+        OrderBookError::OrderNotFound => "Order not found",
+        OrderBookError::InvalidQuantity => "Invalid reduction quantity",
+        OrderBookError::OffTick => "Price off tick",
+        OrderBookError::OddLot => "Quantity not a multiple of lot size",
+        OrderBookError::BelowMinQuantity => "Quantity below minimum",
+        OrderBookError::BelowMinNotional => "Notional below minimum",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::exposure::AccountPosition;
+    use crate::router::message_rate::MessageAlertKind;
+    use crate::storage::{MemoryStorage, Storage};
+    use crate::types::order::{new_order, price_to_u64, OrderKind, OrderSide};
+
+    #[test]
+    fn test_roll_session_expires_orders_and_resets_stats() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.route_order(new_order(1, 0, 100, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 100, 101.0, OrderSide::Sell)).unwrap();
+        router.route_order(new_order(3, 1, 100, 50.0, OrderSide::Buy)).unwrap();
+        assert!(router.route_order(new_order(4, 9, 100, 50.0, OrderSide::Buy)).is_err());
+
+        let summary = router.roll_session();
+
+        assert_eq!(summary.stats.orders_routed, 3);
+        assert_eq!(summary.stats.orders_rejected, 1);
+        assert_eq!(summary.expired_orders.len(), 3);
+        assert_eq!(summary.close_prices[&0], Some(price_to_u64(100.0)));
+        assert_eq!(summary.close_prices[&1], Some(price_to_u64(50.0)));
+        assert_eq!(router.session_state(), SessionState::PreOpen);
+        assert_eq!(router.session_stats().orders_routed, 0);
+        assert_eq!(router.get_best_prices(0), Some((None, None)));
+        assert!(router.open_session().is_ok());
+        assert_eq!(router.session_history().len(), 1);
+    }
+
+    #[test]
+    fn test_drain_symbol_returns_resting_orders() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::PriorityQueue);
+        router.route_order(new_order(1, 0, 100, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 100, 101.0, OrderSide::Sell)).unwrap();
+        router.route_order(new_order(3, 1, 100, 50.0, OrderSide::Buy)).unwrap();
+
+        let drained = router.drain_symbol(0);
+
+        assert_eq!(drained.iter().map(|order| order.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(!router.supports_symbol(0));
+        assert!(router.route_order(new_order(4, 0, 100, 99.0, OrderSide::Buy)).is_err());
+        assert!(router.drain_symbol(0).is_empty());
+    }
+
+    #[test]
+    fn test_good_till_cancel_orders_survive_roll() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.route_order(new_order(1, 0, 100, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::GoodTillCancel)).unwrap();
+        router.route_order(new_order(2, 0, 100, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(3, 0, 100, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::GoodTillCancel)).unwrap();
+
+        let summary = router.roll_session();
+        assert_eq!(summary.expired_orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(summary.carried_orders, 2);
+
+        let snapshot = router.snapshot_all();
+        let mut recovered = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        recovered.restore_all(snapshot).unwrap();
+        assert_eq!(recovered.session_state(), SessionState::PreOpen);
+        recovered.open_session().unwrap();
+
+        let resting = recovered.snapshot_book(0).unwrap().orders;
+        assert_eq!(resting.iter().map(|order| order.id).collect::<Vec<_>>(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_readiness_tracks_session_and_recovery() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        assert!(router.readiness().is_ready());
+
+        router.roll_session();
+        assert!(!router.readiness().session_open);
+
+        let mut snapshot = router.snapshot_all();
+        snapshot.books[0].symbol = 9;
+        assert!(router.restore_all(snapshot).is_err());
+        assert!(!router.readiness().recovery_complete);
+
+        router.restore_all(router.snapshot_all()).unwrap();
+        router.open_session().unwrap();
+        assert!(router.readiness().is_ready());
+    }
+
+    #[test]
+    fn test_freeze_symbols_blocks_intake() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.freeze_symbols(&[0, 1]).unwrap();
+        assert!(router.freeze_symbols(&[0, 7]).is_err());
+        assert_eq!(router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)), Err("Symbol frozen"));
+
+        router.unfreeze_symbols(&[1]).unwrap();
+        assert!(router.route_order(new_order(2, 1, 10, 100.0, OrderSide::Buy)).is_ok());
+        assert!(router.is_frozen(0));
+        assert!(router.unfreeze_symbols(&[0, 1]).is_err());
+        router.unfreeze_symbols(&[0]).unwrap();
+        assert!(router.route_order(new_order(3, 0, 10, 100.0, OrderSide::Buy)).is_ok());
+    }
+
+    #[test]
+    fn test_implied_spread_prices() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1, 2]), OrderBookType::HashMap);
+        router.add_implied_spread(ImpliedSpread { spread: 2, front: 0, back: 1 }).unwrap();
+        assert!(router.add_implied_spread(ImpliedSpread { spread: 2, front: 0, back: 0 }).is_err());
+
+        router.route_order(new_order(1, 0, 100, 105.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 1, 100, 100.0, OrderSide::Sell)).unwrap();
+        router.route_order(new_order(3, 2, 100, 4.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(4, 2, 100, 6.0, OrderSide::Sell)).unwrap();
+
+        assert_eq!(router.implied_best_prices(2), Some((Some(price_to_u64(5.0)), None)));
+        assert_eq!(router.effective_best_prices(2), Some((Some(price_to_u64(5.0)), Some(price_to_u64(6.0)))));
+        assert_eq!(router.implied_best_prices(1), Some((Some(price_to_u64(99.0)), None)));
+        assert_eq!(router.implied_best_prices(9), None);
+    }
+
+    #[test]
+    fn test_implied_spread_cross_fills_all_legs_across_types() {
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1, 2]), order_book_type);
+            router.add_implied_spread(ImpliedSpread { spread: 2, front: 0, back: 1 }).unwrap();
+            router.enable_ledger();
+            router.route_order(new_order(1, 0, 30, 105.0, OrderSide::Sell)).unwrap();
+            router.route_order(new_order(2, 0, 20, 105.0, OrderSide::Sell)).unwrap();
+            router.route_order(new_order(3, 1, 40, 100.0, OrderSide::Buy)).unwrap();
+            router.route_order(new_order(4, 2, 60, 6.0, OrderSide::Buy)).unwrap();
+
+            // The spread bid at 6 crosses the front ask less the back bid, 5
+            assert!(router.match_all_orders().is_empty(), "{order_book_type:?}");
+            let fill = |symbol, order_id, side, price, quantity| ImpliedFill { symbol, order_id, side, price: price_to_u64(price), quantity };
+            assert_eq!(router.take_implied_fills(), vec![
+                fill(2, 4, OrderSide::Buy, 5.0, 40),
+                fill(0, 1, OrderSide::Sell, 105.0, 30),
+                fill(0, 2, OrderSide::Sell, 105.0, 10),
+                fill(1, 3, OrderSide::Buy, 100.0, 40),
+            ], "{order_book_type:?}");
+            assert_eq!(router.get_best_prices(0), Some((None, Some(price_to_u64(105.0)))), "{order_book_type:?}");
+            assert_eq!(router.get_best_prices(1), Some((None, None)), "{order_book_type:?}");
+            let resting: Vec<(u64, u64)> = router.resting_in_books().iter().map(|order| (order.id, order.quantity)).collect();
+            assert_eq!(resting.len(), 2, "{order_book_type:?}");
+            assert!(resting.contains(&(2, 10)) && resting.contains(&(4, 20)), "{order_book_type:?}");
+            assert_eq!(router.reconcile(), Some(Vec::new()), "{order_book_type:?}");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_participant_limits_reject_queue_hogging() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::PriorityQueue);
+        router.set_participant_limits(ParticipantLimits::default().with_max_orders_per_level(2));
+
+        for id in 1..=2 {
+            router.route_order(new_order(id, 0, 100, 99.0, OrderSide::Buy).with_participant(1)).unwrap();
+        }
+        assert_eq!(router.route_order(new_order(3, 0, 100, 99.0, OrderSide::Buy).with_participant(1)), Err("Participant order limit exceeded"));
+        assert!(router.route_order(new_order(4, 0, 100, 99.0, OrderSide::Buy).with_participant(2)).is_ok());
+
+        router.route_order(new_order(5, 0, 100, 99.0, OrderSide::Sell)).unwrap();
+        router.match_all_orders();
+        assert!(router.route_order(new_order(6, 0, 100, 99.0, OrderSide::Buy).with_participant(1)).is_ok());
+        assert_eq!(router.session_stats().orders_rejected, 1);
+    }
+
+    #[test]
+    fn test_imbalance_only_orders_offset_opening_surplus() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        let imbalance_only = |id, quantity, price, side| new_order(id, 0, quantity, price, side).with_time_in_force(TimeInForce::ImbalanceOnly);
+        assert_eq!(router.route_order(imbalance_only(1, 100, 100.0, OrderSide::Sell)), Err("Imbalance-only order outside pre-open"));
+
+        router.roll_session();
+        for id in 2..=4 {
+            router.route_order(new_order(id, 0, 100, 101.0, OrderSide::Buy)).unwrap();
+        }
+        router.route_order(new_order(5, 0, 100, 100.0, OrderSide::Sell)).unwrap();
+        router.route_order(imbalance_only(6, 150, 100.0, OrderSide::Sell)).unwrap();
+        router.route_order(imbalance_only(7, 100, 99.0, OrderSide::Sell)).unwrap();
+        router.route_order(imbalance_only(8, 50, 102.0, OrderSide::Sell)).unwrap();
+        router.route_order(imbalance_only(9, 10, 101.0, OrderSide::Buy)).unwrap();
+        assert_eq!(router.indicative_open(0).unwrap().surplus, 200);
+
+        let cancelled = router.open_session().unwrap();
+        assert_eq!(cancelled.iter().map(|order| order.id).collect::<Vec<_>>(), vec![8, 9]);
+        let trades = router.match_all_orders();
+        assert_eq!(trades.iter().map(|trade| trade.quantity).sum::<u64>(), 300);
+        let resting = router.snapshot_book(0).unwrap().orders;
+        assert_eq!(resting.iter().map(|order| (order.id, order.quantity)).collect::<Vec<_>>(), vec![(6, 50)]);
+        assert!(router.imbalance_orders().is_empty());
+    }
+
+    #[test]
+    fn test_expiry_listener_reports_reasons() {
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.attach_expiry_listener(sender);
+        router.set_max_depth(Some(1));
+
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.roll_session();
+        router.route_order(new_order(3, 0, 10, 99.0, OrderSide::Sell).with_time_in_force(TimeInForce::ImbalanceOnly)).unwrap();
+        let immediate_or_cancel = new_order(5, 0, 20, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImmediateOrCancel);
+        assert_eq!(router.route_order(immediate_or_cancel.clone()), Err("Immediate-or-cancel order in pre-open"));
+        router.open_session().unwrap();
+        router.route_order(new_order(4, 0, 5, 99.0, OrderSide::Sell)).unwrap();
+        router.route_order(immediate_or_cancel).unwrap();
+        assert_eq!(router.match_all_orders().iter().map(|trade| trade.quantity).collect::<Vec<_>>(), vec![5]);
+
+        let expired: Vec<_> = receiver.try_iter().map(|event| (event.order.id, event.order.quantity, event.reason)).collect();
+        assert_eq!(expired, vec![
+            (1, 10, ExpiryReason::DepthEviction),
+            (2, 10, ExpiryReason::SessionEnd),
+            (3, 10, ExpiryReason::AuctionUnfilled),
+            (5, 15, ExpiryReason::ImmediateOrCancel),
+        ]);
+        assert!(router.take_evicted_orders().is_empty());
+    }
+
+    #[test]
+    fn test_expire_orders_sweeps_books_and_held_orders() {
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.attach_expiry_listener(sender);
+        router.enable_ledger();
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::GoodTillCancel).with_expiry(5_000)).unwrap();
+        router.route_order(new_order(2, 0, 10, 101.0, OrderSide::Sell).with_expiry(1_000)).unwrap();
+        router.roll_session();
+        assert_eq!(router.carried_orders().len(), 1);
+
+        // The carried order expires before it is reloaded at the open
+        assert_eq!(router.expire_orders(5_000).iter().map(|order| order.id).collect::<Vec<_>>(), vec![1]);
+        router.open_session().unwrap();
+        router.route_order(new_order(3, 0, 10, 99.0, OrderSide::Buy).with_expiry(6_000)).unwrap();
+        assert_eq!(router.expire_orders(6_000).iter().map(|order| order.id).collect::<Vec<_>>(), vec![3]);
+
+        let reasons: Vec<_> = receiver.try_iter().map(|event| (event.order.id, event.reason)).collect();
+        assert_eq!(reasons, vec![
+            (2, ExpiryReason::SessionEnd),
+            (1, ExpiryReason::GoodTillDate),
+            (3, ExpiryReason::GoodTillDate),
+        ]);
+        assert_eq!(router.reconcile(), Some(Vec::new()));
+        assert_eq!(router.get_best_prices(0), Some((None, None)));
+    }
+
+    #[test]
+    fn test_delist_symbol_cancels_archives_and_removes_book() {
+        let (expiry_sender, expiry_receiver) = crossbeam::channel::unbounded();
+        let (delisting_sender, delisting_receiver) = crossbeam::channel::unbounded();
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.attach_expiry_listener(expiry_sender);
+        router.attach_delisting_listener(delisting_sender);
+        router.enable_ledger();
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 4, 100.0, OrderSide::Sell)).unwrap();
+        router.match_all_orders();
+        router.route_order(new_order(3, 0, 10, 101.0, OrderSide::Sell).with_time_in_force(TimeInForce::GoodTillCancel)).unwrap();
+        router.route_order(new_order(4, 1, 10, 50.0, OrderSide::Buy)).unwrap();
+        router.freeze_symbols(&[0]).unwrap();
+
+        let delisted = router.delist_symbol(0).unwrap();
+        assert_eq!(delisted.final_snapshot.orders.iter().map(|order| (order.id, order.quantity)).collect::<Vec<_>>(), vec![(1, 6), (3, 10)]);
+        let mut cancelled: Vec<u64> = delisted.cancelled.iter().map(|order| order.id).collect();
+        cancelled.sort_unstable();
+        assert_eq!(cancelled, vec![1, 3]);
+        assert_eq!(delisted.last_trade_price, Some(price_to_u64(100.0)));
+        assert_eq!(delisted.sequence, router.sequence());
+        assert!(expiry_receiver.try_iter().all(|event| event.reason == ExpiryReason::Delisted));
+        assert_eq!(delisting_receiver.try_recv().unwrap().symbol, 0);
+
+        let storage = MemoryStorage::new();
+        let key = delisted.archive_to(&storage).unwrap();
+        let archived: SymbolDelisted = serde_json::from_slice(&storage.get(&key).unwrap().unwrap()).unwrap();
+        assert_eq!(archived.cancelled.len(), 2);
+
+        // The symbol is gone; its neighbour is untouched
+        assert!(!router.is_frozen(0));
+        assert_eq!(router.route_order(new_order(5, 0, 10, 100.0, OrderSide::Buy)), Err("Invalid symbol"));
+        assert_eq!(router.delist_symbol(0).map(|_| ()), Err("Invalid symbol"));
+        assert_eq!(router.get_best_prices(1), Some((Some(price_to_u64(50.0)), None)));
+        assert_eq!(router.reconcile(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_iceberg_shows_one_slice_and_refills_at_back() {
+        let iceberg = OrderKind::Iceberg { display_quantity: 100 };
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), order_book_type);
+            router.enable_ledger();
+            router.route_order(new_order(1, 0, 250, 100.0, OrderSide::Sell).with_kind(iceberg)).unwrap();
+            router.route_order(new_order(2, 0, 50, 100.0, OrderSide::Sell)).unwrap();
+            let shown: u64 = router.snapshot_book(0).unwrap().orders.iter().map(|order| order.quantity).sum();
+            assert_eq!(shown, 150, "{order_book_type:?}");
+
+            // Taking the whole slice brings the next one out of the reserve in the same cycle
+            router.route_order(new_order(3, 0, 220, 100.0, OrderSide::Buy)).unwrap();
+            let traded: u64 = router.match_all_orders().iter().map(|trade| trade.quantity).sum();
+            assert_eq!(traded, 220, "{order_book_type:?}");
+            let shown: u64 = router.snapshot_book(0).unwrap().orders.iter().map(|order| order.quantity).sum();
+            assert_eq!(shown, 30, "{order_book_type:?}");
+            assert_eq!(router.reconcile(), Some(Vec::new()), "{order_book_type:?}");
+
+            // A snapshot keeps the reserve, and a cancel hands it back with the slice
+            let mut restored = OrderRouter::new_direct(FxHashSet::from_iter([0]), order_book_type);
+            restored.restore_all(router.snapshot_all()).unwrap();
+            restored.enable_ledger();
+            let cancelled = restored.cancel_orders_batch(&[1]).cancelled;
+            assert_eq!(cancelled.iter().map(|order| order.quantity).sum::<u64>(), 250 + 50 - 220, "{order_book_type:?}");
+            assert_eq!(restored.reconcile(), Some(Vec::new()), "{order_book_type:?}");
+        }
+
+        // In a FIFO book the refilled slice queues behind orders already at its price
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.route_order(new_order(1, 0, 250, 100.0, OrderSide::Sell).with_kind(iceberg)).unwrap();
+        router.route_order(new_order(2, 0, 50, 100.0, OrderSide::Sell)).unwrap();
+        router.route_order(new_order(3, 0, 120, 100.0, OrderSide::Buy)).unwrap();
+        let fills: Vec<(u64, u64)> = router.match_all_orders().iter().map(|trade| (trade.maker_order_id, trade.quantity)).collect();
+        assert_eq!(fills, vec![(1, 100), (2, 20)]);
+        router.route_order(new_order(4, 0, 40, 100.0, OrderSide::Buy)).unwrap();
+        let fills: Vec<(u64, u64)> = router.match_all_orders().iter().map(|trade| (trade.maker_order_id, trade.quantity)).collect();
+        assert_eq!(fills, vec![(2, 30), (1, 10)]);
+    }
+
+    #[test]
+    fn test_ledger_reconciles_across_types() {
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), order_book_type);
+            router.route_order(new_order(1, 0, 40, 99.0, OrderSide::Buy)).unwrap();
+            router.enable_ledger();
+            router.set_max_depth(Some(3));
+
+            let mut id = 2;
+            for round in 0..60u64 {
+                let side = if round % 2 == 0 { OrderSide::Buy } else { OrderSide::Sell };
+                let price = 100.0 + (round % 7) as f64 - 3.0;
+                let order = new_order(id, (round % 2) as SymbolId, 10 + round, price, side);
+                let order = match round % 5 {
+                    0 => order.with_time_in_force(TimeInForce::GoodTillCancel),
+                    3 => order.with_time_in_force(TimeInForce::ImmediateOrCancel),
+                    _ => order,
+                };
+                router.route_order(order).unwrap();
+                id += 1;
+                if round % 3 == 0 {
+                    router.match_all_orders();
+                }
+            }
+            router.roll_session();
+            router.route_order(new_order(id, 0, 10, 90.0, OrderSide::Sell).with_time_in_force(TimeInForce::ImbalanceOnly)).unwrap();
+            router.open_session().unwrap();
+            router.match_all_orders();
+            router.drain_symbol(1);
+
+            assert_eq!(router.reconcile(), Some(Vec::new()), "{order_book_type}");
+        }
+        assert_eq!(OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap).reconcile(), None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_tick_sizes_reject_off_tick_prices() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        router.attach_reject_listener(sender);
+        router.set_tick_size(0, 50);
+        assert_eq!(router.route_order(new_order(1, 0, 10, 100.02, OrderSide::Buy)), Err("Price off tick"));
+        assert_eq!(receiver.try_recv().unwrap().code, RejectCode::OffTick);
+        assert!(router.route_order(new_order(2, 0, 10, 100.05, OrderSide::Buy)).is_ok());
+        assert!(router.route_order(new_order(3, 1, 10, 100.02, OrderSide::Buy)).is_ok());
+
+        // Books created later, like a restore's, get the table too
+        router.restore_books(router.snapshot_books()).unwrap();
+        assert!(router.route_order(new_order(4, 0, 10, 100.03, OrderSide::Sell)).is_err());
+        router.set_tick_size(0, 1);
+        assert!(router.route_order(new_order(4, 0, 10, 100.03, OrderSide::Sell)).is_ok());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_lot_sizes_reject_odd_lots_and_small_orders() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        router.attach_reject_listener(sender);
+        router.set_lot_size(0, LotSize::new(10).with_min_quantity(20));
+        assert_eq!(router.route_order(new_order(1, 0, 25, 100.0, OrderSide::Buy)), Err("Quantity not a multiple of lot size"));
+        assert_eq!(receiver.try_recv().unwrap().code, RejectCode::InvalidQuantity);
+        assert_eq!(router.route_order(new_order(2, 0, 10, 100.0, OrderSide::Buy)), Err("Quantity below minimum"));
+        assert_eq!(receiver.try_recv().unwrap().code, RejectCode::TooLarge);
+        assert!(router.route_order(new_order(3, 0, 20, 100.0, OrderSide::Buy)).is_ok());
+        assert!(router.route_order(new_order(4, 1, 5, 100.0, OrderSide::Buy)).is_ok());
+
+        router.set_lot_size(0, LotSize::default().with_min_notional(price_to_u64(10_000.0) as u128));
+        assert_eq!(router.route_order(new_order(5, 0, 50, 100.0, OrderSide::Sell)), Err("Notional below minimum"));
+        // Books created later, like a restore's, get the table too
+        router.restore_books(router.snapshot_books()).unwrap();
+        assert!(router.route_order(new_order(5, 0, 50, 100.0, OrderSide::Sell)).is_err());
+        assert!(router.route_order(new_order(5, 0, 100, 100.0, OrderSide::Sell)).is_ok());
+    }
+
+    #[test]
+    fn test_price_scales_per_symbol() {
+        use crate::types::price_scale::PriceScale;
+
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1, 2]), OrderBookType::HashMap);
+        let fx = PriceScale::new(5).unwrap();
+        router.set_price_scale(1, fx);
+        assert_eq!((router.price_scale(0), router.price_scale(1)), (PriceScale::DEFAULT, fx));
+
+        router.route_order(router.price_scale(1).order(1, 1, 10, 1.23456, OrderSide::Buy)).unwrap();
+        router.route_order(router.price_scale(1).order(2, 1, 10, 1.23456, OrderSide::Sell)).unwrap();
+        assert_eq!(router.match_all_orders()[0].price, 123_456);
+        assert!(router.export_report(1).unwrap().to_string().contains("last trade 1.23456 "));
+
+        assert_eq!(router.add_implied_spread(ImpliedSpread { spread: 2, front: 0, back: 1 }), Err("Implied spread legs must share a price scale"));
+        router.set_price_scale(1, PriceScale::DEFAULT);
+        assert!(router.price_scales().is_empty());
+        assert!(router.add_implied_spread(ImpliedSpread { spread: 2, front: 0, back: 1 }).is_ok());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_dedup_window_per_participant() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.enable_dedup(DedupWindow::new(1));
+        router.set_dedup_window(2, DedupWindow::new(4));
+
+        for participant in [1, 2] {
+            router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_participant(participant)).unwrap();
+            router.route_order(new_order(2, 0, 10, 98.0, OrderSide::Buy).with_participant(participant)).unwrap();
+        }
+        // Participant 1 only remembers id 2; participant 2 still remembers id 1
+        assert_eq!(router.route_order(new_order(2, 0, 10, 98.0, OrderSide::Buy).with_participant(1)), Err("Duplicate order"));
+        assert!(router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_participant(1)).is_ok());
+        assert_eq!(router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_participant(2)), Err("Duplicate order"));
+        // A rejected order is not remembered, so its retransmit gets through
+        assert!(router.route_order(new_order(3, 9, 10, 99.0, OrderSide::Buy).with_participant(2)).is_err());
+        assert!(router.route_order(new_order(3, 0, 10, 99.0, OrderSide::Buy).with_participant(2)).is_ok());
+
+        assert_eq!(router.dedup_stats(1), Some(DedupStats { hits: 1, evictions: 2, remembered: 1 }));
+        assert_eq!(router.dedup_stats(2).map(|stats| stats.remembered), Some(3));
+        assert_eq!(router.dedup_hits(), 2);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "strip-validation", ignore = "needs validation")]
+    fn test_reject_listener_reports_codes() {
+        use crate::engine::ParticipantLimits;
+
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        router.attach_reject_listener(sender);
+        router.set_participant_limits(ParticipantLimits::default().with_max_orders_per_book(1));
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_participant(4)).unwrap();
+        assert!(router.route_order(new_order(2, 0, 10, 98.0, OrderSide::Buy).with_participant(4)).is_err());
+        assert!(router.route_order(new_order(3, 9, 10, 98.0, OrderSide::Buy)).is_err());
+        router.freeze_symbols(&[0]).unwrap();
+        assert!(router.route_order(new_order(4, 0, 10, 98.0, OrderSide::Buy)).is_err());
+
+        let rejects: Vec<_> = receiver.try_iter().map(|reject| (reject.order_id, reject.participant, reject.code)).collect();
+        assert_eq!(rejects, vec![(2, 4, RejectCode::Throttled), (3, 0, RejectCode::UnknownSymbol), (4, 0, RejectCode::Halted)]);
+        assert_eq!(router.session_stats().orders_rejected, 3);
+    }
+
+    #[test]
+    fn test_symbol_default_time_in_force_echoed_in_ack() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        router.attach_ack_listener(sender);
+        router.set_default_time_in_force(1, TimeInForce::GoodTillCancel).unwrap();
+        assert_eq!(router.set_default_time_in_force(1, TimeInForce::ImbalanceOnly), Err("Unsupported default time in force"));
+        assert_eq!(router.set_default_time_in_force(7, TimeInForce::Day), Err("Invalid symbol"));
+
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::SymbolDefault)).unwrap();
+        router.route_order(new_order(2, 1, 10, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::SymbolDefault)).unwrap();
+        router.route_order(new_order(3, 1, 10, 98.0, OrderSide::Buy).with_time_in_force(TimeInForce::Day)).unwrap();
+        let acks: Vec<_> = receiver.try_iter().map(|ack| (ack.order_id, ack.time_in_force, ack.sequence)).collect();
+        assert_eq!(acks, vec![(1, TimeInForce::Day, 1), (2, TimeInForce::GoodTillCancel, 2), (3, TimeInForce::Day, 3)]);
+
+        // Only the orders that resolved to Day expire at the close
+        let summary = router.roll_session();
+        assert_eq!(summary.expired_orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(summary.carried_orders, 1);
+    }
+
+    #[test]
+    fn test_tie_break_per_symbol_survives_roll() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.set_tie_break(1, TieBreak::SizePriority).unwrap();
+        assert_eq!(router.set_tie_break(7, TieBreak::SizePriority), Err("Invalid symbol"));
+        router.roll_session();
+        router.open_session().unwrap();
+
+        for symbol in [0, 1] {
+            let base = symbol as u64 * 10;
+            router.route_order(new_order(base + 1, symbol, 5, 100.0, OrderSide::Buy)).unwrap();
+            router.route_order(new_order(base + 2, symbol, 20, 100.0, OrderSide::Buy)).unwrap();
+            router.route_order(new_order(base + 3, symbol, 20, 100.0, OrderSide::Sell)).unwrap();
+        }
+        router.match_all_orders();
+
+        let resting = |router: &OrderRouter, symbol| router.snapshot_book(symbol).unwrap().orders.iter().map(|order| order.id).collect::<Vec<_>>();
+        assert_eq!((router.tie_break(0), resting(&router, 0)), (TieBreak::Fifo, vec![2]));
+        assert_eq!((router.tie_break(1), resting(&router, 1)), (TieBreak::SizePriority, vec![11]));
+    }
+
+    #[test]
+    fn test_reduce_order_accounts_and_notifies() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        router.enable_ledger();
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        router.attach_reduction_listener(sender);
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy)).unwrap();
+
+        assert_eq!(router.reduce_order(0, 1, 3).map(|order| order.quantity), Ok(7));
+        assert_eq!(router.reduce_order(0, 1, 7).err(), Some("Invalid reduction quantity"));
+        assert_eq!(router.reduce_order(0, 2, 1).err(), Some("Order not found"));
+        assert_eq!(router.reduce_order(5, 1, 1).err(), Some("Invalid symbol"));
+
+        let reductions: Vec<_> = receiver.try_iter().map(|event| (event.order.id, event.order.quantity, event.reduced_by, event.sequence)).collect();
+        assert_eq!(reductions, vec![(1, 7, 3, 2)]);
+        assert_eq!(router.reconcile(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_cancel_orders_batch_across_symbols() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.enable_ledger();
+        for (id, symbol, price) in [(1, 0, 99.0), (2, 1, 98.0), (3, 0, 97.0), (4, 1, 96.0)] {
+            router.route_order(new_order(id, symbol, 10, price, OrderSide::Buy)).unwrap();
+        }
+
+        let result = router.cancel_orders_batch(&[4, 7, 1, 2]);
+        let cancelled: Vec<_> = result.cancelled.iter().map(|order| (order.symbol, order.id)).collect();
+        assert_eq!(cancelled, vec![(0, 1), (1, 4), (1, 2)]);
+        assert_eq!(result.not_found, vec![7]);
+        assert_eq!(router.get_best_prices(0), Some((Some(price_to_u64(97.0)), None)));
+        assert_eq!(router.get_best_prices(1), Some((None, None)));
+        assert_eq!(router.reconcile(), Some(Vec::new()));
+        assert_eq!(router.cancel_orders_batch(&[1]).not_found, vec![1]);
+    }
+
+    #[test]
+    fn test_cancel_orders_batch_after_fills_and_session_roll() {
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), order_book_type);
+            router.enable_ledger();
+            router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::GoodTillCancel)).unwrap();
+            router.route_order(new_order(2, 0, 4, 99.0, OrderSide::Sell)).unwrap();
+            router.route_order(new_order(3, 1, 10, 98.0, OrderSide::Buy)).unwrap();
+            router.route_order(new_order(4, 1, 3, 98.0, OrderSide::Sell)).unwrap();
+            assert_eq!(router.match_all_orders().len(), 2, "{order_book_type:?}");
+
+            let result = router.cancel_orders_batch(&[3, 4]);
+            assert_eq!(result.cancelled.iter().map(|order| (order.id, order.quantity)).collect::<Vec<_>>(), vec![(3, 7)], "{order_book_type:?}");
+            assert_eq!(result.not_found, vec![4], "{order_book_type:?}");
+
+            // Held until the next open, the good-till-cancel remainder is no longer in a book
+            router.roll_session();
+            let result = router.cancel_orders_batch(&[2, 1]);
+            assert_eq!(result.cancelled.iter().map(|order| (order.id, order.quantity)).collect::<Vec<_>>(), vec![(1, 6)], "{order_book_type:?}");
+            assert_eq!(result.not_found, vec![2], "{order_book_type:?}");
+            assert!(router.carried_orders().is_empty(), "{order_book_type:?}");
+            assert_eq!(router.reconcile(), Some(Vec::new()), "{order_book_type:?}");
+        }
+    }
+
+    #[test]
+    fn test_stale_quotes_and_cancel_on_disconnect() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.enable_ledger();
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        router.attach_expiry_listener(sender);
+        router.route_quote(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_participant(7), Duration::ZERO).unwrap();
+        router.route_quote(new_order(2, 0, 10, 101.0, OrderSide::Sell).with_participant(7), Duration::from_secs(60)).unwrap();
+        router.route_order(new_order(1, 1, 10, 98.0, OrderSide::Buy).with_participant(8)).unwrap();
+        router.route_order(new_order(3, 1, 10, 97.0, OrderSide::Buy).with_participant(7)).unwrap();
+
+        assert_eq!(router.refresh_quotes(7), 2);
+        router.route_quote(new_order(4, 1, 10, 96.0, OrderSide::Buy).with_participant(7), Duration::ZERO).unwrap();
+        // Only the zero-TTL quotes lapse, and participant 8's order 1 is left alone
+        let expired: Vec<_> = router.expire_stale_quotes().iter().map(|order| (order.symbol, order.id)).collect();
+        assert_eq!(expired, vec![(0, 1), (1, 4)]);
+        assert!(router.expire_stale_quotes().is_empty());
+
+        assert!(router.connection_lost(7).is_empty());
+        router.set_cancel_on_disconnect(7, true);
+        let pulled: Vec<_> = router.connection_lost(7).iter().map(|order| order.id).collect();
+        assert_eq!(pulled, vec![2, 3]);
+        assert_eq!(router.get_best_prices(1), Some((Some(price_to_u64(98.0)), None)));
+
+        let reasons: Vec<_> = receiver.try_iter().map(|event| (event.order.id, event.reason)).collect();
+        assert_eq!(reasons, vec![
+            (1, ExpiryReason::QuoteExpired),
+            (4, ExpiryReason::QuoteExpired),
+            (2, ExpiryReason::Disconnected),
+            (3, ExpiryReason::Disconnected),
+        ]);
+        assert_eq!(router.reconcile(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_max_depth_evicts_far_levels() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        for (id, price) in [(1, 100.0), (2, 99.0), (3, 98.0)] {
+            router.route_order(new_order(id, 0, 10, price, OrderSide::Buy)).unwrap();
+        }
+        router.set_max_depth(Some(2));
+        assert_eq!(router.take_evicted_orders().iter().map(|order| order.id).collect::<Vec<_>>(), vec![3]);
+
+        router.route_order(new_order(4, 0, 10, 101.0, OrderSide::Buy)).unwrap();
+        assert_eq!(router.take_evicted_orders().iter().map(|order| order.id).collect::<Vec<_>>(), vec![2]);
+        assert!(router.take_evicted_orders().is_empty());
+        assert_eq!(router.get_best_prices(0), Some((Some(price_to_u64(101.0)), None)));
+    }
+
+    #[test]
+    fn test_lifecycle_metrics_time_fills_and_cancels() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        assert!(router.lifecycle_metrics(0).is_none());
+        router.enable_lifecycle_metrics(100);
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 4, 100.0, OrderSide::Sell)).unwrap();
+        router.route_order(new_order(3, 1, 5, 50.0, OrderSide::Buy)).unwrap();
+        assert!(router.route_order(new_order(4, 9, 5, 50.0, OrderSide::Buy)).is_err());
+        router.match_all_orders();
+
+        let timeline = router.order_timeline(0, 1).unwrap();
+        assert!(timeline.received_at <= timeline.accepted_at && timeline.first_fill_at.is_some_and(|at| at >= timeline.accepted_at));
+        assert_eq!(timeline.remaining, 6);
+        assert!(router.order_timeline(0, 2).is_none());
+        router.cancel_orders_batch(&[1, 3]);
+        assert!(router.order_timeline(0, 1).is_none());
+
+        let metrics = router.lifecycle_metrics(0).unwrap();
+        assert_eq!(metrics.time_to_ack.samples, 2);
+        assert_eq!(metrics.time_to_first_fill.samples, 2);
+        assert_eq!(metrics.time_to_fill.samples, 1);
+        assert_eq!(metrics.resting_before_cancel.samples, 1);
+        let other = router.lifecycle_metrics(1).unwrap();
+        assert_eq!((other.time_to_ack.samples, other.time_to_first_fill.samples, other.resting_before_cancel.samples), (1, 0, 1));
+    }
+
+    #[test]
+    fn test_best_execution_reports_aggressive_orders_across_types() {
+        for order_book_type in [OrderBookType::HashMap, OrderBookType::PriorityQueue, OrderBookType::ArrayQueue, OrderBookType::Persistent, OrderBookType::FixedCapacity] {
+            let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), order_book_type);
+            assert!(router.best_execution_reports().is_none());
+            router.enable_best_execution();
+            router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Sell)).unwrap();
+            router.route_order(new_order(2, 0, 10, 101.0, OrderSide::Sell)).unwrap();
+            router.route_order(new_order(3, 0, 15, 101.0, OrderSide::Buy)).unwrap();
+            router.route_order(new_order(4, 0, 5, 99.0, OrderSide::Buy)).unwrap();
+            router.route_order(new_order(5, 0, 30, 101.0, OrderSide::Buy)).unwrap();
+            router.match_all_orders();
+
+            let reports = router.best_execution_reports().unwrap();
+            assert_eq!(reports.iter().map(|report| report.order_id).collect::<Vec<_>>(), vec![3, 5], "{order_book_type:?}");
+            let report = &reports[0];
+            assert_eq!((report.arrival_best, report.available_quantity), (price_to_u64(100.0), 20), "{order_book_type:?}");
+            assert_eq!(report.filled_quantity, 15, "{order_book_type:?}");
+            assert_eq!(report.average_price(), Some((price_to_u64(100.0) * 10 + price_to_u64(101.0) * 5) as f64 / 15.0), "{order_book_type:?}");
+            assert_eq!(report.price_improvement(), Some(price_to_u64(101.0) as f64 - report.average_price().unwrap()), "{order_book_type:?}");
+            assert_eq!((reports[1].filled_quantity, reports[1].available_quantity), (5, 20), "{order_book_type:?}");
+
+            let closed = router.take_best_execution_reports();
+            assert_eq!(closed.iter().map(|report| report.order_id).collect::<Vec<_>>(), vec![3], "{order_book_type:?}");
+            assert_eq!(router.best_execution_reports().unwrap().len(), 1, "{order_book_type:?}");
+        }
+    }
+
+    #[test]
+    fn test_message_analytics_count_per_participant_and_alert() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap);
+        assert!(router.message_stats(1).is_none());
+        router.enable_message_analytics(MessageRateConfig { max_order_to_trade: Some(2.0), min_messages: 3, ..MessageRateConfig::default() });
+        let (alerts, alert_receiver) = crossbeam::channel::unbounded();
+        router.attach_message_alert_listener(alerts);
+
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Sell).with_participant(1)).unwrap();
+        router.route_order(new_order(2, 0, 5, 101.0, OrderSide::Sell).with_participant(1)).unwrap();
+        router.cancel_orders_batch(&[2]);
+        router.reduce_order(0, 1, 2).unwrap();
+        router.route_order(new_order(3, 0, 4, 100.0, OrderSide::Buy).with_participant(2)).unwrap();
+        assert!(router.route_order(new_order(4, 9, 4, 100.0, OrderSide::Buy).with_participant(2)).is_err());
+        router.match_all_orders();
+
+        let alerts: Vec<_> = alert_receiver.try_iter().map(|alert| (alert.participant, alert.kind, alert.value)).collect();
+        assert_eq!(alerts, vec![(1, MessageAlertKind::OrderToTradeRatio, 3.0)]);
+        let counts = |stats: MessageStats| (stats.participant, stats.adds, stats.cancels, stats.modifies, stats.trades);
+        assert_eq!(router.all_message_stats().into_iter().map(counts).collect::<Vec<_>>(), vec![(1, 2, 1, 1, 1), (2, 2, 0, 0, 1)]);
+        assert_eq!(router.message_stats(1).unwrap().order_to_trade_ratio(), 4.0);
+    }
+
+    #[test]
+    fn test_open_orders_and_exposure_per_participant() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy).with_participant(7)).unwrap();
+        router.enable_position_tracking();
+        router.route_order(new_order(2, 1, 300, 20.0, OrderSide::Sell).with_participant(7).with_kind(OrderKind::Iceberg { display_quantity: 100 })).unwrap();
+        router.route_order(new_order(3, 0, 5, 101.0, OrderSide::Sell).with_participant(8)).unwrap();
+        router.route_order(new_order(4, 0, 4, 100.0, OrderSide::Sell).with_participant(8)).unwrap();
+        router.match_all_orders();
+
+        assert_eq!(router.open_orders(7).iter().map(|order| (order.id, order.quantity)).collect::<Vec<_>>(), vec![(1, 6), (2, 300)]);
+        let exposure = router.exposure(7);
+        assert_eq!((exposure.open_orders, exposure.open_bid_quantity, exposure.open_ask_quantity), (2, 6, 300));
+        assert_eq!(exposure.open_ask_notional, price_to_u64(20.0) as i128 * 300);
+        // The order resting before tracking began still counts from its next fill
+        assert_eq!(exposure.positions, vec![AccountPosition { symbol: 0, quantity: 4, mark_price: price_to_u64(100.0), market_value: price_to_u64(100.0) as i128 * 4 }]);
+        assert_eq!(router.exposure(8).positions[0].quantity, -4);
+        assert_eq!(exposure.gross_exposure(), price_to_u64(100.0) as i128 * 10 + price_to_u64(20.0) as i128 * 300);
+
+        router.cancel_all(None);
+        assert!(router.open_orders(7).is_empty());
+        assert_eq!(router.exposure(7).positions.len(), 1);
+        assert_eq!(router.exposure(9), AccountExposure { participant: 9, ..Default::default() });
+    }
+
+    #[test]
+    fn test_export_report() {
+        let mut router = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::PriorityQueue);
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 40, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(3, 0, 4, 99.5, OrderSide::Sell)).unwrap();
+        let trades = router.match_all_orders();
+        assert_eq!(trades.iter().map(|trade| (trade.taker_order_id, trade.maker_order_id, trade.quantity)).collect::<Vec<_>>(), vec![(3, 1, 4)]);
+        // The crossing ask trades at the resting bid's price
+        assert_eq!(trades[0].price, price_to_u64(100.0));
+
+        let report = router.export_report(0).unwrap();
+        assert_eq!(report.session_stats.orders_routed, 3);
+        assert_eq!(report.last_trade_price, Some(price_to_u64(100.0)));
+        assert_eq!(report.trade_price_rule, TradePriceRule::RestingOrder);
+        assert_eq!(report.last_match_stats.map(|stats| stats.trades), Some(1));
+        assert_eq!(report.bids.iter().map(|level| level.quantity).collect::<Vec<_>>(), vec![6, 40]);
+        assert!(report.asks.is_empty());
+        assert_eq!(report.largest_orders[0].id, 2);
+        assert_eq!(report.oldest_orders[0].id, 1);
+        assert!(router.export_report(9).is_none());
     }
 }
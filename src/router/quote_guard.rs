@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::types::order::ParticipantId;
+
+#[derive(Debug, Clone, Copy)]
+struct QuoteTtl {
+    ttl: Duration,
+    deadline: Instant,
+}
+
+/// Live quote deadlines keyed by (participant, order id), plus the participants that
+/// asked for their orders to be pulled when their connection drops. Quotes that fill
+/// or are cancelled stay tracked until their deadline passes and are then dropped.
+#[derive(Debug, Default)]
+pub(crate) struct QuoteGuard {
+    quotes: FxHashMap<(ParticipantId, u64), QuoteTtl>,
+    cancel_on_disconnect: FxHashSet<ParticipantId>,
+}
+
+impl QuoteGuard {
+    #[inline(always)]
+    pub(crate) fn track(&mut self, participant: ParticipantId, order_id: u64, ttl: Duration, now: Instant) {
+        self.quotes.insert((participant, order_id), QuoteTtl { ttl, deadline: now + ttl });
+    }
+
+    /// Restarts the TTL of every quote the participant has tracked and returns how many.
+    pub(crate) fn refresh(&mut self, participant: ParticipantId, now: Instant) -> usize {
+        let mut refreshed = 0;
+        for (_, quote) in self.quotes.iter_mut().filter(|((owner, _), _)| *owner == participant) {
+            quote.deadline = now + quote.ttl;
+            refreshed += 1;
+        }
+        refreshed
+    }
+
+    pub(crate) fn take_expired(&mut self, now: Instant) -> FxHashSet<(ParticipantId, u64)> {
+        let expired: FxHashSet<(ParticipantId, u64)> = self.quotes.iter()
+            .filter(|(_, quote)| quote.deadline <= now)
+            .map(|(&key, _)| key)
+            .collect();
+        self.quotes.retain(|key, _| !expired.contains(key));
+        expired
+    }
+
+    pub(crate) fn forget_participant(&mut self, participant: ParticipantId) {
+        self.quotes.retain(|(owner, _), _| *owner != participant);
+    }
+
+    pub(crate) fn set_cancel_on_disconnect(&mut self, participant: ParticipantId, enabled: bool) {
+        if enabled {
+            self.cancel_on_disconnect.insert(participant);
+        } else {
+            self.cancel_on_disconnect.remove(&participant);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn cancels_on_disconnect(&self, participant: ParticipantId) -> bool {
+        self.cancel_on_disconnect.contains(&participant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_extends_only_own_quotes() {
+        let mut guard = QuoteGuard::default();
+        let start = Instant::now();
+        guard.track(1, 10, Duration::from_millis(5), start);
+        guard.track(2, 10, Duration::from_millis(5), start);
+        guard.track(2, 11, Duration::from_millis(50), start);
+
+        assert_eq!(guard.refresh(1, start + Duration::from_millis(4)), 1);
+        let expired = guard.take_expired(start + Duration::from_millis(6));
+        assert_eq!(expired, FxHashSet::from_iter([(2, 10)]));
+        assert_eq!(guard.take_expired(start + Duration::from_millis(9)), FxHashSet::from_iter([(1, 10)]));
+
+        guard.forget_participant(2);
+        assert!(guard.take_expired(start + Duration::from_secs(1)).is_empty());
+    }
+}
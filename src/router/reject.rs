@@ -0,0 +1,127 @@
+use crate::engine::OrderBookError;
+use crate::router::sharded_router::TryRouteError;
+use crate::types::order::ParticipantId;
+use crate::types::symbol_mapping::SymbolId;
+
+/// Stable numeric code for every way the engine refuses a request, for clients to
+/// branch on instead of the message text. Codes are part of the wire contract: add new
+/// ones, never renumber or reuse one.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+pub enum RejectCode {
+    /// A refusal this build has no code for yet.
+    Other = 0,
+    UnknownSymbol = 1,
+    /// The symbol is frozen or the session is closed.
+    Halted = 2,
+    /// The price is not a multiple of the symbol's tick size.
+    OffTick = 3,
+    /// The quantity or notional is outside what the symbol accepts.
+    TooLarge = 4,
+    Duplicate = 5,
+    /// A participant limit or tenant quota is used up.
+    Throttled = 6,
+    /// A post-only order would have crossed the book.
+    PostOnlyCross = 7,
+    UnknownOrder = 8,
+    InvalidQuantity = 9,
+    /// The request is not allowed in the current session phase.
+    WrongSessionState = 10,
+    InsufficientBuyingPower = 11,
+    /// A fixed-capacity book has no room left.
+    CapacityExceeded = 12,
+    /// A shard is stopped or its queue is full.
+    Unavailable = 13,
+    /// The request itself is invalid, such as an unparseable line or a bad setting.
+    InvalidRequest = 14,
+}
+
+impl RejectCode {
+    #[inline(always)]
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    /// Code for one of the router's rejection messages. Every message the router,
+    /// sharded router and tenant engine return has a code other than `Other`.
+    pub fn from_reason(reason: &str) -> Self {
+        match reason {
+            "Invalid symbol" => RejectCode::UnknownSymbol,
+            "Symbol frozen" | "Session closed" => RejectCode::Halted,
+            "Duplicate order" | "Duplicate order id" | "Tenant already exists" => RejectCode::Duplicate,
+            "Participant order limit exceeded" | "Tenant order quota exceeded" | "Tenant symbol quota exceeded" => RejectCode::Throttled,
+            "Price off tick" => RejectCode::OffTick,
+            "Quantity not a multiple of lot size" => RejectCode::InvalidQuantity,
+            "Quantity below minimum" | "Notional below minimum" => RejectCode::TooLarge,
+            "Order not found" => RejectCode::UnknownOrder,
+            "Invalid reduction quantity" => RejectCode::InvalidQuantity,
+            "Imbalance-only order outside pre-open" | "Immediate-or-cancel order in pre-open" | "Session not in pre-open" => RejectCode::WrongSessionState,
+            "Insufficient buying power" => RejectCode::InsufficientBuyingPower,
+            "Book capacity exceeded" => RejectCode::CapacityExceeded,
+            "Shard stopped" | "Shard queue full" => RejectCode::Unavailable,
+            "Unsupported default time in force" | "Implied spread legs must be distinct" | "Symbol not frozen"
+            | "Implied spread legs must share a price scale"            | "Invalid shard" | "Unknown tenant" | "Iceberg orders unsupported on shards" => RejectCode::InvalidRequest,
+            _ => RejectCode::Other,
+        }
+    }
+}
+
+impl From<OrderBookError> for RejectCode {
+    fn from(error: OrderBookError) -> Self {
+        match error {
+            OrderBookError::InvalidSymbol => RejectCode::UnknownSymbol,
+            OrderBookError::ParticipantLimitExceeded => RejectCode::Throttled,
+            OrderBookError::CapacityExceeded => RejectCode::CapacityExceeded,
+            OrderBookError::OrderNotFound => RejectCode::UnknownOrder,
+            OrderBookError::InvalidQuantity => RejectCode::InvalidQuantity,
+            OrderBookError::OffTick => RejectCode::OffTick,
+            OrderBookError::OddLot => RejectCode::InvalidQuantity,
+            OrderBookError::BelowMinQuantity | OrderBookError::BelowMinNotional => RejectCode::TooLarge,
+        }
+    }
+}
+
+impl TryRouteError {
+    pub fn reject_code(&self) -> RejectCode {
+        match self {
+            TryRouteError::WouldBlock(_) => RejectCode::Unavailable,
+            TryRouteError::Rejected(reason) => RejectCode::from_reason(reason),
+        }
+    }
+}
+
+/// Sent for every order the router refuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct OrderRejected {
+    pub order_id: u64,
+    pub symbol: SymbolId,
+    pub participant: ParticipantId,
+    pub code: RejectCode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_router_reason_has_a_code() {
+        let reasons = [
+            "Invalid symbol", "Invalid shard", "Unknown tenant", "Symbol frozen", "Session closed",
+            "Duplicate order", "Duplicate order id", "Tenant already exists", "Participant order limit exceeded",
+            "Tenant order quota exceeded", "Tenant symbol quota exceeded", "Order not found",
+            "Invalid reduction quantity", "Imbalance-only order outside pre-open", "Immediate-or-cancel order in pre-open",
+            "Session not in pre-open",
+            "Insufficient buying power", "Book capacity exceeded", "Shard stopped", "Shard queue full",
+            "Unsupported default time in force", "Implied spread legs must be distinct", "Symbol not frozen",
+            "Implied spread legs must share a price scale",
+            "Iceberg orders unsupported on shards", "Price off tick",
+            "Quantity not a multiple of lot size", "Quantity below minimum", "Notional below minimum",
+        ];
+        for reason in reasons {
+            assert_ne!(RejectCode::from_reason(reason), RejectCode::Other, "{reason}");
+        }
+        assert_eq!(RejectCode::from_reason("something new"), RejectCode::Other);
+        assert_eq!(RejectCode::from(OrderBookError::ParticipantLimitExceeded).code(), 6);
+        assert_eq!(serde_json::to_string(&RejectCode::PostOnlyCross).unwrap(), "\"PostOnlyCross\"");
+    }
+}
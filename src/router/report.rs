@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::MatchStats;
+use crate::router::session::{SessionState, SessionStats};
+use crate::types::order::{Order, OrderSide};
+use crate::types::price_scale::PriceScale;
+use crate::types::symbol_mapping::SymbolId;
+use crate::types::trade::TradePriceRule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookReportConfig {
+    /// Price levels listed per side, best first.
+    pub levels: usize,
+    pub largest_orders: usize,
+    pub oldest_orders: usize,
+}
+
+impl Default for BookReportConfig {
+    fn default() -> Self {
+        Self { levels: 10, largest_orders: 5, oldest_orders: 5 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportLevel {
+    pub price: u64,
+    pub quantity: u64,
+    pub orders: usize,
+}
+
+/// Point-in-time state of one symbol's book for end-of-day checks and incident
+/// investigations. Serializes to JSON; `Display` renders it as text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookReport {
+    pub symbol: SymbolId,
+    /// Nanoseconds since the Unix epoch when the report was taken.
+    pub generated_at: u64,
+    pub sequence: u64,
+    pub session_id: u64,
+    pub session_state: SessionState,
+    /// Router-wide counts for the current session, not just this symbol's.
+    pub session_stats: SessionStats,
+    pub last_trade_price: Option<u64>,
+    /// How the book prices executions, so `last_trade_price` can be read against the
+    /// limits that crossed.
+    #[serde(default)]
+    pub trade_price_rule: TradePriceRule,
+    /// Decimals the symbol's prices carry, used to render them as text.
+    #[serde(default)]
+    pub price_scale: PriceScale,
+    pub last_match_stats: Option<MatchStats>,
+    pub resting_orders: usize,
+    pub bids: Vec<ReportLevel>,
+    pub asks: Vec<ReportLevel>,
+    /// Largest resting quantity first, either side.
+    pub largest_orders: Vec<Order>,
+    /// Earliest arrival first, either side. Ids double as arrival sequence, so this
+    /// holds for unstamped orders too.
+    pub oldest_orders: Vec<Order>,
+}
+
+/// The parts of a report that come from the router rather than the book.
+pub(crate) struct ReportContext {
+    pub(crate) generated_at: u64,
+    pub(crate) sequence: u64,
+    pub(crate) session_id: u64,
+    pub(crate) session_state: SessionState,
+    pub(crate) session_stats: SessionStats,
+    pub(crate) last_trade_price: Option<u64>,
+    pub(crate) trade_price_rule: TradePriceRule,
+    pub(crate) price_scale: PriceScale,
+    pub(crate) last_match_stats: Option<MatchStats>,
+}
+
+impl BookReport {
+    pub(crate) fn from_orders(symbol: SymbolId, orders: Vec<Order>, context: ReportContext, config: BookReportConfig) -> Self {
+        let mut bid_levels: BTreeMap<u64, ReportLevel> = BTreeMap::new();
+        let mut ask_levels: BTreeMap<u64, ReportLevel> = BTreeMap::new();
+        for order in &orders {
+            let levels = match order.order_type {
+                OrderSide::Buy => &mut bid_levels,
+                OrderSide::Sell => &mut ask_levels,
+            };
+            let level = levels.entry(order.price)
+                .or_insert(ReportLevel { price: order.price, quantity: 0, orders: 0 });
+            level.quantity += order.quantity;
+            level.orders += 1;
+        }
+
+        let mut largest_orders = orders.clone();
+        largest_orders.sort_by(|a, b| b.quantity.cmp(&a.quantity).then(a.id.cmp(&b.id)));
+        largest_orders.truncate(config.largest_orders);
+
+        let resting_orders = orders.len();
+        let mut oldest_orders = orders;
+        oldest_orders.sort_unstable_by_key(|order| order.id);
+        oldest_orders.truncate(config.oldest_orders);
+
+        Self {
+            symbol,
+            generated_at: context.generated_at,
+            sequence: context.sequence,
+            session_id: context.session_id,
+            session_state: context.session_state,
+            session_stats: context.session_stats,
+            last_trade_price: context.last_trade_price,
+            trade_price_rule: context.trade_price_rule,
+            price_scale: context.price_scale,
+            last_match_stats: context.last_match_stats,
+            resting_orders,
+            bids: bid_levels.into_values().rev().take(config.levels).collect(),
+            asks: ask_levels.into_values().take(config.levels).collect(),
+            largest_orders,
+            oldest_orders,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+struct Price(Option<u64>, PriceScale);
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(price) => write!(f, "{}", self.1.display(price)),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+fn write_orders(f: &mut fmt::Formatter<'_>, title: &str, orders: &[Order], scale: PriceScale) -> fmt::Result {
+    writeln!(f, "{title}:")?;
+    if orders.is_empty() {
+        return writeln!(f, "  (none)");
+    }
+    for order in orders {
+        let side = match order.order_type {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+        writeln!(
+            f,
+            "  #{:<8} {side:<4} {:>10} @ {:>12}  participant {} ts {}",
+            order.id, order.quantity, Price(Some(order.price), scale), order.participant, order.timestamp,
+        )?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for BookReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Book report: symbol {}", self.symbol)?;
+        writeln!(f, "  generated at {} ns, sequence {}", self.generated_at, self.sequence)?;
+        writeln!(f, "  session {} ({:?})", self.session_id, self.session_state)?;
+        writeln!(
+            f,
+            "  orders routed {}, rejected {} (all symbols)",
+            self.session_stats.orders_routed, self.session_stats.orders_rejected,
+        )?;
+        writeln!(f, "  last trade {} ({:?} price rule)", Price(self.last_trade_price, self.price_scale), self.trade_price_rule)?;
+        if let Some(stats) = &self.last_match_stats {
+            writeln!(
+                f,
+                "  last match: {} trades, volume {}, {} levels cleared in {:?}",
+                stats.trades, stats.volume, stats.levels_cleared, stats.duration,
+            )?;
+        }
+        writeln!(f, "  resting orders {}", self.resting_orders)?;
+
+        writeln!(f, "Depth:")?;
+        writeln!(f, "  {:>12} {:>8} {:>12} | {:<12} {:<12} {:<8}", "bid qty", "orders", "bid", "ask", "ask qty", "orders")?;
+        for row in 0..self.bids.len().max(self.asks.len()) {
+            let bid = self.bids.get(row);
+            let ask = self.asks.get(row);
+            let cell = |value: Option<String>| value.unwrap_or_default();
+            writeln!(
+                f,
+                "  {:>12} {:>8} {:>12} | {:<12} {:<12} {:<8}",
+                cell(bid.map(|level| level.quantity.to_string())),
+                cell(bid.map(|level| level.orders.to_string())),
+                cell(bid.map(|level| Price(Some(level.price), self.price_scale).to_string())),
+                cell(ask.map(|level| Price(Some(level.price), self.price_scale).to_string())),
+                cell(ask.map(|level| level.quantity.to_string())),
+                cell(ask.map(|level| level.orders.to_string())),
+            )?;
+        }
+
+        write_orders(f, "Largest resting orders", &self.largest_orders, self.price_scale)?;
+        write_orders(f, "Oldest resting orders", &self.oldest_orders, self.price_scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::new_order;
+
+    #[test]
+    fn test_report_shape() {
+        let orders = vec![
+            new_order(4, 0, 30, 99.0, OrderSide::Buy),
+            new_order(1, 0, 10, 100.0, OrderSide::Buy),
+            new_order(2, 0, 90, 100.0, OrderSide::Buy),
+            new_order(3, 0, 50, 101.0, OrderSide::Sell),
+        ];
+        let context = ReportContext {
+            generated_at: 1,
+            sequence: 4,
+            session_id: 0,
+            session_state: SessionState::Open,
+            session_stats: SessionStats::default(),
+            last_trade_price: None,
+            trade_price_rule: TradePriceRule::RestingOrder,
+            price_scale: PriceScale::DEFAULT,
+            last_match_stats: None,
+        };
+        let config = BookReportConfig { levels: 1, largest_orders: 2, oldest_orders: 2 };
+        let report = BookReport::from_orders(0, orders, context, config);
+
+        assert_eq!(report.resting_orders, 4);
+        assert_eq!(report.bids, vec![ReportLevel { price: 100_000, quantity: 100, orders: 2 }]);
+        assert_eq!(report.asks, vec![ReportLevel { price: 101_000, quantity: 50, orders: 1 }]);
+        assert_eq!(report.largest_orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(report.oldest_orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![1, 2]);
+
+        let text = report.to_string();
+        assert!(text.contains("100.000 | 101.000"));
+        assert!(text.contains("last trade - (RestingOrder price rule)"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["bids"][0]["quantity"], 100);
+
+        // Text follows the symbol's scale; the integer levels do not change
+        let fx = BookReport { price_scale: PriceScale::new(5).unwrap(), last_trade_price: Some(123_456), ..report };
+        assert!(fx.to_string().contains("last trade 1.23456 "));
+        assert!(fx.to_string().contains("1.00000 | 1.01000"));
+    }
+}
@@ -0,0 +1,40 @@
+use rustc_hash::FxHashMap;
+
+use crate::types::{order::Order, symbol_mapping::SymbolId, trade};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+pub enum SessionState {
+    PreOpen,
+    #[default]
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+pub struct SessionStats {
+    pub orders_routed: u64,
+    pub orders_rejected: u64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SessionSummary {
+    pub session_id: u64,
+    pub stats: SessionStats,
+    /// Official close per symbol: the session's last trade price, falling back to the
+    /// BBO midpoint (or the only side present) when nothing traded.
+    pub close_prices: FxHashMap<SymbolId, Option<u64>>,
+    pub expired_orders: Vec<Order>,
+    pub carried_orders: usize,
+}
+
+#[inline(always)]
+pub fn close_price(last_trade_price: Option<u64>, best_bid: Option<u64>, best_ask: Option<u64>) -> Option<u64> {
+    if last_trade_price.is_some() {
+        return last_trade_price;
+    }
+    match (best_bid, best_ask) {
+        (Some(bid), Some(ask)) => Some(trade::midpoint(bid, ask)),
+        (Some(price), None) | (None, Some(price)) => Some(price),
+        (None, None) => None,
+    }
+}
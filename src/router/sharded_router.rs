@@ -0,0 +1,1345 @@
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::{self, Receiver, RecvTimeoutError, Sender, TryRecvError, TrySendError};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::engine::{OrderBookTrait, OrderBookType, create_order_book};
+use crate::rng::{mix64, SplitMix64};
+use crate::router::SessionState;
+use crate::router::ack::OrderAck;
+use crate::router::health::{BatchCounters, BatchStats, ProgressWatch, QueueDepth, ShardHealth};
+use crate::snapshot::{BookSnapshot, RouterSnapshot};
+use crate::types::order::{Order, OrderFlags, TimeInForce};
+use crate::types::symbol_mapping::SymbolId;
+
+const DEFAULT_SHARD_QUEUE_SIZE: usize = 65536;
+const DEFAULT_SPIN_LIMIT: u32 = 1024;
+const TIMED_OUT_QUEUE_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WaitStrategy {
+    /// Poll the queue continuously. Lowest latency, burns a full core.
+    BusySpin,
+    /// Poll `spin_limit` times, then yield the thread between polls.
+    SpinThenYield { spin_limit: u32 },
+    /// Block until a command arrives.
+    #[default]
+    Park,
+}
+
+impl WaitStrategy {
+    pub fn spin_then_yield() -> Self {
+        WaitStrategy::SpinThenYield { spin_limit: DEFAULT_SPIN_LIMIT }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShardConfig {
+    pub queue_capacity: usize,
+    /// Core to pin the shard's matcher thread to. Only honoured with the `affinity` feature.
+    pub core_id: Option<usize>,
+    pub wait_strategy: WaitStrategy,
+    /// Longest an order may wait in the shard queue before it is rejected instead of executed.
+    pub latency_budget: Option<Duration>,
+    /// Chaos-testing delivery jitter. Leave unset outside of tests.
+    pub jitter: Option<JitterConfig>,
+    /// Acknowledge executed orders on `ShardedRouter::acks`, each after a synthetic delay.
+    /// Shards without it send no acks.
+    pub ack_delay: Option<AckDelayConfig>,
+    /// Execute queued orders in batches with one matching pass each, instead of
+    /// matching after every order. Ignored while `jitter` is set.
+    pub batching: Option<AdaptiveBatching>,
+}
+
+/// Batch size bounds for a shard's matching passes. A pass adds up to the current
+/// target of already queued orders, then matches once. The target doubles while the
+/// queue still holds at least that many orders after a pass and halves once it
+/// drains, so an idle shard matches every order as it arrives and a loaded one
+/// amortizes the pass across up to `max_batch` orders. Orders in one batch rest
+/// together before matching, so crossing orders within it may trade with each other
+/// in a different order than they would one at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveBatching {
+    pub min_batch: usize,
+    pub max_batch: usize,
+}
+
+impl AdaptiveBatching {
+    pub fn new(min_batch: usize, max_batch: usize) -> Self {
+        let min_batch = min_batch.max(1);
+        Self { min_batch, max_batch: max_batch.max(min_batch) }
+    }
+
+    /// Target for the next pass, given the current one and the orders still queued.
+    #[inline(always)]
+    fn next_target(self, target: usize, queued: usize) -> usize {
+        if queued >= target {
+            target.saturating_mul(2).min(self.max_batch)
+        } else if queued == 0 {
+            (target / 2).max(self.min_batch)
+        } else {
+            target
+        }
+    }
+}
+
+/// Seeded, bounded delivery jitter for a shard. Orders are held in windows of up to
+/// `max_reorder + 1`, each window for a random time up to `max_delay`, then applied in
+/// shuffled order, so no order moves more than `max_reorder` places. `sync` and
+/// `snapshot_all` release held orders first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JitterConfig {
+    pub max_delay: Duration,
+    pub max_reorder: usize,
+    pub seed: u64,
+}
+
+/// Distribution of the synthetic delay between a shard executing an order and its ack
+/// being delivered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AckDelay {
+    Fixed(Duration),
+    Uniform { min: Duration, max: Duration },
+    /// `min` plus an exponential draw with mean `mean`, capped at `max`: mostly quick
+    /// with an occasional slow ack, like a venue under load.
+    Exponential { min: Duration, mean: Duration, max: Duration },
+}
+
+/// Seeded ack delays for integration-testing clients against variable ack timing.
+/// Each shard draws from its own stream, so a run is reproducible from the seed. Only
+/// `Fixed` delays keep acks in execution order. An ack's `sequence` counts the orders
+/// its shard has processed, timed-out ones included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AckDelayConfig {
+    pub delay: AckDelay,
+    pub seed: u64,
+}
+
+impl Default for ShardConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: DEFAULT_SHARD_QUEUE_SIZE,
+            core_id: None,
+            wait_strategy: WaitStrategy::default(),
+            latency_budget: None,
+            jitter: None,
+            ack_delay: None,
+            batching: None,
+        }
+    }
+}
+
+impl ShardConfig {
+    pub fn with_queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity;
+        self
+    }
+
+    pub fn pinned_to(mut self, core_id: usize) -> Self {
+        self.core_id = Some(core_id);
+        self
+    }
+
+    pub fn with_wait_strategy(mut self, wait_strategy: WaitStrategy) -> Self {
+        self.wait_strategy = wait_strategy;
+        self
+    }
+
+    pub fn with_latency_budget(mut self, latency_budget: Duration) -> Self {
+        self.latency_budget = Some(latency_budget);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: JitterConfig) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+
+    pub fn with_ack_delay(mut self, ack_delay: AckDelayConfig) -> Self {
+        self.ack_delay = Some(ack_delay);
+        self
+    }
+
+    pub fn with_adaptive_batching(mut self, batching: AdaptiveBatching) -> Self {
+        self.batching = Some(batching);
+        self
+    }
+}
+
+/// How symbols are spread over shards when the router starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShardAssignment {
+    /// Sorted symbols dealt to shards in turn.
+    #[default]
+    RoundRobin,
+    /// Each shard owns `virtual_nodes` points on a hash ring. Changing the shard count
+    /// only moves the symbols whose ring segment changed hands.
+    ConsistentHash { virtual_nodes: usize },
+}
+
+impl ShardAssignment {
+    fn assign(self, sorted_symbols: &[SymbolId], shard_count: usize) -> Vec<usize> {
+        match self {
+            ShardAssignment::RoundRobin => (0..sorted_symbols.len()).map(|i| i % shard_count).collect(),
+            ShardAssignment::ConsistentHash { virtual_nodes } => {
+                let mut ring: Vec<(u64, usize)> = (0..shard_count)
+                    .flat_map(|shard| {
+                        (0..virtual_nodes.max(1) as u64).map(move |node| (mix64(((shard as u64) << 32) | node), shard))
+                    })
+                    .collect();
+                ring.sort_unstable();
+                sorted_symbols.iter()
+                    .map(|&symbol| {
+                        let point = mix64(u64::from(symbol) ^ 0xA5A5_A5A5_0000_0000);
+                        let index = ring.partition_point(|&(node_point, _)| node_point < point);
+                        ring[index % ring.len()].1
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// An order a shard dropped because its deadline passed while it was queued.
+#[derive(Debug, Clone)]
+pub struct TimedOutOrder {
+    pub order: Order,
+    pub queued_for: Duration,
+}
+
+/// Why `try_route_order` did not queue an order.
+#[derive(Debug, Clone)]
+pub enum TryRouteError {
+    /// The shard's queue is full; the order is handed back so the caller can shed or retry it.
+    WouldBlock(Order),
+    Rejected(&'static str),
+}
+
+enum ShardCommand {
+    Order(Order, Option<Deadline>),
+    Sync(Sender<()>),
+    Snapshot(u64, Sender<Vec<BookSnapshot>>),
+    /// Drop a symbol from the shard's book and reply with its resting orders.
+    Release(SymbolId, Sender<Vec<Order>>),
+    /// Take ownership of a symbol along with its resting orders.
+    Adopt(SymbolId, Vec<Order>),
+}
+
+#[derive(Clone, Copy)]
+struct Deadline {
+    enqueued_at: Instant,
+    expires_at: Instant,
+}
+
+struct Shard {
+    sender: Option<Sender<ShardCommand>>,
+    handle: Option<JoinHandle<()>>,
+    symbols: Vec<SymbolId>,
+    latency_budget: Option<Duration>,
+    processed: Arc<AtomicU64>,
+    progress: Mutex<ProgressWatch>,
+    /// Deepest the queue has been right after an enqueue, since the last reset.
+    high_water: AtomicUsize,
+    /// Set for shards with adaptive batching.
+    batches: Option<Arc<BatchCounters>>,
+}
+
+struct Jitter {
+    config: JitterConfig,
+    rng: SplitMix64,
+    held: Vec<(Order, Option<Deadline>)>,
+    release_at: Option<Instant>,
+}
+
+impl Jitter {
+    fn new(config: JitterConfig, shard_index: usize) -> Self {
+        Self {
+            config,
+            rng: SplitMix64::stream(config.seed, shard_index),
+            held: Vec::with_capacity(config.max_reorder + 1),
+            release_at: None,
+        }
+    }
+
+    /// Holds `order`, returning true once the window is full and must be released.
+    fn hold(&mut self, order: Order, deadline: Option<Deadline>) -> bool {
+        if self.held.is_empty() {
+            let max_nanos = self.config.max_delay.as_nanos().min(u64::MAX as u128) as u64;
+            let delay = Duration::from_nanos(self.rng.next_u64() % max_nanos.saturating_add(1));
+            self.release_at = Some(Instant::now() + delay);
+        }
+        self.held.push((order, deadline));
+        self.held.len() > self.config.max_reorder
+    }
+
+    fn release(&mut self) -> std::vec::Drain<'_, (Order, Option<Deadline>)> {
+        for i in (1..self.held.len()).rev() {
+            let j = (self.rng.next_u64() % (i as u64 + 1)) as usize;
+            self.held.swap(i, j);
+        }
+        self.release_at = None;
+        self.held.drain(..)
+    }
+}
+
+struct AckDelaySampler {
+    delay: AckDelay,
+    rng: Cell<SplitMix64>,
+}
+
+impl AckDelaySampler {
+    fn new(config: AckDelayConfig, shard_index: usize) -> Self {
+        Self {
+            delay: config.delay,
+            rng: Cell::new(SplitMix64::stream(config.seed, shard_index)),
+        }
+    }
+
+    fn next_u64(&self) -> u64 {
+        let mut rng = self.rng.get();
+        let draw = rng.next_u64();
+        self.rng.set(rng);
+        draw
+    }
+
+    fn sample(&self) -> Duration {
+        match self.delay {
+            AckDelay::Fixed(delay) => delay,
+            AckDelay::Uniform { min, max } => {
+                let spread = max.saturating_sub(min).as_nanos().min(u64::MAX as u128) as u64;
+                min + Duration::from_nanos(self.next_u64() % spread.saturating_add(1))
+            }
+            AckDelay::Exponential { min, mean, max } => {
+                // Uniform on (0, 1] from the top 53 bits, so the log is always finite
+                let unit = ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64;
+                (min + mean.mul_f64(-unit.ln())).min(max.max(min))
+            }
+        }
+    }
+}
+
+/// An ack waiting for its delivery time; `order` breaks ties in arrival order.
+struct PendingAck {
+    due: Instant,
+    order: u64,
+    ack: OrderAck,
+}
+
+impl PartialEq for PendingAck {
+    fn eq(&self, other: &Self) -> bool {
+        (self.due, self.order) == (other.due, other.order)
+    }
+}
+
+impl Eq for PendingAck {}
+
+impl PartialOrd for PendingAck {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingAck {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.due, self.order).cmp(&(other.due, other.order))
+    }
+}
+
+/// Holds acks from every shard until they are due, so a delay never stalls a shard.
+/// Once the shards stop, whatever is still held is delivered straight away.
+fn run_ack_dispatcher(intake: Receiver<(Instant, OrderAck)>, delivered: Sender<OrderAck>) {
+    let mut pending = BinaryHeap::new();
+    let mut arrivals = 0u64;
+    loop {
+        let received = match pending.peek() {
+            Some(Reverse(next)) => {
+                let next: &PendingAck = next;
+                intake.recv_deadline(next.due)
+            }
+            None => intake.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok((due, ack)) => {
+                arrivals += 1;
+                pending.push(Reverse(PendingAck { due, order: arrivals, ack }));
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        let now = Instant::now();
+        while pending.peek().is_some_and(|Reverse(next)| next.due <= now) {
+            if let Some(Reverse(next)) = pending.pop() {
+                let _ = delivered.send(next.ack);
+            }
+        }
+    }
+    while let Some(Reverse(next)) = pending.pop() {
+        let _ = delivered.send(next.ack);
+    }
+}
+
+#[derive(Clone)]
+struct TimeoutReporter {
+    count: Arc<AtomicU64>,
+    sender: Sender<TimedOutOrder>,
+    receiver: Receiver<TimedOutOrder>,
+}
+
+impl TimeoutReporter {
+    fn report(&self, mut timed_out: TimedOutOrder) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        // Keep the newest rejections when the caller is not draining them
+        while let Err(channel::TrySendError::Full(rejected)) = self.sender.try_send(timed_out) {
+            let _ = self.receiver.try_recv();
+            timed_out = rejected;
+        }
+    }
+}
+
+pub struct ShardedRouter {
+    shards: Vec<Shard>,
+    symbol_to_shard: FxHashMap<SymbolId, usize>,
+    order_book_type: OrderBookType,
+    sequence: AtomicU64,
+    timeouts: TimeoutReporter,
+    /// Outstanding freezes per symbol; overlapping freezes nest.
+    freezes: FxHashMap<SymbolId, AtomicU32>,
+    /// Routing calls between their freeze check and their enqueue.
+    in_flight: AtomicUsize,
+    acks: Option<Receiver<OrderAck>>,
+    ack_dispatcher: Option<JoinHandle<()>>,
+}
+
+impl ShardedRouter {
+    pub fn new(symbols: FxHashSet<SymbolId>, order_book_type: OrderBookType, shard_configs: Vec<ShardConfig>) -> Self {
+        Self::with_assignment(symbols, order_book_type, shard_configs, ShardAssignment::default())
+    }
+
+    pub fn with_assignment(
+        symbols: FxHashSet<SymbolId>,
+        order_book_type: OrderBookType,
+        shard_configs: Vec<ShardConfig>,
+        assignment: ShardAssignment,
+    ) -> Self {
+        assert!(!shard_configs.is_empty(), "ShardedRouter needs at least one shard");
+
+        let mut sorted_symbols: Vec<SymbolId> = symbols.into_iter().collect();
+        sorted_symbols.sort_unstable();
+
+        let mut shard_symbols = vec![Vec::new(); shard_configs.len()];
+        let mut symbol_to_shard = FxHashMap::default();
+        let freezes = sorted_symbols.iter().map(|&symbol| (symbol, AtomicU32::new(0))).collect();
+        let shard_indices = assignment.assign(&sorted_symbols, shard_configs.len());
+        for (symbol, shard_index) in sorted_symbols.into_iter().zip(shard_indices) {
+            shard_symbols[shard_index].push(symbol);
+            symbol_to_shard.insert(symbol, shard_index);
+        }
+
+        let (sender, receiver) = channel::bounded(TIMED_OUT_QUEUE_SIZE);
+        let timeouts = TimeoutReporter {
+            count: Arc::default(),
+            sender,
+            receiver,
+        };
+
+        let (ack_intake, acks, ack_dispatcher) = if shard_configs.iter().any(|config| config.ack_delay.is_some()) {
+            let (intake_sender, intake_receiver) = channel::unbounded();
+            let (delivered_sender, delivered_receiver) = channel::unbounded();
+            let dispatcher = thread::Builder::new()
+                .name("order-book-ack-delay".to_string())
+                .spawn(move || run_ack_dispatcher(intake_receiver, delivered_sender))
+                .expect("failed to spawn ack dispatcher thread");
+            (Some(intake_sender), Some(delivered_receiver), Some(dispatcher))
+        } else {
+            (None, None, None)
+        };
+
+        let shards = shard_configs
+            .into_iter()
+            .zip(shard_symbols)
+            .enumerate()
+            .map(|(index, (config, symbols))| {
+                let ack_intake = config.ack_delay.and(ack_intake.clone());
+                spawn_shard(index, config, symbols, order_book_type, timeouts.clone(), ack_intake)
+            })
+            .collect();
+
+        Self {
+            shards,
+            symbol_to_shard,
+            order_book_type,
+            sequence: AtomicU64::new(0),
+            timeouts,
+            freezes,
+            in_flight: AtomicUsize::new(0),
+            acks,
+            ack_dispatcher,
+        }
+    }
+
+    pub fn with_shard_count(symbols: FxHashSet<SymbolId>, order_book_type: OrderBookType, shard_count: usize) -> Self {
+        Self::new(symbols, order_book_type, vec![ShardConfig::default(); shard_count])
+    }
+
+    /// Queues `order` on its shard, subject to the shard's latency budget if one is configured.
+    #[inline(always)]
+    pub fn route_order(&self, order: Order) -> Result<(), &'static str> {
+        let shard_index = *self.symbol_to_shard.get(&order.symbol).ok_or("Invalid symbol")?;
+        let shard = &self.shards[shard_index];
+        let deadline = shard.latency_budget.map(|budget| {
+            let enqueued_at = Instant::now();
+            Deadline { enqueued_at, expires_at: enqueued_at + budget }
+        });
+        self.send_order(shard, order, deadline)
+    }
+
+    /// Queues `order` with an explicit deadline, overriding the shard's latency budget.
+    #[inline(always)]
+    pub fn route_order_with_deadline(&self, order: Order, expires_at: Instant) -> Result<(), &'static str> {
+        let shard_index = *self.symbol_to_shard.get(&order.symbol).ok_or("Invalid symbol")?;
+        let deadline = Deadline { enqueued_at: Instant::now(), expires_at };
+        self.send_order(&self.shards[shard_index], order, Some(deadline))
+    }
+
+    /// Like `route_order`, but hands the order back as `WouldBlock` instead of waiting
+    /// when the shard's queue is full.
+    #[inline(always)]
+    pub fn try_route_order(&self, order: Order) -> Result<(), TryRouteError> {
+        let shard_index = *self.symbol_to_shard.get(&order.symbol).ok_or(TryRouteError::Rejected("Invalid symbol"))?;
+        let shard = &self.shards[shard_index];
+        let deadline = shard.latency_budget.map(|budget| {
+            let enqueued_at = Instant::now();
+            Deadline { enqueued_at, expires_at: enqueued_at + budget }
+        });
+        self.enqueue(shard, order, deadline, false)
+    }
+
+    #[inline(always)]
+    fn send_order(&self, shard: &Shard, order: Order, deadline: Option<Deadline>) -> Result<(), &'static str> {
+        self.enqueue(shard, order, deadline, true).map_err(|error| match error {
+            TryRouteError::WouldBlock(_) => "Shard queue full",
+            TryRouteError::Rejected(reason) => reason,
+        })
+    }
+
+    #[inline(always)]
+    fn enqueue(&self, shard: &Shard, order: Order, deadline: Option<Deadline>, block: bool) -> Result<(), TryRouteError> {
+        // Shards trade continuously and never run an opening auction
+        if order.flags().contains(OrderFlags::AUCTION_ONLY) {
+            return Err(TryRouteError::Rejected("Imbalance-only order outside pre-open"));
+        }
+        // Hidden reserves are refilled by `OrderRouter`, which shards do not run
+        if order.flags().contains(OrderFlags::HIDDEN_RESERVE) {
+            return Err(TryRouteError::Rejected("Iceberg orders unsupported on shards"));
+        }
+        // Paired with the SeqCst store and in-flight wait in `freeze_symbols`: either this
+        // call sees the freeze, or the freezer waits for this order to be enqueued.
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let result = if self.is_frozen(order.symbol) {
+            Err(TryRouteError::Rejected("Symbol frozen"))
+        } else if let Some(sender) = shard.sender.as_ref() {
+            let command = ShardCommand::Order(order, deadline);
+            let sent = if block {
+                sender.send(command).map_err(|_| TryRouteError::Rejected("Shard stopped"))
+            } else {
+                sender.try_send(command).map_err(|error| match error {
+                    TrySendError::Full(ShardCommand::Order(order, _)) => TryRouteError::WouldBlock(order),
+                    _ => TryRouteError::Rejected("Shard stopped"),
+                })
+            };
+            if sent.is_ok() {
+                shard.high_water.fetch_max(sender.len(), Ordering::Relaxed);
+            }
+            sent
+        } else {
+            Err(TryRouteError::Rejected("Shard stopped"))
+        };
+        self.in_flight.fetch_sub(1, Ordering::Release);
+        result?;
+        self.sequence.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn is_frozen(&self, symbol: SymbolId) -> bool {
+        self.freezes.get(&symbol).is_some_and(|freezes| freezes.load(Ordering::SeqCst) > 0)
+    }
+
+    /// Stops intake for every symbol in `symbols` at once and returns after each of
+    /// their shards has executed the orders accepted before the freeze, so the books
+    /// are quiescent until `unfreeze_symbols`. Rejects the whole set if any symbol is
+    /// unknown.
+    pub fn freeze_symbols(&self, symbols: &[SymbolId]) -> Result<(), &'static str> {
+        if !symbols.iter().all(|symbol| self.freezes.contains_key(symbol)) {
+            return Err("Invalid symbol");
+        }
+        for symbol in symbols {
+            self.freezes[symbol].fetch_add(1, Ordering::SeqCst);
+        }
+        while self.in_flight.load(Ordering::SeqCst) != 0 {
+            std::hint::spin_loop();
+        }
+        self.sync();
+        Ok(())
+    }
+
+    pub fn unfreeze_symbols(&self, symbols: &[SymbolId]) -> Result<(), &'static str> {
+        if !symbols.iter().all(|symbol| self.freezes.get(symbol).is_some_and(|freezes| freezes.load(Ordering::SeqCst) > 0)) {
+            return Err("Symbol not frozen");
+        }
+        for symbol in symbols {
+            self.freezes[symbol].fetch_sub(1, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    /// Orders rejected with a timeout since the router started.
+    #[inline(always)]
+    pub fn timed_out_count(&self) -> u64 {
+        self.timeouts.count.load(Ordering::Relaxed)
+    }
+
+    /// Rejected orders, most recent `TIMED_OUT_QUEUE_SIZE` kept when nobody drains them.
+    pub fn timed_out_orders(&self) -> Receiver<TimedOutOrder> {
+        self.timeouts.receiver.clone()
+    }
+
+    #[inline(always)]
+    pub fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::Relaxed)
+    }
+
+    /// Acks from the shards configured with an ack delay, each delivered once its delay
+    /// has passed. `None` when no shard has one. Unbounded, so callers must drain it.
+    pub fn acks(&self) -> Option<Receiver<OrderAck>> {
+        self.acks.clone()
+    }
+
+    /// Taking `&mut self` guarantees no order is being routed concurrently, so every
+    /// shard answers after exactly the orders counted in the returned sequence.
+    pub fn snapshot_all(&mut self) -> RouterSnapshot {
+        let sequence = self.sequence();
+        let (reply_sender, reply_receiver) = channel::bounded(self.shards.len());
+        let mut pending = 0;
+        for shard in &self.shards {
+            if let Some(sender) = &shard.sender
+                && sender.send(ShardCommand::Snapshot(sequence, reply_sender.clone())).is_ok()
+            {
+                pending += 1;
+            }
+        }
+
+        let mut books: Vec<BookSnapshot> = (0..pending)
+            .filter_map(|_| reply_receiver.recv().ok())
+            .flatten()
+            .collect();
+        books.sort_unstable_by_key(|book| book.symbol);
+
+        RouterSnapshot {
+            sequence,
+            books,
+            session_state: SessionState::default(),
+            carried_orders: Vec::new(),
+            imbalance_orders: Vec::new(),
+            iceberg_reserves: Vec::new(),
+        }
+    }
+
+    /// Moves `symbol`'s book to another shard without stopping either one: the source
+    /// shard finishes the symbol's queued orders and hands over its resting orders,
+    /// which the target loads before any order routed after this call. Both shards
+    /// rebuild their books, so their last trade prices reset.
+    pub fn move_symbol(&mut self, symbol: SymbolId, to_shard: usize) -> Result<(), &'static str> {
+        let from_shard = self.shard_for_symbol(symbol).ok_or("Invalid symbol")?;
+        if to_shard >= self.shards.len() {
+            return Err("Invalid shard");
+        }
+        if from_shard == to_shard {
+            return Ok(());
+        }
+
+        let (reply_sender, reply_receiver) = channel::bounded(1);
+        self.shards[from_shard].sender
+            .as_ref()
+            .ok_or("Shard stopped")?
+            .send(ShardCommand::Release(symbol, reply_sender))
+            .map_err(|_| "Shard stopped")?;
+        let orders = reply_receiver.recv().map_err(|_| "Shard stopped")?;
+        self.shards[to_shard].sender
+            .as_ref()
+            .ok_or("Shard stopped")?
+            .send(ShardCommand::Adopt(symbol, orders))
+            .map_err(|_| "Shard stopped")?;
+
+        self.shards[from_shard].symbols.retain(|&other| other != symbol);
+        let target_symbols = &mut self.shards[to_shard].symbols;
+        let position = target_symbols.partition_point(|&other| other < symbol);
+        target_symbols.insert(position, symbol);
+        self.symbol_to_shard.insert(symbol, to_shard);
+        Ok(())
+    }
+
+    /// Blocks until every shard has processed all commands queued before this call.
+    pub fn sync(&self) {
+        let (ack_sender, ack_receiver) = channel::bounded(self.shards.len());
+        let mut pending = 0;
+        for shard in &self.shards {
+            if let Some(sender) = &shard.sender
+                && sender.send(ShardCommand::Sync(ack_sender.clone())).is_ok()
+            {
+                pending += 1;
+            }
+        }
+        for _ in 0..pending {
+            let _ = ack_receiver.recv();
+        }
+    }
+
+    /// Per-shard liveness. A shard counts as stalled when it has queued orders but
+    /// has processed none of them for `stall_after`, measured across calls.
+    pub fn shard_health(&self, stall_after: Duration) -> Vec<ShardHealth> {
+        self.shards.iter()
+            .enumerate()
+            .map(|(shard_index, shard)| {
+                let processed = shard.processed.load(Ordering::Relaxed);
+                let queue_depth = shard.sender.as_ref().map_or(0, |sender| sender.len());
+                let unchanged_for = shard.progress.lock().unwrap().observe(processed);
+                ShardHealth {
+                    shard: shard_index,
+                    processed,
+                    queue_depth,
+                    stalled: queue_depth > 0 && unchanged_for >= stall_after,
+                    alive: shard.handle.as_ref().is_some_and(|handle| !handle.is_finished()),
+                }
+            })
+            .collect()
+    }
+
+    /// Current intake queue depth per shard, with its capacity and high-water mark.
+    pub fn queue_depths(&self) -> Vec<QueueDepth> {
+        self.shards.iter()
+            .enumerate()
+            .map(|(shard_index, shard)| QueueDepth {
+                shard: shard_index,
+                depth: shard.sender.as_ref().map_or(0, |sender| sender.len()),
+                capacity: shard.sender.as_ref().and_then(|sender| sender.capacity()).unwrap_or(0),
+                high_water: shard.high_water.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Batch sizes chosen by each shard configured with adaptive batching; shards
+    /// without it are left out.
+    pub fn batch_stats(&self) -> Vec<BatchStats> {
+        self.shards.iter()
+            .enumerate()
+            .filter_map(|(shard_index, shard)| Some(shard.batches.as_ref()?.stats(shard_index)))
+            .collect()
+    }
+
+    /// Starts a new high-water window on every shard.
+    pub fn reset_high_water_marks(&self) {
+        for shard in &self.shards {
+            shard.high_water.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[inline(always)]
+    pub fn shard_for_symbol(&self, symbol: SymbolId) -> Option<usize> {
+        self.symbol_to_shard.get(&symbol).copied()
+    }
+
+    #[inline(always)]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    #[inline(always)]
+    pub fn shard_symbols(&self, shard_index: usize) -> Option<&[SymbolId]> {
+        self.shards.get(shard_index).map(|shard| shard.symbols.as_slice())
+    }
+
+    #[inline(always)]
+    pub fn supports_symbol(&self, symbol: SymbolId) -> bool {
+        self.symbol_to_shard.contains_key(&symbol)
+    }
+
+    #[inline(always)]
+    pub fn order_book_type(&self) -> OrderBookType {
+        self.order_book_type
+    }
+
+    pub fn shutdown(&mut self) {
+        for shard in &mut self.shards {
+            shard.sender.take();
+        }
+        for shard in &mut self.shards {
+            if let Some(handle) = shard.handle.take() {
+                let _ = handle.join();
+            }
+        }
+        if let Some(dispatcher) = self.ack_dispatcher.take() {
+            let _ = dispatcher.join();
+        }
+    }
+}
+
+impl Drop for ShardedRouter {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn spawn_shard(
+    index: usize,
+    config: ShardConfig,
+    symbols: Vec<SymbolId>,
+    order_book_type: OrderBookType,
+    timeouts: TimeoutReporter,
+    ack_intake: Option<Sender<(Instant, OrderAck)>>,
+) -> Shard {
+    let (sender, receiver) = channel::bounded(config.queue_capacity.max(1));
+    let book_symbols = FxHashSet::from_iter(symbols.iter().copied());
+    let processed = Arc::new(AtomicU64::new(0));
+    let shard_processed = Arc::clone(&processed);
+    let batches = config.batching.filter(|_| config.jitter.is_none()).map(|batching| {
+        let counters = Arc::new(BatchCounters::default());
+        counters.set_target(batching.min_batch);
+        counters
+    });
+    let batcher = config.batching.zip(batches.clone()).map(|(config, counters)| Batcher { config, target: config.min_batch, counters });
+
+    let handle = thread::Builder::new()
+        .name(format!("order-book-shard-{index}"))
+        .spawn(move || {
+            pin_current_thread(config.core_id);
+            let jitter = config.jitter.map(|jitter| Jitter::new(jitter, index));
+            let acks = config.ack_delay.zip(ack_intake).map(|(ack_delay, intake)| ShardAcks {
+                intake,
+                sampler: AckDelaySampler::new(ack_delay, index),
+            });
+            let context = ShardContext { timeouts, processed: shard_processed, acks };
+            run_shard(receiver, config.wait_strategy, jitter, batcher, book_symbols, order_book_type, context);
+        })
+        .expect("failed to spawn shard thread");
+
+    Shard {
+        sender: Some(sender),
+        handle: Some(handle),
+        symbols,
+        latency_budget: config.latency_budget,
+        processed,
+        progress: Mutex::new(ProgressWatch::new()),
+        high_water: AtomicUsize::new(0),
+        batches,
+    }
+}
+
+struct ShardContext {
+    timeouts: TimeoutReporter,
+    processed: Arc<AtomicU64>,
+    acks: Option<ShardAcks>,
+}
+
+struct ShardAcks {
+    intake: Sender<(Instant, OrderAck)>,
+    sampler: AckDelaySampler,
+}
+
+#[inline(always)]
+fn next_command(receiver: &Receiver<ShardCommand>, wait_strategy: WaitStrategy) -> Option<ShardCommand> {
+    match wait_strategy {
+        WaitStrategy::Park => receiver.recv().ok(),
+        WaitStrategy::BusySpin => loop {
+            match receiver.try_recv() {
+                Ok(command) => return Some(command),
+                Err(TryRecvError::Empty) => std::hint::spin_loop(),
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        },
+        WaitStrategy::SpinThenYield { spin_limit } => {
+            let mut spins = 0u32;
+            loop {
+                match receiver.try_recv() {
+                    Ok(command) => return Some(command),
+                    Err(TryRecvError::Empty) if spins < spin_limit => {
+                        spins += 1;
+                        std::hint::spin_loop();
+                    }
+                    Err(TryRecvError::Empty) => thread::yield_now(),
+                    Err(TryRecvError::Disconnected) => return None,
+                }
+            }
+        }
+    }
+}
+
+struct Batcher {
+    config: AdaptiveBatching,
+    target: usize,
+    counters: Arc<BatchCounters>,
+}
+
+/// Executes `order`, then up to the batch target less one more queued orders, and
+/// matches once. Hands back a non-order command that cut the batch short.
+fn execute_batch(
+    order_book: &mut dyn OrderBookTrait,
+    receiver: &Receiver<ShardCommand>,
+    batcher: &mut Batcher,
+    order: Order,
+    deadline: Option<Deadline>,
+    context: &ShardContext,
+) -> Option<ShardCommand> {
+    execute_order(order_book, order, deadline, false, context);
+    let mut size = 1;
+    let mut interrupted = None;
+    while size < batcher.target {
+        match receiver.try_recv() {
+            Ok(ShardCommand::Order(order, deadline)) => {
+                execute_order(order_book, order, deadline, false, context);
+                size += 1;
+            }
+            Ok(command) => {
+                interrupted = Some(command);
+                break;
+            }
+            Err(_) => break,
+        }
+    }
+    order_book.match_orders();
+    batcher.target = batcher.config.next_target(batcher.target, receiver.len());
+    batcher.counters.record(size, batcher.target);
+    interrupted
+}
+
+#[inline(always)]
+fn execute_order(
+    order_book: &mut dyn OrderBookTrait,
+    order: Order,
+    deadline: Option<Deadline>,
+    match_now: bool,
+    context: &ShardContext,
+) {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline.expires_at => {
+            let queued_for = deadline.enqueued_at.elapsed();
+            context.timeouts.report(TimedOutOrder { order, queued_for });
+        }
+        _ => {
+            let ack = context.acks.as_ref().map(|_| OrderAck {
+                order_id: order.id,
+                symbol: order.symbol,
+                participant: order.participant,
+                // Shards have no per-symbol defaults, so books rest these as Day
+                time_in_force: match order.time_in_force {
+                    TimeInForce::SymbolDefault => TimeInForce::Day,
+                    time_in_force => time_in_force,
+                },
+                sequence: context.processed.load(Ordering::Relaxed) + 1,
+            });
+            let immediate_or_cancel = order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL);
+            let accepted = order_book.add_order_fast(order);
+            if match_now {
+                order_book.match_orders();
+            }
+            // Shards report no expiries, so unfilled remainders are simply dropped
+            if immediate_or_cancel {
+                order_book.take_evicted_orders();
+            }
+            if let (true, Some(ack), Some(acks)) = (accepted, ack, &context.acks) {
+                let _ = acks.intake.send((Instant::now() + acks.sampler.sample(), ack));
+            }
+        }
+    }
+    context.processed.fetch_add(1, Ordering::Relaxed);
+}
+
+fn release_held(order_book: &mut dyn OrderBookTrait, jitter: &mut Option<Jitter>, context: &ShardContext) {
+    if let Some(jitter) = jitter {
+        for (order, deadline) in jitter.release() {
+            execute_order(order_book, order, deadline, true, context);
+        }
+    }
+}
+
+fn run_shard(
+    receiver: Receiver<ShardCommand>,
+    wait_strategy: WaitStrategy,
+    mut jitter: Option<Jitter>,
+    mut batcher: Option<Batcher>,
+    symbols: FxHashSet<SymbolId>,
+    order_book_type: OrderBookType,
+    context: ShardContext,
+) {
+    let mut order_book = create_order_book(order_book_type, symbols);
+    let mut deferred = None;
+
+    loop {
+        let command = match jitter.as_ref().and_then(|jitter| jitter.release_at) {
+            _ if deferred.is_some() => deferred.take(),
+            Some(release_at) => match receiver.recv_deadline(release_at) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => {
+                    release_held(order_book.as_mut(), &mut jitter, &context);
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => None,
+            },
+            None => next_command(&receiver, wait_strategy),
+        };
+        let Some(command) = command else {
+            release_held(order_book.as_mut(), &mut jitter, &context);
+            break;
+        };
+
+        match command {
+            ShardCommand::Order(order, deadline) => match &mut jitter {
+                Some(held) => {
+                    if held.hold(order, deadline) {
+                        release_held(order_book.as_mut(), &mut jitter, &context);
+                    }
+                }
+                None => match &mut batcher {
+                    Some(batcher) => deferred = execute_batch(order_book.as_mut(), &receiver, batcher, order, deadline, &context),
+                    None => execute_order(order_book.as_mut(), order, deadline, true, &context),
+                },
+            },
+            ShardCommand::Sync(ack) => {
+                release_held(order_book.as_mut(), &mut jitter, &context);
+                let _ = ack.send(());
+            }
+            ShardCommand::Snapshot(sequence, reply) => {
+                release_held(order_book.as_mut(), &mut jitter, &context);
+                let mut symbols: Vec<SymbolId> = order_book.get_symbols().iter().copied().collect();
+                symbols.sort_unstable();
+                let books = symbols.into_iter()
+                    .filter_map(|symbol| {
+                        let orders = order_book.resting_orders(symbol)?;
+                        Some(BookSnapshot { symbol, sequence, orders })
+                    })
+                    .collect();
+                let _ = reply.send(books);
+            }
+            ShardCommand::Release(symbol, reply) => {
+                release_held(order_book.as_mut(), &mut jitter, &context);
+                let orders = order_book.resting_orders(symbol).unwrap_or_default();
+                let mut symbols = order_book.get_symbols().clone();
+                symbols.remove(&symbol);
+                order_book = rebuild_book(order_book.as_ref(), symbols, order_book_type, Vec::new());
+                let _ = reply.send(orders);
+            }
+            ShardCommand::Adopt(symbol, orders) => {
+                release_held(order_book.as_mut(), &mut jitter, &context);
+                let mut symbols = order_book.get_symbols().clone();
+                symbols.insert(symbol);
+                order_book = rebuild_book(order_book.as_ref(), symbols, order_book_type, orders);
+            }
+        }
+    }
+}
+
+/// Books cannot drop or gain symbols in place, so ownership changes build a new book
+/// and replay the surviving resting orders in priority order.
+fn rebuild_book(
+    order_book: &dyn OrderBookTrait,
+    symbols: FxHashSet<SymbolId>,
+    order_book_type: OrderBookType,
+    adopted_orders: Vec<Order>,
+) -> Box<dyn OrderBookTrait + Send + Sync> {
+    let resting: Vec<Order> = symbols.iter()
+        .filter_map(|&symbol| order_book.resting_orders(symbol))
+        .flatten()
+        .collect();
+    let mut rebuilt = create_order_book(order_book_type, symbols);
+    for order in resting.into_iter().chain(adopted_orders) {
+        rebuilt.add_order_fast(order);
+    }
+    rebuilt
+}
+
+#[cfg(feature = "affinity")]
+fn pin_current_thread(core_id: Option<usize>) {
+    if let Some(id) = core_id {
+        core_affinity::set_for_current(core_affinity::CoreId { id });
+    }
+}
+
+#[cfg(not(feature = "affinity"))]
+fn pin_current_thread(_core_id: Option<usize>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, OrderSide};
+
+    #[test]
+    fn test_symbols_spread_across_shards() {
+        let router = ShardedRouter::with_shard_count(FxHashSet::from_iter([0, 1, 2, 3]), OrderBookType::HashMap, 2);
+
+        assert_eq!(router.shard_count(), 2);
+        assert_eq!(router.shard_for_symbol(0), Some(0));
+        assert_eq!(router.shard_for_symbol(1), Some(1));
+        assert_eq!(router.shard_symbols(0), Some(&[0, 2][..]));
+        assert_eq!(router.shard_for_symbol(9), None);
+    }
+
+    #[test]
+    fn test_route_order_to_shard() {
+        let configs = vec![ShardConfig::default().with_queue_capacity(16).pinned_to(0)];
+        let router = ShardedRouter::new(FxHashSet::from_iter([0]), OrderBookType::PriorityQueue, configs);
+
+        assert!(router.route_order(new_order(1, 0, 100, 150.0, OrderSide::Buy)).is_ok());
+        assert!(router.route_order(new_order(2, 0, 100, 150.0, OrderSide::Sell)).is_ok());
+        assert!(router.route_order(new_order(3, 7, 100, 150.0, OrderSide::Sell)).is_err());
+        router.sync();
+    }
+
+    #[test]
+    fn test_snapshot_all_is_consistent_across_shards() {
+        let mut router = ShardedRouter::with_shard_count(FxHashSet::from_iter([0, 1, 2]), OrderBookType::HashMap, 3);
+
+        for id in 0..30 {
+            router.route_order(new_order(id, (id % 3) as SymbolId, 10, 100.0 + id as f64, OrderSide::Buy)).unwrap();
+        }
+        let snapshot = router.snapshot_all();
+
+        assert_eq!(snapshot.sequence, 30);
+        assert_eq!(snapshot.books.iter().map(|book| book.symbol).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(snapshot.books.iter().map(|book| book.orders.len()).sum::<usize>(), 30);
+    }
+
+    #[test]
+    fn test_wait_strategies_process_orders() {
+        for wait_strategy in [WaitStrategy::BusySpin, WaitStrategy::spin_then_yield(), WaitStrategy::Park] {
+            let configs = vec![ShardConfig::default().with_wait_strategy(wait_strategy); 2];
+            let mut router = ShardedRouter::new(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap, configs);
+
+            for id in 0..100 {
+                let side = if id % 2 == 0 { OrderSide::Buy } else { OrderSide::Sell };
+                assert!(router.route_order(new_order(id, (id % 2) as SymbolId, 10, 100.0, side)).is_ok());
+            }
+            router.sync();
+            router.shutdown();
+        }
+    }
+
+    #[test]
+    fn test_orders_past_deadline_are_rejected() {
+        let configs = vec![ShardConfig::default().with_latency_budget(Duration::from_secs(60))];
+        let router = ShardedRouter::new(FxHashSet::from_iter([0]), OrderBookType::HashMap, configs);
+
+        router.route_order(new_order(1, 0, 100, 150.0, OrderSide::Buy)).unwrap();
+        router.route_order_with_deadline(new_order(2, 0, 100, 150.0, OrderSide::Sell), Instant::now()).unwrap();
+        router.sync();
+
+        assert_eq!(router.timed_out_count(), 1);
+        let timed_out = router.timed_out_orders().try_recv().unwrap();
+        assert_eq!(timed_out.order.id, 2);
+        assert_eq!(router.sequence(), 2);
+    }
+
+    #[test]
+    fn test_consistent_hash_only_moves_symbols_to_new_shard() {
+        let symbols = FxHashSet::from_iter(0..200);
+        let assignment = ShardAssignment::ConsistentHash { virtual_nodes: 64 };
+        let four = ShardedRouter::with_assignment(symbols.clone(), OrderBookType::HashMap, vec![ShardConfig::default(); 4], assignment);
+        let five = ShardedRouter::with_assignment(symbols, OrderBookType::HashMap, vec![ShardConfig::default(); 5], assignment);
+
+        let moved: Vec<SymbolId> = (0..200).filter(|&symbol| four.shard_for_symbol(symbol) != five.shard_for_symbol(symbol)).collect();
+        assert!(!moved.is_empty() && moved.len() < 100, "{} symbols moved", moved.len());
+        assert!(moved.iter().all(|&symbol| five.shard_for_symbol(symbol) == Some(4)));
+        assert!((0..4).all(|shard| !four.shard_symbols(shard).unwrap().is_empty()));
+    }
+
+    #[test]
+    fn test_move_symbol_hands_book_to_target_shard() {
+        let mut router = ShardedRouter::with_shard_count(FxHashSet::from_iter([0, 1]), OrderBookType::PriorityQueue, 2);
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 10, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(3, 1, 10, 50.0, OrderSide::Buy)).unwrap();
+
+        router.move_symbol(0, 1).unwrap();
+        assert_eq!(router.shard_for_symbol(0), Some(1));
+        assert_eq!(router.shard_symbols(0), Some(&[][..]));
+        assert_eq!(router.shard_symbols(1), Some(&[0, 1][..]));
+        assert!(router.move_symbol(0, 5).is_err());
+
+        router.route_order(new_order(4, 0, 10, 100.0, OrderSide::Sell)).unwrap();
+        let snapshot = router.snapshot_all();
+        let ids = |symbol: SymbolId| snapshot.books.iter()
+            .find(|book| book.symbol == symbol)
+            .map(|book| book.orders.iter().map(|order| order.id).collect::<Vec<_>>());
+        assert_eq!(ids(0), Some(vec![2]));
+        assert_eq!(ids(1), Some(vec![3]));
+    }
+
+    #[test]
+    fn test_frozen_symbols_reject_until_released() {
+        let router = ShardedRouter::with_shard_count(FxHashSet::from_iter([0, 1, 2]), OrderBookType::HashMap, 2);
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+
+        router.freeze_symbols(&[0, 1]).unwrap();
+        router.freeze_symbols(&[0]).unwrap();
+        assert!(router.freeze_symbols(&[2, 9]).is_err());
+        assert!(!router.is_frozen(2));
+        assert_eq!(router.route_order(new_order(2, 0, 10, 100.0, OrderSide::Sell)), Err("Symbol frozen"));
+        assert!(router.route_order(new_order(3, 2, 10, 100.0, OrderSide::Sell)).is_ok());
+
+        router.unfreeze_symbols(&[0, 1]).unwrap();
+        assert!(router.is_frozen(0) && !router.is_frozen(1));
+        router.unfreeze_symbols(&[0]).unwrap();
+        assert!(router.unfreeze_symbols(&[0]).is_err());
+        assert!(router.route_order(new_order(4, 0, 10, 100.0, OrderSide::Sell)).is_ok());
+        assert_eq!(router.sequence(), 3);
+    }
+
+    #[test]
+    fn test_try_route_order_sheds_when_queue_full() {
+        let configs = vec![ShardConfig::default().with_queue_capacity(2)];
+        let router = ShardedRouter::new(FxHashSet::from_iter([0]), OrderBookType::HashMap, configs);
+
+        // Park the shard on an unanswered sync so the queue cannot drain
+        let (ack_sender, ack_receiver) = channel::bounded(0);
+        router.shards[0].sender.as_ref().unwrap().send(ShardCommand::Sync(ack_sender)).unwrap();
+        while router.queue_depths()[0].depth > 0 {
+            thread::yield_now();
+        }
+
+        for id in 1..=2 {
+            router.try_route_order(new_order(id, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        }
+        match router.try_route_order(new_order(3, 0, 10, 100.0, OrderSide::Buy)) {
+            Err(TryRouteError::WouldBlock(order)) => assert_eq!(order.id, 3),
+            other => panic!("expected WouldBlock, got {other:?}"),
+        }
+        assert!(matches!(router.try_route_order(new_order(4, 9, 10, 100.0, OrderSide::Buy)), Err(TryRouteError::Rejected(_))));
+        assert_eq!(router.queue_depths()[0], QueueDepth { shard: 0, depth: 2, capacity: 2, high_water: 2 });
+
+        ack_receiver.recv().unwrap();
+        router.sync();
+        router.reset_high_water_marks();
+        assert_eq!((router.queue_depths()[0].depth, router.queue_depths()[0].high_water), (0, 0));
+        assert_eq!(router.sequence(), 2);
+    }
+
+    #[test]
+    fn test_adaptive_batching_grows_under_load_and_shrinks_when_idle() {
+        let batching = AdaptiveBatching::new(4, 32);
+        let configs = vec![ShardConfig::default().with_adaptive_batching(batching), ShardConfig::default()];
+        let mut router = ShardedRouter::new(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap, configs);
+        assert_eq!(router.batch_stats(), vec![BatchStats { shard: 0, passes: 0, orders: 0, target: 4, largest: 0 }]);
+
+        // Queue 100 orders behind a parked shard so every pass sees the same backlog
+        let (ack_sender, ack_receiver) = channel::bounded(0);
+        router.shards[0].sender.as_ref().unwrap().send(ShardCommand::Sync(ack_sender)).unwrap();
+        while router.queue_depths()[0].depth > 0 {
+            thread::yield_now();
+        }
+        for id in 1..=100 {
+            let (side, price) = if id % 2 == 0 { (OrderSide::Buy, 99.0) } else { (OrderSide::Sell, 101.0) };
+            router.route_order(new_order(id, 0, 10, price, side)).unwrap();
+        }
+        ack_receiver.recv().unwrap();
+        while router.batch_stats()[0].orders < 100 {
+            thread::yield_now();
+        }
+
+        // Passes of 4, 8, 16, 32 and 32 against the backlog, then the last 8
+        let stats = router.batch_stats()[0];
+        assert_eq!((stats.passes, stats.largest, stats.target), (6, 32, 16));
+        assert!((stats.mean_batch_size() - 100.0 / 6.0).abs() < 1e-9);
+        let snapshot = router.snapshot_all();
+        assert_eq!(snapshot.books[0].orders.len(), 100);
+
+        assert_eq!(batching.next_target(16, 0), 8);
+        assert_eq!(batching.next_target(4, 0), 4);
+        assert_eq!(batching.next_target(8, 3), 8);
+    }
+
+    #[test]
+    fn test_shard_health_reports_progress() {
+        let mut router = ShardedRouter::with_shard_count(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap, 2);
+
+        for id in 0..10 {
+            router.route_order(new_order(id, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        }
+        router.sync();
+        let health = router.shard_health(Duration::ZERO);
+        assert_eq!(health.iter().map(|shard| shard.processed).collect::<Vec<_>>(), vec![10, 0]);
+        assert!(health.iter().all(|shard| shard.is_healthy() && shard.queue_depth == 0));
+
+        router.shutdown();
+        assert!(router.shard_health(Duration::ZERO).iter().all(|shard| !shard.alive));
+    }
+
+    #[test]
+    fn test_jitter_reorders_reproducibly_within_bound() {
+        let resting_ids = |seed| {
+            let jitter = JitterConfig { max_delay: Duration::from_secs(60), max_reorder: 3, seed };
+            let mut router = ShardedRouter::new(
+                FxHashSet::from_iter([0]),
+                OrderBookType::HashMap,
+                vec![ShardConfig::default().with_jitter(jitter)],
+            );
+            for id in 0..16 {
+                router.route_order(new_order(id, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+            }
+            let snapshot = router.snapshot_all();
+            snapshot.books[0].orders.iter().map(|order| order.id).collect::<Vec<_>>()
+        };
+
+        let ids = resting_ids(7);
+        assert_eq!(ids, resting_ids(7));
+        assert_ne!(ids, (0..16).collect::<Vec<_>>());
+        for (position, id) in ids.iter().enumerate() {
+            assert!(position.abs_diff(*id as usize) <= 3, "{ids:?}");
+        }
+    }
+
+    #[test]
+    fn test_ack_delay_samples_reproducibly_within_bounds() {
+        let (min, max) = (Duration::from_micros(100), Duration::from_millis(5));
+        for delay in [AckDelay::Uniform { min, max }, AckDelay::Exponential { min, mean: Duration::from_millis(1), max }] {
+            let samples = |seed| {
+                let sampler = AckDelaySampler::new(AckDelayConfig { delay, seed }, 0);
+                (0..64).map(|_| sampler.sample()).collect::<Vec<_>>()
+            };
+            let drawn = samples(11);
+            assert_eq!(drawn, samples(11));
+            assert_ne!(drawn, samples(12));
+            assert!(drawn.iter().all(|sample| (min..=max).contains(sample)), "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn test_acks_delivered_after_fixed_delay() {
+        let delay = Duration::from_millis(30);
+        let mut router = ShardedRouter::new(
+            FxHashSet::from_iter([0, 1]),
+            OrderBookType::HashMap,
+            vec![ShardConfig::default().with_ack_delay(AckDelayConfig { delay: AckDelay::Fixed(delay), seed: 1 }), ShardConfig::default()],
+        );
+        let acks = router.acks().unwrap();
+        let routed_at = Instant::now();
+        for id in 1..=3 {
+            router.route_order(new_order(id, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        }
+        router.route_order(new_order(4, 1, 10, 100.0, OrderSide::Buy)).unwrap();
+
+        let first = acks.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(routed_at.elapsed() >= delay);
+        let rest: Vec<_> = (0..2).map(|_| acks.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        let delivered: Vec<_> = std::iter::once(&first).chain(&rest).map(|ack| (ack.order_id, ack.sequence)).collect();
+        assert_eq!(delivered, vec![(1, 1), (2, 2), (3, 3)]);
+
+        // The second shard has no ack delay configured, so order 4 is never acked
+        router.shutdown();
+        assert!(acks.try_recv().is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::router::SessionState;
+use crate::types::{order::{self, Order}, symbol_mapping::SymbolId};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+#[inline(always)]
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct BookSnapshot {
+    pub symbol: SymbolId,
+    pub sequence: u64,
+    pub orders: Vec<Order>,
+}
+
+impl BookSnapshot {
+    /// FNV-1a over the wire encoding of the resting orders in priority order. Identical
+    /// books hash identically across implementations and runs; the sequence is excluded.
+    pub fn checksum(&self) -> u64 {
+        let hash = fnv1a(FNV_OFFSET_BASIS, &self.symbol.to_le_bytes());
+        self.orders.iter().fold(hash, |hash, order| fnv1a(hash, &order::encode_order(order)))
+    }
+}
+
+/// Every book captured at the same router sequence number.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RouterSnapshot {
+    pub sequence: u64,
+    pub books: Vec<BookSnapshot>,
+    #[serde(default)]
+    pub session_state: SessionState,
+    /// Good-till-cancel orders held between a session roll and the next open.
+    #[serde(default)]
+    pub carried_orders: Vec<Order>,
+    /// Imbalance-only orders waiting for the opening auction.
+    #[serde(default)]
+    pub imbalance_orders: Vec<Order>,
+    /// Hidden reserves of iceberg orders, each as an order for the quantity not yet shown.
+    #[serde(default)]
+    pub iceberg_reserves: Vec<Order>,
+}
+
+impl RouterSnapshot {
+    /// Combined checksum of every book, independent of the order `books` is listed in.
+    pub fn checksum(&self) -> u64 {
+        let mut checksums: Vec<u64> = self.books.iter().map(BookSnapshot::checksum).collect();
+        checksums.sort_unstable();
+        checksums.iter().fold(FNV_OFFSET_BASIS, |hash, checksum| fnv1a(hash, &checksum.to_le_bytes()))
+    }
+}
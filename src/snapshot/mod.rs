@@ -0,0 +1,5 @@
+pub mod book_snapshot;
+pub mod snapshot_service;
+
+pub use book_snapshot::{BookSnapshot, RouterSnapshot};
+pub use snapshot_service::{SnapshotPolicy, SnapshotService, latest_snapshot, latest_snapshot_in, write_snapshot, write_snapshot_to};
\ No newline at end of file
@@ -0,0 +1,227 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::{self, RecvTimeoutError, Sender};
+
+use crate::router::OrderRouter;
+use crate::snapshot::RouterSnapshot;
+use crate::storage::{FileStorage, Storage};
+
+const SNAPSHOT_PREFIX: &str = "snapshot-";
+const SNAPSHOT_EXTENSION: &str = "json";
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone)]
+pub struct SnapshotPolicy {
+    pub directory: PathBuf,
+    pub interval: Option<Duration>,
+    pub every_events: Option<u64>,
+    pub retention: usize,
+}
+
+impl SnapshotPolicy {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            interval: Some(Duration::from_secs(60)),
+            every_events: None,
+            retention: 5,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    pub fn with_event_threshold(mut self, events: u64) -> Self {
+        self.every_events = Some(events);
+        self
+    }
+
+    pub fn with_retention(mut self, retention: usize) -> Self {
+        self.retention = retention.max(1);
+        self
+    }
+}
+
+pub struct SnapshotService {
+    shutdown: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SnapshotService {
+    /// Snapshots into files under `policy.directory`.
+    pub fn start(router: Arc<Mutex<OrderRouter>>, policy: SnapshotPolicy) -> io::Result<Self> {
+        let storage = FileStorage::open(&policy.directory)?;
+        Self::start_with_storage(router, policy, Arc::new(storage))
+    }
+
+    /// Snapshots into `storage`; `policy.directory` is ignored.
+    pub fn start_with_storage(
+        router: Arc<Mutex<OrderRouter>>,
+        policy: SnapshotPolicy,
+        storage: Arc<dyn Storage>,
+    ) -> io::Result<Self> {
+        let (shutdown, shutdown_receiver) = channel::bounded::<()>(1);
+
+        let handle = thread::Builder::new()
+            .name("order-book-snapshotter".into())
+            .spawn(move || {
+                let mut last_snapshot_at = Instant::now();
+                let mut last_sequence = router.lock().map(|router| router.sequence()).unwrap_or(0);
+
+                while let Err(RecvTimeoutError::Timeout) = shutdown_receiver.recv_timeout(POLL_INTERVAL) {
+                    let Ok(router) = router.lock() else { break };
+                    let sequence = router.sequence();
+                    let interval_due = policy.interval.is_some_and(|interval| last_snapshot_at.elapsed() >= interval);
+                    let events_due = policy.every_events.is_some_and(|events| sequence.saturating_sub(last_sequence) >= events);
+
+                    if (interval_due || events_due) && sequence != last_sequence {
+                        if write_snapshot_to(&router, storage.as_ref()).is_ok() {
+                            last_sequence = sequence;
+                            let _ = prune_snapshots(storage.as_ref(), policy.retention);
+                        }
+                        last_snapshot_at = Instant::now();
+                    } else if interval_due {
+                        last_snapshot_at = Instant::now();
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for SnapshotService {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+pub fn write_snapshot(router: &OrderRouter, directory: &Path) -> io::Result<PathBuf> {
+    let storage = FileStorage::open(directory)?;
+    let key = write_snapshot_to(router, &storage)?;
+    Ok(storage.path(&key))
+}
+
+/// Stores the router snapshot and returns the key it was written under.
+pub fn write_snapshot_to(router: &OrderRouter, storage: &dyn Storage) -> io::Result<String> {
+    let snapshot = router.snapshot_all();
+    let key = format!("{SNAPSHOT_PREFIX}{:020}.{SNAPSHOT_EXTENSION}", snapshot.sequence);
+    storage.put(&key, &serde_json::to_vec(&snapshot)?)?;
+    Ok(key)
+}
+
+pub fn latest_snapshot(directory: &Path) -> io::Result<Option<RouterSnapshot>> {
+    latest_snapshot_in(&FileStorage::open(directory)?)
+}
+
+pub fn latest_snapshot_in(storage: &dyn Storage) -> io::Result<Option<RouterSnapshot>> {
+    let Some(key) = snapshot_keys(storage)?.pop() else {
+        return Ok(None);
+    };
+    match storage.get(&key)? {
+        Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        None => Ok(None),
+    }
+}
+
+fn snapshot_keys(storage: &dyn Storage) -> io::Result<Vec<String>> {
+    // Zero-padded sequence numbers make lexical order chronological
+    let mut keys = storage.list(SNAPSHOT_PREFIX)?;
+    keys.retain(|key| key.ends_with(SNAPSHOT_EXTENSION));
+    Ok(keys)
+}
+
+fn prune_snapshots(storage: &dyn Storage, retention: usize) -> io::Result<()> {
+    let keys = snapshot_keys(storage)?;
+    let excess = keys.len().saturating_sub(retention);
+    for key in &keys[..excess] {
+        storage.remove(key)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::OrderBookType;
+    use crate::storage::MemoryStorage;
+    use crate::types::order::{new_order, OrderSide};
+    use rustc_hash::FxHashSet;
+    use std::fs;
+
+    fn snapshot_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-order-book-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_event_threshold_snapshots_with_retention() {
+        let dir = snapshot_dir("snapshots");
+        let router = Arc::new(Mutex::new(OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap)));
+        let policy = SnapshotPolicy::new(&dir).with_event_threshold(1).with_retention(2);
+        let mut service = SnapshotService::start(router.clone(), policy).unwrap();
+
+        for id in 1..=3 {
+            router.lock().unwrap().route_order(new_order(id, 0, 100, 150.0, OrderSide::Buy)).unwrap();
+            thread::sleep(POLL_INTERVAL * 5);
+        }
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while latest_snapshot(&dir).unwrap().is_none_or(|snapshot| snapshot.sequence < 3) && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        service.stop();
+
+        assert_eq!(snapshot_keys(&FileStorage::open(&dir).unwrap()).unwrap().len(), 2);
+        let snapshot = latest_snapshot(&dir).unwrap().unwrap();
+        assert_eq!(snapshot.sequence, 3);
+        assert_eq!(snapshot.books[0].orders.len(), 3);
+
+        let mut restored = OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::PriorityQueue);
+        restored.restore_all(snapshot).unwrap();
+        assert_eq!(restored.sequence(), 3);
+        assert_eq!(restored.snapshot_book(0).unwrap().orders.len(), 3);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_memory_storage_keeps_latest_snapshots() {
+        let storage = Arc::new(MemoryStorage::new());
+        let router = Arc::new(Mutex::new(OrderRouter::new_direct(FxHashSet::from_iter([0]), OrderBookType::HashMap)));
+        let policy = SnapshotPolicy::new("unused").with_event_threshold(1).with_retention(1);
+        let mut service = SnapshotService::start_with_storage(router.clone(), policy, storage.clone()).unwrap();
+
+        for id in 1..=2 {
+            router.lock().unwrap().route_order(new_order(id, 0, 100, 150.0, OrderSide::Sell)).unwrap();
+            thread::sleep(POLL_INTERVAL * 5);
+        }
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while latest_snapshot_in(storage.as_ref()).unwrap().is_none_or(|snapshot| snapshot.sequence < 2) && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        service.stop();
+
+        assert_eq!(snapshot_keys(storage.as_ref()).unwrap().len(), 1);
+        assert_eq!(latest_snapshot_in(storage.as_ref()).unwrap().unwrap().books[0].orders.len(), 2);
+        assert!(!Path::new("unused").exists());
+    }
+}
@@ -0,0 +1,234 @@
+use std::io::{self, Read, Write};
+
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+
+use crate::storage::Storage;
+
+const MAGIC: &[u8; 4] = b"OBZ1";
+const BLOCK_SIZE: usize = 64 * 1024;
+const FRAME_HEADER_SIZE: usize = 8;
+
+#[inline(always)]
+pub fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Deflates input in blocks of up to 64 KiB. Each frame is prefixed with its compressed
+/// and raw lengths (u32 LE) so a reader only ever holds one block in memory.
+/// Call `finish`; dropping the encoder loses the last partial block.
+pub struct FrameEncoder<W: Write> {
+    inner: W,
+    block: Vec<u8>,
+    level: Compression,
+    started: bool,
+}
+
+impl<W: Write> FrameEncoder<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_level(inner, Compression::fast().level())
+    }
+
+    /// `level` runs from 0 (store) to 9 (smallest).
+    pub fn with_level(inner: W, level: u32) -> Self {
+        Self {
+            inner,
+            block: Vec::with_capacity(BLOCK_SIZE),
+            level: Compression::new(level.min(9)),
+            started: false,
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_frame()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn write_frame(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.inner.write_all(MAGIC)?;
+            self.started = true;
+        }
+        if self.block.is_empty() {
+            return Ok(());
+        }
+        let mut encoder = DeflateEncoder::new(Vec::new(), self.level);
+        encoder.write_all(&self.block)?;
+        let compressed = encoder.finish()?;
+        self.inner.write_all(&(compressed.len() as u32).to_le_bytes())?;
+        self.inner.write_all(&(self.block.len() as u32).to_le_bytes())?;
+        self.inner.write_all(&compressed)?;
+        self.block.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for FrameEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(BLOCK_SIZE - self.block.len());
+        self.block.extend_from_slice(&buf[..len]);
+        if self.block.len() == BLOCK_SIZE {
+            self.write_frame()?;
+        }
+        Ok(len)
+    }
+
+    /// Ends the current frame early.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_frame()?;
+        self.inner.flush()
+    }
+}
+
+/// Streams the output of `FrameEncoder` back, one frame at a time.
+pub struct FrameDecoder<R: Read> {
+    inner: R,
+    block: Vec<u8>,
+    position: usize,
+    started: bool,
+}
+
+impl<R: Read> FrameDecoder<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, block: Vec::new(), position: 0, started: false }
+    }
+
+    /// Loads the next frame, returning false at a clean end of stream.
+    fn next_frame(&mut self) -> io::Result<bool> {
+        if !self.started {
+            let mut magic = [0u8; MAGIC.len()];
+            self.inner.read_exact(&mut magic)?;
+            if &magic != MAGIC {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "not a compressed frame stream"));
+            }
+            self.started = true;
+        }
+
+        let mut header = [0u8; FRAME_HEADER_SIZE];
+        let mut filled = 0;
+        while filled < header.len() {
+            match self.inner.read(&mut header[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        match filled {
+            0 => return Ok(false),
+            FRAME_HEADER_SIZE => {}
+            _ => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated frame header")),
+        }
+        let compressed_len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        let raw_len = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+
+        let mut compressed = Vec::with_capacity(compressed_len);
+        (&mut self.inner).take(compressed_len as u64).read_to_end(&mut compressed)?;
+        if compressed.len() != compressed_len {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated frame"));
+        }
+        self.block.clear();
+        self.position = 0;
+        DeflateDecoder::new(compressed.as_slice()).read_to_end(&mut self.block)?;
+        if self.block.len() != raw_len {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "frame length mismatch"));
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for FrameDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.block.len() {
+            if !self.next_frame()? {
+                return Ok(0);
+            }
+        }
+        let len = buf.len().min(self.block.len() - self.position);
+        buf[..len].copy_from_slice(&self.block[self.position..self.position + len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// Compresses values on the way into `inner`. Values written before compression was
+/// turned on are read back unchanged.
+#[derive(Debug, Default)]
+pub struct CompressedStorage<S> {
+    inner: S,
+    level: u32,
+}
+
+impl<S: Storage> CompressedStorage<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner, level: Compression::fast().level() }
+    }
+
+    pub fn with_level(mut self, level: u32) -> Self {
+        self.level = level.min(9);
+        self
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: Storage> Storage for CompressedStorage<S> {
+    fn put(&self, key: &str, value: &[u8]) -> io::Result<()> {
+        let mut encoder = FrameEncoder::with_level(Vec::new(), self.level);
+        encoder.write_all(value)?;
+        self.inner.put(key, &encoder.finish()?)
+    }
+
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let Some(stored) = self.inner.get(key)? else {
+            return Ok(None);
+        };
+        if !is_compressed(&stored) {
+            return Ok(Some(stored));
+        }
+        let mut value = Vec::new();
+        FrameDecoder::new(stored.as_slice()).read_to_end(&mut value)?;
+        Ok(Some(value))
+    }
+
+    fn list(&self, prefix: &str) -> io::Result<Vec<String>> {
+        self.inner.list(prefix)
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        self.inner.remove(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_frames_round_trip_across_blocks() {
+        let value: Vec<u8> = (0..BLOCK_SIZE * 2 + 17).map(|i| (i % 251) as u8).collect();
+        let storage = CompressedStorage::new(MemoryStorage::new());
+        storage.put("journal", &value).unwrap();
+
+        let stored = storage.inner().get("journal").unwrap().unwrap();
+        assert!(is_compressed(&stored) && stored.len() < value.len());
+        assert_eq!(storage.get("journal").unwrap().unwrap(), value);
+
+        let mut streamed = Vec::new();
+        let mut buf = [0u8; 1000];
+        let mut decoder = FrameDecoder::new(stored.as_slice());
+        while let Ok(read @ 1..) = decoder.read(&mut buf) {
+            streamed.extend_from_slice(&buf[..read]);
+        }
+        assert_eq!(streamed, value);
+
+        assert!(FrameDecoder::new(&stored[..stored.len() - 1]).read_to_end(&mut Vec::new()).is_err());
+        storage.inner().put("legacy", b"{}").unwrap();
+        assert_eq!(storage.get("legacy").unwrap().unwrap(), b"{}");
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::Mutex;
+
+use crate::storage::Storage;
+
+/// Keeps everything in process memory; lost on exit. The default backend.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> io::Result<std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>>> {
+        self.entries.lock().map_err(|_| io::Error::other("memory storage lock poisoned"))
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn put(&self, key: &str, value: &[u8]) -> io::Result<()> {
+        self.entries()?.insert(key.to_owned(), value.to_vec());
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.entries()?.get(key).cloned())
+    }
+
+    fn list(&self, prefix: &str) -> io::Result<Vec<String>> {
+        Ok(self.entries()?
+            .range(prefix.to_owned()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        self.entries()?.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_is_prefix_scoped_and_ordered() {
+        let storage = MemoryStorage::new();
+        for key in ["snapshot-2", "journal-1", "snapshot-1", "snapshots"] {
+            storage.put(key, key.as_bytes()).unwrap();
+        }
+        assert_eq!(storage.list("snapshot-").unwrap(), vec!["snapshot-1", "snapshot-2"]);
+
+        storage.remove("snapshot-1").unwrap();
+        storage.remove("missing").unwrap();
+        assert_eq!(storage.get("snapshot-1").unwrap(), None);
+        assert_eq!(storage.get("snapshot-2").unwrap().as_deref(), Some(&b"snapshot-2"[..]));
+    }
+}
@@ -0,0 +1,23 @@
+pub mod file_storage;
+pub mod memory_storage;
+#[cfg(feature = "compression")]
+pub mod compressed_storage;
+
+#[cfg(feature = "compression")]
+pub use compressed_storage::{CompressedStorage, FrameDecoder, FrameEncoder, is_compressed};
+pub use file_storage::FileStorage;
+pub use memory_storage::MemoryStorage;
+
+use std::io;
+
+/// Named blobs that snapshots (and later journals or the trade tape) persist through,
+/// so the durability level is picked by the deployment rather than the engine.
+pub trait Storage: Send + Sync {
+    /// Replaces `key` atomically: readers see the old value or the new one, never a mix.
+    fn put(&self, key: &str, value: &[u8]) -> io::Result<()>;
+    fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+    /// Keys starting with `prefix`, in lexical order.
+    fn list(&self, prefix: &str) -> io::Result<Vec<String>>;
+    /// Removing a missing key is not an error.
+    fn remove(&self, key: &str) -> io::Result<()>;
+}
@@ -0,0 +1,94 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::types::order::{new_order, Order, OrderSide};
+use crate::types::price_scale::PriceScale;
+use crate::types::symbol_mapping::SymbolId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceConversionError {
+    Negative,
+    /// More decimal places than the fixed-point price can hold.
+    TooPrecise,
+    OutOfRange,
+}
+
+impl PriceScale {
+    /// Exact conversion: fails instead of rounding when `price` has more significant
+    /// decimal places than this scale.
+    pub fn decimal_to_u64(self, price: Decimal) -> Result<u64, PriceConversionError> {
+        if price.is_sign_negative() && !price.is_zero() {
+            return Err(PriceConversionError::Negative);
+        }
+        if price.normalize().scale() > self.decimals() {
+            return Err(PriceConversionError::TooPrecise);
+        }
+
+        price
+            .checked_mul(Decimal::from(self.factor()))
+            .and_then(|scaled| scaled.to_u64())
+            .ok_or(PriceConversionError::OutOfRange)
+    }
+
+    #[inline(always)]
+    pub fn to_decimal(self, price: u64) -> Decimal {
+        Decimal::from_i128_with_scale(price as i128, self.decimals())
+    }
+}
+
+/// `PriceScale::decimal_to_u64` at the default scale: fails instead of rounding when
+/// `price` has more than `PRICE_DECIMALS` significant decimal places.
+pub fn decimal_to_price(price: Decimal) -> Result<u64, PriceConversionError> {
+    PriceScale::DEFAULT.decimal_to_u64(price)
+}
+
+#[inline(always)]
+pub fn price_to_decimal(price: u64) -> Decimal {
+    PriceScale::DEFAULT.to_decimal(price)
+}
+
+pub fn new_order_from_decimal(
+    id: u64,
+    symbol: SymbolId,
+    quantity: u64,
+    price: Decimal,
+    order_type: OrderSide,
+) -> Result<Order, PriceConversionError> {
+    let price = decimal_to_price(price)?;
+    let mut order = new_order(id, symbol, quantity, 0.0, order_type);
+    order.price = price;
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let price = Decimal::from_str("101.250").unwrap();
+
+        assert_eq!(decimal_to_price(price), Ok(101_250));
+        assert_eq!(price_to_decimal(101_250), price);
+        assert_eq!(new_order_from_decimal(1, 0, 100, price, OrderSide::Buy).unwrap().price, 101_250);
+    }
+
+    #[test]
+    fn test_decimal_rejects_inexact_prices() {
+        assert_eq!(decimal_to_price(Decimal::from_str("1.2345").unwrap()), Err(PriceConversionError::TooPrecise));
+        assert_eq!(decimal_to_price(Decimal::from_str("-1").unwrap()), Err(PriceConversionError::Negative));
+        assert_eq!(decimal_to_price(Decimal::MAX), Err(PriceConversionError::OutOfRange));
+        assert_eq!(decimal_to_price(Decimal::from_str("1.2300").unwrap()), Ok(1_230));
+    }
+
+    #[test]
+    fn test_decimal_at_symbol_scale() {
+        let fx = PriceScale::new(5).unwrap();
+        let price = Decimal::from_str("1.23456").unwrap();
+
+        assert_eq!(fx.decimal_to_u64(price), Ok(123_456));
+        assert_eq!(fx.to_decimal(123_456), price);
+        assert_eq!(PriceScale::new(0).unwrap().decimal_to_u64(Decimal::from_str("0.5").unwrap()), Err(PriceConversionError::TooPrecise));
+    }
+}
@@ -1,2 +1,8 @@
 pub mod order;
-pub mod symbol_mapping;
\ No newline at end of file
+pub mod price_scale;
+pub mod symbol_mapping;
+pub mod trade;
+pub mod timestamp;
+pub mod validation;
+#[cfg(feature = "decimal")]
+pub mod decimal;
\ No newline at end of file
@@ -1,11 +1,170 @@
+use crate::types::price_scale::PriceScale;
 use crate::types::symbol_mapping::SymbolId;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
 pub enum OrderSide {
     Buy,
     Sell,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+pub enum TimeInForce {
+    /// Expires at the end of the session.
+    #[default]
+    Day,
+    /// Survives session rolls until filled or cancelled.
+    GoodTillCancel,
+    /// Opening auction only: executes solely against the opening imbalance and is
+    /// rejected during continuous trading.
+    ImbalanceOnly,
+    /// Matches whatever it can on arrival; books cancel the remainder instead of
+    /// resting it.
+    ImmediateOrCancel,
+    /// Left to the venue: `OrderRouter` applies the symbol's configured default, `Day`
+    /// unless set. Books that receive it directly treat it as `Day`.
+    SymbolDefault,
+}
+
+impl TimeInForce {
+    /// Behaviour this time in force adds to an order.
+    #[inline(always)]
+    pub const fn flags(self) -> OrderFlags {
+        match self {
+            TimeInForce::Day | TimeInForce::SymbolDefault => OrderFlags::NONE,
+            TimeInForce::GoodTillCancel => OrderFlags::CARRIES_OVER,
+            TimeInForce::ImbalanceOnly => OrderFlags::AUCTION_ONLY,
+            TimeInForce::ImmediateOrCancel => OrderFlags::MATCH_ON_ARRIVAL,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn wire_code(self) -> u8 {
+        match self {
+            TimeInForce::Day => 0,
+            TimeInForce::GoodTillCancel => 1,
+            TimeInForce::ImbalanceOnly => 2,
+            TimeInForce::SymbolDefault => 3,
+            TimeInForce::ImmediateOrCancel => 4,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn from_wire_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(TimeInForce::Day),
+            1 => Some(TimeInForce::GoodTillCancel),
+            2 => Some(TimeInForce::ImbalanceOnly),
+            3 => Some(TimeInForce::SymbolDefault),
+            4 => Some(TimeInForce::ImmediateOrCancel),
+            _ => None,
+        }
+    }
+}
+
+impl OrderSide {
+    #[inline(always)]
+    pub const fn wire_code(self) -> u8 {
+        match self {
+            OrderSide::Buy => 0,
+            OrderSide::Sell => 1,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn from_wire_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(OrderSide::Buy),
+            1 => Some(OrderSide::Sell),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn opposite(self) -> Self {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+
+    /// Whether an order on this side limited at `price` trades with one resting on the
+    /// other side at `resting_price`.
+    #[inline(always)]
+    pub const fn crosses(self, price: u64, resting_price: u64) -> bool {
+        match self {
+            OrderSide::Buy => price >= resting_price,
+            OrderSide::Sell => price <= resting_price,
+        }
+    }
+}
+
+/// What an order is, apart from its side and how long it lives. A new type (a peg, a
+/// cross, an auction-only order) is a variant here that describes itself through
+/// `flags`; books, the router and validators act on the flags rather than matching on
+/// kinds, so they only change when a type needs behaviour no flag covers yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Deserialize, serde::Serialize)]
+pub enum OrderKind {
+    /// Rests at its limit price until filled or cancelled.
+    #[default]
+    Limit,
+    /// Shows at most `display_quantity` at a time. When the shown slice fills, the next
+    /// one is cut from the hidden reserve and joins the back of the price level.
+    Iceberg { display_quantity: u64 },
+}
+
+impl OrderKind {
+    #[inline(always)]
+    pub fn is_limit(&self) -> bool {
+        *self == OrderKind::Limit
+    }
+
+    #[inline(always)]
+    pub const fn flags(self) -> OrderFlags {
+        match self {
+            OrderKind::Limit => OrderFlags::NONE,
+            OrderKind::Iceberg { .. } => OrderFlags::HIDDEN_RESERVE,
+        }
+    }
+}
+
+/// Behaviour an order's kind and time in force ask of the engine, combined by
+/// `Order::flags`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OrderFlags(u8);
+
+impl OrderFlags {
+    pub const NONE: Self = Self(0);
+    /// Matches what it can on arrival; the remainder is cancelled instead of resting.
+    pub const MATCH_ON_ARRIVAL: Self = Self(1);
+    /// Held out of the books and only executed in the opening auction.
+    pub const AUCTION_ONLY: Self = Self(1 << 1);
+    /// Survives session rolls.
+    pub const CARRIES_OVER: Self = Self(1 << 2);
+    /// Rests only part of its quantity at a time, with the rest held back by the router.
+    pub const HIDDEN_RESERVE: Self = Self(1 << 3);
+
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline(always)]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitOr for OrderFlags {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+pub type ParticipantId = u32;
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Order {
     pub id: u64,
@@ -13,6 +172,59 @@ pub struct Order {
     pub quantity: u64,
     pub price: u64,
     pub order_type: OrderSide,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+    /// Nanoseconds since the Unix epoch, 0 when the caller did not stamp the order.
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Submitting firm or account, 0 when unattributed.
+    #[serde(default)]
+    pub participant: ParticipantId,
+    /// Good-till-date: nanoseconds since the Unix epoch at which `expire_orders`
+    /// cancels the order if it is still resting. Not carried by the binary wire format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    /// Not carried by the binary wire format, which decodes every order as `Limit`.
+    #[serde(default, skip_serializing_if = "OrderKind::is_limit")]
+    pub kind: OrderKind,
+}
+
+impl Order {
+    #[inline(always)]
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_participant(mut self, participant: ParticipantId) -> Self {
+        self.participant = participant;
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_kind(mut self, kind: OrderKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Everything the order's kind and time in force ask of the engine.
+    #[inline(always)]
+    pub const fn flags(&self) -> OrderFlags {
+        self.kind.flags().union(self.time_in_force.flags())
+    }
+
+    #[inline(always)]
+    pub fn with_expiry(mut self, expires_at: u64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
 }
 
 pub fn new_order(id: u64, symbol: SymbolId, quantity: u64, price: f64, order_type: OrderSide) -> Order {
@@ -24,16 +236,74 @@ pub fn new_order(id: u64, symbol: SymbolId, quantity: u64, price: f64, order_typ
         quantity,
         price,
         order_type,
+        time_in_force: TimeInForce::Day,
+        timestamp: 0,
+        participant: 0,
+        expires_at: None,
+        kind: OrderKind::Limit,
     }
 }
 
+/// Decimals of `PriceScale::DEFAULT`, used by symbols without a scale of their own.
+pub const PRICE_DECIMALS: u32 = 3;
+pub const PRICE_SCALE: u64 = 10u64.pow(PRICE_DECIMALS);
+
+/// Converts at `PriceScale::DEFAULT`; symbols with their own scale go through
+/// `PriceScale::to_u64`.
 #[inline(always)]
 pub const fn price_to_u64(price: f64) -> u64 {
-    (price * 1000.0) as u64
+    PriceScale::DEFAULT.to_u64(price)
 }
 
 #[inline(always)]
 pub const fn u64_to_price(price: u64) -> f64 {
-    price as f64 / 1000.0
+    PriceScale::DEFAULT.to_price(price)
 }
 
+
+pub const ORDER_WIRE_SIZE: usize = 42;
+
+// Fixed little-endian layout: id (8) | quantity (8) | price (8) | symbol (4) | side (1) | tif (1) | timestamp (8) | participant (4)
+pub fn encode_order(order: &Order) -> [u8; ORDER_WIRE_SIZE] {
+    let mut bytes = [0u8; ORDER_WIRE_SIZE];
+    bytes[0..8].copy_from_slice(&order.id.to_le_bytes());
+    bytes[8..16].copy_from_slice(&order.quantity.to_le_bytes());
+    bytes[16..24].copy_from_slice(&order.price.to_le_bytes());
+    bytes[24..28].copy_from_slice(&order.symbol.to_le_bytes());
+    bytes[28] = order.order_type.wire_code();
+    bytes[29] = order.time_in_force.wire_code();
+    bytes[30..38].copy_from_slice(&order.timestamp.to_le_bytes());
+    bytes[38..42].copy_from_slice(&order.participant.to_le_bytes());
+    bytes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    Truncated { len: usize },
+    InvalidSide(u8),
+    InvalidTimeInForce(u8),
+}
+
+/// Decodes the first `ORDER_WIRE_SIZE` bytes; anything after them is ignored.
+pub fn decode_order(bytes: &[u8]) -> Result<Order, DecodeError> {
+    let Some(bytes) = bytes.first_chunk::<ORDER_WIRE_SIZE>() else {
+        return Err(DecodeError::Truncated { len: bytes.len() });
+    };
+
+    let order_type = OrderSide::from_wire_code(bytes[28]).ok_or(DecodeError::InvalidSide(bytes[28]))?;
+    let time_in_force = TimeInForce::from_wire_code(bytes[29]).ok_or(DecodeError::InvalidTimeInForce(bytes[29]))?;
+
+    // Fixed-size subslices of a length-checked array, so these conversions cannot fail
+    Ok(Order {
+        id: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+        symbol: SymbolId::from_le_bytes(bytes[24..28].try_into().unwrap()),
+        quantity: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+        price: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+        order_type,
+        time_in_force,
+        timestamp: u64::from_le_bytes(bytes[30..38].try_into().unwrap()),
+        participant: ParticipantId::from_le_bytes(bytes[38..42].try_into().unwrap()),
+        expires_at: None,
+        kind: OrderKind::Limit,
+    })
+}
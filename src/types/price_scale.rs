@@ -0,0 +1,155 @@
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+
+use crate::types::order::{new_order, Order, OrderSide, PRICE_DECIMALS};
+use crate::types::symbol_mapping::SymbolId;
+
+/// How many decimal places a symbol's integer prices carry. Books only compare and
+/// add prices, so the scale matters where prices are converted to or from decimals:
+/// building orders, decoding feeds and displaying levels and trades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct PriceScale {
+    decimals: u32,
+}
+
+impl PriceScale {
+    /// `PRICE_DECIMALS`, the scale of `price_to_u64` and `new_order`.
+    pub const DEFAULT: Self = Self { decimals: PRICE_DECIMALS };
+    /// The most decimals a `u64` price can carry while still holding a price of 1.
+    pub const MAX_DECIMALS: u32 = 18;
+
+    pub const fn new(decimals: u32) -> Option<Self> {
+        if decimals <= Self::MAX_DECIMALS { Some(Self { decimals }) } else { None }
+    }
+
+    #[inline(always)]
+    pub const fn decimals(self) -> u32 {
+        self.decimals
+    }
+
+    /// Integer units per 1.0 of price.
+    #[inline(always)]
+    pub const fn factor(self) -> u64 {
+        10u64.pow(self.decimals)
+    }
+
+    /// Rounds to the nearest unit. Negative prices become 0 and prices past the
+    /// range saturate at `u64::MAX`.
+    #[inline(always)]
+    pub const fn to_u64(self, price: f64) -> u64 {
+        (price * self.factor() as f64).round() as u64
+    }
+
+    #[inline(always)]
+    pub const fn to_price(self, price: u64) -> f64 {
+        price as f64 / self.factor() as f64
+    }
+
+    /// `new_order` with the price converted at this scale.
+    pub fn order(self, id: u64, symbol: SymbolId, quantity: u64, price: f64, order_type: OrderSide) -> Order {
+        let mut order = new_order(id, symbol, quantity, 0.0, order_type);
+        order.price = self.to_u64(price);
+        order
+    }
+
+    /// Formats `price` with exactly this scale's decimals, without going through `f64`.
+    #[inline(always)]
+    pub fn display(self, price: u64) -> ScaledPrice {
+        ScaledPrice { price, scale: self }
+    }
+}
+
+impl Default for PriceScale {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TryFrom<u32> for PriceScale {
+    type Error = String;
+
+    fn try_from(decimals: u32) -> Result<Self, Self::Error> {
+        Self::new(decimals).ok_or_else(|| format!("price scale of {decimals} decimals exceeds {}", Self::MAX_DECIMALS))
+    }
+}
+
+impl From<PriceScale> for u32 {
+    fn from(scale: PriceScale) -> Self {
+        scale.decimals
+    }
+}
+
+/// A price rendered at its scale, e.g. `101.250` at three decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScaledPrice {
+    price: u64,
+    scale: PriceScale,
+}
+
+impl fmt::Display for ScaledPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let factor = self.scale.factor();
+        let whole = self.price / factor;
+        match self.scale.decimals() {
+            0 => write!(f, "{whole}"),
+            decimals => write!(f, "{whole}.{:0width$}", self.price % factor, width = decimals as usize),
+        }
+    }
+}
+
+/// `PriceScale` per symbol. Symbols without an entry use `PriceScale::DEFAULT`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct PriceScales {
+    scales: FxHashMap<SymbolId, PriceScale>,
+}
+
+impl PriceScales {
+    pub fn with_scale(mut self, symbol: SymbolId, scale: PriceScale) -> Self {
+        self.set_scale(symbol, scale);
+        self
+    }
+
+    /// The default scale removes the symbol's entry.
+    pub fn set_scale(&mut self, symbol: SymbolId, scale: PriceScale) {
+        if scale == PriceScale::DEFAULT {
+            self.scales.remove(&symbol);
+        } else {
+            self.scales.insert(symbol, scale);
+        }
+    }
+
+    #[inline(always)]
+    pub fn scale(&self, symbol: SymbolId) -> PriceScale {
+        self.scales.get(&symbol).copied().unwrap_or_default()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.scales.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::price_to_u64;
+
+    #[test]
+    fn test_scales_convert_and_display_exactly() {
+        let fx = PriceScale::new(5).unwrap();
+        assert_eq!(fx.to_u64(1.23456), 123_456);
+        assert_eq!(fx.order(1, 0, 10, 0.00001, OrderSide::Buy).price, 1);
+        assert_eq!(fx.display(123_456).to_string(), "1.23456");
+        assert_eq!(PriceScale::new(0).unwrap().display(42).to_string(), "42");
+        assert_eq!(PriceScale::DEFAULT.to_u64(100.02), price_to_u64(100.02));
+        assert_eq!(PriceScale::DEFAULT.display(price_to_u64(100.02)).to_string(), "100.020");
+        assert_eq!(PriceScale::new(19), None);
+        assert!(serde_json::from_str::<PriceScale>("19").is_err());
+
+        let scales = PriceScales::default().with_scale(1, fx).with_scale(2, PriceScale::DEFAULT);
+        assert_eq!((scales.scale(1), scales.scale(2), scales.scale(3)), (fx, PriceScale::DEFAULT, PriceScale::DEFAULT));
+        assert_eq!(serde_json::from_str::<PriceScales>(&serde_json::to_string(&scales).unwrap()).unwrap(), scales);
+    }
+}
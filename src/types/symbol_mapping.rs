@@ -1,6 +1,6 @@
 use phf::phf_map;
 
-pub type SymbolId = u16;
+pub type SymbolId = u32;
 
 pub static SYMBOL_TO_ID: phf::Map<&'static str, SymbolId> = phf_map! {
     "AAPL" => 0,
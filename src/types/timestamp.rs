@@ -0,0 +1,169 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current wall-clock time as nanoseconds since the Unix epoch.
+#[inline(always)]
+pub fn now_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// Hybrid logical clock for stamping events that must merge into one ordered stream.
+/// Ticks follow wall-clock nanoseconds but are strictly increasing: a tick never goes
+/// backwards when the wall clock does, and ticks within the same nanosecond count up.
+/// Share one clock (e.g. in an `Arc`) between shards to sequence them centrally.
+#[derive(Debug, Default)]
+pub struct HybridClock {
+    last: AtomicU64,
+}
+
+impl HybridClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    pub fn tick(&self) -> u64 {
+        self.observe(now_nanos())
+    }
+
+    /// Next tick that is also later than `nanos`, e.g. a timestamp received from another
+    /// clock, so events caused by it sort after it.
+    #[inline(always)]
+    pub fn observe(&self, nanos: u64) -> u64 {
+        let mut last = self.last.load(Ordering::Relaxed);
+        loop {
+            let next = nanos.max(last + 1);
+            match self.last.compare_exchange_weak(last, next, Ordering::AcqRel, Ordering::Relaxed) {
+                Ok(_) => return next,
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    /// Most recent tick handed out, zero before the first.
+    #[inline(always)]
+    pub fn last(&self) -> u64 {
+        self.last.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_interop {
+    use chrono::{DateTime, Utc};
+
+    use crate::types::order::Order;
+
+    /// `None` for instants before the epoch or beyond what a u64 of nanoseconds can hold.
+    #[inline(always)]
+    pub fn datetime_to_nanos(datetime: &DateTime<Utc>) -> Option<u64> {
+        datetime.timestamp_nanos_opt().and_then(|nanos| u64::try_from(nanos).ok())
+    }
+
+    #[inline(always)]
+    pub fn nanos_to_datetime(nanos: u64) -> DateTime<Utc> {
+        DateTime::from_timestamp_nanos(i64::try_from(nanos).unwrap_or(i64::MAX))
+    }
+
+    impl Order {
+        pub fn with_datetime(self, datetime: &DateTime<Utc>) -> Option<Self> {
+            datetime_to_nanos(datetime).map(|nanos| self.with_timestamp(nanos))
+        }
+
+        #[inline(always)]
+        pub fn datetime(&self) -> DateTime<Utc> {
+            nanos_to_datetime(self.timestamp)
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub use chrono_interop::{datetime_to_nanos, nanos_to_datetime};
+
+#[cfg(feature = "time")]
+mod time_interop {
+    use time::OffsetDateTime;
+
+    use crate::types::order::Order;
+
+    #[inline(always)]
+    pub fn offset_datetime_to_nanos(datetime: OffsetDateTime) -> Option<u64> {
+        u64::try_from(datetime.unix_timestamp_nanos()).ok()
+    }
+
+    #[inline(always)]
+    pub fn nanos_to_offset_datetime(nanos: u64) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp_nanos(nanos as i128)
+            .expect("u64 nanoseconds are always within OffsetDateTime range")
+    }
+
+    impl Order {
+        pub fn with_offset_datetime(self, datetime: OffsetDateTime) -> Option<Self> {
+            offset_datetime_to_nanos(datetime).map(|nanos| self.with_timestamp(nanos))
+        }
+
+        #[inline(always)]
+        pub fn offset_datetime(&self) -> OffsetDateTime {
+            nanos_to_offset_datetime(self.timestamp)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+pub use time_interop::{nanos_to_offset_datetime, offset_datetime_to_nanos};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    use crate::types::order::{new_order, OrderSide};
+
+    const NANOS: u64 = 1_700_000_000_123_456_789;
+
+    #[test]
+    fn test_hybrid_clock_is_strictly_monotonic_across_threads() {
+        let clock = std::sync::Arc::new(HybridClock::new());
+        let handles: Vec<_> = (0..4).map(|_| {
+            let clock = clock.clone();
+            std::thread::spawn(move || (0..10_000).map(|_| clock.tick()).collect::<Vec<_>>())
+        }).collect();
+
+        let mut ticks = Vec::new();
+        for handle in handles {
+            let thread_ticks = handle.join().unwrap();
+            assert!(thread_ticks.windows(2).all(|pair| pair[0] < pair[1]));
+            ticks.extend(thread_ticks);
+        }
+        ticks.sort_unstable();
+        ticks.dedup();
+        assert_eq!(ticks.len(), 40_000);
+
+        let future = clock.last() + NANOS;
+        assert_eq!(clock.observe(future), future);
+        assert_eq!(clock.tick(), future + 1);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_round_trip() {
+        let datetime = nanos_to_datetime(NANOS);
+        let order = new_order(1, 0, 100, 10.0, OrderSide::Buy).with_datetime(&datetime).unwrap();
+
+        assert_eq!(order.timestamp, NANOS);
+        assert_eq!(order.datetime(), datetime);
+        assert_eq!(datetime_to_nanos(&chrono::DateTime::UNIX_EPOCH), Some(0));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_round_trip() {
+        let datetime = nanos_to_offset_datetime(NANOS);
+        let order = new_order(1, 0, 100, 10.0, OrderSide::Buy).with_offset_datetime(datetime).unwrap();
+
+        assert_eq!(order.timestamp, NANOS);
+        assert_eq!(order.offset_datetime(), datetime);
+        assert_eq!(offset_datetime_to_nanos(time::OffsetDateTime::UNIX_EPOCH - time::Duration::SECOND), None);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::types::order::{Order, OrderSide};
+use crate::types::symbol_mapping::SymbolId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+pub enum TradePriceRule {
+    /// Trade at the price of the order that was resting first (standard exchange behaviour).
+    #[default]
+    RestingOrder,
+    /// Trade halfway between the bid and ask limit prices, rounded down.
+    Midpoint,
+    /// Trade at the price of the order that arrived last.
+    Aggressor,
+}
+
+/// One execution between a resting order and the order that crossed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Trade {
+    pub symbol: SymbolId,
+    /// The order that arrived last and crossed the book.
+    pub taker_order_id: u64,
+    /// The order that was resting first.
+    pub maker_order_id: u64,
+    pub price: u64,
+    pub quantity: u64,
+    /// The taker's side: `Buy` when a bid lifted an offer.
+    pub side: OrderSide,
+}
+
+impl Trade {
+    /// A trade between two resting orders, taking the later id as the taker.
+    #[inline(always)]
+    pub fn between(bid: &Order, ask: &Order, price: u64) -> Self {
+        let (taker, maker) = if bid.id < ask.id { (ask, bid) } else { (bid, ask) };
+        Self::taken(taker, maker, price)
+    }
+
+    /// A trade where `taker` is known to have crossed `maker`, whatever their ids.
+    #[inline(always)]
+    pub fn taken(taker: &Order, maker: &Order, price: u64) -> Self {
+        Self {
+            symbol: taker.symbol,
+            taker_order_id: taker.id,
+            maker_order_id: maker.id,
+            price,
+            quantity: taker.quantity.min(maker.quantity),
+            side: taker.order_type,
+        }
+    }
+}
+
+#[inline(always)]
+pub const fn midpoint(bid_price: u64, ask_price: u64) -> u64 {
+    bid_price / 2 + ask_price / 2 + (bid_price % 2 + ask_price % 2) / 2
+}
+
+// Order ids double as arrival sequence, the same time priority the PriorityQueue book uses
+#[inline(always)]
+pub fn execution_price(bid: &Order, ask: &Order, rule: TradePriceRule) -> u64 {
+    if bid.id < ask.id { taker_price(ask, bid, rule) } else { taker_price(bid, ask, rule) }
+}
+
+/// `execution_price` when the taker is known rather than inferred from the ids.
+#[inline(always)]
+pub fn taker_price(taker: &Order, maker: &Order, rule: TradePriceRule) -> u64 {
+    match rule {
+        TradePriceRule::RestingOrder => maker.price,
+        TradePriceRule::Aggressor => taker.price,
+        TradePriceRule::Midpoint => midpoint(taker.price, maker.price),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::order::{new_order, price_to_u64, OrderSide};
+
+    #[test]
+    fn test_execution_price_rules() {
+        let bid = new_order(1, 0, 100, 101.0, OrderSide::Buy);
+        let ask = new_order(2, 0, 100, 100.0, OrderSide::Sell);
+
+        assert_eq!(execution_price(&bid, &ask, TradePriceRule::RestingOrder), price_to_u64(101.0));
+        assert_eq!(execution_price(&bid, &ask, TradePriceRule::Aggressor), price_to_u64(100.0));
+        assert_eq!(execution_price(&bid, &ask, TradePriceRule::Midpoint), price_to_u64(100.5));
+
+        // A taker known to have arrived last prices the trade even with the lower id
+        assert_eq!(taker_price(&bid, &ask, TradePriceRule::RestingOrder), price_to_u64(100.0));
+        assert_eq!(taker_price(&bid, &ask, TradePriceRule::Aggressor), price_to_u64(101.0));
+        assert_eq!(Trade::taken(&bid, &ask, 100_000).side, OrderSide::Buy);
+    }
+}
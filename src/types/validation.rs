@@ -0,0 +1,6 @@
+/// Whether the optional validation layers run: participant limits, duplicate order
+/// filtering, tick sizes and lot sizes, plus any check added behind this flag later.
+/// Building with `--features strip-validation` makes it `false`, so those branches
+/// compile out and their cost can be measured without a second code path. Their
+/// configuration calls keep working but are not enforced.
+pub const ENABLED: bool = !cfg!(feature = "strip-validation");
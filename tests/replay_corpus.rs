@@ -0,0 +1,129 @@
+//! Replays every journal in `tests/corpus` through each book implementation and compares
+//! the outcome with the committed `<name>.golden.json`.
+//!
+//! Journals are event schema version 1: one JSON `Order` per line, decoded through
+//! `events::decode_journal` so they keep replaying as the schema moves on. After adding a journal or deliberately
+//! changing matching behaviour, regenerate the goldens with:
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test --test replay_corpus
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+
+use rust_order_book::engine::OrderBookType;
+use rust_order_book::events::{self, BookEvent};
+use rust_order_book::router::OrderRouter;
+use rust_order_book::types::order::Order;
+use rust_order_book::types::symbol_mapping::SymbolId;
+
+const ORDER_BOOK_TYPES: [OrderBookType; 4] = [
+    OrderBookType::HashMap,
+    OrderBookType::PriorityQueue,
+    OrderBookType::ArrayQueue,
+    OrderBookType::Persistent,
+];
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct BookOutcome {
+    symbol: SymbolId,
+    resting_ids: Vec<u64>,
+    last_trade_price: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct ReplayOutcome {
+    /// Last trade price of the order's symbol after each journal entry is matched.
+    trade_prices: Vec<Option<u64>>,
+    books: Vec<BookOutcome>,
+    checksum: u64,
+}
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("corpus")
+}
+
+fn load_journal(path: &Path) -> Vec<Order> {
+    let journal = fs::read_to_string(path).unwrap_or_else(|err| panic!("reading {}: {err}", path.display()));
+    events::decode_journal(&journal)
+        .unwrap_or_else(|(line, err)| panic!("{}:{line}: {err:?}", path.display()))
+        .into_iter()
+        .filter_map(|event| match event {
+            BookEvent::Accepted { order, .. } => Some(order),
+            _ => None,
+        })
+        .collect()
+}
+
+fn replay(order_book_type: OrderBookType, journal: &[Order]) -> ReplayOutcome {
+    let symbols: FxHashSet<SymbolId> = journal.iter().map(|order| order.symbol).collect();
+    let mut router = OrderRouter::new_direct(symbols, order_book_type);
+
+    let mut trade_prices = Vec::with_capacity(journal.len());
+    for order in journal {
+        let symbol = order.symbol;
+        router.route_order(order.clone()).expect("journal order rejected");
+        router.match_all_orders();
+        trade_prices.push(router.last_trade_price(symbol));
+    }
+
+    let snapshot = router.snapshot_all();
+    let books = snapshot.books.iter()
+        .map(|book| BookOutcome {
+            symbol: book.symbol,
+            resting_ids: book.orders.iter().map(|order| order.id).collect(),
+            last_trade_price: router.last_trade_price(book.symbol),
+        })
+        .collect();
+
+    ReplayOutcome {
+        trade_prices,
+        books,
+        checksum: snapshot.checksum(),
+    }
+}
+
+#[test]
+fn replay_corpus_matches_golden_outputs() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut journals: Vec<PathBuf> = fs::read_dir(corpus_dir())
+        .expect("corpus directory")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "jsonl"))
+        .collect();
+    journals.sort();
+    assert!(!journals.is_empty(), "no journals in {}", corpus_dir().display());
+
+    for journal_path in journals {
+        let journal = load_journal(&journal_path);
+        let outcomes: BTreeMap<String, ReplayOutcome> = ORDER_BOOK_TYPES.iter()
+            .map(|&order_book_type| (order_book_type.to_string(), replay(order_book_type, &journal)))
+            .collect();
+
+        let golden_path = journal_path.with_extension("golden.json");
+        if update {
+            let json = serde_json::to_string_pretty(&outcomes).unwrap();
+            fs::write(&golden_path, json + "\n").unwrap();
+            continue;
+        }
+
+        let golden: BTreeMap<String, ReplayOutcome> = serde_json::from_str(
+            &fs::read_to_string(&golden_path)
+                .unwrap_or_else(|err| panic!("{}: {err}, run with UPDATE_GOLDEN=1", golden_path.display())),
+        )
+        .unwrap();
+        for (implementation, outcome) in &outcomes {
+            assert_eq!(
+                Some(outcome),
+                golden.get(implementation),
+                "{implementation} diverged from {}",
+                golden_path.display(),
+            );
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Drives routers through full session cycles (continuous trading, roll, pre-open,
+//! opening auction) and checks that every implementation expires, carries and reports
+//! the same orders. Sessions advance only through explicit calls, so no wall-clock time
+//! is involved.
+
+use crossbeam::channel;
+use rustc_hash::FxHashSet;
+
+use rust_order_book::engine::OrderBookType;
+use rust_order_book::router::{ExpiryReason, OrderRouter, SessionState};
+use rust_order_book::types::order::{new_order, price_to_u64, Order, OrderSide, TimeInForce};
+
+const ORDER_BOOK_TYPES: [OrderBookType; 5] = [
+    OrderBookType::HashMap,
+    OrderBookType::PriorityQueue,
+    OrderBookType::ArrayQueue,
+    OrderBookType::Persistent,
+    OrderBookType::FixedCapacity,
+];
+
+fn router(order_book_type: OrderBookType) -> OrderRouter {
+    OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), order_book_type)
+}
+
+fn ids<'a>(orders: impl IntoIterator<Item = &'a Order>) -> Vec<u64> {
+    let mut ids: Vec<u64> = orders.into_iter().map(|order| order.id).collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[test]
+fn session_transitions_gate_order_entry() {
+    for order_book_type in ORDER_BOOK_TYPES {
+        let mut router = router(order_book_type);
+        assert_eq!(router.session_state(), SessionState::Open);
+        assert_eq!(router.open_session().err(), Some("Session not in pre-open"), "{order_book_type}");
+
+        router.route_order(new_order(1, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        let summary = router.roll_session();
+        assert_eq!((summary.session_id, summary.stats.orders_routed), (0, 1), "{order_book_type}");
+        assert_eq!(router.session_state(), SessionState::PreOpen);
+        assert!(!router.readiness().is_ready(), "{order_book_type}");
+
+        // Pre-open accepts orders but nothing trades until the open
+        router.route_order(new_order(2, 0, 10, 100.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(3, 0, 10, 100.0, OrderSide::Sell)).unwrap();
+        assert!(router.open_session().unwrap().is_empty(), "{order_book_type}");
+        assert_eq!(router.session_state(), SessionState::Open);
+        assert!(router.readiness().is_ready(), "{order_book_type}");
+
+        router.match_all_orders();
+        assert_eq!(router.last_trade_price(0), Some(price_to_u64(100.0)), "{order_book_type}");
+        assert_eq!(router.session_history().len(), 1, "{order_book_type}");
+    }
+}
+
+#[test]
+fn day_orders_expire_and_good_till_cancel_orders_carry() {
+    for order_book_type in ORDER_BOOK_TYPES {
+        let mut router = router(order_book_type);
+        let (sender, receiver) = channel::unbounded();
+        router.attach_expiry_listener(sender);
+
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 10, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::GoodTillCancel)).unwrap();
+        router.route_order(new_order(3, 0, 10, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::GoodTillCancel)).unwrap();
+        router.route_order(new_order(4, 1, 10, 50.0, OrderSide::Sell)).unwrap();
+
+        let summary = router.roll_session();
+        assert_eq!(ids(&summary.expired_orders), vec![1, 4], "{order_book_type}");
+        assert_eq!(summary.carried_orders, 2, "{order_book_type}");
+        assert_eq!(summary.close_prices[&0], Some(price_to_u64(99.0)), "{order_book_type}");
+        assert_eq!(router.get_best_prices(0), Some((None, None)), "{order_book_type}");
+
+        let expired: Vec<_> = receiver.try_iter().collect();
+        assert!(expired.iter().all(|event| event.reason == ExpiryReason::SessionEnd), "{order_book_type}");
+        assert_eq!(ids(expired.iter().map(|event| &event.order)), vec![1, 4], "{order_book_type}");
+
+        // Carried orders come back in their original priority order
+        router.open_session().unwrap();
+        let resting = router.snapshot_book(0).unwrap().orders;
+        assert_eq!(resting.iter().map(|order| order.id).collect::<Vec<_>>(), vec![2, 3], "{order_book_type}");
+
+        router.route_order(new_order(5, 0, 10, 99.0, OrderSide::Sell)).unwrap();
+        router.match_all_orders();
+        assert_eq!(router.snapshot_book(0).unwrap().orders[0].id, 3, "{order_book_type}");
+    }
+}
+
+#[test]
+fn unfilled_auction_and_evicted_orders_report_their_reason() {
+    for order_book_type in ORDER_BOOK_TYPES {
+        let mut router = router(order_book_type);
+        let (sender, receiver) = channel::unbounded();
+        router.attach_expiry_listener(sender);
+        router.set_max_depth(Some(1));
+
+        router.route_order(new_order(1, 0, 10, 98.0, OrderSide::Buy)).unwrap();
+        router.route_order(new_order(2, 0, 10, 99.0, OrderSide::Buy)).unwrap();
+        router.roll_session();
+        // Nothing on the sell side, so there is no imbalance for this order to offset
+        router.route_order(new_order(3, 1, 10, 50.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImbalanceOnly)).unwrap();
+        let cancelled = router.open_session().unwrap();
+        assert_eq!(ids(&cancelled), vec![3], "{order_book_type}");
+
+        let events: Vec<_> = receiver.try_iter().map(|event| (event.order.id, event.reason, event.sequence)).collect();
+        let reasons: Vec<_> = events.iter().map(|&(id, reason, _)| (id, reason)).collect();
+        assert_eq!(reasons, vec![
+            (1, ExpiryReason::DepthEviction),
+            (2, ExpiryReason::SessionEnd),
+            (3, ExpiryReason::AuctionUnfilled),
+        ], "{order_book_type}");
+        assert!(events.windows(2).all(|pair| pair[0].2 <= pair[1].2), "{order_book_type}");
+    }
+}
+
+#[test]
+fn pre_open_state_survives_restore_into_another_implementation() {
+    for order_book_type in ORDER_BOOK_TYPES {
+        let mut router = router(order_book_type);
+        router.route_order(new_order(1, 0, 10, 99.0, OrderSide::Buy).with_time_in_force(TimeInForce::GoodTillCancel)).unwrap();
+        router.roll_session();
+        router.route_order(new_order(2, 0, 10, 101.0, OrderSide::Sell)).unwrap();
+
+        let mut restored = OrderRouter::new_direct(FxHashSet::from_iter([0, 1]), OrderBookType::HashMap);
+        restored.restore_all(router.snapshot_all()).unwrap();
+        assert_eq!(restored.session_state(), SessionState::PreOpen, "{order_book_type}");
+        assert_eq!(ids(restored.carried_orders()), vec![1], "{order_book_type}");
+
+        restored.open_session().unwrap();
+        router.open_session().unwrap();
+        assert_eq!(restored.get_best_prices(0), router.get_best_prices(0), "{order_book_type}");
+        assert_eq!(restored.snapshot_all().checksum(), router.snapshot_all().checksum(), "{order_book_type}");
+    }
+}
@@ -0,0 +1,197 @@
+//! Long-running soak test, ignored by default. Run it in release mode:
+//!
+//! ```text
+//! cargo test --release --test soak -- --ignored --nocapture
+//! ```
+//!
+//! `SOAK_ORDERS` overrides the number of orders pushed through each implementation.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::time::Instant;
+
+use rand::prelude::*;
+use rustc_hash::FxHashSet;
+
+use rust_order_book::engine::{create_order_book, OrderBookTrait, OrderBookType};
+use rust_order_book::types::order::{new_order, Order, OrderSide};
+use rust_order_book::types::symbol_mapping::SymbolId;
+
+struct CountingAllocator;
+
+static LIVE_BYTES: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.fetch_add(layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LIVE_BYTES.fetch_add(new_size as isize - layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const SYMBOL: SymbolId = 0;
+const DEFAULT_ORDERS: u64 = 250_000_000;
+const ORDERS_PER_PHASE: usize = 64;
+const MID_PRICE: u64 = 100_000;
+const PRICE_RANGE: u64 = 5_000;
+const WARMUP_ROUNDS: u64 = 16;
+const MAX_MEMORY_GROWTH: isize = 4 << 20;
+
+struct Soak {
+    order_book: Box<dyn OrderBookTrait + Send + Sync>,
+    rng: StdRng,
+    next_id: u64,
+    orders: u64,
+}
+
+impl Soak {
+    fn new(order_book_type: OrderBookType) -> Self {
+        Self {
+            order_book: create_order_book(order_book_type, FxHashSet::from_iter([SYMBOL])),
+            rng: StdRng::seed_from_u64(0x50a4),
+            next_id: 0,
+            orders: 0,
+        }
+    }
+
+    fn order(&mut self, side: OrderSide, price: u64, quantity: u64) -> Order {
+        self.next_id += 1;
+        let mut order = new_order(self.next_id, SYMBOL, quantity, 0.0, side);
+        order.price = price;
+        order
+    }
+
+    fn add(&mut self, side: OrderSide, price: u64, quantity: u64) {
+        let order = self.order(side, price, quantity);
+        assert!(self.order_book.add_order_fast(order), "order rejected");
+        self.orders += 1;
+    }
+
+    fn quantity(&mut self) -> u64 {
+        self.rng.gen_range(1..1_000)
+    }
+
+    /// Matches until nothing more trades, however many passes the book needs.
+    fn match_all(&mut self) {
+        while !self.order_book.match_orders().is_empty() {}
+    }
+
+    /// Rests a side, takes out part of it, then sweeps the remainder so every round ends flat.
+    /// Fills are partial, so the checks follow resting quantity rather than order counts.
+    fn round(&mut self) {
+        for (side, aggressor, aggressive_price) in [
+            (OrderSide::Buy, OrderSide::Sell, 1),
+            (OrderSide::Sell, OrderSide::Buy, u64::MAX),
+        ] {
+            let mut resting = 0;
+            for _ in 0..ORDERS_PER_PHASE {
+                let price = match side {
+                    OrderSide::Buy => self.rng.gen_range(MID_PRICE - PRICE_RANGE..MID_PRICE),
+                    OrderSide::Sell => self.rng.gen_range(MID_PRICE..MID_PRICE + PRICE_RANGE),
+                };
+                let quantity = self.quantity();
+                self.add(side, price, quantity);
+                resting += quantity;
+            }
+            self.match_all();
+            self.check_invariants(side, resting);
+
+            for _ in 0..ORDERS_PER_PHASE / 4 {
+                let quantity = self.quantity().min(resting - 1);
+                if quantity == 0 {
+                    break;
+                }
+                self.add(aggressor, aggressive_price, quantity);
+                resting -= quantity;
+            }
+            self.match_all();
+            self.check_invariants(side, resting);
+
+            while resting > 0 {
+                let quantity = self.quantity().min(resting);
+                self.add(aggressor, aggressive_price, quantity);
+                resting -= quantity;
+            }
+            self.match_all();
+            self.check_invariants(side, 0);
+            assert!(self.order_book.last_trade_price(SYMBOL).is_some());
+        }
+    }
+
+    fn check_invariants(&self, side: OrderSide, expected_quantity: u64) {
+        let resting = self.order_book.resting_orders(SYMBOL).unwrap();
+        let ids: FxHashSet<u64> = resting.iter().map(|order| order.id).collect();
+        assert_eq!(ids.len(), resting.len(), "orders duplicated");
+        assert!(resting.iter().all(|order| order.order_type == side), "aggressor left resting");
+        assert_eq!(resting.iter().map(|order| order.quantity).sum::<u64>(), expected_quantity, "quantity lost or duplicated");
+
+        let best_bid = resting.iter().filter(|order| order.order_type == OrderSide::Buy).map(|order| order.price).max();
+        let best_ask = resting.iter().filter(|order| order.order_type == OrderSide::Sell).map(|order| order.price).min();
+        assert_eq!(self.order_book.get_best_prices(SYMBOL), Some((best_bid, best_ask)), "best-price cache drifted");
+        assert!(!self.order_book.can_match(SYMBOL));
+    }
+}
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.load(Ordering::Relaxed)
+}
+
+fn soak(order_book_type: OrderBookType, total_orders: u64) {
+    let mut soak = Soak::new(order_book_type);
+    let orders_per_round = (ORDERS_PER_PHASE * 4) as u64;
+    let rounds = (total_orders / orders_per_round).max(WARMUP_ROUNDS + 1);
+    let report_every = (rounds / 10).max(1);
+
+    for _ in 0..WARMUP_ROUNDS {
+        soak.round();
+    }
+    let baseline = live_bytes();
+    let started = Instant::now();
+
+    for round in WARMUP_ROUNDS..rounds {
+        soak.round();
+
+        if round % report_every == 0 {
+            let growth = live_bytes() - baseline;
+            eprintln!(
+                "{order_book_type}: {} orders, {:.0} orders/s, memory growth {growth} bytes",
+                soak.orders,
+                soak.orders as f64 / started.elapsed().as_secs_f64(),
+            );
+            assert!(growth < MAX_MEMORY_GROWTH, "{order_book_type} grew by {growth} bytes");
+        }
+    }
+
+    let growth = live_bytes() - baseline;
+    assert!(growth < MAX_MEMORY_GROWTH, "{order_book_type} grew by {growth} bytes");
+}
+
+#[test]
+#[ignore = "long-running soak test, run with --ignored"]
+fn soak_all_order_books() {
+    let total_orders = std::env::var("SOAK_ORDERS")
+        .ok()
+        .and_then(|orders| orders.parse().ok())
+        .unwrap_or(DEFAULT_ORDERS);
+
+    for order_book_type in [
+        OrderBookType::HashMap,
+        OrderBookType::PriorityQueue,
+        OrderBookType::ArrayQueue,
+        OrderBookType::Persistent,
+    ] {
+        soak(order_book_type, total_orders);
+    }
+}
@@ -0,0 +1,47 @@
+//! Property tests for the binary order decoder, the one decoder that takes untrusted
+//! bytes (the shared-memory ring). The same check runs under libFuzzer with
+//! `cargo fuzz run decode_order` from the `fuzz` directory.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rust_order_book::types::order::{DecodeError, ORDER_WIRE_SIZE, decode_order, encode_order, new_order, OrderSide};
+
+const ITERATIONS: usize = 200_000;
+
+/// Decoding must not panic, and either round-trips the header bytes or names what was wrong.
+fn check_frame(frame: &[u8]) {
+    match decode_order(frame) {
+        Ok(order) => assert_eq!(encode_order(&order)[..], frame[..ORDER_WIRE_SIZE]),
+        Err(DecodeError::Truncated { len }) => assert!(len == frame.len() && len < ORDER_WIRE_SIZE),
+        Err(DecodeError::InvalidSide(side)) => assert!(side == frame[28] && side > 1),
+        Err(DecodeError::InvalidTimeInForce(time_in_force)) => {
+            assert!(time_in_force == frame[29] && time_in_force > 4)
+        }
+    }
+}
+
+#[test]
+fn decode_order_handles_random_frames() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _ in 0..ITERATIONS {
+        let mut frame = vec![0u8; rng.gen_range(0..=ORDER_WIRE_SIZE * 2)];
+        rng.fill(frame.as_mut_slice());
+        // Random bytes almost never form a valid side and time in force, so half the
+        // frames get valid enum bytes to keep the successful path covered
+        if frame.len() >= ORDER_WIRE_SIZE && rng.gen_bool(0.5) {
+            frame[28] = rng.gen_range(0..2);
+            frame[29] = rng.gen_range(0..5);
+        }
+        check_frame(&frame);
+    }
+}
+
+#[test]
+fn decode_order_rejects_every_truncation() {
+    let frame = encode_order(&new_order(7, 1, 100, 150.0, OrderSide::Sell));
+    for len in 0..ORDER_WIRE_SIZE {
+        assert_eq!(decode_order(&frame[..len]).err(), Some(DecodeError::Truncated { len }));
+    }
+    check_frame(&frame);
+}
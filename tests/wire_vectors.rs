@@ -0,0 +1,206 @@
+//! Canonical byte vectors for the binary order format, committed in
+//! `tests/vectors/order_wire.json` so codecs in other languages (FFI, Python, C#) can be
+//! checked against this implementation. Each valid vector pairs an order, in the same
+//! JSON form as the journals, with its hex encoding; each invalid vector pairs a frame
+//! with the `DecodeError` it must produce.
+//!
+//! After changing the wire format or the cases below, regenerate the file with:
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test --test wire_vectors
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use rust_order_book::types::order::{
+    decode_order, encode_order, new_order, Order, OrderFlags, OrderKind, OrderSide, TimeInForce, ORDER_WIRE_SIZE,
+};
+
+const LAYOUT: &str = "little-endian: id u64 | quantity u64 | price u64 | symbol u32 | side u8 (0 buy, 1 sell) \
+    | time_in_force u8 (0 day, 1 good-till-cancel, 2 imbalance-only, 3 symbol default, 4 immediate-or-cancel) | timestamp u64 | participant u32";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ValidVector {
+    name: String,
+    order: Order,
+    hex: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InvalidVector {
+    name: String,
+    hex: String,
+    error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrderWireVectors {
+    size: usize,
+    layout: String,
+    valid: Vec<ValidVector>,
+    invalid: Vec<InvalidVector>,
+}
+
+fn vectors_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("vectors").join("order_wire.json")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or_else(|err| panic!("{hex}: {err}")))
+        .collect()
+}
+
+fn canonical_orders() -> Vec<(&'static str, Order)> {
+    vec![
+        ("zeroed_buy", new_order(0, 0, 0, 0.0, OrderSide::Buy)),
+        ("simple_buy", new_order(1, 0, 100, 150.0, OrderSide::Buy)),
+        ("sell_fractional_price", new_order(2, 3, 25, 2799.125, OrderSide::Sell)),
+        (
+            "good_till_cancel_attributed",
+            new_order(42, 7, 500, 99.99, OrderSide::Sell)
+                .with_time_in_force(TimeInForce::GoodTillCancel)
+                .with_timestamp(1_700_000_000_123_456_789)
+                .with_participant(1001),
+        ),
+        (
+            "imbalance_only",
+            new_order(43, 1, 10, 100.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImbalanceOnly),
+        ),
+        (
+            "symbol_default",
+            new_order(44, 2, 10, 100.0, OrderSide::Sell).with_time_in_force(TimeInForce::SymbolDefault),
+        ),
+        (
+            "immediate_or_cancel",
+            new_order(45, 2, 10, 100.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImmediateOrCancel),
+        ),
+        (
+            "byte_order_probe",
+            Order {
+                id: 0x0102_0304_0506_0708,
+                symbol: 0x1112_1314,
+                quantity: 0x2122_2324_2526_2728,
+                price: 0x3132_3334_3536_3738,
+                order_type: OrderSide::Sell,
+                time_in_force: TimeInForce::Day,
+                timestamp: 0x4142_4344_4546_4748,
+                participant: 0x5152_5354,
+                expires_at: None,
+                kind: OrderKind::Limit,
+            },
+        ),
+        (
+            "maximum_values",
+            Order {
+                id: u64::MAX,
+                symbol: u32::MAX,
+                quantity: u64::MAX,
+                price: u64::MAX,
+                order_type: OrderSide::Sell,
+                time_in_force: TimeInForce::GoodTillCancel,
+                timestamp: u64::MAX,
+                participant: u32::MAX,
+                expires_at: None,
+                kind: OrderKind::Limit,
+            },
+        ),
+    ]
+}
+
+fn canonical_invalid() -> Vec<(&'static str, Vec<u8>)> {
+    let frame = encode_order(&new_order(1, 0, 100, 150.0, OrderSide::Buy));
+    let with_byte = |index: usize, value: u8| {
+        let mut frame = frame.to_vec();
+        frame[index] = value;
+        frame
+    };
+    vec![
+        ("empty", Vec::new()),
+        ("truncated_by_one", frame[..ORDER_WIRE_SIZE - 1].to_vec()),
+        ("side_out_of_range", with_byte(28, 2)),
+        ("side_all_ones", with_byte(28, 0xff)),
+        ("time_in_force_out_of_range", with_byte(29, 5)),
+    ]
+}
+
+fn generate() -> OrderWireVectors {
+    OrderWireVectors {
+        size: ORDER_WIRE_SIZE,
+        layout: LAYOUT.to_string(),
+        valid: canonical_orders()
+            .into_iter()
+            .map(|(name, order)| ValidVector { name: name.to_string(), hex: to_hex(&encode_order(&order)), order })
+            .collect(),
+        invalid: canonical_invalid()
+            .into_iter()
+            .map(|(name, frame)| InvalidVector {
+                name: name.to_string(),
+                error: format!("{:?}", decode_order(&frame).expect_err(name)),
+                hex: to_hex(&frame),
+            })
+            .collect(),
+    }
+}
+
+fn load() -> OrderWireVectors {
+    let path = vectors_path();
+    serde_json::from_str(
+        &fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {err}, run with UPDATE_GOLDEN=1", path.display())),
+    )
+    .unwrap()
+}
+
+#[test]
+fn order_vectors_match_reference_encoder() {
+    let generated = generate();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let json = serde_json::to_string_pretty(&generated).unwrap();
+        fs::write(vectors_path(), json + "\n").unwrap();
+        return;
+    }
+    // Order has no PartialEq, so compare the JSON forms
+    assert_eq!(
+        serde_json::to_value(load()).unwrap(),
+        serde_json::to_value(generated).unwrap(),
+        "{} is stale, run with UPDATE_GOLDEN=1",
+        vectors_path().display(),
+    );
+}
+
+#[test]
+fn order_vectors_round_trip() {
+    let vectors = load();
+    assert_eq!(vectors.size, ORDER_WIRE_SIZE);
+    for vector in &vectors.valid {
+        let frame = from_hex(&vector.hex);
+        assert_eq!(frame.len(), ORDER_WIRE_SIZE, "{}", vector.name);
+        assert_eq!(encode_order(&vector.order)[..], frame[..], "{}", vector.name);
+        let decoded = decode_order(&frame).unwrap_or_else(|err| panic!("{}: {err:?}", vector.name));
+        assert_eq!(serde_json::to_value(decoded).unwrap(), serde_json::to_value(&vector.order).unwrap(), "{}", vector.name);
+    }
+    for vector in &vectors.invalid {
+        let error = decode_order(&from_hex(&vector.hex)).expect_err(&vector.name);
+        assert_eq!(format!("{error:?}"), vector.error, "{}", vector.name);
+    }
+}
+
+#[test]
+fn order_kind_and_flags_stay_off_the_wire() {
+    let order = new_order(1, 0, 10, 100.0, OrderSide::Buy).with_time_in_force(TimeInForce::ImmediateOrCancel);
+    assert!(order.flags().contains(OrderFlags::MATCH_ON_ARRIVAL));
+    assert!(!order.flags().contains(OrderFlags::CARRIES_OVER));
+    let json = serde_json::to_value(&order).unwrap();
+    assert!(json.get("kind").is_none());
+    let decoded: Order = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.kind, OrderKind::Limit);
+    assert_eq!(decode_order(&encode_order(&order)).unwrap().flags(), order.flags());
+}